@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::PublicIdentifier;
+
+/// Bounds how many requests may be in progress against the same agent at the same time.
+///
+/// Agents are told apart by their public identifier, so all instances of an agent
+/// (e.g. one re-created after a configuration change) share the same bound.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimiter {
+    /// The maximum number of concurrent requests per agent. `0` means unlimited.
+    max_concurrent_requests_per_agent: usize,
+
+    semaphores: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(max_concurrent_requests_per_agent: usize) -> Self {
+        Self {
+            max_concurrent_requests_per_agent,
+            semaphores: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(0)
+    }
+
+    /// Waits until a request can be made against the given agent.
+    ///
+    /// The returned permit (if any) is to be held for as long as the request is in progress.
+    pub async fn acquire(&self, identifier: &PublicIdentifier) -> Option<OwnedSemaphorePermit> {
+        if self.max_concurrent_requests_per_agent == 0 {
+            return None;
+        }
+
+        let semaphore = self
+            .semaphores
+            .lock()
+            .expect("agent semaphores lock poisoned")
+            .entry(identifier.as_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_concurrent_requests_per_agent)))
+            .clone();
+
+        if semaphore.available_permits() == 0 {
+            tracing::debug!(
+                agent_id = identifier.as_string(),
+                max_concurrent_requests_per_agent = self.max_concurrent_requests_per_agent,
+                "Waiting for other requests to the agent to complete"
+            );
+        }
+
+        // We never close the semaphore, so acquiring cannot fail.
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_requests_per_agent() {
+        let limiter = ConcurrencyLimiter::new(1);
+
+        let agent = PublicIdentifier::Static("agent".to_owned());
+        let other_agent = PublicIdentifier::Static("other-agent".to_owned());
+
+        let permit = limiter.acquire(&agent).await;
+        assert!(permit.is_some());
+
+        // Another agent is not affected by the first one being busy.
+        assert!(limiter.acquire(&other_agent).await.is_some());
+
+        // The same agent (even if re-created) has no slot left until the permit is released.
+        let blocked = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            limiter.clone().acquire(&agent),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        drop(permit);
+
+        assert!(limiter.acquire(&agent).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn unlimited_never_waits() {
+        let limiter = ConcurrencyLimiter::unlimited();
+
+        let agent = PublicIdentifier::Static("agent".to_owned());
+
+        assert!(limiter.acquire(&agent).await.is_none());
+        assert!(limiter.acquire(&agent).await.is_none());
+    }
+}
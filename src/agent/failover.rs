@@ -0,0 +1,162 @@
+use super::retry::{is_retryable_error, run_with_retries};
+use super::{AgentInstance, AgentPurpose};
+
+/// Runs the given operation against each agent in the chain (in order), until one of them succeeds.
+///
+/// Each agent first gets to retry according to its own retry policy (see [`run_with_retries`]).
+/// The next agent is only tried when the previous one still fails with a retryable error (see [`is_retryable_error`]).
+/// Returns the agent which produced the final result (successful or not), along with that result.
+///
+/// The chain must not be empty.
+pub async fn run_with_failover<'a, T>(
+    agents: &'a [AgentInstance],
+    purpose: AgentPurpose,
+    mut operation: impl AsyncFnMut(&'a AgentInstance) -> anyhow::Result<T>,
+) -> (&'a AgentInstance, anyhow::Result<T>) {
+    let (last_agent, other_agents) = agents
+        .split_last()
+        .expect("run_with_failover requires at least one agent");
+
+    for agent in other_agents {
+        let result = run_with_retries(agent, purpose, &mut operation).await;
+
+        match result {
+            Err(err) if is_retryable_error(&err) => {
+                tracing::warn!(
+                    ?purpose,
+                    agent_id = agent.identifier().as_string(),
+                    ?err,
+                    "Agent failed with a retryable error, so failing over to the next agent in the chain"
+                );
+            }
+            result => return (agent, result),
+        }
+    }
+
+    (
+        last_agent,
+        run_with_retries(last_agent, purpose, &mut operation).await,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use async_openai::error::OpenAIError;
+
+    use super::*;
+    use crate::agent::{
+        AgentProvider, ConcurrencyLimiter, PublicIdentifier,
+        create_from_provider_and_yaml_value_config, default_config_for_provider,
+    };
+
+    fn openai_agent(identifier: PublicIdentifier) -> AgentInstance {
+        let mut config = default_config_for_provider(&AgentProvider::OpenAI);
+
+        // Retrying is covered by the retry module. Here, each agent only gets a single attempt.
+        config.as_mapping_mut().expect("a mapping").insert(
+            "retry".into(),
+            serde_yaml_ng::from_str("max_retries: 0").unwrap(),
+        );
+
+        create_from_provider_and_yaml_value_config(
+            &AgentProvider::OpenAI,
+            &identifier,
+            config,
+            ConcurrencyLimiter::unlimited(),
+        )
+        .expect("a valid agent")
+    }
+
+    fn openai_api_error(error_type: &str, code: &str) -> anyhow::Error {
+        anyhow::Error::new(OpenAIError::ApiError(
+            serde_json::from_value(serde_json::json!({
+                "message": "The request failed",
+                "type": error_type,
+                "param": null,
+                "code": code,
+            }))
+            .expect("a valid API error"),
+        ))
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_provider_library_errors() {
+        struct TestCase {
+            name: &'static str,
+            primary_error: fn() -> anyhow::Error,
+            expected_calls: Vec<&'static str>,
+            expected_success: bool,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "OpenAI rate limit",
+                primary_error: || openai_api_error("requests", "rate_limit_exceeded"),
+                expected_calls: vec!["static/openai", "global/groq-llama"],
+                expected_success: true,
+            },
+            TestCase {
+                name: "Anthropic overload",
+                primary_error: || {
+                    crate::agent::provider::recognize_http_error(anyhow::anyhow!(
+                        r#"ApiError {{ type: "overloaded_error", message: "Overloaded" }}"#
+                    ))
+                },
+                expected_calls: vec!["static/openai", "global/groq-llama"],
+                expected_success: true,
+            },
+            TestCase {
+                name: "OpenAI invalid request",
+                primary_error: || openai_api_error("invalid_request_error", "invalid_value"),
+                expected_calls: vec!["static/openai"],
+                expected_success: false,
+            },
+        ];
+
+        for test_case in test_cases {
+            let agents = vec![
+                openai_agent(PublicIdentifier::Static("openai".to_owned())),
+                openai_agent(PublicIdentifier::DynamicGlobal("groq-llama".to_owned())),
+            ];
+
+            let mut calls = vec![];
+
+            let (agent, result) = run_with_failover(
+                &agents,
+                AgentPurpose::TextGeneration,
+                async |agent: &AgentInstance| {
+                    let agent_id = agent.identifier().as_string();
+
+                    let result = if agent_id == "static/openai" {
+                        Err((test_case.primary_error)())
+                    } else {
+                        Ok(())
+                    };
+
+                    calls.push(agent_id);
+
+                    result
+                },
+            )
+            .await;
+
+            assert_eq!(
+                calls, test_case.expected_calls,
+                "Test case: {}",
+                test_case.name
+            );
+            assert_eq!(
+                result.is_ok(),
+                test_case.expected_success,
+                "Test case: {}",
+                test_case.name
+            );
+            assert_eq!(
+                agent.identifier().as_string(),
+                *test_case.expected_calls.last().unwrap(),
+                "Test case: {}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use super::{
-    AgentDefinition, AgentProvider, PublicIdentifier,
+    AgentDefinition, AgentProvider, ConcurrencyLimiter, PublicIdentifier,
     provider::{self, ControllerType},
+    retry::RetryConfig,
+    secrets,
 };
 
 // Dead-code is allowed. We do not use these enum struct payloads directly,
@@ -16,6 +18,12 @@ pub enum Error {
     ConstructionFailed(anyhow::Error),
     // Contains the error from the YAML deserialization function
     Yaml(serde_yaml_ng::Error),
+    // Contains the agent ID
+    SecretReferencesNotAllowed(String),
+    // Contains the error describing which secret reference failed to resolve and why
+    SecretReferenceUnresolvable(anyhow::Error),
+    // Contains the agent ID
+    LocalFilesNotAllowed(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -25,6 +33,8 @@ pub struct AgentInstance {
     identifier: PublicIdentifier,
     definition: AgentDefinition,
     controller: ControllerType,
+    retry_config: RetryConfig,
+    concurrency_limiter: ConcurrencyLimiter,
 }
 
 impl AgentInstance {
@@ -32,11 +42,15 @@ impl AgentInstance {
         identifier: PublicIdentifier,
         definition: AgentDefinition,
         controller: ControllerType,
+        retry_config: RetryConfig,
+        concurrency_limiter: ConcurrencyLimiter,
     ) -> Self {
         Self {
             identifier,
             definition,
             controller,
+            retry_config,
+            concurrency_limiter,
         }
     }
 
@@ -51,29 +65,84 @@ impl AgentInstance {
     pub fn controller(&self) -> &ControllerType {
         &self.controller
     }
+
+    pub fn retry_config(&self) -> &RetryConfig {
+        &self.retry_config
+    }
+
+    pub fn concurrency_limiter(&self) -> &ConcurrencyLimiter {
+        &self.concurrency_limiter
+    }
 }
 
 pub(super) fn create(
     identifier: PublicIdentifier,
     definition: AgentDefinition,
+    concurrency_limiter: ConcurrencyLimiter,
 ) -> Result<AgentInstance> {
+    // Secret references are only resolved for the controller's sake.
+    // The definition keeps them as they are, so raw secrets never make it to Account Data or to rooms.
+    let config = resolve_secret_references(&identifier, &definition)?;
+
+    // Like secret references, files on the bot's server (e.g. tokenizers) are off limits to room-local agents.
+    if let PublicIdentifier::DynamicRoomLocal(_) = identifier
+        && references_local_files(&definition.config)
+    {
+        return Err(Error::LocalFilesNotAllowed(definition.id.clone()));
+    }
+
     let controller = create_controller_from_provider_and_json_value_config(
         &definition.id,
         &definition.provider,
-        definition.config.clone(),
+        config,
     )?;
 
-    Ok(AgentInstance::new(identifier, definition, controller))
+    // The retry policy is common to all providers, so it's handled here instead of in each provider's configuration.
+    let retry_config = RetryConfig::from_agent_config(&definition.config).map_err(Error::Yaml)?;
+
+    Ok(AgentInstance::new(
+        identifier,
+        definition,
+        controller,
+        retry_config,
+        concurrency_limiter,
+    ))
+}
+
+fn resolve_secret_references(
+    identifier: &PublicIdentifier,
+    definition: &AgentDefinition,
+) -> Result<serde_yaml_ng::Value> {
+    if !secrets::contains_references(&definition.config) {
+        return Ok(definition.config.clone());
+    }
+
+    // Room-local agents may be defined by non-administrators.
+    // Letting them read environment variables or files on the bot's server (and send them to a base URL of their choosing) would leak its secrets.
+    if let PublicIdentifier::DynamicRoomLocal(_) = identifier {
+        return Err(Error::SecretReferencesNotAllowed(definition.id.clone()));
+    }
+
+    secrets::resolve(&definition.config).map_err(Error::SecretReferenceUnresolvable)
+}
+
+/// Tells if the given agent configuration points to files on the bot's server (other than via secret references).
+fn references_local_files(config: &serde_yaml_ng::Value) -> bool {
+    config
+        .get("text_generation")
+        .and_then(|text_generation| text_generation.get("tokenizer_path"))
+        .is_some_and(|tokenizer_path| !tokenizer_path.is_null())
 }
 
 pub fn create_from_provider_and_yaml_value_config(
     provider: &AgentProvider,
     identifier: &PublicIdentifier,
     config: serde_yaml_ng::Value,
+    concurrency_limiter: ConcurrencyLimiter,
 ) -> Result<AgentInstance> {
     let definition = AgentDefinition::new(identifier.prefixless(), provider.to_owned(), config);
 
-    create(identifier.to_owned(), definition)
+    create(identifier.to_owned(), definition, concurrency_limiter)
 }
 
 fn create_controller_from_provider_and_json_value_config(
@@ -85,6 +154,9 @@ fn create_controller_from_provider_and_json_value_config(
         AgentProvider::Anthropic => {
             provider::anthropic::create_controller_from_yaml_value_config(agent_id, config)
         }
+        AgentProvider::Gemini => {
+            provider::gemini::create_controller_from_yaml_value_config(agent_id, config)
+        }
         AgentProvider::Groq => {
             provider::openai_compat::create_controller_from_yaml_value_config(agent_id, config)
         }
@@ -95,7 +167,7 @@ fn create_controller_from_provider_and_json_value_config(
             provider::openai_compat::create_controller_from_yaml_value_config(agent_id, config)
         }
         AgentProvider::Ollama => {
-            provider::openai_compat::create_controller_from_yaml_value_config(agent_id, config)
+            provider::ollama::create_controller_from_yaml_value_config(agent_id, config)
         }
         AgentProvider::OpenAI => {
             provider::openai::create_controller_from_yaml_value_config(agent_id, config)
@@ -121,6 +193,10 @@ pub fn default_config_for_provider(provider: &AgentProvider) -> serde_yaml_ng::V
             let config = super::provider::anthropic::default_config();
             serde_yaml_ng::to_value(config).expect("Failed to serialize config")
         }
+        AgentProvider::Gemini => {
+            let config = super::provider::gemini::default_config();
+            serde_yaml_ng::to_value(config).expect("Failed to serialize config")
+        }
         AgentProvider::Groq => {
             let config = super::provider::groq::default_config();
             serde_yaml_ng::to_value(config).expect("Failed to serialize config")
@@ -1,22 +1,98 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use mxlink::matrix_sdk::ruma::{OwnedRoomId, RoomId};
+use quick_cache::sync::Cache;
+
 use super::AgentDefinition;
+use super::ConcurrencyLimiter;
 use super::PublicIdentifier;
 use super::instantiation;
 use super::instantiation::AgentInstance;
 use crate::entity::RoomConfigContext;
 
-#[derive(Debug)]
+/// Identifies a dynamic agent instance: its public identifier (along with its room, for room-local agents)
+/// and a hash of the definition it was created from.
+///
+/// Changing an agent's definition (provider or configuration) changes its key,
+/// so the next lookup instantiates the agent afresh instead of reusing an instance built from the old definition.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct InstanceCacheKey {
+    identifier: String,
+
+    /// Room-local agents with the same identifier (and even definition) in different rooms are different agents,
+    /// which must not share the state their instances keep (learned unsupported fields, concurrency limits, etc.).
+    room_id: Option<OwnedRoomId>,
+
+    definition_hash: u64,
+}
+
+impl InstanceCacheKey {
+    fn new(identifier: &PublicIdentifier, room_id: &RoomId, definition: &AgentDefinition) -> Self {
+        let room_id = match identifier {
+            PublicIdentifier::DynamicRoomLocal(_) => Some(room_id.to_owned()),
+            PublicIdentifier::Static(_) | PublicIdentifier::DynamicGlobal(_) => None,
+        };
+
+        Self {
+            identifier: identifier.as_string(),
+            room_id,
+            definition_hash: hash_definition(definition),
+        }
+    }
+}
+
+fn hash_definition(definition: &AgentDefinition) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    definition.id.hash(&mut hasher);
+    definition.provider.to_static_str().hash(&mut hasher);
+
+    // Hashing the serialized form avoids depending on how (or whether) the YAML value type implements `Hash`.
+    // Serializing a value which was deserialized just fine does not fail in practice.
+    serde_yaml_ng::to_string(&definition.config)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+
+    hasher.finish()
+}
+
 pub struct Manager {
     static_agents: Vec<AgentInstance>,
+
+    /// Dynamic (global and room-local) agents are defined in configuration which is loaded for each message.
+    /// Instantiating them anew each time would rebuild their HTTP clients (losing connection pools)
+    /// and any state their controllers keep, so instances are cached and reused as long as their definition stays the same.
+    dynamic_agents_cache: Cache<InstanceCacheKey, AgentInstance>,
+
+    /// Shared by all agent instances, so that requests to each agent are bounded across rooms.
+    concurrency_limiter: ConcurrencyLimiter,
+}
+
+impl std::fmt::Debug for Manager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Manager")
+            .field("static_agents", &self.static_agents)
+            .field("dynamic_agents_cached", &self.dynamic_agents_cache.len())
+            .finish()
+    }
 }
 
 impl Manager {
-    pub fn new(static_agent_definitions: Vec<AgentDefinition>) -> anyhow::Result<Self> {
+    pub fn new(
+        static_agent_definitions: Vec<AgentDefinition>,
+        dynamic_agents_cache_size: usize,
+        concurrency_limiter: ConcurrencyLimiter,
+    ) -> anyhow::Result<Self> {
         let mut static_agents = Vec::with_capacity(static_agent_definitions.len());
 
         for definition in static_agent_definitions {
             let identifier = PublicIdentifier::Static(definition.id.clone());
 
-            match instantiation::create(identifier.clone(), definition.to_owned()) {
+            match instantiation::create(
+                identifier.clone(),
+                definition.to_owned(),
+                concurrency_limiter.clone(),
+            ) {
                 Ok(instance) => static_agents.push(instance),
                 Err(e) => {
                     return Err(anyhow::anyhow!(
@@ -28,7 +104,16 @@ impl Manager {
             }
         }
 
-        Ok(Self { static_agents })
+        Ok(Self {
+            static_agents,
+            dynamic_agents_cache: Cache::new(dynamic_agents_cache_size),
+            concurrency_limiter,
+        })
+    }
+
+    /// The limiter shared by all agents, which agents instantiated outside of the manager should use as well.
+    pub fn concurrency_limiter(&self) -> &ConcurrencyLimiter {
+        &self.concurrency_limiter
     }
 
     pub fn available_room_agents_by_room_config_context(
@@ -44,25 +129,188 @@ impl Manager {
         for definition in &room_config_context.global_config.agents {
             let identifier = PublicIdentifier::DynamicGlobal(definition.id.clone());
 
-            match instantiation::create(identifier.clone(), definition.to_owned()) {
-                Ok(instance) => agents.push(instance),
-                Err(e) => {
-                    tracing::warn!("Failed to create {} agent: {:?}. Skipping.", identifier, e);
-                }
+            if let Some(instance) = self.get_or_create_dynamic_agent(
+                identifier,
+                room_config_context.room_id(),
+                definition,
+            ) {
+                agents.push(instance);
             }
         }
 
         for definition in &room_config_context.room_config.agents {
             let identifier = PublicIdentifier::DynamicRoomLocal(definition.id.clone());
 
-            match instantiation::create(identifier.clone(), definition.to_owned()) {
-                Ok(instance) => agents.push(instance),
-                Err(e) => {
-                    tracing::warn!("Failed to create {} agent: {:?}. Skipping.", identifier, e);
-                }
+            if let Some(instance) = self.get_or_create_dynamic_agent(
+                identifier,
+                room_config_context.room_id(),
+                definition,
+            ) {
+                agents.push(instance);
             }
         }
 
         agents
     }
+
+    /// Drops the cached instance of the given (dynamic) agent, so that no state of it lingers after the agent is deleted.
+    ///
+    /// Cached instances of agents whose definition changed are never used again and get evicted on their own eventually,
+    /// so calling this is not necessary for correctness.
+    pub fn forget_dynamic_agent(
+        &self,
+        room_config_context: &RoomConfigContext,
+        agent: &AgentInstance,
+    ) {
+        self.dynamic_agents_cache.remove(&InstanceCacheKey::new(
+            agent.identifier(),
+            room_config_context.room_id(),
+            agent.definition(),
+        ));
+    }
+
+    fn get_or_create_dynamic_agent(
+        &self,
+        identifier: PublicIdentifier,
+        room_id: &RoomId,
+        definition: &AgentDefinition,
+    ) -> Option<AgentInstance> {
+        let key = InstanceCacheKey::new(&identifier, room_id, definition);
+
+        if let Some(instance) = self.dynamic_agents_cache.get(&key) {
+            return Some(instance);
+        }
+
+        // Failures are not cached. An invalid definition is cheap to re-validate and will likely be fixed soon anyway.
+        match instantiation::create(
+            identifier.clone(),
+            definition.to_owned(),
+            self.concurrency_limiter.clone(),
+        ) {
+            Ok(instance) => {
+                tracing::debug!(agent_id = identifier.as_string(), "Instantiated agent");
+
+                self.dynamic_agents_cache.insert(key, instance.clone());
+
+                Some(instance)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to create {} agent: {:?}. Skipping.", identifier, e);
+
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mxlink::matrix_sdk::ruma::room_id;
+
+    use super::*;
+    use crate::agent::AgentProvider;
+    use crate::entity::globalconfig::GlobalConfig;
+    use crate::entity::roomconfig::RoomConfig;
+
+    fn definition(config: &str) -> AgentDefinition {
+        AgentDefinition::new(
+            "test".to_owned(),
+            AgentProvider::OpenAICompat,
+            serde_yaml_ng::from_str(config).unwrap(),
+        )
+    }
+
+    #[test]
+    fn definition_changes_change_the_cache_key() {
+        let room_id = room_id!("!room:example.com");
+
+        let identifier = PublicIdentifier::DynamicGlobal("test".to_owned());
+
+        let original = definition("base_url: https://one.example.com\napi_key: secret");
+
+        assert_eq!(
+            InstanceCacheKey::new(&identifier, room_id, &original),
+            InstanceCacheKey::new(&identifier, room_id, &original.clone()),
+        );
+
+        assert_ne!(
+            InstanceCacheKey::new(&identifier, room_id, &original),
+            InstanceCacheKey::new(
+                &identifier,
+                room_id,
+                &definition("base_url: https://two.example.com\napi_key: secret")
+            ),
+        );
+
+        // The same definition under another identifier (e.g. global vs room-local) is a different agent.
+        assert_ne!(
+            InstanceCacheKey::new(&identifier, room_id, &original),
+            InstanceCacheKey::new(
+                &PublicIdentifier::DynamicRoomLocal("test".to_owned()),
+                room_id,
+                &original
+            ),
+        );
+    }
+
+    #[test]
+    fn room_local_agents_of_different_rooms_do_not_share_instances() {
+        let identifier = PublicIdentifier::DynamicRoomLocal("test".to_owned());
+        let original = definition("base_url: https://one.example.com");
+
+        assert_ne!(
+            InstanceCacheKey::new(&identifier, room_id!("!one:example.com"), &original),
+            InstanceCacheKey::new(&identifier, room_id!("!two:example.com"), &original),
+        );
+
+        // Global agents are the same agent in all rooms.
+        let identifier = PublicIdentifier::DynamicGlobal("test".to_owned());
+
+        assert_eq!(
+            InstanceCacheKey::new(&identifier, room_id!("!one:example.com"), &original),
+            InstanceCacheKey::new(&identifier, room_id!("!two:example.com"), &original),
+        );
+    }
+
+    #[test]
+    fn dynamic_agents_are_reused_until_their_definition_changes() {
+        let manager = Manager::new(vec![], 10, ConcurrencyLimiter::unlimited()).unwrap();
+
+        let room_config_context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            GlobalConfig::default(),
+            RoomConfig::default(),
+        );
+        let room_id = room_config_context.room_id();
+
+        let identifier = PublicIdentifier::DynamicGlobal("test".to_owned());
+        let original = definition("base_url: https://one.example.com");
+
+        manager
+            .get_or_create_dynamic_agent(identifier.clone(), room_id, &original)
+            .unwrap();
+        assert_eq!(manager.dynamic_agents_cache.len(), 1);
+
+        manager
+            .get_or_create_dynamic_agent(identifier.clone(), room_id, &original)
+            .unwrap();
+        assert_eq!(manager.dynamic_agents_cache.len(), 1);
+
+        let changed = definition("base_url: https://two.example.com");
+        let instance = manager
+            .get_or_create_dynamic_agent(identifier.clone(), room_id, &changed)
+            .unwrap();
+        assert_eq!(manager.dynamic_agents_cache.len(), 2);
+
+        manager.forget_dynamic_agent(&room_config_context, &instance);
+        assert_eq!(manager.dynamic_agents_cache.len(), 1);
+
+        // Invalid definitions are skipped and not cached.
+        assert!(
+            manager
+                .get_or_create_dynamic_agent(identifier, room_id, &definition("base_url: ''"))
+                .is_none()
+        );
+        assert_eq!(manager.dynamic_agents_cache.len(), 1);
+    }
 }
@@ -1,11 +1,17 @@
+mod concurrency;
 mod definition;
+pub mod failover;
 mod identifier;
 mod instantiation;
 mod manager;
 pub mod provider;
 mod purpose;
+pub mod retry;
+pub mod secrets;
+pub mod tool;
 pub mod utils;
 
+pub use concurrency::ConcurrencyLimiter;
 pub use identifier::PublicIdentifier;
 pub use manager::Manager;
 
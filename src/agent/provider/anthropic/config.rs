@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::agent::{default_prompt, provider::ConfigTrait};
+use crate::agent::{default_prompt, provider::ConfigTrait, tool::ToolsConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -32,6 +32,9 @@ impl ConfigTrait for Config {
         if self.api_key.is_empty() {
             return Err("The API key must not be empty.".to_owned());
         }
+        if let Some(text_generation) = &self.text_generation {
+            text_generation.tools.validate()?;
+        }
 
         Ok(())
     }
@@ -53,6 +56,9 @@ pub struct TextGenerationConfig {
 
     #[serde(default)]
     pub max_context_tokens: u32,
+
+    #[serde(default)]
+    pub tools: ToolsConfig,
 }
 
 impl Default for TextGenerationConfig {
@@ -63,6 +69,7 @@ impl Default for TextGenerationConfig {
             temperature: super::super::default_temperature(),
             max_response_tokens: 8192,
             max_context_tokens: 204_800,
+            tools: ToolsConfig::default(),
         }
     }
 }
@@ -1,40 +1,57 @@
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anthropic::client::{Client, ClientBuilder};
-use anthropic::types::ContentBlock;
+use anthropic::types::{ContentBlock, ContentBlockDelta, MessagesRequest, MessagesStreamEvent};
+use futures_util::StreamExt;
 
 use super::super::ControllerTrait;
+use super::super::model_listing;
 use crate::agent::AgentPurpose;
 use crate::agent::provider::entity::{
-    ImageEditResult, ImageGenerationResult, ImageSource, PingResult, TextGenerationParams,
-    TextGenerationResult, TextToSpeechParams, TextToSpeechResult,
+    ImageEditResult, ImageGenerationResult, ImageSource, ModelInfo, PingResult,
+    TextGenerationParams, TextGenerationResult, TextToSpeechParams, TextToSpeechResult, TokenUsage,
 };
 use crate::agent::provider::{
-    ImageEditParams, ImageGenerationParams, SpeechToTextParams, SpeechToTextResult,
+    ImageEditParams, ImageGenerationParams, LearnedUnsupportedFields, ProviderHttpError,
+    SpeechToTextParams, SpeechToTextResult, recognize_http_error, retry_after_from_headers,
 };
+use crate::agent::tool::{MAX_TOOL_CALL_ROUNDS, ToolCall, ToolRegistry, mcp::McpToolsConfig};
 use crate::conversation::llm::{
-    Author as LLMAuthor, Conversation as LLMConversation, Message as LLMMessage,
-    MessageContent as LLMMessageContent, TokenEstimate, shorten_messages_list_to_context_size,
+    Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost, Message as LLMMessage,
+    MessageContent as LLMMessageContent, TokenEstimate, estimate_messages_token_size,
+    shorten_messages_list_to_context_size,
 };
 use crate::strings;
 
 use super::config::Config;
 
+// The API version sent along with the requests we make without the library (see `generate_text_with_tools` and `list_models`).
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
 struct ControllerInner {
     client: Client,
+    // The library cannot do tool use (its content blocks are only text and images),
+    // so requests which involve tools are made directly.
+    http: reqwest::Client,
+    // Cleared once the token counting API turns out to be missing (e.g. with Anthropic-compatible servers),
+    // so that it's not asked again.
+    token_counting_available: AtomicBool,
 }
 
 #[derive(Clone)]
 pub struct Controller {
     config: Config,
     inner: Arc<ControllerInner>,
+    tools: ToolRegistry,
 }
 
 impl Debug for Controller {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Controller")
             .field("config", &self.config)
+            .field("tools", &self.tools)
             .finish()
     }
 }
@@ -56,39 +73,30 @@ impl Controller {
             .api_key(config.api_key.clone())
             .build()?;
 
+        let tools = match &config.text_generation {
+            Some(text_generation_config) => {
+                ToolRegistry::with_builtin_tools(&text_generation_config.tools.enabled)
+            }
+            None => ToolRegistry::default(),
+        };
+
         Ok(Self {
             config,
-            inner: Arc::new(ControllerInner { client }),
+            inner: Arc::new(ControllerInner {
+                client,
+                http: reqwest::Client::new(),
+                token_counting_available: AtomicBool::new(true),
+            }),
+            tools,
         })
     }
-}
-
-impl ControllerTrait for Controller {
-    async fn ping(&self) -> anyhow::Result<PingResult> {
-        if !self.supports_purpose(AgentPurpose::TextGeneration) {
-            return Ok(PingResult::Inconclusive);
-        }
-
-        let messages = vec![LLMMessage {
-            author: LLMAuthor::User,
-            sender_id: None,
-            content: LLMMessageContent::Text("Hello!".to_string()),
-            timestamp: chrono::Utc::now(),
-        }];
-
-        let conversation = LLMConversation { messages };
-
-        self.generate_text(conversation, TextGenerationParams::default())
-            .await?;
-
-        Ok(PingResult::Successful)
-    }
 
-    async fn generate_text(
+    async fn create_text_generation_request(
         &self,
         conversation: LLMConversation,
         params: TextGenerationParams,
-    ) -> anyhow::Result<TextGenerationResult> {
+        stream: bool,
+    ) -> anyhow::Result<MessagesRequest> {
         let Some(text_generation_config) = &self.config.text_generation else {
             return Err(anyhow::anyhow!(
                 strings::agent::no_configuration_for_purpose_so_cannot_be_used(
@@ -97,12 +105,17 @@ impl ControllerTrait for Controller {
             ));
         };
 
-        let prompt_text = params.prompt_variables.format(
-            params
-                .prompt_override
-                .unwrap_or(self.text_generation_prompt().unwrap_or("".to_owned()))
-                .trim(),
-        );
+        let model_id = params.model_id(&text_generation_config.model_id);
+
+        // Anthropic requires a response token limit, so the override can only replace the configured one.
+        let max_response_tokens = params
+            .max_response_tokens_override
+            .unwrap_or(text_generation_config.max_response_tokens);
+
+        // `reasoning_effort_override` is not applied. Extended thinking is configured via a token budget,
+        // which comes with its own constraints (e.g. no custom temperature).
+
+        let prompt_text = params.prompt_text(self.text_generation_prompt());
 
         let prompt_message = if prompt_text.is_empty() {
             None
@@ -129,11 +142,22 @@ impl ControllerTrait for Controller {
         if params.context_management_enabled {
             tracing::trace!("Shortening messages list to context size");
 
+            let estimate = self
+                .context_token_estimate(
+                    &model_id,
+                    &prompt_message,
+                    &conversation_messages,
+                    max_response_tokens,
+                    text_generation_config.max_context_tokens,
+                )
+                .await;
+
             conversation_messages = shorten_messages_list_to_context_size(
-                TokenEstimate::Approximate,
+                estimate,
+                self.text_generation_media_token_cost(),
                 &prompt_message,
                 conversation_messages,
-                Some(text_generation_config.max_response_tokens),
+                Some(max_response_tokens),
                 text_generation_config.max_context_tokens,
             );
 
@@ -154,9 +178,10 @@ impl ControllerTrait for Controller {
             request.system = text.clone();
         }
 
-        request.model = text_generation_config.model_id.clone();
+        request.model = model_id;
         request.temperature = Some(temperature as f64);
-        request.max_tokens = text_generation_config.max_response_tokens as usize;
+        request.max_tokens = max_response_tokens as usize;
+        request.stream = stream;
 
         if let Ok(request_as_json) = serde_json::to_string(&request) {
             tracing::trace!(
@@ -167,10 +192,312 @@ impl ControllerTrait for Controller {
             );
         }
 
-        let response = self.inner.client.messages(request).await?;
+        Ok(request)
+    }
+
+    /// Returns how to count tokens when trimming the conversation to the context window.
+    ///
+    /// The approximation is calibrated against Anthropic's token counting API, which counts text and images exactly.
+    /// Counting is an additional request, so it's only done for conversations large enough to possibly need trimming.
+    async fn context_token_estimate(
+        &self,
+        model_id: &str,
+        prompt_message: &Option<LLMMessage>,
+        messages: &[LLMMessage],
+        max_response_tokens: u32,
+        max_context_tokens: u32,
+    ) -> TokenEstimate<'static> {
+        let approximate_tokens = estimate_messages_token_size(
+            TokenEstimate::Approximate,
+            self.text_generation_media_token_cost(),
+            prompt_message,
+            messages,
+        );
+
+        // Even an approximation which is off by a factor of 2 would not overflow the context window.
+        let far_from_limit = approximate_tokens
+            .saturating_add(max_response_tokens)
+            .saturating_mul(2)
+            < max_context_tokens;
+
+        if far_from_limit
+            || messages.is_empty()
+            || !self.inner.token_counting_available.load(Ordering::Relaxed)
+        {
+            return TokenEstimate::Approximate;
+        }
+
+        match self.count_tokens(model_id, prompt_message, messages).await {
+            Ok(Some(exact_tokens)) => {
+                tracing::debug!(
+                    exact_tokens,
+                    approximate_tokens,
+                    "Calibrating the token estimate via the Anthropic count message tokens API"
+                );
+
+                TokenEstimate::Calibrated(exact_tokens as f32 / approximate_tokens.max(1) as f32)
+            }
+            Ok(None) => {
+                tracing::info!(
+                    "The Anthropic count message tokens API is not available, so tokens will be approximated"
+                );
+
+                self.inner
+                    .token_counting_available
+                    .store(false, Ordering::Relaxed);
+
+                TokenEstimate::Approximate
+            }
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    "Failed counting tokens via the Anthropic count message tokens API, so approximating them instead"
+                );
+
+                TokenEstimate::Approximate
+            }
+        }
+    }
+
+    /// Counts the tokens of the given conversation via the count message tokens API.
+    ///
+    /// Returns `Ok(None)` if the server does not provide this API.
+    async fn count_tokens(
+        &self,
+        model_id: &str,
+        prompt_message: &Option<LLMMessage>,
+        messages: &[LLMMessage],
+    ) -> anyhow::Result<Option<u32>> {
+        let request = super::utils::create_anthropic_message_request(messages.to_vec());
+        let request = serde_json::to_value(&request)?;
+
+        // The API only accepts the fields which affect the token count (e.g. no `max_tokens`).
+        let mut body = serde_json::json!({
+            "model": model_id,
+            "messages": request["messages"],
+        });
+
+        if let Some(prompt_message) = prompt_message
+            && let LLMMessageContent::Text(text) = &prompt_message.content
+        {
+            body["system"] = serde_json::Value::String(text.clone());
+        }
+
+        let url = format!("{}/messages/count_tokens", self.config.base_url);
+
+        let response = self
+            .inner
+            .http
+            .post(&url)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(anyhow::anyhow!(
+                "The Anthropic count message tokens API responded with status {status}: {body}"
+            ));
+        }
+
+        let response: super::utils::CountTokensResponse = response.json().await?;
+
+        Ok(Some(response.input_tokens))
+    }
+
+    async fn generate_text_with_tools(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+    ) -> anyhow::Result<TextGenerationResult> {
+        let tools = self.tools.merged_with(&params.tools);
+
+        let request = self
+            .create_text_generation_request(conversation, params, false)
+            .await?;
+
+        let mut body = serde_json::to_value(&request)?;
+        body["tools"] =
+            serde_json::to_value(super::utils::create_anthropic_tools(tools.definitions()))?;
+
+        let url = format!("{}/messages", self.config.base_url);
+
+        let mut usage = TokenUsage::default();
+
+        for _ in 0..MAX_TOOL_CALL_ROUNDS {
+            let response = self
+                .inner
+                .http
+                .post(&url)
+                .header("x-api-key", &self.config.api_key)
+                .header("anthropic-version", ANTHROPIC_API_VERSION)
+                .json(&body)
+                .send()
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let retry_after = retry_after_from_headers(response.headers());
+
+                let body = response.text().await.unwrap_or_default();
+                tracing::warn!(%status, body, "Anthropic create message request failed");
+
+                return Err(anyhow::Error::new(
+                    ProviderHttpError::new(
+                        status,
+                        format!("The Anthropic create message API responded with status {status}"),
+                    )
+                    .with_retry_after(retry_after),
+                ));
+            }
+
+            let response: super::utils::ToolUseResponse = response.json().await?;
+
+            tracing::trace!(?response, "Got response from Anthropic create message API");
+
+            if let Some(response_usage) = response.usage {
+                usage += TokenUsage::from(response_usage);
+            }
+
+            let mut text_parts = vec![];
+            let mut tool_calls = vec![];
+            for block in &response.content {
+                match serde_json::from_value(block.clone()) {
+                    Ok(super::utils::ToolUseResponseBlock::Text { text }) => {
+                        text_parts.push(text);
+                    }
+                    Ok(super::utils::ToolUseResponseBlock::ToolUse { id, name, input }) => {
+                        tool_calls.push(ToolCall {
+                            id,
+                            name,
+                            arguments: input.to_string(),
+                        });
+                    }
+                    Ok(super::utils::ToolUseResponseBlock::Other) | Err(_) => {}
+                }
+            }
+
+            if tool_calls.is_empty() {
+                if text_parts.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "No text content in response from the Anthropic create message API"
+                    ));
+                }
+
+                return Ok(TextGenerationResult {
+                    text: text_parts.join("\n\n"),
+                    usage,
+                });
+            }
+
+            let mut tool_results = Vec::with_capacity(tool_calls.len());
+            for tool_call in &tool_calls {
+                let output = tools.call(tool_call).await;
+                tool_results.push(super::utils::create_tool_result_block(
+                    &tool_call.id,
+                    output,
+                ));
+            }
+
+            let Some(messages) = body["messages"].as_array_mut() else {
+                return Err(anyhow::anyhow!(
+                    "The create message request unexpectedly has no messages list"
+                ));
+            };
+
+            // The assistant turn is sent back as-is, followed by a user turn carrying the results.
+            messages.push(serde_json::json!({
+                "role": "assistant",
+                "content": response.content,
+            }));
+            messages.push(serde_json::json!({
+                "role": "user",
+                "content": tool_results,
+            }));
+        }
+
+        Err(anyhow::anyhow!(strings::agent::tool_call_rounds_exhausted(
+            MAX_TOOL_CALL_ROUNDS
+        )))
+    }
+}
+
+impl ControllerTrait for Controller {
+    async fn ping(&self) -> anyhow::Result<PingResult> {
+        if !self.supports_purpose(AgentPurpose::TextGeneration) {
+            return Ok(PingResult::Inconclusive);
+        }
+
+        let messages = vec![LLMMessage {
+            author: LLMAuthor::User,
+            sender_id: None,
+            content: LLMMessageContent::Text("Hello!".to_string()),
+            timestamp: chrono::Utc::now(),
+        }];
+
+        let conversation = LLMConversation { messages };
+
+        self.generate_text(conversation, TextGenerationParams::default())
+            .await?;
+
+        Ok(PingResult::Successful)
+    }
+
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
+        // 1000 is the largest page size the API allows, which is more than enough to get all models at once.
+        let url = format!("{}/models?limit=1000", self.config.base_url);
+
+        let request = self
+            .inner
+            .http
+            .get(url)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION);
+
+        let response: super::utils::ModelsResponse =
+            model_listing::get_json(request, "Anthropic").await?;
+
+        Ok(model_listing::sorted(response.into_models()))
+    }
+
+    async fn generate_text(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+    ) -> anyhow::Result<TextGenerationResult> {
+        if !self.tools.is_empty() || !params.tools.is_empty() {
+            return self.generate_text_with_tools(conversation, params).await;
+        }
+
+        let request = self
+            .create_text_generation_request(conversation, params, false)
+            .await?;
+
+        // The library does not expose the response's status, which we need for deciding whether to retry.
+        let response = self
+            .inner
+            .client
+            .messages(request)
+            .await
+            .map_err(|err| recognize_http_error(err.into()))?;
 
         tracing::trace!(?response, "Got response from Anthropic create message API");
 
+        let usage = TokenUsage {
+            prompt_tokens: u64::from(response.usage.input_tokens),
+            completion_tokens: u64::from(response.usage.output_tokens),
+            ..Default::default()
+        };
+
         // response.content usually contains a single element, but we support handling multiple to account for all possibilities
         let mut text_parts = vec![];
         for content in response.content {
@@ -192,9 +519,78 @@ impl ControllerTrait for Controller {
 
         Ok(TextGenerationResult {
             text: text_parts.join("\n\n"),
+            usage,
         })
     }
 
+    async fn generate_text_streaming(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> anyhow::Result<TextGenerationResult> {
+        // Tool use needs a request/response loop, so replies which may involve it are not streamed.
+        if !self.tools.is_empty() || !params.tools.is_empty() {
+            return self.generate_text(conversation, params).await;
+        }
+
+        let request = self
+            .create_text_generation_request(conversation, params, true)
+            .await?;
+
+        let mut stream = self
+            .inner
+            .client
+            .messages_stream(request)
+            .await
+            .map_err(|err| recognize_http_error(err.into()))?;
+
+        // Mirrors the non-streaming variant, which joins multiple text blocks with a blank line.
+        let mut text = String::new();
+        let mut last_block_index: Option<usize> = None;
+        let mut usage = TokenUsage::default();
+
+        while let Some(event) = stream.next().await {
+            match event.map_err(|err| recognize_http_error(err.into()))? {
+                MessagesStreamEvent::ContentBlockDelta {
+                    index,
+                    delta: ContentBlockDelta::TextDelta { text: delta },
+                } => {
+                    if last_block_index.is_some_and(|last_index| last_index != index) {
+                        text.push_str("\n\n");
+                    }
+                    last_block_index = Some(index);
+
+                    text.push_str(&delta);
+                    partial_text.send_replace(text.clone());
+                }
+                // The input token count arrives at the start, while the final output token count arrives at the end.
+                MessagesStreamEvent::MessageStart { message } => {
+                    usage.prompt_tokens = u64::from(message.usage.input_tokens);
+                }
+                MessagesStreamEvent::MessageDelta {
+                    usage: delta_usage, ..
+                } => {
+                    usage.completion_tokens = u64::from(delta_usage.output_tokens);
+                }
+                _ => {}
+            }
+        }
+
+        tracing::trace!(
+            ?text,
+            "Got streamed response from Anthropic create message API"
+        );
+
+        if text.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No text content in response from the Anthropic create message API"
+            ));
+        }
+
+        Ok(TextGenerationResult { text, usage })
+    }
+
     async fn speech_to_text(
         &self,
         _mime_type: &mxlink::mime::Mime,
@@ -260,6 +656,35 @@ impl ControllerTrait for Controller {
             .map(|config| config.temperature)
     }
 
+    fn text_generation_max_response_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.max_response_tokens)
+    }
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.max_context_tokens)
+    }
+
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost {
+        MediaTokenCost::Anthropic
+    }
+
+    fn text_generation_tools(&self) -> ToolRegistry {
+        self.tools.clone()
+    }
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.tools.mcp.clone())
+    }
+
     fn text_to_speech_voice(&self) -> Option<String> {
         None
     }
@@ -267,4 +692,9 @@ impl ControllerTrait for Controller {
     fn text_to_speech_speed(&self) -> Option<f32> {
         None
     }
+
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields {
+        // Requests are not auto-recovered for this provider, so nothing is ever learned.
+        LearnedUnsupportedFields::new()
+    }
 }
@@ -2,6 +2,9 @@ use anthropic::types::{
     ContentBlock, ImageSource, Message, MessagesRequest, MessagesRequestBuilder, Role,
 };
 
+use crate::agent::AgentPurpose;
+use crate::agent::provider::{ModelInfo, TokenUsage};
+use crate::agent::tool::ToolDefinition;
 use crate::conversation::llm::{
     Author as LLMAuthor, Message as LLMMessage, MessageContent as LLMMessageContent,
 };
@@ -48,3 +51,193 @@ pub(super) fn create_anthropic_message_request(llm_messages: Vec<LLMMessage>) ->
         .build()
         .expect("Failed to build messages request")
 }
+
+pub(super) fn create_anthropic_tools(definitions: Vec<ToolDefinition>) -> Vec<serde_json::Value> {
+    definitions
+        .into_iter()
+        .map(|definition| {
+            serde_json::json!({
+                "name": definition.name,
+                "description": definition.description,
+                "input_schema": definition.parameters,
+            })
+        })
+        .collect()
+}
+
+pub(super) fn create_tool_result_block(tool_use_id: &str, output: String) -> serde_json::Value {
+    serde_json::json!({
+        "type": "tool_result",
+        "tool_use_id": tool_use_id,
+        "content": output,
+    })
+}
+
+// A create message API response, as read when tools are involved.
+// Content blocks are kept raw, so that the assistant turn can be sent back exactly as received.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ToolUseResponse {
+    #[serde(default)]
+    pub content: Vec<serde_json::Value>,
+
+    #[serde(default)]
+    pub usage: Option<ToolUseResponseUsage>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub(super) struct ToolUseResponseUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+
+    #[serde(default)]
+    pub output_tokens: u64,
+
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+}
+
+impl From<ToolUseResponseUsage> for TokenUsage {
+    fn from(usage: ToolUseResponseUsage) -> Self {
+        // Anthropic's `input_tokens` only counts the part of the prompt after the last cache breakpoint.
+        Self {
+            prompt_tokens: usage.input_tokens
+                + usage.cache_creation_input_tokens
+                + usage.cache_read_input_tokens,
+            completion_tokens: usage.output_tokens,
+            reasoning_tokens: 0,
+            cached_tokens: usage.cache_read_input_tokens,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum ToolUseResponseBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+// A count message tokens API response.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct CountTokensResponse {
+    pub input_tokens: u32,
+}
+
+// A list models API response.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ModelsResponse {
+    #[serde(default)]
+    pub data: Vec<ModelsResponseEntry>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ModelsResponseEntry {
+    pub id: String,
+
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+impl ModelsResponse {
+    // All Claude models are text-generation models.
+    pub(super) fn into_models(self) -> Vec<ModelInfo> {
+        self.data
+            .into_iter()
+            .map(|entry| {
+                ModelInfo::new(entry.id, vec![AgentPurpose::TextGeneration])
+                    .with_details(entry.display_name)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_use_response_blocks_are_recognized() {
+        let response: ToolUseResponse = serde_json::from_str(
+            r#"{
+                "content": [
+                    {"type": "thinking", "thinking": "...", "signature": "..."},
+                    {"type": "text", "text": "Let me check."},
+                    {"type": "tool_use", "id": "toolu_1", "name": "current_time", "input": {}}
+                ],
+                "stop_reason": "tool_use"
+            }"#,
+        )
+        .unwrap();
+
+        let blocks: Vec<ToolUseResponseBlock> = response
+            .content
+            .into_iter()
+            .map(|block| serde_json::from_value(block).unwrap())
+            .collect();
+
+        assert!(matches!(blocks[0], ToolUseResponseBlock::Other));
+        assert!(
+            matches!(&blocks[1], ToolUseResponseBlock::Text { text } if text == "Let me check.")
+        );
+        assert!(
+            matches!(&blocks[2], ToolUseResponseBlock::ToolUse { id, name, .. } if id == "toolu_1" && name == "current_time")
+        );
+    }
+
+    #[test]
+    fn tool_use_response_usage_includes_cached_input() {
+        let response: ToolUseResponse = serde_json::from_str(
+            r#"{
+                "content": [],
+                "usage": {
+                    "input_tokens": 10,
+                    "output_tokens": 20,
+                    "cache_creation_input_tokens": 100,
+                    "cache_read_input_tokens": 1000
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let usage = TokenUsage::from(response.usage.unwrap());
+
+        assert_eq!(usage.prompt_tokens, 1110);
+        assert_eq!(usage.completion_tokens, 20);
+        assert_eq!(usage.cached_tokens, 1000);
+    }
+
+    #[test]
+    fn models_response_is_converted() {
+        let response: ModelsResponse = serde_json::from_str(
+            r#"{
+                "data": [
+                    {"type": "model", "id": "claude-sonnet-4-5", "display_name": "Claude Sonnet 4.5", "created_at": "2025-09-29T00:00:00Z"}
+                ],
+                "has_more": false
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            response.into_models(),
+            vec![
+                ModelInfo::new(
+                    "claude-sonnet-4-5".to_owned(),
+                    vec![AgentPurpose::TextGeneration]
+                )
+                .with_details(Some("Claude Sonnet 4.5".to_owned()))
+            ]
+        );
+    }
+}
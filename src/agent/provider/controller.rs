@@ -1,10 +1,17 @@
-use crate::{agent::AgentPurpose, conversation::llm::Conversation};
+use crate::{
+    agent::{
+        AgentPurpose,
+        tool::{ToolRegistry, mcp::McpToolsConfig},
+    },
+    conversation::llm::{Conversation, MediaTokenCost},
+};
 
 use super::{
-    ImageEditParams, ImageGenerationParams, SpeechToTextParams, SpeechToTextResult,
+    ImageEditParams, ImageGenerationParams, LearnedUnsupportedFields, SpeechToTextParams,
+    SpeechToTextResult,
     entity::{
-        ImageEditResult, ImageGenerationResult, ImageSource, PingResult, TextGenerationParams,
-        TextGenerationResult, TextToSpeechParams, TextToSpeechResult,
+        ImageEditResult, ImageGenerationResult, ImageSource, ModelInfo, PingResult,
+        TextGenerationParams, TextGenerationResult, TextToSpeechParams, TextToSpeechResult,
     },
 };
 
@@ -13,22 +20,52 @@ pub trait ControllerTrait {
 
     fn ping(&self) -> impl std::future::Future<Output = anyhow::Result<PingResult>> + Send;
 
+    /// Queries the provider's model-listing endpoint for the models available with the agent's configuration.
+    fn list_models(
+        &self,
+    ) -> impl std::future::Future<Output = anyhow::Result<Vec<ModelInfo>>> + Send;
+
     fn text_generation_model_id(&self) -> Option<String>;
 
     fn text_generation_prompt(&self) -> Option<String>;
 
     fn text_generation_temperature(&self) -> Option<f32>;
 
+    fn text_generation_max_response_tokens(&self) -> Option<u32>;
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32>;
+
+    /// How the images and files sent to the agent's models count against their context window.
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost;
+
+    /// The client-side tools the agent itself is configured with (not including MCP tools).
+    fn text_generation_tools(&self) -> ToolRegistry;
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig>;
+
     fn text_to_speech_voice(&self) -> Option<String>;
 
     fn text_to_speech_speed(&self) -> Option<f32>;
 
+    /// Optional request fields which the agent's models were found (at runtime) not to support,
+    /// and which are thus no longer sent to them (see `agent::provider::recovery`).
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields;
+
     fn generate_text(
         &self,
         conversation: Conversation,
         params: TextGenerationParams,
     ) -> impl std::future::Future<Output = anyhow::Result<TextGenerationResult>> + Send;
 
+    // Like `generate_text`, but publishes the text accumulated so far to `partial_text` as it arrives.
+    // Providers that cannot stream return the whole text at once without publishing anything.
+    fn generate_text_streaming(
+        &self,
+        conversation: Conversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> impl std::future::Future<Output = anyhow::Result<TextGenerationResult>> + Send;
+
     fn speech_to_text(
         &self,
         mime_type: &mxlink::mime::Mime,
@@ -62,6 +99,7 @@ pub enum ControllerType {
     OpenAICompat(Box<super::openai_compat::Controller>),
     Anthropic(Box<super::anthropic::Controller>),
     Venice(Box<super::venice::Controller>),
+    Gemini(Box<super::gemini::Controller>),
 }
 
 impl ControllerTrait for ControllerType {
@@ -71,6 +109,7 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.supports_purpose(purpose),
             ControllerType::Anthropic(controller) => controller.supports_purpose(purpose),
             ControllerType::Venice(controller) => controller.supports_purpose(purpose),
+            ControllerType::Gemini(controller) => controller.supports_purpose(purpose),
         }
     }
 
@@ -80,6 +119,7 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.text_generation_model_id(),
             ControllerType::Anthropic(controller) => controller.text_generation_model_id(),
             ControllerType::Venice(controller) => controller.text_generation_model_id(),
+            ControllerType::Gemini(controller) => controller.text_generation_model_id(),
         }
     }
 
@@ -89,6 +129,7 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.text_generation_prompt(),
             ControllerType::Anthropic(controller) => controller.text_generation_prompt(),
             ControllerType::Venice(controller) => controller.text_generation_prompt(),
+            ControllerType::Gemini(controller) => controller.text_generation_prompt(),
         }
     }
 
@@ -98,6 +139,7 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.text_to_speech_voice(),
             ControllerType::Anthropic(controller) => controller.text_to_speech_voice(),
             ControllerType::Venice(controller) => controller.text_to_speech_voice(),
+            ControllerType::Gemini(controller) => controller.text_to_speech_voice(),
         }
     }
 
@@ -107,6 +149,7 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.text_to_speech_speed(),
             ControllerType::Anthropic(controller) => controller.text_to_speech_speed(),
             ControllerType::Venice(controller) => controller.text_to_speech_speed(),
+            ControllerType::Gemini(controller) => controller.text_to_speech_speed(),
         }
     }
 
@@ -116,6 +159,77 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.text_generation_temperature(),
             ControllerType::Anthropic(controller) => controller.text_generation_temperature(),
             ControllerType::Venice(controller) => controller.text_generation_temperature(),
+            ControllerType::Gemini(controller) => controller.text_generation_temperature(),
+        }
+    }
+
+    fn text_generation_max_response_tokens(&self) -> Option<u32> {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.text_generation_max_response_tokens(),
+            ControllerType::OpenAICompat(controller) => {
+                controller.text_generation_max_response_tokens()
+            }
+            ControllerType::Anthropic(controller) => {
+                controller.text_generation_max_response_tokens()
+            }
+            ControllerType::Venice(controller) => controller.text_generation_max_response_tokens(),
+            ControllerType::Gemini(controller) => controller.text_generation_max_response_tokens(),
+        }
+    }
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32> {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.text_generation_max_context_tokens(),
+            ControllerType::OpenAICompat(controller) => {
+                controller.text_generation_max_context_tokens()
+            }
+            ControllerType::Anthropic(controller) => {
+                controller.text_generation_max_context_tokens()
+            }
+            ControllerType::Venice(controller) => controller.text_generation_max_context_tokens(),
+            ControllerType::Gemini(controller) => controller.text_generation_max_context_tokens(),
+        }
+    }
+
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.text_generation_media_token_cost(),
+            ControllerType::OpenAICompat(controller) => {
+                controller.text_generation_media_token_cost()
+            }
+            ControllerType::Anthropic(controller) => controller.text_generation_media_token_cost(),
+            ControllerType::Venice(controller) => controller.text_generation_media_token_cost(),
+            ControllerType::Gemini(controller) => controller.text_generation_media_token_cost(),
+        }
+    }
+
+    fn text_generation_tools(&self) -> ToolRegistry {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.text_generation_tools(),
+            ControllerType::OpenAICompat(controller) => controller.text_generation_tools(),
+            ControllerType::Anthropic(controller) => controller.text_generation_tools(),
+            ControllerType::Venice(controller) => controller.text_generation_tools(),
+            ControllerType::Gemini(controller) => controller.text_generation_tools(),
+        }
+    }
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig> {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.text_generation_mcp_config(),
+            ControllerType::OpenAICompat(controller) => controller.text_generation_mcp_config(),
+            ControllerType::Anthropic(controller) => controller.text_generation_mcp_config(),
+            ControllerType::Venice(controller) => controller.text_generation_mcp_config(),
+            ControllerType::Gemini(controller) => controller.text_generation_mcp_config(),
+        }
+    }
+
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.learned_unsupported_fields(),
+            ControllerType::OpenAICompat(controller) => controller.learned_unsupported_fields(),
+            ControllerType::Anthropic(controller) => controller.learned_unsupported_fields(),
+            ControllerType::Venice(controller) => controller.learned_unsupported_fields(),
+            ControllerType::Gemini(controller) => controller.learned_unsupported_fields(),
         }
     }
 
@@ -125,6 +239,17 @@ impl ControllerTrait for ControllerType {
             ControllerType::OpenAICompat(controller) => controller.ping().await,
             ControllerType::Anthropic(controller) => controller.ping().await,
             ControllerType::Venice(controller) => controller.ping().await,
+            ControllerType::Gemini(controller) => controller.ping().await,
+        }
+    }
+
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
+        match &self {
+            ControllerType::OpenAI(controller) => controller.list_models().await,
+            ControllerType::OpenAICompat(controller) => controller.list_models().await,
+            ControllerType::Anthropic(controller) => controller.list_models().await,
+            ControllerType::Venice(controller) => controller.list_models().await,
+            ControllerType::Gemini(controller) => controller.list_models().await,
         }
     }
 
@@ -146,6 +271,44 @@ impl ControllerTrait for ControllerType {
             ControllerType::Venice(controller) => {
                 controller.generate_text(conversation, params).await
             }
+            ControllerType::Gemini(controller) => {
+                controller.generate_text(conversation, params).await
+            }
+        }
+    }
+
+    async fn generate_text_streaming(
+        &self,
+        conversation: Conversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> anyhow::Result<TextGenerationResult> {
+        match &self {
+            ControllerType::OpenAI(controller) => {
+                controller
+                    .generate_text_streaming(conversation, params, partial_text)
+                    .await
+            }
+            ControllerType::OpenAICompat(controller) => {
+                controller
+                    .generate_text_streaming(conversation, params, partial_text)
+                    .await
+            }
+            ControllerType::Anthropic(controller) => {
+                controller
+                    .generate_text_streaming(conversation, params, partial_text)
+                    .await
+            }
+            ControllerType::Venice(controller) => {
+                controller
+                    .generate_text_streaming(conversation, params, partial_text)
+                    .await
+            }
+            ControllerType::Gemini(controller) => {
+                controller
+                    .generate_text_streaming(conversation, params, partial_text)
+                    .await
+            }
         }
     }
 
@@ -168,6 +331,9 @@ impl ControllerTrait for ControllerType {
             ControllerType::Venice(controller) => {
                 controller.speech_to_text(mime_type, media, params).await
             }
+            ControllerType::Gemini(controller) => {
+                controller.speech_to_text(mime_type, media, params).await
+            }
         }
     }
 
@@ -185,6 +351,7 @@ impl ControllerTrait for ControllerType {
                 controller.generate_image(prompt, params).await
             }
             ControllerType::Venice(controller) => controller.generate_image(prompt, params).await,
+            ControllerType::Gemini(controller) => controller.generate_image(prompt, params).await,
         }
     }
 
@@ -207,6 +374,9 @@ impl ControllerTrait for ControllerType {
             ControllerType::Venice(controller) => {
                 controller.create_image_edit(prompt, images, params).await
             }
+            ControllerType::Gemini(controller) => {
+                controller.create_image_edit(prompt, images, params).await
+            }
         }
     }
 
@@ -222,6 +392,7 @@ impl ControllerTrait for ControllerType {
             }
             ControllerType::Anthropic(controller) => controller.text_to_speech(text, params).await,
             ControllerType::Venice(controller) => controller.text_to_speech(text, params).await,
+            ControllerType::Gemini(controller) => controller.text_to_speech(text, params).await,
         }
     }
 }
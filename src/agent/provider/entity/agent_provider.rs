@@ -3,6 +3,7 @@ use crate::agent::AgentPurpose;
 #[derive(Debug, Clone)]
 pub enum AgentProvider {
     Anthropic,
+    Gemini,
     Groq,
     LocalAI,
     Mistral,
@@ -18,6 +19,7 @@ impl AgentProvider {
     pub fn choices() -> Vec<&'static Self> {
         vec![
             &Self::Anthropic,
+            &Self::Gemini,
             &Self::Groq,
             &Self::LocalAI,
             &Self::Mistral,
@@ -33,6 +35,7 @@ impl AgentProvider {
     pub fn to_static_str(&self) -> &'static str {
         match &self {
             Self::Anthropic => "anthropic",
+            Self::Gemini => "gemini",
             Self::Groq => "groq",
             Self::LocalAI => "localai",
             Self::Mistral => "mistral",
@@ -48,6 +51,7 @@ impl AgentProvider {
     pub fn from_string(s: &str) -> Result<Self, &'static str> {
         match s {
             "anthropic" => Ok(Self::Anthropic),
+            "gemini" => Ok(Self::Gemini),
             "groq" => Ok(Self::Groq),
             "localai" => Ok(Self::LocalAI),
             "mistral" => Ok(Self::Mistral),
@@ -73,7 +77,24 @@ impl AgentProvider {
                 models_list_url: Some("https://docs.anthropic.com/en/docs/about-claude/models"),
                 supported_purposes: vec![AgentPurpose::TextGeneration],
                 text_generation_supports_vision: true,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
+            },
+            Self::Gemini => AgentProviderInfo {
+                id: Self::Gemini.to_static_str(),
+                name: "Google Gemini",
+                description: "Gemini is a family of multimodal models by Google, which natively understand images, PDF documents, audio and video. This provider talks to the [Gemini API](https://ai.google.dev/gemini-api/docs) directly, and also supports image generation via Imagen and speech generation.",
+                homepage_url: Some("https://ai.google.dev/"),
+                wiki_url: Some("https://en.wikipedia.org/wiki/Gemini_(language_model)"),
+                sign_up_url: Some("https://aistudio.google.com/apikey"),
+                models_list_url: Some("https://ai.google.dev/gemini-api/docs/models"),
+                supported_purposes: vec![
+                    AgentPurpose::ImageGeneration,
+                    AgentPurpose::TextGeneration,
+                    AgentPurpose::TextToSpeech,
+                    AgentPurpose::SpeechToText,
+                ],
+                text_generation_supports_vision: true,
+                text_generation_supports_tools: true,
             },
             Self::Groq => AgentProviderInfo {
                 id: Self::Groq.to_static_str(),
@@ -85,7 +106,7 @@ impl AgentProvider {
                 models_list_url: Some("https://console.groq.com/docs/models"),
                 supported_purposes: vec![AgentPurpose::TextGeneration, AgentPurpose::SpeechToText],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::LocalAI => AgentProviderInfo {
                 id: Self::LocalAI.to_static_str(),
@@ -101,7 +122,7 @@ impl AgentProvider {
                     AgentPurpose::SpeechToText,
                 ],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::Mistral => AgentProviderInfo {
                 id: Self::Mistral.to_static_str(),
@@ -113,7 +134,7 @@ impl AgentProvider {
                 models_list_url: Some("https://docs.mistral.ai/getting-started/models/"),
                 supported_purposes: vec![AgentPurpose::TextGeneration],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::Ollama => AgentProviderInfo {
                 id: Self::Ollama.to_static_str(),
@@ -125,7 +146,7 @@ impl AgentProvider {
                 models_list_url: Some("https://ollama.com/library"),
                 supported_purposes: vec![AgentPurpose::TextGeneration],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::OpenAI => AgentProviderInfo {
                 id: Self::OpenAI.to_static_str(),
@@ -159,7 +180,7 @@ impl AgentProvider {
                     AgentPurpose::SpeechToText,
                 ],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::OpenRouter => AgentProviderInfo {
                 id: Self::OpenRouter.to_static_str(),
@@ -171,7 +192,7 @@ impl AgentProvider {
                 models_list_url: Some("https://openrouter.ai/models"),
                 supported_purposes: vec![AgentPurpose::TextGeneration],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::TogetherAI => AgentProviderInfo {
                 id: Self::TogetherAI.to_static_str(),
@@ -183,7 +204,7 @@ impl AgentProvider {
                 models_list_url: Some("https://api.together.xyz/models"),
                 supported_purposes: vec![AgentPurpose::TextGeneration],
                 text_generation_supports_vision: false,
-                text_generation_supports_tools: false,
+                text_generation_supports_tools: true,
             },
             Self::Venice => AgentProviderInfo {
                 id: Self::Venice.to_static_str(),
@@ -200,9 +221,9 @@ impl AgentProvider {
                     AgentPurpose::SpeechToText,
                 ],
                 text_generation_supports_vision: true,
-                // Venice does native web search via `venice_parameters`, NOT baibot's built-in
-                // tools mechanism (the OpenAI web_search/code_interpreter block), so this is false.
-                text_generation_supports_tools: false,
+                // Client-side tools go through the standard `tools` array. Venice's native web
+                // search is separate from this and is configured via `venice_parameters`.
+                text_generation_supports_tools: true,
             },
         }
     }
@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+/// An unsuccessful HTTP response from a provider's API.
+///
+/// Providers return this (instead of formatting the status into an ad-hoc message),
+/// so that callers can tell whether trying again (or with another agent) may help.
+#[derive(Debug)]
+pub struct ProviderHttpError {
+    pub status: reqwest::StatusCode,
+
+    /// How long the provider asked us to wait before trying again (via the `Retry-After` header), if at all.
+    pub retry_after: Option<Duration>,
+
+    message: String,
+}
+
+impl ProviderHttpError {
+    pub fn new(status: reqwest::StatusCode, message: String) -> Self {
+        Self {
+            status,
+            retry_after: None,
+            message,
+        }
+    }
+
+    pub fn with_retry_after(mut self, retry_after: Option<Duration>) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+}
+
+impl std::fmt::Display for ProviderHttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ProviderHttpError {}
+
+/// Error types (or codes) which providers report in their error responses, along with the HTTP status they go with.
+///
+/// Some provider libraries do not expose the response's status, so these (and the status' reason phrases)
+/// are our only clue as to what went wrong.
+const KNOWN_ERROR_TYPES: &[(&str, u16)] = &[
+    // OpenAI and OpenAI-compatible providers
+    ("rate_limit_exceeded", 429),
+    ("server_error", 500),
+    // Anthropic
+    ("rate_limit_error", 429),
+    ("overloaded_error", 529),
+    ("api_error", 500),
+    // Reason phrases
+    ("Too Many Requests", 429),
+    ("Internal Server Error", 500),
+    ("Bad Gateway", 502),
+    ("Service Unavailable", 503),
+    ("Gateway Timeout", 504),
+];
+
+/// Tells which HTTP status an error message (of a provider library which does not expose the status itself) is about, if any.
+pub fn status_from_error_message(message: &str) -> Option<reqwest::StatusCode> {
+    let status = KNOWN_ERROR_TYPES
+        .iter()
+        .find(|(error_type, _)| message.contains(error_type))
+        .map(|(_, status)| *status)
+        .or_else(|| {
+            // Some libraries only tell about an HTTP failure in a message like: `status code 503`
+            let (_, rest) = message.split_once("status code ")?;
+            rest.get(..3)?.parse::<u16>().ok()
+        })?;
+
+    reqwest::StatusCode::from_u16(status).ok()
+}
+
+/// Turns an error of a provider library which does not expose the response's HTTP status into a [`ProviderHttpError`],
+/// if its message gives the status away, so that it can be retried like errors of other providers.
+///
+/// Other errors are returned as they are.
+pub fn recognize_http_error(err: anyhow::Error) -> anyhow::Error {
+    let Some(status) = status_from_error_message(&format!("{err:?}")) else {
+        return err;
+    };
+
+    anyhow::Error::new(ProviderHttpError::new(status, format!("{err:#}")))
+}
+
+/// Parses the `Retry-After` header, which holds either a number of seconds or an HTTP date.
+pub fn retry_after_from_headers(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let remaining = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+
+    // A date in the past means we may retry right away.
+    Some(remaining.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(retry_after: &str) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            retry_after.parse().expect("valid header value"),
+        );
+        headers
+    }
+
+    #[test]
+    fn retry_after_is_parsed_from_seconds_and_dates() {
+        assert_eq!(
+            retry_after_from_headers(&headers("12")),
+            Some(Duration::from_secs(12))
+        );
+
+        assert_eq!(
+            retry_after_from_headers(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+
+        assert_eq!(retry_after_from_headers(&headers("soon")), None);
+        assert_eq!(
+            retry_after_from_headers(&reqwest::header::HeaderMap::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn status_is_recognized_from_error_messages() {
+        struct TestCase {
+            name: &'static str,
+            message: &'static str,
+            expected: Option<u16>,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "Anthropic rate limit",
+                message: r#"ApiError { type: "rate_limit_error", message: "Number of request tokens has exceeded your per-minute rate limit" }"#,
+                expected: Some(429),
+            },
+            TestCase {
+                name: "Anthropic overloaded",
+                message: r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+                expected: Some(529),
+            },
+            TestCase {
+                name: "Anthropic internal error",
+                message: r#"StreamError("{\"type\":\"api_error\",\"message\":\"Internal server error\"}")"#,
+                expected: Some(500),
+            },
+            TestCase {
+                name: "OpenAI-compatible rate limit",
+                message: r#"Failed to get response from the OpenAI-compat image creation API: ApiError("Rate limit reached (code: rate_limit_exceeded)")"#,
+                expected: Some(429),
+            },
+            TestCase {
+                name: "Status code",
+                message: "Failed to get response from the OpenAI-compat audio transcription API: RequestError(\"https://api.example.com/v1/audio/transcriptions: status code 503\")",
+                expected: Some(503),
+            },
+            TestCase {
+                name: "Reason phrase",
+                message: "HTTP status server error (502 Bad Gateway)",
+                expected: Some(502),
+            },
+            TestCase {
+                name: "Invalid request",
+                message: r#"ApiError { type: "invalid_request_error", message: "max_tokens: field required" }"#,
+                expected: None,
+            },
+            TestCase {
+                name: "Quota exceeded",
+                message: r#"ApiError { type: "insufficient_quota", message: "You exceeded your current quota" }"#,
+                expected: None,
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                status_from_error_message(test_case.message).map(|status| status.as_u16()),
+                test_case.expected,
+                "Test case: {}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn recognized_errors_become_provider_http_errors() {
+        let err = recognize_http_error(anyhow::anyhow!(
+            r#"ApiError {{ type: "overloaded_error", message: "Overloaded" }}"#
+        ));
+
+        let http_error = err
+            .downcast_ref::<ProviderHttpError>()
+            .expect("a provider HTTP error");
+        assert_eq!(http_error.status.as_u16(), 529);
+        assert!(http_error.to_string().contains("Overloaded"));
+
+        let err = recognize_http_error(anyhow::anyhow!("Something went wrong"));
+        assert!(err.downcast_ref::<ProviderHttpError>().is_none());
+    }
+}
@@ -40,6 +40,7 @@ pub struct ImageEditResult {
     pub mime_type: mime::Mime,
 }
 
+#[derive(Clone)]
 pub struct ImageSource {
     pub filename: String,
     pub bytes: Vec<u8>,
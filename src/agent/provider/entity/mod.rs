@@ -1,17 +1,24 @@
 mod agent_provider;
+mod http_error;
 mod image;
+mod model;
 mod ping;
 mod speech_to_text;
 mod text_generation;
 mod text_to_speech;
 
 pub use agent_provider::{AgentProvider, AgentProviderInfo};
+pub use http_error::{
+    ProviderHttpError, recognize_http_error, retry_after_from_headers, status_from_error_message,
+};
 pub use image::{
     ImageEditParams, ImageEditResult, ImageGenerationParams, ImageGenerationResult, ImageSource,
 };
+pub use model::ModelInfo;
 pub use ping::PingResult;
 pub use speech_to_text::{SpeechToTextParams, SpeechToTextResult};
 pub use text_generation::{
-    TextGenerationParams, TextGenerationPromptVariables, TextGenerationResult,
+    ReasoningEffort, TextGenerationParams, TextGenerationPromptVariables, TextGenerationResult,
+    TokenUsage,
 };
 pub use text_to_speech::{TextToSpeechParams, TextToSpeechResult};
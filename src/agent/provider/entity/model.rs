@@ -0,0 +1,102 @@
+use crate::agent::AgentPurpose;
+
+/// A model offered by a provider, as reported by its model-listing endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+    pub id: String,
+
+    /// The purposes the model can serve.
+    /// Empty for models which serve none of the purposes we support (e.g. embedding or moderation models).
+    pub purposes: Vec<AgentPurpose>,
+
+    /// Short provider-specific notes worth showing next to the model (capabilities, size, etc.).
+    pub details: Option<String>,
+}
+
+impl ModelInfo {
+    pub fn new(id: String, purposes: Vec<AgentPurpose>) -> Self {
+        Self {
+            id,
+            purposes,
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Option<String>) -> Self {
+        self.details = details.filter(|details| !details.is_empty());
+        self
+    }
+
+    /// Creates a model whose purposes are guessed from its identifier.
+    ///
+    /// OpenAI-style `/models` endpoints only return identifiers, without any capability metadata,
+    /// so the naming conventions (`whisper-1`, `tts-1`, `dall-e-3`, `text-embedding-3-small`, etc.) are all we can go by.
+    /// Anything not recognized is assumed to be a text-generation model, which is what most listed models are.
+    pub fn from_model_id(id: String) -> Self {
+        let purposes = guess_purposes_from_model_id(&id);
+        Self::new(id, purposes)
+    }
+}
+
+fn guess_purposes_from_model_id(id: &str) -> Vec<AgentPurpose> {
+    let id = id.to_lowercase();
+
+    let matches_any = |needles: &[&str]| needles.iter().any(|needle| id.contains(needle));
+
+    if matches_any(&["embed", "moderation", "rerank"]) {
+        return vec![];
+    }
+
+    if matches_any(&["whisper", "transcribe"]) {
+        return vec![AgentPurpose::SpeechToText];
+    }
+
+    if matches_any(&["tts"]) {
+        return vec![AgentPurpose::TextToSpeech];
+    }
+
+    if matches_any(&[
+        "dall-e",
+        "gpt-image",
+        "imagen",
+        "flux",
+        "stable-diffusion",
+        "sdxl",
+    ]) {
+        return vec![AgentPurpose::ImageGeneration];
+    }
+
+    vec![AgentPurpose::TextGeneration]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_purposes_from_model_ids() {
+        let cases = [
+            ("gpt-4.1", vec![AgentPurpose::TextGeneration]),
+            ("whisper-1", vec![AgentPurpose::SpeechToText]),
+            ("gpt-4o-mini-transcribe", vec![AgentPurpose::SpeechToText]),
+            ("tts-1-hd", vec![AgentPurpose::TextToSpeech]),
+            ("gpt-4o-mini-tts", vec![AgentPurpose::TextToSpeech]),
+            ("dall-e-3", vec![AgentPurpose::ImageGeneration]),
+            ("gpt-image-1", vec![AgentPurpose::ImageGeneration]),
+            (
+                "black-forest-labs/FLUX.1-schnell",
+                vec![AgentPurpose::ImageGeneration],
+            ),
+            ("text-embedding-3-small", vec![]),
+            ("omni-moderation-latest", vec![]),
+        ];
+
+        for (id, expected) in cases {
+            assert_eq!(
+                ModelInfo::from_model_id(id.to_owned()).purposes,
+                expected,
+                "{id}"
+            );
+        }
+    }
+}
@@ -1,15 +1,73 @@
+mod prompt_template;
 mod prompt_variables;
+mod reasoning_effort;
+mod token_usage;
 
 pub use prompt_variables::TextGenerationPromptVariables;
+pub use reasoning_effort::ReasoningEffort;
+pub use token_usage::TokenUsage;
+
+use crate::agent::tool::ToolRegistry;
+
+/// Introduces the summary of the earlier conversation in the system prompt.
+const CONTEXT_SUMMARY_INTRO: &str = "The earliest messages of this conversation no longer fit in your context window. This is a summary of them:";
 
 #[derive(Default)]
 pub struct TextGenerationParams {
     pub context_management_enabled: bool,
     pub prompt_override: Option<String>,
     pub temperature_override: Option<f32>,
+    /// A model to use instead of the one the agent is configured with.
+    pub model_override: Option<String>,
+    pub max_response_tokens_override: Option<u32>,
+    pub reasoning_effort_override: Option<ReasoningEffort>,
     pub prompt_variables: TextGenerationPromptVariables,
+    /// A summary of the earlier part of the conversation, which no longer fits in the context window.
+    /// It gets appended to the system prompt (see [`TextGenerationParams::prompt_text`]).
+    pub context_summary: Option<String>,
+    /// Tools offered on top of the agent's own (e.g. those coming from MCP servers).
+    pub tools: ToolRegistry,
+}
+
+impl TextGenerationParams {
+    /// Returns the model to use: the overriding one (if any) or the one the agent is configured with.
+    pub fn model_id(&self, configured_model_id: &str) -> String {
+        self.model_override
+            .clone()
+            .unwrap_or_else(|| configured_model_id.to_owned())
+    }
+
+    /// Returns the system prompt to use: the overriding one (if any) or the agent's own,
+    /// with its variables filled in and the summary of the earlier conversation (if any) appended.
+    pub fn prompt_text(&self, configured_prompt: Option<String>) -> String {
+        let prompt_text = self.prompt_variables.format(
+            self.prompt_override
+                .clone()
+                .unwrap_or(configured_prompt.unwrap_or_default())
+                .trim(),
+        );
+
+        let Some(context_summary) = &self.context_summary else {
+            return prompt_text;
+        };
+
+        let summary_section = format!("{}\n\n{}", CONTEXT_SUMMARY_INTRO, context_summary.trim());
+
+        if prompt_text.is_empty() {
+            summary_section
+        } else {
+            format!("{prompt_text}\n\n{summary_section}")
+        }
+    }
+
+    /// Returns the maximum number of tokens to generate: the overriding value (if any) or the agent's own.
+    pub fn max_response_tokens(&self, configured_max_response_tokens: Option<u32>) -> Option<u32> {
+        self.max_response_tokens_override
+            .or(configured_max_response_tokens)
+    }
 }
 
 pub struct TextGenerationResult {
     pub text: String,
+    pub usage: TokenUsage,
 }
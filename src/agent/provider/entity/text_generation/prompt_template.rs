@@ -0,0 +1,623 @@
+//! A small template language for prompts, modeled after a tiny subset of Jinja:
+//! - `{{ variable }}` and `{{ variable | default("fallback") }}`
+//! - `{% if variable %}`, `{% if not variable %}` and `{% if variable == "value" %}` (or `!=`),
+//!   along with `{% elif ... %}`, `{% else %}` and `{% endif %}`
+//!
+//! Variables hold strings. In conditions, a variable is considered true if it's set, non-empty and not `false`.
+//! Expressions referring to variables which are not defined at all (and have no default) are kept as written.
+//! Block tags (`{% ... %}`) which sit on a line of their own don't leave empty lines behind.
+
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplateError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for PromptTemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PromptTemplateError {}
+
+fn error(line: usize, message: impl Into<String>) -> PromptTemplateError {
+    PromptTemplateError {
+        line,
+        message: message.into(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplate {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Variable {
+        name: String,
+        default: Option<String>,
+        /// The expression as written (e.g. `{{ name }}`), used when the variable is not defined.
+        source: String,
+    },
+    If {
+        branches: Vec<(Condition, Vec<Node>)>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Truthy {
+        name: String,
+        negated: bool,
+    },
+    Comparison {
+        name: String,
+        value: String,
+        equal: bool,
+    },
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Expression {
+        content: String,
+        source: String,
+        line: usize,
+    },
+    Tag {
+        content: String,
+        line: usize,
+    },
+}
+
+impl PromptTemplate {
+    pub fn parse(text: &str) -> Result<Self, PromptTemplateError> {
+        let tokens = tokenize(text)?;
+
+        let mut position = 0;
+        let (nodes, terminator) = parse_nodes(&tokens, &mut position, &[])?;
+
+        if let Some((keyword, _, line)) = terminator {
+            return Err(error(line, format!("unexpected `{{% {keyword} %}}`")));
+        }
+
+        Ok(Self { nodes })
+    }
+
+    pub fn render(&self, variables: &HashMap<String, String>) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, variables, &mut output);
+        output
+    }
+
+    /// Returns the names of all variables the template refers to.
+    pub fn variable_names(&self) -> BTreeSet<&str> {
+        fn walk<'a>(nodes: &'a [Node], names: &mut BTreeSet<&'a str>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Variable { name, .. } => {
+                        names.insert(name.as_str());
+                    }
+                    Node::If {
+                        branches,
+                        otherwise,
+                    } => {
+                        for (condition, nodes) in branches {
+                            names.insert(condition.variable_name());
+                            walk(nodes, names);
+                        }
+                        walk(otherwise, names);
+                    }
+                }
+            }
+        }
+
+        let mut names = BTreeSet::new();
+        walk(&self.nodes, &mut names);
+        names
+    }
+}
+
+impl Condition {
+    fn variable_name(&self) -> &str {
+        match self {
+            Self::Truthy { name, .. } | Self::Comparison { name, .. } => name,
+        }
+    }
+
+    fn evaluate(&self, variables: &HashMap<String, String>) -> bool {
+        match self {
+            Self::Truthy { name, negated } => {
+                let truthy = variables
+                    .get(name)
+                    .is_some_and(|value| !value.is_empty() && value != "false");
+
+                truthy != *negated
+            }
+            Self::Comparison { name, value, equal } => {
+                let actual = variables.get(name).map(String::as_str).unwrap_or("");
+
+                (actual == value) == *equal
+            }
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, PromptTemplateError> {
+    let mut tokens = Vec::new();
+
+    let mut rest = text;
+    let mut line = 1;
+
+    // Whether the previous token was a block tag, whose trailing newline is to be dropped.
+    let mut after_tag = false;
+    // Whether `rest` starts at the beginning of a line.
+    let mut at_line_start = true;
+
+    loop {
+        let start = [rest.find("{{"), rest.find("{%")]
+            .into_iter()
+            .flatten()
+            .min();
+
+        let Some(start) = start else {
+            let mut text = rest;
+            if after_tag {
+                text = text.strip_prefix('\n').unwrap_or(text);
+            }
+            if !text.is_empty() {
+                tokens.push(Token::Text(text.to_owned()));
+            }
+            break;
+        };
+
+        let is_tag = rest[start..].starts_with("{%");
+        let (opening, closing) = if is_tag { ("{%", "%}") } else { ("{{", "}}") };
+
+        let tag_line = line + rest[..start].matches('\n').count();
+
+        let Some(length) = rest[start + 2..].find(closing) else {
+            return Err(error(
+                tag_line,
+                format!("`{opening}` is never closed with `{closing}`"),
+            ));
+        };
+
+        let content = rest[start + 2..start + 2 + length].trim().to_owned();
+
+        let mut before = &rest[..start];
+        let mut before_at_line_start = at_line_start;
+        if after_tag && let Some(stripped) = before.strip_prefix('\n') {
+            before = stripped;
+            before_at_line_start = true;
+        }
+
+        // Indentation before a block tag which starts its line is dropped along with the tag.
+        if is_tag {
+            let (line_start, starts_line) = match before.rfind('\n') {
+                Some(index) => (index + 1, true),
+                None => (0, before_at_line_start),
+            };
+
+            if starts_line && before[line_start..].chars().all(|c| c == ' ' || c == '\t') {
+                before = &before[..line_start];
+            }
+        }
+
+        if !before.is_empty() {
+            tokens.push(Token::Text(before.to_owned()));
+        }
+
+        tokens.push(if is_tag {
+            Token::Tag {
+                content,
+                line: tag_line,
+            }
+        } else {
+            Token::Expression {
+                content,
+                source: rest[start..start + 2 + length + 2].to_owned(),
+                line: tag_line,
+            }
+        });
+
+        let consumed = start + 2 + length + 2;
+        line += rest[..consumed].matches('\n').count();
+        rest = &rest[consumed..];
+
+        after_tag = is_tag;
+        at_line_start = false;
+    }
+
+    Ok(tokens)
+}
+
+/// Parses nodes until the end of the tokens or until one of the given block tags is reached.
+/// The block tag which stopped parsing is returned as a `(keyword, arguments, line)` tuple.
+fn parse_nodes(
+    tokens: &[Token],
+    position: &mut usize,
+    terminators: &[&str],
+) -> Result<(Vec<Node>, Option<(String, String, usize)>), PromptTemplateError> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.get(*position) {
+        *position += 1;
+
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text.clone())),
+            Token::Expression {
+                content,
+                source,
+                line,
+            } => nodes.push(parse_expression(content, source, *line)?),
+            Token::Tag { content, line } => {
+                let (keyword, arguments) = content
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((content.as_str(), ""));
+                let arguments = arguments.trim();
+
+                if keyword == "if" {
+                    nodes.push(parse_if(tokens, position, arguments, *line)?);
+                    continue;
+                }
+
+                if terminators.contains(&keyword) {
+                    return Ok((
+                        nodes,
+                        Some((keyword.to_owned(), arguments.to_owned(), *line)),
+                    ));
+                }
+
+                return Err(error(*line, format!("unexpected `{{% {content} %}}`")));
+            }
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+fn parse_if(
+    tokens: &[Token],
+    position: &mut usize,
+    arguments: &str,
+    line: usize,
+) -> Result<Node, PromptTemplateError> {
+    let mut branches = Vec::new();
+    let mut otherwise = Vec::new();
+
+    let mut condition = parse_condition(arguments, line)?;
+
+    loop {
+        let (nodes, terminator) = parse_nodes(tokens, position, &["elif", "else", "endif"])?;
+
+        let Some((keyword, arguments, tag_line)) = terminator else {
+            return Err(error(line, "`{% if %}` is never closed with `{% endif %}`"));
+        };
+
+        branches.push((condition, nodes));
+
+        match keyword.as_str() {
+            "elif" => {
+                condition = parse_condition(&arguments, tag_line)?;
+            }
+            "else" => {
+                expect_no_arguments("else", &arguments, tag_line)?;
+
+                let (nodes, terminator) = parse_nodes(tokens, position, &["endif"])?;
+
+                let Some((_, arguments, tag_line)) = terminator else {
+                    return Err(error(line, "`{% if %}` is never closed with `{% endif %}`"));
+                };
+
+                expect_no_arguments("endif", &arguments, tag_line)?;
+
+                otherwise = nodes;
+                break;
+            }
+            _ => {
+                expect_no_arguments("endif", &arguments, tag_line)?;
+                break;
+            }
+        }
+    }
+
+    Ok(Node::If {
+        branches,
+        otherwise,
+    })
+}
+
+fn expect_no_arguments(
+    keyword: &str,
+    arguments: &str,
+    line: usize,
+) -> Result<(), PromptTemplateError> {
+    if arguments.is_empty() {
+        return Ok(());
+    }
+
+    Err(error(
+        line,
+        format!("`{{% {keyword} %}}` does not take arguments (found `{arguments}`)"),
+    ))
+}
+
+fn parse_expression(content: &str, source: &str, line: usize) -> Result<Node, PromptTemplateError> {
+    let (name, filter) = match content.split_once('|') {
+        Some((name, filter)) => (name.trim(), Some(filter.trim())),
+        None => (content, None),
+    };
+
+    let name = parse_identifier(name, line)?;
+
+    let default = match filter {
+        None => None,
+        Some(filter) => {
+            let Some(argument) = filter
+                .strip_prefix("default")
+                .map(str::trim_start)
+                .and_then(|rest| rest.strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'))
+            else {
+                return Err(error(
+                    line,
+                    format!(
+                        "unsupported filter `{filter}` (only `default(\"fallback value\")` is supported)"
+                    ),
+                ));
+            };
+
+            Some(parse_string_literal(argument, line)?)
+        }
+    };
+
+    Ok(Node::Variable {
+        name,
+        default,
+        source: source.to_owned(),
+    })
+}
+
+fn parse_condition(text: &str, line: usize) -> Result<Condition, PromptTemplateError> {
+    let (negated, text) = match text.strip_prefix("not ") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, text),
+    };
+
+    // The operator which comes first wins, as the compared value may contain the other one.
+    let comparison = [("==", true), ("!=", false)]
+        .into_iter()
+        .filter_map(|(operator, equal)| text.find(operator).map(|index| (index, equal)))
+        .min()
+        .map(|(index, equal)| (&text[..index], &text[index + 2..], equal));
+
+    match comparison {
+        None => Ok(Condition::Truthy {
+            name: parse_identifier(text, line)?,
+            negated,
+        }),
+        Some(_) if negated => Err(error(
+            line,
+            "`not` cannot be combined with a comparison (use `!=` instead)",
+        )),
+        Some((name, value, equal)) => Ok(Condition::Comparison {
+            name: parse_identifier(name.trim(), line)?,
+            value: parse_string_literal(value.trim(), line)?,
+            equal,
+        }),
+    }
+}
+
+fn parse_identifier(text: &str, line: usize) -> Result<String, PromptTemplateError> {
+    let mut chars = text.chars();
+
+    let is_valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !is_valid {
+        return Err(error(
+            line,
+            format!("expected a variable name, but found `{text}`"),
+        ));
+    }
+
+    Ok(text.to_owned())
+}
+
+fn parse_string_literal(text: &str, line: usize) -> Result<String, PromptTemplateError> {
+    for quote in ['"', '\''] {
+        if let Some(value) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+            && !value.contains(quote)
+        {
+            return Ok(value.to_owned());
+        }
+    }
+
+    Err(error(
+        line,
+        format!("expected a quoted string (like `\"value\"`), but found `{text}`"),
+    ))
+}
+
+fn render_nodes(nodes: &[Node], variables: &HashMap<String, String>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable {
+                name,
+                default,
+                source,
+            } => {
+                let value = variables.get(name);
+
+                if value.is_none() && default.is_none() {
+                    output.push_str(source);
+                    continue;
+                }
+
+                if let Some(value) = value.filter(|value| !value.is_empty()).or(default.as_ref()) {
+                    output.push_str(value);
+                }
+            }
+            Node::If {
+                branches,
+                otherwise,
+            } => {
+                let nodes = branches
+                    .iter()
+                    .find(|(condition, _)| condition.evaluate(variables))
+                    .map(|(_, nodes)| nodes)
+                    .unwrap_or(otherwise);
+
+                render_nodes(nodes, variables, output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, variables: &[(&str, &str)]) -> String {
+        let variables = variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        PromptTemplate::parse(template).unwrap().render(&variables)
+    }
+
+    #[test]
+    fn renders_variables_and_defaults() {
+        assert_eq!(
+            render("Hello, {{ name }}!", &[("name", "Alice")]),
+            "Hello, Alice!"
+        );
+        assert_eq!(render("Hello, {{name}}!", &[("name", "")]), "Hello, !");
+        assert_eq!(render("Hello, {{name}}!", &[]), "Hello, {{name}}!");
+        assert_eq!(
+            render("Hello, {{ name | default(\"stranger\") }}!", &[]),
+            "Hello, stranger!"
+        );
+        assert_eq!(
+            render("Hello, {{ name | default('stranger') }}!", &[("name", "")]),
+            "Hello, stranger!"
+        );
+        assert_eq!(
+            render("No variables { here }", &[]),
+            "No variables { here }"
+        );
+    }
+
+    #[test]
+    fn renders_conditionals() {
+        let template =
+            "{% if is_dm %}Private{% elif room == \"Lobby\" %}Lobby{% else %}Group{% endif %}";
+
+        assert_eq!(render(template, &[("is_dm", "true")]), "Private");
+        assert_eq!(
+            render(template, &[("is_dm", "false"), ("room", "Lobby")]),
+            "Lobby"
+        );
+        assert_eq!(render(template, &[("is_dm", "false")]), "Group");
+
+        assert_eq!(
+            render("{% if not topic %}No topic{% endif %}", &[]),
+            "No topic"
+        );
+        assert_eq!(
+            render("{% if room != 'Lobby' %}Elsewhere{% endif %}", &[]),
+            "Elsewhere"
+        );
+    }
+
+    #[test]
+    fn block_tags_on_their_own_lines_leave_no_empty_lines() {
+        let template = "You are a bot.\n{% if is_dm %}\n  This is a private chat.\n{% else %}\n  This is a group chat.\n  {% endif %}\nBe brief.";
+
+        assert_eq!(
+            render(template, &[("is_dm", "true")]),
+            "You are a bot.\n  This is a private chat.\nBe brief."
+        );
+        assert_eq!(
+            render(template, &[]),
+            "You are a bot.\n  This is a group chat.\nBe brief."
+        );
+
+        // Inline block tags keep the text around them intact
+        assert_eq!(render("A {% if x %}b{% endif %} c", &[("x", "1")]), "A b c");
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let cases = [
+            ("Hello {{ name", 1, "`{{` is never closed with `}}`"),
+            (
+                "Hello\n{% if x %}\nhi",
+                2,
+                "`{% if %}` is never closed with `{% endif %}`",
+            ),
+            ("{% endif %}", 1, "unexpected `{% endif %}`"),
+            (
+                "{% for x in y %}{% endfor %}",
+                1,
+                "unexpected `{% for x in y %}`",
+            ),
+            (
+                "\n\n{{ room name }}",
+                3,
+                "expected a variable name, but found `room name`",
+            ),
+            (
+                "{{ name | upper }}",
+                1,
+                "unsupported filter `upper` (only `default(\"fallback value\")` is supported)",
+            ),
+            (
+                "{% if x == Lobby %}{% endif %}",
+                1,
+                "expected a quoted string (like `\"value\"`), but found `Lobby`",
+            ),
+            (
+                "{% if x %}{% else y %}{% endif %}",
+                1,
+                "`{% else %}` does not take arguments (found `y`)",
+            ),
+        ];
+
+        for (template, line, message) in cases {
+            assert_eq!(
+                PromptTemplate::parse(template),
+                Err(error(line, message)),
+                "template: {template}"
+            );
+        }
+    }
+
+    #[test]
+    fn variable_names_works() {
+        let template = PromptTemplate::parse(
+            "{{ a }} {% if b %}{{ c | default(\"x\") }}{% elif d == \"y\" %}{% else %}{{ e }}{% endif %}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            template.variable_names().into_iter().collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d", "e"]
+        );
+    }
+}
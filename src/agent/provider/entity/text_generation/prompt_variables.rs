@@ -1,9 +1,12 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use std::collections::HashMap;
 
+use super::prompt_template::PromptTemplate;
+
 #[derive(Clone)]
 pub struct TextGenerationPromptVariables {
     map: HashMap<String, String>,
+    now_time: DateTime<Utc>,
 }
 
 impl Default for TextGenerationPromptVariables {
@@ -14,6 +17,24 @@ impl Default for TextGenerationPromptVariables {
 }
 
 impl TextGenerationPromptVariables {
+    /// The names of all variables which prompts may refer to.
+    /// Some of them may be unset (e.g. `room_topic` for rooms without a topic), in which case they render as empty.
+    pub const NAMES: [&str; 13] = [
+        "baibot_name",
+        "baibot_model_id",
+        "baibot_now_utc",
+        "baibot_conversation_start_time_utc",
+        "room_name",
+        "room_topic",
+        "room_member_count",
+        "room_is_dm",
+        "room_is_encrypted",
+        "sender_id",
+        "sender_display_name",
+        "room_timezone",
+        "room_now_local",
+    ];
+
     pub fn new(
         bot_name: &str,
         model_id: &str,
@@ -36,18 +57,93 @@ impl TextGenerationPromptVariables {
             baibot_conversation_start_time_utc,
         );
 
-        Self { map }
+        Self { map, now_time }
     }
 
+    pub fn with_room_details(
+        mut self,
+        name: Option<String>,
+        topic: Option<String>,
+        member_count: u64,
+        is_dm: bool,
+        is_encrypted: bool,
+        timezone: Option<FixedOffset>,
+    ) -> Self {
+        if let Some(name) = name {
+            self.map.insert("room_name".to_string(), name);
+        }
+        if let Some(topic) = topic {
+            self.map.insert("room_topic".to_string(), topic);
+        }
+
+        self.map
+            .insert("room_member_count".to_string(), member_count.to_string());
+        self.map.insert("room_is_dm".to_string(), is_dm.to_string());
+        self.map
+            .insert("room_is_encrypted".to_string(), is_encrypted.to_string());
+
+        if let Some(timezone) = timezone {
+            self.map
+                .insert("room_timezone".to_string(), timezone.to_string());
+            self.map.insert(
+                "room_now_local".to_string(),
+                self.now_time
+                    .with_timezone(&timezone)
+                    .format("%Y-%m-%d (%A), %H:%M:%S %:z")
+                    .to_string(),
+            );
+        }
+
+        self
+    }
+
+    pub fn with_sender_details(mut self, id: &str, display_name: Option<String>) -> Self {
+        self.map.insert("sender_id".to_string(), id.to_string());
+
+        if let Some(display_name) = display_name {
+            self.map
+                .insert("sender_display_name".to_string(), display_name);
+        }
+
+        self
+    }
+
+    /// Renders the given prompt template (see [`PromptTemplate`]) using these variables.
+    ///
+    /// Prompts are validated when they're set (see [`Self::validate`]), but the agent-level ones or
+    /// ones set before templating was introduced may still be invalid. These are used as they are.
+    /// Likewise, placeholders for unknown variables (e.g. `{{ something }}`) are kept as they are.
     pub fn format(&self, text: &str) -> String {
-        let mut formatted_text = text.to_string();
+        let mut map = self.map.clone();
+        for name in Self::NAMES {
+            map.entry(name.to_string()).or_default();
+        }
+
+        match PromptTemplate::parse(text) {
+            Ok(template) => template.render(&map),
+            Err(err) => {
+                tracing::warn!(%err, "Failed parsing prompt template, so using it as is");
+                text.to_string()
+            }
+        }
+    }
+
+    /// Checks that the given prompt is a valid template which only refers to known variables.
+    pub fn validate(text: &str) -> Result<(), String> {
+        let template = PromptTemplate::parse(text).map_err(|err| err.to_string())?;
+
+        let unknown_names = template
+            .variable_names()
+            .into_iter()
+            .filter(|name| !Self::NAMES.contains(name))
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>();
 
-        for (key, value) in &self.map {
-            let placeholder = format!("{{{{ {} }}}}", key);
-            formatted_text = formatted_text.replace(&placeholder, value);
+        if !unknown_names.is_empty() {
+            return Err(format!("unknown variables: {}", unknown_names.join(", ")));
         }
 
-        formatted_text
+        Ok(())
     }
 }
 
@@ -104,4 +200,90 @@ mod tests {
 
         assert_eq!(variables.format(prompt), expected);
     }
+
+    #[test]
+    fn test_room_and_sender_details() {
+        let now_utc = Utc.with_ymd_and_hms(2024, 9, 20, 18, 34, 15).unwrap();
+
+        let variables = TextGenerationPromptVariables::new("baibot", "gpt-4o", now_utc, None)
+            .with_room_details(
+                None,
+                Some("Rust talk".to_string()),
+                2,
+                true,
+                false,
+                FixedOffset::east_opt(2 * 3600),
+            )
+            .with_sender_details("@alice:example.com", Some("Alice".to_string()));
+
+        let prompt = "{% if room_is_dm %}You're chatting with {{ sender_display_name }}{% else %}You're in {{ room_name | default(\"a group\") }}{% endif %} about: {{ room_topic }}. Their time is {{ room_now_local }}.";
+        let expected = "You're chatting with Alice about: Rust talk. Their time is 2024-09-20 (Friday), 20:34:15 +02:00.";
+
+        assert_eq!(variables.format(prompt), expected);
+
+        // Invalid templates are used as they are
+        assert_eq!(variables.format("{% if %}"), "{% if %}");
+    }
+
+    #[test]
+    fn test_format_keeps_unknown_placeholders() {
+        struct TestCase {
+            prompt: &'static str,
+            expected: &'static str,
+        }
+
+        let now_utc = Utc.with_ymd_and_hms(2024, 9, 20, 18, 34, 15).unwrap();
+
+        let variables = TextGenerationPromptVariables::new("baibot", "gpt-4o", now_utc, None)
+            .with_room_details(None, None, 2, false, false, None);
+
+        let test_cases = vec![
+            TestCase {
+                prompt: "I'm {{ baibot_name }}. {{ custom_placeholder }} stays.",
+                expected: "I'm baibot. {{ custom_placeholder }} stays.",
+            },
+            TestCase {
+                prompt: "Unknown {{unknown}} and {{  spaced  }} are kept as written.",
+                expected: "Unknown {{unknown}} and {{  spaced  }} are kept as written.",
+            },
+            TestCase {
+                // Known, but unset variables still render as empty
+                prompt: "Topic: [{{ room_topic }}], time: [{{ room_now_local }}]",
+                expected: "Topic: [], time: []",
+            },
+            TestCase {
+                prompt: "{{ unknown | default(\"fallback\") }}",
+                expected: "fallback",
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                variables.format(test_case.prompt),
+                test_case.expected,
+                "prompt: {}",
+                test_case.prompt
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            TextGenerationPromptVariables::validate(
+                "{% if room_is_dm %}Hi {{ sender_display_name | default(\"there\") }}{% endif %}"
+            ),
+            Ok(())
+        );
+
+        assert_eq!(
+            TextGenerationPromptVariables::validate("Hi {{ sender_name }} in {{ room }}"),
+            Err("unknown variables: `room`, `sender_name`".to_string())
+        );
+
+        assert_eq!(
+            TextGenerationPromptVariables::validate("Hi\n{% if room_is_dm %}"),
+            Err("line 2: `{% if %}` is never closed with `{% endif %}`".to_string())
+        );
+    }
 }
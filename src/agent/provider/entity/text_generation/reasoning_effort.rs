@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// How much effort reasoning models should put into thinking before answering.
+///
+/// Each provider maps this to its own way of controlling reasoning
+/// (a `reasoning_effort` field, a thinking token budget, etc.).
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub enum ReasoningEffort {
+    #[serde(rename = "minimal")]
+    Minimal,
+
+    #[serde(rename = "low")]
+    Low,
+
+    #[serde(rename = "medium")]
+    Medium,
+
+    #[serde(rename = "high")]
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn choices() -> Vec<Self> {
+        vec![Self::Minimal, Self::Low, Self::Medium, Self::High]
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "minimal" => Some(Self::Minimal),
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl std::fmt::Display for ReasoningEffort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
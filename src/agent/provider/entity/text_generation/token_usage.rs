@@ -0,0 +1,24 @@
+/// Token counts reported by a provider for a single text-generation reply.
+///
+/// When a reply takes several requests (e.g. a tool-call loop), the counts of all of them are summed up.
+/// Providers which don't report some of these leave them at zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+
+    /// The part of `completion_tokens` which the model spent thinking.
+    pub reasoning_tokens: u64,
+
+    /// The part of `prompt_tokens` which was served from the provider's prompt cache.
+    pub cached_tokens: u64,
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cached_tokens += other.cached_tokens;
+    }
+}
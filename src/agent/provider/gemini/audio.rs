@@ -0,0 +1,190 @@
+use crate::agent::AgentPurpose;
+use crate::agent::provider::entity::{TextToSpeechParams, TextToSpeechResult};
+use crate::agent::provider::{SpeechToTextParams, SpeechToTextResult};
+use crate::strings;
+use crate::utils::base64::{base64_decode, base64_encode};
+
+use super::chat::{answer_text, extract_candidate_content};
+use super::config::Config;
+use super::utils::{ensure_inline_data_fits, model_method_url, post_json};
+use super::wire::{
+    Content, GenerateContentRequest, GenerateContentResponse, GenerationConfig, Part,
+    PrebuiltVoiceConfig, SpeechConfig, VoiceConfig,
+};
+
+/// The sample rate of the raw 16-bit PCM produced by the TTS models, for when the mime type doesn't mention it.
+const DEFAULT_PCM_SAMPLE_RATE: u32 = 24_000;
+
+pub async fn speech_to_text(
+    config: &Config,
+    http: &reqwest::Client,
+    mime_type: &mxlink::mime::Mime,
+    media: Vec<u8>,
+    params: SpeechToTextParams,
+) -> anyhow::Result<SpeechToTextResult> {
+    let Some(speech_to_text_config) = &config.speech_to_text else {
+        return Err(anyhow::anyhow!(
+            strings::agent::no_configuration_for_purpose_so_cannot_be_used(
+                &AgentPurpose::SpeechToText
+            ),
+        ));
+    };
+
+    ensure_inline_data_fits(media.len())?;
+
+    let request = GenerateContentRequest {
+        contents: vec![Content::new(
+            "user",
+            vec![
+                Part::text(transcription_instruction(
+                    params.language_override.as_deref(),
+                )),
+                Part::inline_data(mime_type.essence_str().to_owned(), base64_encode(&media)),
+            ],
+        )],
+        system_instruction: None,
+        tools: None,
+        safety_settings: vec![],
+        // Transcripts should be faithful, not creative.
+        generation_config: Some(GenerationConfig {
+            temperature: Some(0.0),
+            ..Default::default()
+        }),
+    };
+
+    let url = model_method_url(config, &speech_to_text_config.model_id, "generateContent");
+
+    tracing::trace!(
+        model_id = speech_to_text_config.model_id,
+        language = ?params.language_override,
+        "Sending Gemini transcription request"
+    );
+
+    let response = post_json(config, http, &url, &request, "speech-to-text").await?;
+    let response: GenerateContentResponse = response.json().await?;
+
+    let content = extract_candidate_content(response)?;
+
+    Ok(SpeechToTextResult {
+        text: answer_text(&content.parts).trim().to_owned(),
+    })
+}
+
+pub async fn text_to_speech(
+    config: &Config,
+    http: &reqwest::Client,
+    input: &str,
+    params: TextToSpeechParams,
+) -> anyhow::Result<TextToSpeechResult> {
+    let Some(text_to_speech_config) = &config.text_to_speech else {
+        return Err(anyhow::anyhow!(
+            strings::agent::no_configuration_for_purpose_so_cannot_be_used(
+                &AgentPurpose::TextToSpeech
+            ),
+        ));
+    };
+
+    // Gemini has no speed knob, so `params.speed_override` is ignored.
+    let voice = params
+        .voice_override
+        .or_else(|| text_to_speech_config.voice.clone());
+
+    let request = GenerateContentRequest {
+        contents: vec![Content::new("user", vec![Part::text(input.to_owned())])],
+        system_instruction: None,
+        tools: None,
+        safety_settings: vec![],
+        generation_config: Some(GenerationConfig {
+            response_modalities: Some(vec!["AUDIO".to_owned()]),
+            speech_config: voice.map(|voice_name| SpeechConfig {
+                voice_config: VoiceConfig {
+                    prebuilt_voice_config: PrebuiltVoiceConfig { voice_name },
+                },
+            }),
+            ..Default::default()
+        }),
+    };
+
+    let url = model_method_url(config, &text_to_speech_config.model_id, "generateContent");
+
+    tracing::trace!(
+        model_id = text_to_speech_config.model_id,
+        "Sending Gemini text-to-speech request"
+    );
+
+    let response = post_json(config, http, &url, &request, "text-to-speech").await?;
+    let response: GenerateContentResponse = response.json().await?;
+
+    let content = extract_candidate_content(response)?;
+
+    let Some(blob) = content.parts.into_iter().find_map(|part| part.inline_data) else {
+        return Err(anyhow::anyhow!(
+            "The Gemini text-to-speech response contained no audio"
+        ));
+    };
+
+    let pcm = base64_decode(&blob.data).map_err(|decode_err| {
+        tracing::warn!(%decode_err, "Gemini text-to-speech returned undecodable base64");
+        anyhow::anyhow!("Gemini text-to-speech returned invalid base64 audio data")
+    })?;
+
+    let sample_rate = pcm_sample_rate(&blob.mime_type).unwrap_or(DEFAULT_PCM_SAMPLE_RATE);
+
+    // Raw PCM is not something Matrix clients can play, so it's wrapped in a WAV container.
+    Ok(TextToSpeechResult {
+        bytes: wrap_pcm_in_wav(&pcm, sample_rate),
+        mime_type: "audio/wav"
+            .parse()
+            .unwrap_or(mxlink::mime::APPLICATION_OCTET_STREAM),
+    })
+}
+
+fn transcription_instruction(language: Option<&str>) -> String {
+    let mut instruction = "Transcribe this audio recording verbatim. Respond with the transcript only, without any commentary, timestamps or speaker labels.".to_owned();
+
+    if let Some(language) = language {
+        instruction.push_str(&format!(" The recording is in the `{language}` language."));
+    }
+
+    instruction
+}
+
+/// Extracts the sample rate out of a mime type like `audio/L16;codec=pcm;rate=24000`.
+pub(super) fn pcm_sample_rate(mime_type: &str) -> Option<u32> {
+    mime_type.split(';').find_map(|param| {
+        let (key, value) = param.trim().split_once('=')?;
+        if key.trim().eq_ignore_ascii_case("rate") {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Prepends a WAV header to mono 16-bit little-endian PCM samples.
+pub(super) fn wrap_pcm_in_wav(pcm: &[u8], sample_rate: u32) -> Vec<u8> {
+    const CHANNELS: u16 = 1;
+    const BITS_PER_SAMPLE: u16 = 16;
+
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let byte_rate = sample_rate * u32::from(block_align);
+    let data_len = pcm.len() as u32;
+
+    let mut wav = Vec::with_capacity(44 + pcm.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&CHANNELS.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.extend_from_slice(pcm);
+
+    wav
+}
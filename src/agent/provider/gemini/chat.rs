@@ -0,0 +1,327 @@
+use crate::agent::AgentPurpose;
+use crate::agent::provider::entity::{
+    ReasoningEffort, TextGenerationParams, TextGenerationResult, TokenUsage,
+};
+use crate::agent::tool::{MAX_TOOL_CALL_ROUNDS, ToolCall, ToolDefinition, ToolRegistry};
+use crate::conversation::llm::{
+    Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost, Message as LLMMessage,
+    MessageContent as LLMMessageContent, TokenEstimate, shorten_messages_list_to_context_size,
+};
+use crate::strings;
+
+use super::super::sse::EventStreamReader;
+use super::config::Config;
+use super::utils::{convert_llm_messages_to_gemini, model_method_url, post_json};
+use super::wire::{
+    Content, FunctionDeclaration, FunctionResponse, GenerateContentRequest,
+    GenerateContentResponse, GenerationConfig, Part, ThinkingConfig, Tool,
+};
+
+pub async fn generate_text(
+    config: &Config,
+    http: &reqwest::Client,
+    tools: &ToolRegistry,
+    conversation: LLMConversation,
+    params: TextGenerationParams,
+) -> anyhow::Result<TextGenerationResult> {
+    let (mut request, model_id) = create_generate_content_request(config, conversation, params)?;
+
+    if !tools.is_empty() {
+        request.tools = Some(function_declarations(tools.definitions()));
+    }
+
+    let url = model_method_url(config, &model_id, "generateContent");
+
+    let mut usage = TokenUsage::default();
+
+    // Each round either returns the final answer or asks for function calls, whose results are appended
+    // to the conversation for the next round. Without tools, the first round always answers.
+    for round in 0..MAX_TOOL_CALL_ROUNDS {
+        tracing::trace!(
+            model = model_id,
+            contents_count = request.contents.len(),
+            "Sending Gemini generateContent API request"
+        );
+
+        let response = post_json(config, http, &url, &request, "text generation").await?;
+        let mut response: GenerateContentResponse = response.json().await?;
+
+        if let Some(usage_metadata) = response.usage_metadata.take() {
+            usage += TokenUsage::from(usage_metadata);
+        }
+
+        let content = extract_candidate_content(response)?;
+
+        // Gemini only assigns IDs to function calls on some models. Ours are made up when missing,
+        // but only the real ones are echoed back in the function responses.
+        let function_calls: Vec<(Option<String>, ToolCall)> = content
+            .parts
+            .iter()
+            .filter_map(|part| part.function_call.as_ref())
+            .enumerate()
+            .map(|(index, function_call)| {
+                let tool_call = ToolCall {
+                    id: function_call
+                        .id
+                        .clone()
+                        .unwrap_or_else(|| format!("call_{round}_{index}")),
+                    name: function_call.name.clone(),
+                    arguments: function_call.args.to_string(),
+                };
+
+                (function_call.id.clone(), tool_call)
+            })
+            .collect();
+
+        if function_calls.is_empty() {
+            let text = answer_text(&content.parts);
+
+            if text.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "No text was returned from the Gemini generateContent API"
+                ));
+            }
+
+            return Ok(TextGenerationResult { text, usage });
+        }
+
+        let mut response_parts = Vec::with_capacity(function_calls.len());
+        for (id, tool_call) in function_calls {
+            let output = tools.call(&tool_call).await;
+
+            response_parts.push(Part {
+                function_response: Some(FunctionResponse {
+                    id,
+                    name: tool_call.name,
+                    response: serde_json::json!({ "result": output }),
+                }),
+                ..Default::default()
+            });
+        }
+
+        request.contents.push(Content {
+            role: Some("model".to_owned()),
+            parts: content.parts,
+        });
+        request.contents.push(Content::new("user", response_parts));
+    }
+
+    Err(anyhow::anyhow!(strings::agent::tool_call_rounds_exhausted(
+        MAX_TOOL_CALL_ROUNDS
+    )))
+}
+
+/// Streaming variant of `generate_text` (without tool support), using `:streamGenerateContent` with SSE framing.
+pub async fn generate_text_streaming(
+    config: &Config,
+    http: &reqwest::Client,
+    conversation: LLMConversation,
+    params: TextGenerationParams,
+    partial_text: tokio::sync::watch::Sender<String>,
+) -> anyhow::Result<TextGenerationResult> {
+    let (request, model_id) = create_generate_content_request(config, conversation, params)?;
+
+    let url = format!(
+        "{}?alt=sse",
+        model_method_url(config, &model_id, "streamGenerateContent")
+    );
+
+    tracing::trace!(
+        model = model_id,
+        contents_count = request.contents.len(),
+        "Sending Gemini streamGenerateContent API request"
+    );
+
+    let response = post_json(config, http, &url, &request, "text generation").await?;
+
+    let mut reader = EventStreamReader::new(response);
+
+    let mut text = String::new();
+    let mut usage = TokenUsage::default();
+
+    while let Some(data) = reader.next_data().await? {
+        let value: serde_json::Value = serde_json::from_str(&data)?;
+        if value.get("error").is_some() {
+            return Err(anyhow::anyhow!(
+                "Gemini reported an error while streaming: {}",
+                super::utils::extract_error_message(&data)
+            ));
+        }
+
+        let mut chunk: GenerateContentResponse = serde_json::from_value(value)?;
+
+        if let Some(usage_metadata) = chunk.usage_metadata.take() {
+            usage = TokenUsage::from(usage_metadata);
+        }
+
+        if let Some(reason) = blocked_prompt_reason(&chunk) {
+            return Err(anyhow::anyhow!(blocked_prompt_error(&reason)));
+        }
+
+        let Some(content) = chunk
+            .candidates
+            .into_iter()
+            .next()
+            .and_then(|candidate| candidate.content)
+        else {
+            continue;
+        };
+
+        let delta = answer_text(&content.parts);
+        if !delta.is_empty() {
+            text.push_str(&delta);
+            partial_text.send_replace(text.clone());
+        }
+    }
+
+    if text.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No text was streamed from the Gemini streamGenerateContent API"
+        ));
+    }
+
+    Ok(TextGenerationResult { text, usage })
+}
+
+/// Builds the `:generateContent` request body for the given conversation. Shared by the regular
+/// and the streaming variants, which differ only in the endpoint and in how the response is read.
+/// The model is not part of the body (but of the endpoint's URL), so its ID is returned next to it.
+fn create_generate_content_request(
+    config: &Config,
+    conversation: LLMConversation,
+    params: TextGenerationParams,
+) -> anyhow::Result<(GenerateContentRequest, String)> {
+    let Some(text_generation_config) = &config.text_generation else {
+        return Err(anyhow::anyhow!(
+            strings::agent::no_configuration_for_purpose_so_cannot_be_used(
+                &AgentPurpose::TextGeneration
+            ),
+        ));
+    };
+
+    let model_id = params.model_id(&text_generation_config.model_id);
+
+    let max_response_tokens =
+        params.max_response_tokens(text_generation_config.max_response_tokens);
+
+    let thinking_config = params
+        .reasoning_effort_override
+        .map(|reasoning_effort| ThinkingConfig {
+            thinking_budget: thinking_budget_for_reasoning_effort(reasoning_effort),
+        });
+
+    let prompt_text = params.prompt_text(text_generation_config.prompt.clone());
+
+    let prompt_message = if prompt_text.is_empty() {
+        None
+    } else {
+        Some(LLMMessage {
+            author: LLMAuthor::Prompt,
+            sender_id: None,
+            content: LLMMessageContent::Text(prompt_text),
+            timestamp: chrono::Utc::now(),
+        })
+    };
+
+    let mut conversation_messages = conversation.messages;
+
+    if params.context_management_enabled {
+        conversation_messages = shorten_messages_list_to_context_size(
+            TokenEstimate::Approximate,
+            MediaTokenCost::Gemini,
+            &prompt_message,
+            conversation_messages,
+            max_response_tokens,
+            text_generation_config.max_context_tokens,
+        );
+    }
+
+    if let Some(prompt_message) = prompt_message {
+        conversation_messages.insert(0, prompt_message);
+    }
+
+    let (system_instruction, contents) = convert_llm_messages_to_gemini(conversation_messages)?;
+
+    let temperature = params
+        .temperature_override
+        .unwrap_or(text_generation_config.temperature);
+
+    let request = GenerateContentRequest {
+        contents,
+        system_instruction,
+        tools: None,
+        safety_settings: text_generation_config.safety_settings.clone(),
+        generation_config: Some(GenerationConfig {
+            temperature: Some(temperature),
+            max_output_tokens: max_response_tokens,
+            thinking_config,
+            ..Default::default()
+        }),
+    };
+
+    Ok((request, model_id))
+}
+
+/// Gemini controls reasoning via a token budget.
+/// The budgets match the ones Gemini's OpenAI-compatible API uses for `reasoning_effort`,
+/// with `minimal` getting the smallest budget all thinking models accept.
+fn thinking_budget_for_reasoning_effort(reasoning_effort: ReasoningEffort) -> u32 {
+    match reasoning_effort {
+        ReasoningEffort::Minimal => 128,
+        ReasoningEffort::Low => 1024,
+        ReasoningEffort::Medium => 8192,
+        ReasoningEffort::High => 24576,
+    }
+}
+
+pub(super) fn function_declarations(definitions: Vec<ToolDefinition>) -> Vec<Tool> {
+    vec![Tool {
+        function_declarations: definitions
+            .into_iter()
+            .map(|definition| FunctionDeclaration {
+                name: definition.name,
+                description: definition.description,
+                parameters_json_schema: definition.parameters,
+            })
+            .collect(),
+    }]
+}
+
+/// Returns the content of the first candidate, turning the ways Gemini signals a refusal into errors.
+pub(super) fn extract_candidate_content(
+    response: GenerateContentResponse,
+) -> anyhow::Result<Content> {
+    if let Some(reason) = blocked_prompt_reason(&response) {
+        return Err(anyhow::anyhow!(blocked_prompt_error(&reason)));
+    }
+
+    let Some(candidate) = response.candidates.into_iter().next() else {
+        return Err(anyhow::anyhow!(
+            "No candidates were returned from the Gemini API"
+        ));
+    };
+
+    match candidate.content {
+        Some(content) if !content.parts.is_empty() => Ok(content),
+        _ => Err(anyhow::anyhow!(
+            "Gemini returned no content (finish reason: {})",
+            candidate.finish_reason.as_deref().unwrap_or("unknown")
+        )),
+    }
+}
+
+/// Joins the answer parts (skipping thoughts, function calls, etc.)
+pub(super) fn answer_text(parts: &[Part]) -> String {
+    parts.iter().filter_map(Part::answer_text).collect()
+}
+
+fn blocked_prompt_reason(response: &GenerateContentResponse) -> Option<String> {
+    response
+        .prompt_feedback
+        .as_ref()
+        .and_then(|feedback| feedback.block_reason.clone())
+}
+
+fn blocked_prompt_error(reason: &str) -> String {
+    format!("Gemini refused to respond to this conversation (block reason: {reason})")
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agent::{default_prompt, provider::ConfigTrait, tool::ToolsConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub base_url: String,
+
+    pub api_key: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_generation: Option<TextGenerationConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speech_to_text: Option<SpeechToTextConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_to_speech: Option<TextToSpeechConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_generation: Option<ImageGenerationConfig>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            base_url: "https://generativelanguage.googleapis.com/v1beta".to_owned(),
+            api_key: "YOUR_API_KEY_HERE".to_owned(),
+            text_generation: Some(TextGenerationConfig::default()),
+            speech_to_text: Some(SpeechToTextConfig::default()),
+            text_to_speech: Some(TextToSpeechConfig::default()),
+            image_generation: Some(ImageGenerationConfig::default()),
+        }
+    }
+}
+
+impl ConfigTrait for Config {
+    fn validate(&self) -> Result<(), String> {
+        if self.base_url.is_empty() {
+            return Err("The base URL must not be empty.".to_owned());
+        }
+        if self.api_key.is_empty() {
+            return Err("The API key must not be empty.".to_owned());
+        }
+
+        if let Some(text_generation) = &self.text_generation {
+            for safety_setting in &text_generation.safety_settings {
+                if safety_setting.category.is_empty() || safety_setting.threshold.is_empty() {
+                    return Err(
+                        "Each safety setting must specify both a category and a threshold."
+                            .to_owned(),
+                    );
+                }
+            }
+
+            text_generation.tools.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextGenerationConfig {
+    #[serde(default = "default_text_model_id")]
+    pub model_id: String,
+
+    #[serde(default)]
+    pub prompt: Option<String>,
+
+    #[serde(default = "super::super::default_temperature")]
+    pub temperature: f32,
+
+    #[serde(default)]
+    pub max_response_tokens: Option<u32>,
+
+    #[serde(default)]
+    pub max_context_tokens: u32,
+
+    /// Passed as-is to Gemini's `safetySettings`, so any category/threshold the API knows about can be used
+    /// (e.g. `HARM_CATEGORY_HARASSMENT` / `BLOCK_ONLY_HIGH`). When empty, Gemini's own defaults apply.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+
+    #[serde(default)]
+    pub tools: ToolsConfig,
+}
+
+impl Default for TextGenerationConfig {
+    fn default() -> Self {
+        Self {
+            model_id: default_text_model_id(),
+            prompt: Some(default_prompt().to_owned()),
+            temperature: super::super::default_temperature(),
+            max_response_tokens: Some(8192),
+            max_context_tokens: 1_048_576,
+            safety_settings: vec![],
+            tools: ToolsConfig::default(),
+        }
+    }
+}
+
+fn default_text_model_id() -> String {
+    "gemini-2.5-flash".to_owned()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetySetting {
+    pub category: String,
+    pub threshold: String,
+}
+
+/// Gemini has no dedicated transcription endpoint.
+/// Speech-to-text is done by handing the audio to a regular (multimodal) model and asking it for a transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechToTextConfig {
+    #[serde(default = "default_speech_to_text_model_id")]
+    pub model_id: String,
+}
+
+impl Default for SpeechToTextConfig {
+    fn default() -> Self {
+        Self {
+            model_id: default_speech_to_text_model_id(),
+        }
+    }
+}
+
+fn default_speech_to_text_model_id() -> String {
+    "gemini-2.5-flash".to_owned()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextToSpeechConfig {
+    #[serde(default = "default_text_to_speech_model_id")]
+    pub model_id: String,
+
+    /// One of Gemini's prebuilt voices (e.g. `Kore`, `Puck`, `Charon`).
+    #[serde(
+        default = "default_text_to_speech_voice",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voice: Option<String>,
+}
+
+impl Default for TextToSpeechConfig {
+    fn default() -> Self {
+        Self {
+            model_id: default_text_to_speech_model_id(),
+            voice: default_text_to_speech_voice(),
+        }
+    }
+}
+
+fn default_text_to_speech_model_id() -> String {
+    "gemini-2.5-flash-preview-tts".to_owned()
+}
+
+fn default_text_to_speech_voice() -> Option<String> {
+    Some("Kore".to_owned())
+}
+
+/// Image generation goes through Imagen (the `:predict` endpoint), not through Gemini models.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageGenerationConfig {
+    #[serde(default = "default_image_generation_model_id")]
+    pub model_id: String,
+
+    /// For example: `1:1`, `3:4`, `4:3`, `9:16`, `16:9`. Imagen defaults to `1:1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<String>,
+
+    /// One of `dont_allow`, `allow_adult`, `allow_all` (availability depends on the region).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person_generation: Option<String>,
+}
+
+impl Default for ImageGenerationConfig {
+    fn default() -> Self {
+        Self {
+            model_id: default_image_generation_model_id(),
+            aspect_ratio: None,
+            person_generation: None,
+        }
+    }
+}
+
+fn default_image_generation_model_id() -> String {
+    "imagen-4.0-generate-001".to_owned()
+}
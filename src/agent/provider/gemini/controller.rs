@@ -0,0 +1,215 @@
+use crate::agent::AgentPurpose;
+use crate::agent::provider::entity::{
+    ImageEditResult, ImageGenerationResult, ImageSource, ModelInfo, PingResult,
+    TextGenerationParams, TextGenerationResult, TextToSpeechParams, TextToSpeechResult,
+};
+use crate::agent::provider::{
+    ImageEditParams, ImageGenerationParams, LearnedUnsupportedFields, SpeechToTextParams,
+    SpeechToTextResult,
+};
+use crate::agent::tool::{ToolRegistry, mcp::McpToolsConfig};
+use crate::conversation::llm::{
+    Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost, Message as LLMMessage,
+    MessageContent as LLMMessageContent,
+};
+
+use super::super::ControllerTrait;
+use super::config::Config;
+
+#[derive(Debug, Clone)]
+pub struct Controller {
+    config: Config,
+    http: reqwest::Client,
+    tools: ToolRegistry,
+}
+
+impl Controller {
+    pub fn new(config: Config) -> Self {
+        // Image generation, text-to-speech and transcribing long recordings can all take a while.
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        let tools = match &config.text_generation {
+            Some(text_generation_config) => {
+                ToolRegistry::with_builtin_tools(&text_generation_config.tools.enabled)
+            }
+            None => ToolRegistry::default(),
+        };
+
+        Self {
+            config,
+            http,
+            tools,
+        }
+    }
+}
+
+impl ControllerTrait for Controller {
+    async fn ping(&self) -> anyhow::Result<PingResult> {
+        if !self.supports_purpose(AgentPurpose::TextGeneration) {
+            return Ok(PingResult::Inconclusive);
+        }
+
+        let messages = vec![LLMMessage {
+            author: LLMAuthor::User,
+            sender_id: None,
+            content: LLMMessageContent::Text("Hello!".to_string()),
+            timestamp: chrono::Utc::now(),
+        }];
+
+        let conversation = LLMConversation { messages };
+
+        self.generate_text(conversation, TextGenerationParams::default())
+            .await?;
+
+        Ok(PingResult::Successful)
+    }
+
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
+        super::models::list_models(&self.config, &self.http).await
+    }
+
+    async fn generate_text(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+    ) -> anyhow::Result<TextGenerationResult> {
+        let tools = self.tools.merged_with(&params.tools);
+
+        super::chat::generate_text(&self.config, &self.http, &tools, conversation, params).await
+    }
+
+    async fn generate_text_streaming(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> anyhow::Result<TextGenerationResult> {
+        // The tool-call loop needs each round's complete response, so with tools enabled the reply
+        // is produced by the non-streaming path and only the final text is shown.
+        if !self.tools.is_empty() || !params.tools.is_empty() {
+            return self.generate_text(conversation, params).await;
+        }
+
+        super::chat::generate_text_streaming(
+            &self.config,
+            &self.http,
+            conversation,
+            params,
+            partial_text,
+        )
+        .await
+    }
+
+    async fn speech_to_text(
+        &self,
+        mime_type: &mxlink::mime::Mime,
+        media: Vec<u8>,
+        params: SpeechToTextParams,
+    ) -> anyhow::Result<SpeechToTextResult> {
+        super::audio::speech_to_text(&self.config, &self.http, mime_type, media, params).await
+    }
+
+    async fn generate_image(
+        &self,
+        prompt: &str,
+        params: ImageGenerationParams,
+    ) -> anyhow::Result<ImageGenerationResult> {
+        super::images::generate_image(&self.config, &self.http, prompt, params).await
+    }
+
+    async fn create_image_edit(
+        &self,
+        _prompt: &str,
+        _images: Vec<ImageSource>,
+        _params: ImageEditParams,
+    ) -> anyhow::Result<ImageEditResult> {
+        Err(anyhow::anyhow!("Image editing is not supported"))
+    }
+
+    async fn text_to_speech(
+        &self,
+        input: &str,
+        params: TextToSpeechParams,
+    ) -> anyhow::Result<TextToSpeechResult> {
+        super::audio::text_to_speech(&self.config, &self.http, input, params).await
+    }
+
+    fn supports_purpose(&self, purpose: AgentPurpose) -> bool {
+        match purpose {
+            AgentPurpose::TextGeneration => self.config.text_generation.is_some(),
+            AgentPurpose::SpeechToText => self.config.speech_to_text.is_some(),
+            AgentPurpose::TextToSpeech => self.config.text_to_speech.is_some(),
+            AgentPurpose::ImageGeneration => self.config.image_generation.is_some(),
+            AgentPurpose::CatchAll => true,
+        }
+    }
+
+    fn text_generation_model_id(&self) -> Option<String> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.model_id.to_owned())
+    }
+
+    fn text_generation_prompt(&self) -> Option<String> {
+        self.config
+            .text_generation
+            .as_ref()
+            .and_then(|config| config.prompt.clone())
+    }
+
+    fn text_generation_temperature(&self) -> Option<f32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.temperature)
+    }
+
+    fn text_generation_max_response_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .and_then(|config| config.max_response_tokens)
+    }
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.max_context_tokens)
+    }
+
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost {
+        MediaTokenCost::Gemini
+    }
+
+    fn text_generation_tools(&self) -> ToolRegistry {
+        self.tools.clone()
+    }
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.tools.mcp.clone())
+    }
+
+    fn text_to_speech_voice(&self) -> Option<String> {
+        self.config
+            .text_to_speech
+            .as_ref()
+            .and_then(|config| config.voice.clone())
+    }
+
+    fn text_to_speech_speed(&self) -> Option<f32> {
+        None
+    }
+
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields {
+        // Requests are not auto-recovered for this provider, so nothing is ever learned.
+        LearnedUnsupportedFields::new()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::agent::AgentPurpose;
+use crate::agent::provider::ImageGenerationParams;
+use crate::agent::provider::entity::ImageGenerationResult;
+use crate::strings;
+use crate::utils::base64::base64_decode;
+
+use super::config::Config;
+use super::utils::{model_method_url, post_json};
+use super::wire::{PredictInstance, PredictParameters, PredictRequest, PredictResponse};
+
+/// Generates an image via Imagen's `:predict` endpoint.
+///
+/// `params` is advisory only. The aspect ratio and other knobs come from the configuration.
+pub async fn generate_image(
+    config: &Config,
+    http: &reqwest::Client,
+    prompt: &str,
+    _params: ImageGenerationParams,
+) -> anyhow::Result<ImageGenerationResult> {
+    let Some(image_generation_config) = &config.image_generation else {
+        return Err(anyhow::anyhow!(
+            strings::agent::no_configuration_for_purpose_so_cannot_be_used(
+                &AgentPurpose::ImageGeneration
+            ),
+        ));
+    };
+
+    let request = PredictRequest {
+        instances: vec![PredictInstance {
+            prompt: prompt.to_owned(),
+        }],
+        parameters: PredictParameters {
+            sample_count: 1,
+            aspect_ratio: image_generation_config.aspect_ratio.clone(),
+            person_generation: image_generation_config.person_generation.clone(),
+        },
+    };
+
+    let url = model_method_url(config, &image_generation_config.model_id, "predict");
+
+    tracing::trace!(
+        model_id = image_generation_config.model_id,
+        "Sending Imagen image generation request"
+    );
+
+    let response = post_json(config, http, &url, &request, "image generation").await?;
+    let response: PredictResponse = response.json().await?;
+
+    let Some(prediction) = response.predictions.into_iter().next() else {
+        // Imagen drops filtered images from the response entirely, unless asked to explain why.
+        return Err(anyhow::anyhow!(
+            "The Imagen API returned no images, most likely because the prompt or the result was filtered"
+        ));
+    };
+
+    let Some(image_base64) = prediction.bytes_base64_encoded else {
+        return Err(anyhow::anyhow!(
+            "The Imagen API returned no image (reason: {})",
+            prediction
+                .rai_filtered_reason
+                .as_deref()
+                .unwrap_or("unknown")
+        ));
+    };
+
+    let bytes = base64_decode(&image_base64).map_err(|decode_err| {
+        tracing::warn!(%decode_err, "Imagen returned undecodable base64");
+        anyhow::anyhow!("Imagen returned invalid base64 image data")
+    })?;
+
+    let mime_type = prediction
+        .mime_type
+        .and_then(|mime_type| mime_type.parse::<mxlink::mime::Mime>().ok())
+        .unwrap_or(mxlink::mime::IMAGE_PNG);
+
+    Ok(ImageGenerationResult {
+        bytes,
+        mime_type,
+        revised_prompt: None,
+    })
+}
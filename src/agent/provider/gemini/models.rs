@@ -0,0 +1,55 @@
+use crate::agent::AgentPurpose;
+use crate::agent::provider::ModelInfo;
+
+use super::super::model_listing;
+use super::config::Config;
+use super::wire::{ModelsResponse, ModelsResponseEntry};
+
+/// Lists the models available to the API key. 1000 is the largest page size the API allows.
+pub async fn list_models(
+    config: &Config,
+    http: &reqwest::Client,
+) -> anyhow::Result<Vec<ModelInfo>> {
+    let url = format!(
+        "{}/models?pageSize=1000",
+        config.base_url.trim_end_matches('/')
+    );
+
+    let request = http.get(url).header("x-goog-api-key", &config.api_key);
+
+    let response: ModelsResponse = model_listing::get_json(request, "Gemini").await?;
+
+    Ok(model_listing::sorted(convert_models_response(response)))
+}
+
+pub(super) fn convert_models_response(response: ModelsResponse) -> Vec<ModelInfo> {
+    response.models.into_iter().map(convert_model).collect()
+}
+
+fn convert_model(entry: ModelsResponseEntry) -> ModelInfo {
+    let id = entry.name.trim_start_matches("models/").to_owned();
+
+    let supports_method = |method: &str| {
+        entry
+            .supported_generation_methods
+            .iter()
+            .any(|supported| supported == method)
+    };
+
+    // Mirrors how each purpose is served (see `chat.rs`, `audio.rs` and `images.rs`):
+    // Imagen models generate images via `:predict`, while all else goes through `:generateContent`,
+    // where the TTS models only speak and all other models handle both text and audio input.
+    let purposes = if supports_method("predict") && id.contains("imagen") {
+        vec![AgentPurpose::ImageGeneration]
+    } else if supports_method("generateContent") {
+        if id.contains("tts") {
+            vec![AgentPurpose::TextToSpeech]
+        } else {
+            vec![AgentPurpose::TextGeneration, AgentPurpose::SpeechToText]
+        }
+    } else {
+        vec![]
+    };
+
+    ModelInfo::new(id, purposes).with_details(entry.display_name)
+}
@@ -0,0 +1,339 @@
+use mxlink::matrix_sdk::ruma::OwnedMxcUri;
+use mxlink::matrix_sdk::ruma::events::room::message::{
+    FileMessageEventContent, ImageMessageEventContent,
+};
+use mxlink::mime;
+
+use super::super::{ConfigTrait, ControllerTrait, ModelInfo, TokenUsage};
+use crate::agent::AgentPurpose;
+use crate::agent::tool::ToolDefinition;
+use crate::conversation::llm::{
+    Author as LLMAuthor, FileDetails, ImageDetails, Message as LLMMessage,
+    MessageContent as LLMMessageContent,
+};
+
+use super::audio::{pcm_sample_rate, wrap_pcm_in_wav};
+use super::chat::{answer_text, extract_candidate_content, function_declarations};
+use super::config::{Config, SafetySetting};
+use super::controller::Controller;
+use super::models::convert_models_response;
+use super::utils::{convert_llm_messages_to_gemini, extract_error_message, model_method_url};
+use super::wire::{
+    GenerateContentRequest, GenerateContentResponse, GenerationConfig, ModelsResponse,
+};
+
+fn text_message(author: LLMAuthor, text: &str) -> LLMMessage {
+    LLMMessage {
+        author,
+        sender_id: None,
+        timestamp: chrono::Utc::now(),
+        content: LLMMessageContent::Text(text.to_owned()),
+    }
+}
+
+#[test]
+fn config_round_trips_with_safety_settings() {
+    let yaml = r#"
+base_url: https://generativelanguage.googleapis.com/v1beta
+api_key: test-key
+text_generation:
+  model_id: gemini-2.5-pro
+  max_context_tokens: 1048576
+  safety_settings:
+    - category: HARM_CATEGORY_HARASSMENT
+      threshold: BLOCK_ONLY_HIGH
+text_to_speech:
+  voice: Puck
+"#;
+
+    let config: Config = serde_yaml_ng::from_str(yaml).expect("config should deserialize");
+    assert!(config.validate().is_ok());
+
+    let text_generation = config.text_generation.as_ref().unwrap();
+    assert_eq!(text_generation.model_id, "gemini-2.5-pro");
+    assert_eq!(
+        text_generation.safety_settings,
+        vec![SafetySetting {
+            category: "HARM_CATEGORY_HARASSMENT".to_owned(),
+            threshold: "BLOCK_ONLY_HIGH".to_owned(),
+        }]
+    );
+
+    let text_to_speech = config.text_to_speech.as_ref().unwrap();
+    assert_eq!(text_to_speech.model_id, "gemini-2.5-flash-preview-tts");
+    assert_eq!(text_to_speech.voice.as_deref(), Some("Puck"));
+
+    assert!(config.speech_to_text.is_none());
+    assert!(config.image_generation.is_none());
+}
+
+#[test]
+fn config_validation_rejects_incomplete_safety_settings() {
+    let mut config = Config::default();
+    assert!(config.validate().is_ok());
+
+    config
+        .text_generation
+        .as_mut()
+        .unwrap()
+        .safety_settings
+        .push(SafetySetting {
+            category: "HARM_CATEGORY_HARASSMENT".to_owned(),
+            threshold: "".to_owned(),
+        });
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn supports_purpose_follows_configured_sections() {
+    let controller = Controller::new(Config {
+        speech_to_text: None,
+        image_generation: None,
+        ..Config::default()
+    });
+
+    assert!(controller.supports_purpose(AgentPurpose::TextGeneration));
+    assert!(controller.supports_purpose(AgentPurpose::TextToSpeech));
+    assert!(!controller.supports_purpose(AgentPurpose::SpeechToText));
+    assert!(!controller.supports_purpose(AgentPurpose::ImageGeneration));
+    assert!(controller.supports_purpose(AgentPurpose::CatchAll));
+}
+
+#[test]
+fn prompt_becomes_system_instruction_and_media_is_inlined() {
+    let messages = vec![
+        text_message(LLMAuthor::Prompt, "You are a bot"),
+        text_message(LLMAuthor::User, "describe these"),
+        LLMMessage {
+            author: LLMAuthor::User,
+            sender_id: None,
+            timestamp: chrono::Utc::now(),
+            content: LLMMessageContent::Image(ImageDetails::new(
+                ImageMessageEventContent::plain(
+                    "pic.png".to_owned(),
+                    OwnedMxcUri::from("mxc://example.com/abc"),
+                ),
+                mime::IMAGE_PNG,
+                vec![1, 2, 3],
+            )),
+        },
+        LLMMessage {
+            author: LLMAuthor::User,
+            sender_id: None,
+            timestamp: chrono::Utc::now(),
+            content: LLMMessageContent::File(FileDetails::new(
+                FileMessageEventContent::plain(
+                    "doc.pdf".to_owned(),
+                    OwnedMxcUri::from("mxc://example.com/def"),
+                ),
+                mime::APPLICATION_PDF,
+                vec![4, 5, 6],
+            )),
+        },
+        text_message(LLMAuthor::Assistant, "Sure"),
+    ];
+
+    let (system_instruction, contents) =
+        convert_llm_messages_to_gemini(messages).expect("conversion should succeed");
+
+    let system_instruction = system_instruction.expect("system instruction present");
+    assert_eq!(system_instruction.role, None);
+    assert_eq!(
+        system_instruction.parts[0].text.as_deref(),
+        Some("You are a bot")
+    );
+
+    assert_eq!(contents.len(), 4);
+    assert_eq!(contents[0].role.as_deref(), Some("user"));
+    assert_eq!(contents[3].role.as_deref(), Some("model"));
+
+    let image = contents[1].parts[0].inline_data.as_ref().unwrap();
+    assert_eq!(image.mime_type, "image/png");
+    assert_eq!(image.data, "AQID");
+
+    let file = contents[2].parts[0].inline_data.as_ref().unwrap();
+    assert_eq!(file.mime_type, "application/pdf");
+}
+
+#[test]
+fn request_serializes_in_camel_case_and_omits_unset_fields() {
+    let (system_instruction, contents) =
+        convert_llm_messages_to_gemini(vec![text_message(LLMAuthor::User, "Hi")]).unwrap();
+
+    let request = GenerateContentRequest {
+        contents,
+        system_instruction,
+        tools: Some(function_declarations(vec![ToolDefinition {
+            name: "calculator".to_owned(),
+            description: "Calculates".to_owned(),
+            parameters: serde_json::json!({"type": "object", "additionalProperties": false}),
+        }])),
+        safety_settings: vec![],
+        generation_config: Some(GenerationConfig {
+            temperature: Some(0.5),
+            max_output_tokens: Some(100),
+            ..Default::default()
+        }),
+    };
+
+    let json = serde_json::to_value(&request).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "contents": [{"role": "user", "parts": [{"text": "Hi"}]}],
+            "tools": [{"functionDeclarations": [{
+                "name": "calculator",
+                "description": "Calculates",
+                "parametersJsonSchema": {"type": "object", "additionalProperties": false},
+            }]}],
+            "generationConfig": {"temperature": 0.5, "maxOutputTokens": 100},
+        })
+    );
+}
+
+#[test]
+fn thoughts_are_left_out_of_the_answer_and_signatures_survive_echoing() {
+    let response: GenerateContentResponse = serde_json::from_value(serde_json::json!({
+        "candidates": [{
+            "content": {
+                "role": "model",
+                "parts": [
+                    {"text": "Let me think", "thought": true},
+                    {"text": "Hello, "},
+                    {"text": "world", "thoughtSignature": "c2ln"},
+                    {"functionCall": {"name": "calculator", "args": {"expression": "1+1"}}},
+                ],
+            },
+            "finishReason": "STOP",
+        }],
+    }))
+    .unwrap();
+
+    let content = extract_candidate_content(response).unwrap();
+    assert_eq!(answer_text(&content.parts), "Hello, world");
+
+    let echoed = serde_json::to_value(&content).unwrap();
+    assert_eq!(echoed["parts"][2]["thoughtSignature"], "c2ln");
+    assert_eq!(
+        echoed["parts"][3]["functionCall"]["args"]["expression"],
+        "1+1"
+    );
+}
+
+#[test]
+fn blocked_prompts_and_empty_candidates_are_errors() {
+    let response: GenerateContentResponse = serde_json::from_value(serde_json::json!({
+        "promptFeedback": {"blockReason": "SAFETY"},
+    }))
+    .unwrap();
+    let err = extract_candidate_content(response).unwrap_err();
+    assert!(err.to_string().contains("SAFETY"), "{err}");
+
+    let response: GenerateContentResponse = serde_json::from_value(serde_json::json!({
+        "candidates": [{"finishReason": "RECITATION"}],
+    }))
+    .unwrap();
+    let err = extract_candidate_content(response).unwrap_err();
+    assert!(err.to_string().contains("RECITATION"), "{err}");
+}
+
+#[test]
+fn usage_metadata_counts_thoughts_as_completion() {
+    let response: GenerateContentResponse = serde_json::from_value(serde_json::json!({
+        "candidates": [],
+        "usageMetadata": {
+            "promptTokenCount": 100,
+            "candidatesTokenCount": 20,
+            "thoughtsTokenCount": 30,
+            "cachedContentTokenCount": 40,
+            "totalTokenCount": 150,
+        },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        TokenUsage::from(response.usage_metadata.unwrap()),
+        TokenUsage {
+            prompt_tokens: 100,
+            completion_tokens: 50,
+            reasoning_tokens: 30,
+            cached_tokens: 40,
+        }
+    );
+}
+
+#[test]
+fn model_urls_and_error_messages() {
+    let config = Config {
+        base_url: "https://generativelanguage.googleapis.com/v1beta/".to_owned(),
+        ..Config::default()
+    };
+
+    assert_eq!(
+        model_method_url(&config, "models/gemini-2.5-flash", "generateContent"),
+        "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent"
+    );
+
+    assert_eq!(
+        extract_error_message(
+            r#"{"error": {"code": 400, "message": "Unsupported MIME type", "status": "INVALID_ARGUMENT"}}"#
+        ),
+        "Unsupported MIME type"
+    );
+    assert_eq!(extract_error_message("not json"), "not json");
+}
+
+#[test]
+fn pcm_is_wrapped_in_a_wav_container() {
+    assert_eq!(
+        pcm_sample_rate("audio/L16;codec=pcm;rate=24000"),
+        Some(24000)
+    );
+    assert_eq!(pcm_sample_rate("audio/L16"), None);
+
+    let wav = wrap_pcm_in_wav(&[0, 1, 2, 3], 24000);
+
+    assert_eq!(wav.len(), 44 + 4);
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 36 + 4);
+    assert_eq!(&wav[8..12], b"WAVE");
+    assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 24000);
+    assert_eq!(u32::from_le_bytes(wav[28..32].try_into().unwrap()), 48000);
+    assert_eq!(&wav[36..40], b"data");
+    assert_eq!(&wav[44..], &[0, 1, 2, 3]);
+}
+
+#[test]
+fn models_are_classified_by_their_generation_methods() {
+    let body = r#"{"models":[
+        {"name":"models/gemini-2.5-flash","displayName":"Gemini 2.5 Flash","supportedGenerationMethods":["generateContent","countTokens","createCachedContent"]},
+        {"name":"models/gemini-2.5-flash-preview-tts","displayName":"Gemini 2.5 Flash Preview TTS","supportedGenerationMethods":["countTokens","generateContent"]},
+        {"name":"models/imagen-4.0-generate-001","displayName":"Imagen 4","supportedGenerationMethods":["predict"]},
+        {"name":"models/text-embedding-004","supportedGenerationMethods":["embedContent"]}
+    ],"nextPageToken":""}"#;
+
+    let response: ModelsResponse = serde_json::from_str(body).unwrap();
+
+    assert_eq!(
+        convert_models_response(response),
+        vec![
+            ModelInfo::new(
+                "gemini-2.5-flash".to_owned(),
+                vec![AgentPurpose::TextGeneration, AgentPurpose::SpeechToText]
+            )
+            .with_details(Some("Gemini 2.5 Flash".to_owned())),
+            ModelInfo::new(
+                "gemini-2.5-flash-preview-tts".to_owned(),
+                vec![AgentPurpose::TextToSpeech]
+            )
+            .with_details(Some("Gemini 2.5 Flash Preview TTS".to_owned())),
+            ModelInfo::new(
+                "imagen-4.0-generate-001".to_owned(),
+                vec![AgentPurpose::ImageGeneration]
+            )
+            .with_details(Some("Imagen 4".to_owned())),
+            ModelInfo::new("text-embedding-004".to_owned(), vec![]),
+        ]
+    );
+}
@@ -0,0 +1,147 @@
+use serde::Serialize;
+
+use crate::agent::provider::{ProviderHttpError, retry_after_from_headers};
+use crate::conversation::llm::{
+    Author as LLMAuthor, Message as LLMMessage, MessageContent as LLMMessageContent,
+};
+use crate::utils::base64::base64_encode;
+
+use super::config::Config;
+use super::wire::{Content, ErrorResponse, Part};
+
+/// Gemini caps the whole request at 20MB when media is passed inline (rather than via the Files API).
+/// A single file bigger than that can never fit, so it's rejected upfront with a clear message.
+const MAX_INLINE_DATA_BYTES: usize = 20 * 1024 * 1024;
+
+/// Converts the conversation to Gemini `contents`.
+///
+/// Gemini takes the system prompt separately (as a `systemInstruction`), so prompt messages are pulled out
+/// of the conversation and returned on their own.
+pub fn convert_llm_messages_to_gemini(
+    messages: Vec<LLMMessage>,
+) -> anyhow::Result<(Option<Content>, Vec<Content>)> {
+    let mut system_parts: Vec<Part> = vec![];
+    let mut contents: Vec<Content> = Vec::with_capacity(messages.len());
+
+    for message in messages {
+        let role = match message.author {
+            LLMAuthor::Prompt => {
+                if let LLMMessageContent::Text(text) = message.content {
+                    system_parts.push(Part::text(text));
+                }
+                continue;
+            }
+            LLMAuthor::Assistant => "model",
+            LLMAuthor::User => "user",
+        };
+
+        let part = match message.content {
+            LLMMessageContent::Text(text) => Part::text(text),
+            LLMMessageContent::Image(image_details) => {
+                ensure_inline_data_fits(image_details.data.len())?;
+
+                Part::inline_data(
+                    image_details.mime.essence_str().to_owned(),
+                    base64_encode(&image_details.data),
+                )
+            }
+            LLMMessageContent::File(file_details) => {
+                // PDFs, audio, video and plain-text documents are all understood natively.
+                ensure_inline_data_fits(file_details.data.len())?;
+
+                Part::inline_data(
+                    file_details.mime.essence_str().to_owned(),
+                    base64_encode(&file_details.data),
+                )
+            }
+        };
+
+        contents.push(Content::new(role, vec![part]));
+    }
+
+    let system_instruction = if system_parts.is_empty() {
+        None
+    } else {
+        Some(Content {
+            role: None,
+            parts: system_parts,
+        })
+    };
+
+    Ok((system_instruction, contents))
+}
+
+pub(super) fn ensure_inline_data_fits(size: usize) -> anyhow::Result<()> {
+    if size > MAX_INLINE_DATA_BYTES {
+        return Err(anyhow::anyhow!(
+            "The attached file is too large for Gemini (the limit is 20MB)."
+        ));
+    }
+
+    Ok(())
+}
+
+/// Builds the URL for calling a method (e.g. `generateContent`) on a model.
+pub(super) fn model_method_url(config: &Config, model_id: &str, method: &str) -> String {
+    format!(
+        "{}/models/{}:{}",
+        config.base_url.trim_end_matches('/'),
+        model_id.trim_start_matches("models/"),
+        method
+    )
+}
+
+/// Sends a JSON request to the Gemini API and returns the (successful) response with its body still unread.
+///
+/// `operation` describes the request in logs and errors (e.g. "text generation").
+pub(super) async fn post_json(
+    config: &Config,
+    http: &reqwest::Client,
+    url: &str,
+    body: &impl Serialize,
+    operation: &str,
+) -> anyhow::Result<reqwest::Response> {
+    let response = http
+        .post(url)
+        .header("x-goog-api-key", &config.api_key)
+        .json(body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after_from_headers(response.headers());
+
+    let body = response.text().await.unwrap_or_default();
+    tracing::warn!(%status, body, operation, "Gemini API request failed");
+
+    // A 400 is a complaint about the request we built (unsupported file type, unknown safety category, etc.),
+    // so the explanation is useful to show. For other statuses, the body may contain account details,
+    // so it stays in the logs only.
+    if status == reqwest::StatusCode::BAD_REQUEST {
+        return Err(anyhow::anyhow!(
+            "Gemini rejected the {} request (400 Bad Request): {}",
+            operation,
+            extract_error_message(&body)
+        ));
+    }
+
+    Err(anyhow::Error::new(
+        ProviderHttpError::new(
+            status,
+            format!("Gemini {operation} request failed with status {status}"),
+        )
+        .with_retry_after(retry_after),
+    ))
+}
+
+/// Extracts the human-readable message out of a Gemini error body, falling back to the raw body.
+pub(super) fn extract_error_message(body: &str) -> String {
+    match serde_json::from_str::<ErrorResponse>(body) {
+        Ok(response) if !response.error.message.is_empty() => response.error.message,
+        _ => body.trim().to_owned(),
+    }
+}
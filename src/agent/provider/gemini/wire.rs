@@ -0,0 +1,329 @@
+//! Serde structs modeling the Gemini API's `:generateContent` / `:streamGenerateContent`,
+//! Imagen's `:predict` and the `/models` listing wire shapes. Gemini uses camelCase field names throughout.
+//!
+//! `Content` and `Part` are used in both directions: a model turn that asked for tool calls is
+//! echoed back verbatim, which keeps any `thoughtSignature` the model attached (thinking models
+//! refuse to continue a function-calling turn without it).
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::provider::TokenUsage;
+
+use super::config::SafetySetting;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentRequest {
+    pub contents: Vec<Content>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_instruction: Option<Content>,
+
+    /// Client-side tools (function calling). Omitted when the agent has none enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Tool>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Content {
+    /// `user` or `model`. Absent on the system instruction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+
+    #[serde(default)]
+    pub parts: Vec<Part>,
+}
+
+impl Content {
+    pub fn new(role: &str, parts: Vec<Part>) -> Self {
+        Self {
+            role: Some(role.to_owned()),
+            parts,
+        }
+    }
+}
+
+/// A part carries exactly one kind of payload. Modeling it as a struct of optional fields (instead
+/// of an enum) lets unknown payload kinds and extra metadata pass through without failing to parse.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_data: Option<Blob>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<FunctionCall>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_response: Option<FunctionResponse>,
+
+    /// Set on parts which contain the model's thinking rather than its answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought_signature: Option<String>,
+}
+
+impl Part {
+    pub fn text(text: String) -> Self {
+        Self {
+            text: Some(text),
+            ..Default::default()
+        }
+    }
+
+    pub fn inline_data(mime_type: String, data: String) -> Self {
+        Self {
+            inline_data: Some(Blob { mime_type, data }),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the text of this part, unless it's the model's thinking (or not a text part at all).
+    pub fn answer_text(&self) -> Option<&str> {
+        if self.thought == Some(true) {
+            return None;
+        }
+
+        self.text.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Blob {
+    pub mime_type: String,
+
+    /// Base64-encoded bytes.
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// Only some models assign IDs to function calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    pub name: String,
+
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    pub name: String,
+
+    /// Must be a JSON object.
+    pub response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    pub function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionDeclaration {
+    pub name: String,
+
+    pub description: String,
+
+    /// Sent as full JSON Schema (`parametersJsonSchema`) rather than as the OpenAPI subset
+    /// (`parameters`), because tool schemas (especially those coming from MCP servers) regularly use
+    /// keywords the subset rejects (`additionalProperties`, `$schema`, etc.)
+    pub parameters_json_schema: serde_json::Value,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_modalities: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speech_config: Option<SpeechConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking_config: Option<ThinkingConfig>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThinkingConfig {
+    pub thinking_budget: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechConfig {
+    pub voice_config: VoiceConfig,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceConfig {
+    pub prebuilt_voice_config: PrebuiltVoiceConfig,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrebuiltVoiceConfig {
+    pub voice_name: String,
+}
+
+/// Both the regular response and each chunk of a streamed response have this shape.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentResponse {
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+
+    #[serde(default)]
+    pub prompt_feedback: Option<PromptFeedback>,
+
+    /// Cumulative, so on a streamed response the last chunk's is the one that counts.
+    #[serde(default)]
+    pub usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    #[serde(default)]
+    pub content: Option<Content>,
+
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+    #[serde(default)]
+    pub prompt_token_count: u64,
+
+    /// Excludes the thinking, which is counted separately in `thoughts_token_count`.
+    #[serde(default)]
+    pub candidates_token_count: u64,
+
+    #[serde(default)]
+    pub thoughts_token_count: u64,
+
+    #[serde(default)]
+    pub cached_content_token_count: u64,
+}
+
+impl From<UsageMetadata> for TokenUsage {
+    fn from(usage: UsageMetadata) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count + usage.thoughts_token_count,
+            reasoning_tokens: usage.thoughts_token_count,
+            cached_tokens: usage.cached_content_token_count,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFeedback {
+    #[serde(default)]
+    pub block_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PredictRequest {
+    pub instances: Vec<PredictInstance>,
+
+    pub parameters: PredictParameters,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PredictInstance {
+    pub prompt: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictParameters {
+    pub sample_count: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub person_generation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PredictResponse {
+    #[serde(default)]
+    pub predictions: Vec<Prediction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prediction {
+    #[serde(default)]
+    pub bytes_base64_encoded: Option<String>,
+
+    #[serde(default)]
+    pub mime_type: Option<String>,
+
+    /// Set instead of the image when Imagen's filters rejected it.
+    #[serde(default)]
+    pub rai_filtered_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModelsResponse {
+    #[serde(default)]
+    pub models: Vec<ModelsResponseEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelsResponseEntry {
+    /// Prefixed with `models/` (e.g. `models/gemini-2.5-flash`).
+    pub name: String,
+
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    /// The methods the model can be called with (`generateContent`, `predict`, `embedContent`, etc.).
+    #[serde(default)]
+    pub supported_generation_methods: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorResponse {
+    pub error: ErrorDetails,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorDetails {
+    #[serde(default)]
+    pub message: String,
+}
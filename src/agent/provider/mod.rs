@@ -2,13 +2,17 @@ pub mod anthropic;
 mod config;
 mod controller;
 mod entity;
+pub mod gemini;
 pub(super) mod groq;
 pub mod localai;
 pub(super) mod mistral;
+pub(crate) mod model_listing;
 pub mod ollama;
 pub mod openai;
 pub mod openai_compat;
 pub(super) mod openrouter;
+pub(crate) mod recovery;
+pub(crate) mod sse;
 pub(super) mod togetherai;
 pub mod venice;
 
@@ -20,8 +24,12 @@ pub use controller::{ControllerTrait, ControllerType};
 
 pub use config::ConfigTrait;
 
+pub use recovery::LearnedUnsupportedFields;
+
 pub use entity::{
     AgentProvider, AgentProviderInfo, ImageEditParams, ImageGenerationParams, ImageSource,
-    PingResult, SpeechToTextParams, SpeechToTextResult, TextGenerationParams,
-    TextGenerationPromptVariables, TextToSpeechParams,
+    ModelInfo, PingResult, ProviderHttpError, ReasoningEffort, SpeechToTextParams,
+    SpeechToTextResult, TextGenerationParams, TextGenerationPromptVariables, TextGenerationResult,
+    TextToSpeechParams, TokenUsage, recognize_http_error, retry_after_from_headers,
+    status_from_error_message,
 };
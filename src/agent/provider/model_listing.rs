@@ -0,0 +1,94 @@
+//! Shared pieces for querying providers' model-listing endpoints (see `ControllerTrait::list_models`).
+//!
+//! Most providers speak the OpenAI `GET /models` dialect (`{"data": [{"id": "..."}, ...]}`),
+//! which carries no capability metadata, so purposes are guessed from the model identifiers.
+//! Providers with richer listings (Venice, Gemini, Ollama) parse their own responses.
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::entity::{ModelInfo, ProviderHttpError, retry_after_from_headers};
+
+/// Sends a model-listing request and deserializes its (successful) JSON response.
+///
+/// `provider` names the provider in logs and errors.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    provider: &str,
+) -> anyhow::Result<T> {
+    let response = request.send().await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let retry_after = retry_after_from_headers(response.headers());
+
+        let body = response.text().await.unwrap_or_default();
+        tracing::warn!(%status, body, provider, "Model listing request failed");
+
+        return Err(anyhow::Error::new(
+            ProviderHttpError::new(
+                status,
+                format!("The {provider} model listing API responded with status {status}"),
+            )
+            .with_retry_after(retry_after),
+        ));
+    }
+
+    let body = response.text().await?;
+
+    serde_json::from_str(&body).map_err(|err| {
+        anyhow::anyhow!("Failed to parse the {provider} model listing response: {err}")
+    })
+}
+
+/// The response of an OpenAI-style `GET /models` endpoint.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ModelList {
+    #[serde(default)]
+    data: Vec<ModelListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelListEntry {
+    id: String,
+}
+
+impl ModelList {
+    pub(crate) fn into_models(self) -> Vec<ModelInfo> {
+        self.data
+            .into_iter()
+            .map(|entry| ModelInfo::from_model_id(entry.id))
+            .collect()
+    }
+}
+
+/// Sorts models by identifier, so that listings are stable regardless of the order the provider returns them in.
+pub(crate) fn sorted(mut models: Vec<ModelInfo>) -> Vec<ModelInfo> {
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    models
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentPurpose;
+
+    #[test]
+    fn parses_openai_style_model_lists() {
+        let body = r#"{"object":"list","data":[{"id":"whisper-1","object":"model","owned_by":"openai-internal"},{"id":"gpt-4o","object":"model","created":1715367049,"owned_by":"system"}]}"#;
+
+        let list: ModelList = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            sorted(list.into_models()),
+            vec![
+                ModelInfo::new("gpt-4o".to_owned(), vec![AgentPurpose::TextGeneration]),
+                ModelInfo::new("whisper-1".to_owned(), vec![AgentPurpose::SpeechToText]),
+            ]
+        );
+
+        // Some servers omit `data` entirely when they have nothing to offer.
+        let list: ModelList = serde_json::from_str(r#"{"object":"list"}"#).unwrap();
+        assert!(list.into_models().is_empty());
+    }
+}
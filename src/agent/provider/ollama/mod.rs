@@ -1,7 +1,24 @@
 // At the time of testing, Ollama can be powered by `openai`, but we use `openai_compat` for better reliability
 // in the event of future updates to `async-openai`.
 
-use super::openai_compat::Config;
+use serde::Deserialize;
+
+use super::super::AgentInstantiationResult;
+use super::controller::ControllerType;
+use super::entity::ModelInfo;
+use super::model_listing;
+use super::openai_compat::{Config, ModelListingApi};
+
+pub fn create_controller_from_yaml_value_config(
+    agent_id: &str,
+    config: serde_yaml_ng::Value,
+) -> AgentInstantiationResult<ControllerType> {
+    super::openai_compat::create_controller_from_yaml_value_config_with_model_listing_api(
+        agent_id,
+        config,
+        ModelListingApi::Ollama,
+    )
+}
 
 pub fn default_config() -> Config {
     let mut config = Config {
@@ -22,3 +39,127 @@ pub fn default_config() -> Config {
 
     config
 }
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+
+    #[serde(default)]
+    details: Option<TagDetails>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TagDetails {
+    #[serde(default)]
+    families: Option<Vec<String>>,
+
+    #[serde(default)]
+    parameter_size: Option<String>,
+
+    #[serde(default)]
+    quantization_level: Option<String>,
+}
+
+/// Lists the locally available models via Ollama's native `/api/tags` endpoint.
+///
+/// Agents are configured with the OpenAI-compatible base URL (ending with `/v1`), while the native API lives at the root.
+pub(super) async fn list_models(
+    http: &reqwest::Client,
+    base_url: &str,
+) -> anyhow::Result<Vec<ModelInfo>> {
+    let url = format!("{}/api/tags", native_api_base_url(base_url));
+
+    let response: TagsResponse = model_listing::get_json(http.get(url), "Ollama").await?;
+
+    Ok(convert_tags(response))
+}
+
+fn native_api_base_url(base_url: &str) -> &str {
+    let base_url = base_url.trim_end_matches('/');
+    base_url.strip_suffix("/v1").unwrap_or(base_url)
+}
+
+fn convert_tags(response: TagsResponse) -> Vec<ModelInfo> {
+    response
+        .models
+        .into_iter()
+        .map(|tag| {
+            let details = tag.details.unwrap_or_default();
+
+            let mut model = ModelInfo::from_model_id(tag.name);
+
+            // Embedding models (`nomic-embed-text`, `mxbai-embed-large`, etc.) are BERT-based,
+            // which their family gives away even when their name doesn't.
+            let is_bert_based = details
+                .families
+                .iter()
+                .flatten()
+                .any(|family| family.contains("bert"));
+            if is_bert_based {
+                model.purposes.clear();
+            }
+
+            let notes = [details.parameter_size, details.quantization_level]
+                .into_iter()
+                .flatten()
+                .filter(|note| !note.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            model.with_details(Some(notes))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentPurpose;
+
+    #[test]
+    fn derives_the_native_api_base_url() {
+        assert_eq!(
+            native_api_base_url("http://ollama:11434/v1"),
+            "http://ollama:11434"
+        );
+        assert_eq!(
+            native_api_base_url("http://ollama:11434/v1/"),
+            "http://ollama:11434"
+        );
+        assert_eq!(
+            native_api_base_url("http://ollama:11434"),
+            "http://ollama:11434"
+        );
+    }
+
+    #[test]
+    fn converts_tags() {
+        let body = r#"{"models":[
+            {"name":"gemma2:2b","model":"gemma2:2b","size":1629518495,"details":{"format":"gguf","family":"gemma2","families":["gemma2"],"parameter_size":"2.6B","quantization_level":"Q4_0"}},
+            {"name":"all-minilm:latest","model":"all-minilm:latest","details":{"family":"bert","families":["bert"],"parameter_size":"23M","quantization_level":"F16"}},
+            {"name":"custom:latest"}
+        ]}"#;
+
+        let response: TagsResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(
+            convert_tags(response),
+            vec![
+                ModelInfo::new("gemma2:2b".to_owned(), vec![AgentPurpose::TextGeneration])
+                    .with_details(Some("2.6B, Q4_0".to_owned())),
+                ModelInfo::new("all-minilm:latest".to_owned(), vec![])
+                    .with_details(Some("23M, F16".to_owned())),
+                ModelInfo::new(
+                    "custom:latest".to_owned(),
+                    vec![AgentPurpose::TextGeneration]
+                ),
+            ]
+        );
+    }
+}
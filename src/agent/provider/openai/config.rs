@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::OPENAI_IMAGE_MODEL_GPT_IMAGE_2;
-use crate::agent::{default_prompt, provider::ConfigTrait};
+use crate::agent::{default_prompt, provider::ConfigTrait, tool::mcp::McpToolsConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -41,6 +41,11 @@ impl ConfigTrait for Config {
             return Err("The base URL must not be empty.".to_owned());
         }
 
+        if let Some(text_generation) = &self.text_generation {
+            crate::agent::tool::validate_enabled_tool_names(&text_generation.tools.enabled)?;
+            text_generation.tools.mcp.validate()?;
+        }
+
         Ok(())
     }
 }
@@ -89,11 +94,19 @@ fn default_text_model_id() -> String {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ToolsConfig {
+    // Built-in tools, which run on OpenAI's infrastructure
     #[serde(default)]
     pub web_search: bool,
 
     #[serde(default)]
     pub code_interpreter: bool,
+
+    // Client-side tools, which baibot runs itself. See `crate::agent::tool`.
+    #[serde(default)]
+    pub enabled: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "McpToolsConfig::is_default")]
+    pub mcp: McpToolsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,5 +1,7 @@
 use std::ops::Deref;
 
+use futures_util::StreamExt;
+
 use async_openai::{
     Client as OpenAIClient,
     config::OpenAIConfig,
@@ -11,20 +13,28 @@ use async_openai::{
         },
         responses::{
             CodeInterpreterContainerAuto, CodeInterpreterTool, CodeInterpreterToolContainer,
-            CreateResponseArgs, OutputItem, OutputMessageContent, Tool, WebSearchTool,
+            CreateResponse, CreateResponseArgs, FunctionCallOutput, FunctionCallOutputItemParam,
+            FunctionTool, InputItem, InputParam, Item, OutputItem, OutputMessageContent,
+            ReasoningArgs, ReasoningEffort as OpenAIReasoningEffort, Response, ResponseStreamEvent,
+            Tool, WebSearchTool,
         },
     },
 };
 
 use super::super::ControllerTrait;
+use super::super::model_listing::{self, ModelList};
+use super::super::recovery::UnsupportedFieldsCache;
 use crate::{
     agent::provider::{
-        ImageEditParams, ImageGenerationParams, SpeechToTextParams, SpeechToTextResult,
-        entity::{TextGenerationParams, TextGenerationResult},
+        ImageEditParams, ImageGenerationParams, LearnedUnsupportedFields, ReasoningEffort,
+        SpeechToTextParams, SpeechToTextResult,
+        entity::{TextGenerationParams, TextGenerationResult, TokenUsage},
     },
+    agent::tool::{MAX_TOOL_CALL_ROUNDS, ToolCall, ToolRegistry, mcp::McpToolsConfig},
     conversation::llm::{
-        Author as LLMAuthor, Conversation as LLMConversation, Message as LLMMessage,
-        MessageContent as LLMMessageContent, TokenEstimate, shorten_messages_list_to_context_size,
+        Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost,
+        Message as LLMMessage, MessageContent as LLMMessageContent, TokenEstimate,
+        shorten_messages_list_to_context_size,
     },
     utils::base64::base64_decode,
 };
@@ -32,8 +42,8 @@ use crate::{
     agent::{
         AgentPurpose,
         provider::entity::{
-            ImageEditResult, ImageGenerationResult, ImageSource, PingResult, TextToSpeechParams,
-            TextToSpeechResult,
+            ImageEditResult, ImageGenerationResult, ImageSource, ModelInfo, PingResult,
+            TextToSpeechParams, TextToSpeechResult,
         },
     },
     strings,
@@ -45,6 +55,11 @@ use super::config::Config;
 pub struct Controller {
     config: Config,
     client: OpenAIClient<OpenAIConfig>,
+    // Used for the requests we don't make through the library (listing models).
+    http: reqwest::Client,
+    // Per-model record of request fields which were rejected as unsupported, learned at runtime.
+    unsupported_fields: UnsupportedFieldsCache,
+    tools: ToolRegistry,
 }
 
 impl Controller {
@@ -55,36 +70,66 @@ impl Controller {
 
         let client = OpenAIClient::with_config(openai_config);
 
-        Self { config, client }
-    }
-}
+        let tools = match &config.text_generation {
+            Some(text_generation_config) => {
+                ToolRegistry::with_builtin_tools(&text_generation_config.tools.enabled)
+            }
+            None => ToolRegistry::default(),
+        };
 
-impl ControllerTrait for Controller {
-    async fn ping(&self) -> anyhow::Result<PingResult> {
-        if !self.supports_purpose(AgentPurpose::TextGeneration) {
-            return Ok(PingResult::Inconclusive);
+        Self {
+            config,
+            client,
+            http: reqwest::Client::new(),
+            unsupported_fields: UnsupportedFieldsCache::default(),
+            tools,
         }
+    }
 
-        let messages = vec![LLMMessage {
-            author: LLMAuthor::User,
-            sender_id: None,
-            content: LLMMessageContent::Text("Hello!".to_string()),
-            timestamp: chrono::Utc::now(),
-        }];
-
-        let conversation = LLMConversation { messages };
+    // Creates a response, recovering from rejected unsupported fields along the way.
+    //
+    // When a droppable field is the reason for the request being rejected, it gets stripped
+    // (and remembered for this model) and the request is retried.
+    // Each retry strips a distinct field, so the loop is bounded.
+    async fn create_response(
+        &self,
+        model_id: &str,
+        request: &mut CreateResponse,
+    ) -> anyhow::Result<Response> {
+        self.unsupported_fields.strip_known(model_id, request);
+
+        loop {
+            match self.client.responses().create(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(err) => {
+                    if self.unsupported_fields.recover(
+                        "openai",
+                        model_id,
+                        request,
+                        super::utils::rejected_field_from_error(&err),
+                    ) {
+                        continue;
+                    }
 
-        self.generate_text(conversation, TextGenerationParams::default())
-            .await?;
+                    return Err(err.into());
+                }
+            }
+        }
+    }
 
-        Ok(PingResult::Successful)
+    /// Returns the model text gets generated with, taking the overriding one (if any) into account.
+    fn text_generation_model_id_for(&self, params: &TextGenerationParams) -> Option<String> {
+        self.text_generation_model_id()
+            .map(|model_id| params.model_id(&model_id))
     }
 
-    async fn generate_text(
+    fn create_text_generation_request(
         &self,
         conversation: LLMConversation,
         params: TextGenerationParams,
-    ) -> anyhow::Result<TextGenerationResult> {
+        tools: &ToolRegistry,
+        stream: bool,
+    ) -> anyhow::Result<CreateResponse> {
         let Some(text_generation_config) = &self.config.text_generation else {
             return Err(anyhow::anyhow!(
                 strings::agent::no_configuration_for_purpose_so_cannot_be_used(
@@ -93,12 +138,12 @@ impl ControllerTrait for Controller {
             ));
         };
 
-        let prompt_text = params.prompt_variables.format(
-            params
-                .prompt_override
-                .unwrap_or(self.text_generation_prompt().unwrap_or("".to_owned()))
-                .trim(),
-        );
+        let model_id = params.model_id(&text_generation_config.model_id);
+
+        let max_response_tokens =
+            params.max_response_tokens(text_generation_config.max_response_tokens);
+
+        let prompt_text = params.prompt_text(self.text_generation_prompt());
 
         let prompt_message = if prompt_text.is_empty() {
             None
@@ -117,10 +162,11 @@ impl ControllerTrait for Controller {
             tracing::trace!("Shortening messages list to context size");
 
             conversation_messages = shorten_messages_list_to_context_size(
-                TokenEstimate::Tiktoken(&text_generation_config.model_id),
+                TokenEstimate::Tiktoken(&model_id),
+                self.text_generation_media_token_cost(),
                 &prompt_message,
                 conversation_messages,
-                text_generation_config.max_response_tokens,
+                max_response_tokens,
                 text_generation_config.max_context_tokens,
             );
 
@@ -146,10 +192,18 @@ impl ControllerTrait for Controller {
         let mut request_builder = CreateResponseArgs::default();
 
         request_builder
-            .model(&text_generation_config.model_id)
+            .model(&model_id)
             .temperature(temperature)
             .input(input);
 
+        if let Some(reasoning_effort) = params.reasoning_effort_override {
+            request_builder.reasoning(
+                ReasoningArgs::default()
+                    .effort(convert_reasoning_effort(reasoning_effort))
+                    .build()?,
+            );
+        }
+
         let mut tools = Vec::new();
         if text_generation_config.tools.web_search {
             tools.push(Tool::WebSearch(WebSearchTool::default()));
@@ -161,17 +215,29 @@ impl ControllerTrait for Controller {
                 ),
             }));
         }
+        for definition in tools.definitions() {
+            tools.push(Tool::Function(FunctionTool {
+                name: definition.name,
+                description: Some(definition.description),
+                parameters: Some(definition.parameters),
+                ..Default::default()
+            }));
+        }
 
         if !tools.is_empty() {
             request_builder.tools(tools);
         }
 
-        if let Some(max_response_tokens) = text_generation_config.max_response_tokens {
+        if let Some(max_response_tokens) = max_response_tokens {
             request_builder.max_output_tokens(max_response_tokens);
         } else if let Some(max_completion_tokens) = text_generation_config.max_completion_tokens {
             request_builder.max_output_tokens(max_completion_tokens);
         }
 
+        if stream {
+            request_builder.stream(true);
+        }
+
         let request = request_builder.build()?;
 
         if let Ok(request_as_json) = serde_json::to_string(&request) {
@@ -183,25 +249,207 @@ impl ControllerTrait for Controller {
             );
         }
 
-        let response = self.client.responses().create(request).await?;
+        Ok(request)
+    }
+}
 
-        tracing::trace!(?response, "Got response from the OpenAI response API");
+fn extract_text_generation_result(
+    response: Response,
+    earlier_usage: TokenUsage,
+) -> anyhow::Result<TextGenerationResult> {
+    let mut usage = earlier_usage;
+    usage += super::utils::convert_response_usage(response.usage.as_ref());
+
+    for item in response.output {
+        if let OutputItem::Message(message) = item {
+            for content in message.content {
+                if let OutputMessageContent::OutputText(text_content) = content {
+                    return Ok(TextGenerationResult {
+                        text: text_content.text,
+                        usage,
+                    });
+                }
+            }
+        }
+    }
 
-        for item in response.output {
-            if let OutputItem::Message(message) = item {
-                for content in message.content {
-                    if let OutputMessageContent::OutputText(text_content) = content {
-                        return Ok(TextGenerationResult {
-                            text: text_content.text,
-                        });
-                    }
+    Err(anyhow::anyhow!(
+        "No response messages choices were returned from the OpenAI response API"
+    ))
+}
+
+fn convert_reasoning_effort(reasoning_effort: ReasoningEffort) -> OpenAIReasoningEffort {
+    match reasoning_effort {
+        ReasoningEffort::Minimal => OpenAIReasoningEffort::Minimal,
+        ReasoningEffort::Low => OpenAIReasoningEffort::Low,
+        ReasoningEffort::Medium => OpenAIReasoningEffort::Medium,
+        ReasoningEffort::High => OpenAIReasoningEffort::High,
+    }
+}
+
+impl ControllerTrait for Controller {
+    async fn ping(&self) -> anyhow::Result<PingResult> {
+        if !self.supports_purpose(AgentPurpose::TextGeneration) {
+            return Ok(PingResult::Inconclusive);
+        }
+
+        let messages = vec![LLMMessage {
+            author: LLMAuthor::User,
+            sender_id: None,
+            content: LLMMessageContent::Text("Hello!".to_string()),
+            timestamp: chrono::Utc::now(),
+        }];
+
+        let conversation = LLMConversation { messages };
+
+        self.generate_text(conversation, TextGenerationParams::default())
+            .await?;
+
+        Ok(PingResult::Successful)
+    }
+
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
+        let url = format!("{}/models", self.config.base_url.trim_end_matches('/'));
+
+        let request = self.http.get(url).bearer_auth(&self.config.api_key);
+
+        let list: ModelList = model_listing::get_json(request, "OpenAI").await?;
+
+        Ok(model_listing::sorted(list.into_models()))
+    }
+
+    async fn generate_text(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+    ) -> anyhow::Result<TextGenerationResult> {
+        let tools = self.tools.merged_with(&params.tools);
+
+        let model_id = self
+            .text_generation_model_id_for(&params)
+            .unwrap_or_default();
+
+        let mut request =
+            self.create_text_generation_request(conversation, params, &tools, false)?;
+
+        let mut usage = TokenUsage::default();
+
+        for _ in 0..MAX_TOOL_CALL_ROUNDS {
+            let response = self.create_response(&model_id, &mut request).await?;
+
+            tracing::trace!(?response, "Got response from the OpenAI response API");
+
+            let tool_calls: Vec<ToolCall> = response
+                .output
+                .iter()
+                .filter_map(|item| match item {
+                    OutputItem::FunctionCall(call) => Some(ToolCall {
+                        id: call.call_id.clone(),
+                        name: call.name.clone(),
+                        arguments: call.arguments.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect();
+
+            if tool_calls.is_empty() {
+                return extract_text_generation_result(response, usage);
+            }
+
+            usage += super::utils::convert_response_usage(response.usage.as_ref());
+
+            let mut outputs = Vec::with_capacity(tool_calls.len());
+            for tool_call in tool_calls {
+                let output = tools.call(&tool_call).await;
+
+                outputs.push(InputItem::Item(Item::FunctionCallOutput(
+                    FunctionCallOutputItemParam {
+                        call_id: tool_call.id,
+                        output: FunctionCallOutput::Text(output),
+                        id: None,
+                        status: None,
+                    },
+                )));
+            }
+
+            // Continue from the (stored) previous response instead of replaying the whole conversation.
+            // This also carries over any reasoning items, which reasoning models require next to their function calls.
+            request.previous_response_id = Some(response.id);
+            request.input = InputParam::Items(outputs);
+        }
+
+        Err(anyhow::anyhow!(strings::agent::tool_call_rounds_exhausted(
+            MAX_TOOL_CALL_ROUNDS
+        )))
+    }
+
+    async fn generate_text_streaming(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> anyhow::Result<TextGenerationResult> {
+        // Tool calls need a request/response loop, so replies which may involve them are not streamed.
+        if !self.tools.is_empty() || !params.tools.is_empty() {
+            return self.generate_text(conversation, params).await;
+        }
+
+        let model_id = self.text_generation_model_id_for(&params);
+
+        let mut request =
+            self.create_text_generation_request(conversation, params, &self.tools, true)?;
+
+        // Fields learned to be unsupported (while not streaming) are not sent while streaming either.
+        if let Some(model_id) = model_id {
+            self.unsupported_fields.strip_known(&model_id, &mut request);
+        }
+
+        let mut stream = self.client.responses().create_stream(request).await?;
+
+        let mut text = String::new();
+
+        while let Some(event) = stream.next().await {
+            match event? {
+                ResponseStreamEvent::ResponseOutputTextDelta(event) => {
+                    text.push_str(&event.delta);
+                    partial_text.send_replace(text.clone());
+                }
+                ResponseStreamEvent::ResponseCompleted(event) => {
+                    tracing::trace!(
+                        response = ?event.response,
+                        "Got streamed response from the OpenAI response API"
+                    );
+
+                    return extract_text_generation_result(event.response, TokenUsage::default());
+                }
+                ResponseStreamEvent::ResponseFailed(event) => {
+                    return Err(anyhow::anyhow!(
+                        "The OpenAI response API reported a failed response: {:?}",
+                        event.response.error
+                    ));
                 }
+                ResponseStreamEvent::ResponseError(event) => {
+                    return Err(anyhow::anyhow!(
+                        "The OpenAI response API reported an error while streaming: {}",
+                        event.message
+                    ));
+                }
+                _ => {}
             }
         }
 
-        Err(anyhow::anyhow!(
-            "No response messages choices were returned from the OpenAI response API"
-        ))
+        // The stream ended without a completion event. Whatever text we've accumulated is still
+        // better than nothing, even though usage is unknown in this case.
+        if text.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The OpenAI response API stream ended without returning any text"
+            ));
+        }
+
+        Ok(TextGenerationResult {
+            text,
+            usage: TokenUsage::default(),
+        })
     }
 
     async fn speech_to_text(
@@ -583,6 +831,35 @@ impl ControllerTrait for Controller {
             .map(|config| config.temperature)
     }
 
+    fn text_generation_max_response_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .and_then(|config| config.max_response_tokens)
+    }
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.max_context_tokens)
+    }
+
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost {
+        MediaTokenCost::OpenAI
+    }
+
+    fn text_generation_tools(&self) -> ToolRegistry {
+        self.tools.clone()
+    }
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.tools.mcp.clone())
+    }
+
     fn text_to_speech_voice(&self) -> Option<String> {
         let Some(text_to_speech_config) = &self.config.text_to_speech else {
             return None;
@@ -600,6 +877,10 @@ impl ControllerTrait for Controller {
 
         Some(text_to_speech_config.speed)
     }
+
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields {
+        self.unsupported_fields.learned()
+    }
 }
 
 fn response_format_to_mime_type(
@@ -10,6 +10,7 @@ pub(super) use config::ImageGenerationConfig;
 pub(super) use config::SpeechToTextConfig;
 pub(super) use config::TextGenerationConfig;
 pub(super) use config::TextToSpeechConfig;
+pub(super) use config::ToolsConfig;
 
 use super::super::AgentInstantiationError;
 use super::super::AgentInstantiationResult;
@@ -1,6 +1,12 @@
+use async_openai::error::OpenAIError;
 use async_openai::types::responses::{
-    EasyInputContent, EasyInputMessage, ImageDetail, InputContent, InputFileArgs,
-    InputImageContent, InputItem, InputParam, MessageType, Role,
+    CreateResponse, EasyInputContent, EasyInputMessage, ImageDetail, InputContent, InputFileArgs,
+    InputImageContent, InputItem, InputParam, MessageType, ResponseUsage, Role,
+};
+
+use crate::agent::provider::TokenUsage;
+use crate::agent::provider::recovery::{
+    RecoverableRequest, is_unsupported_field_error, parse_rejected_field,
 };
 
 use crate::conversation::llm::{
@@ -62,3 +68,64 @@ pub fn convert_llm_messages_to_openai_response_input(
 
     InputParam::Items(items)
 }
+
+pub fn convert_response_usage(usage: Option<&ResponseUsage>) -> TokenUsage {
+    let Some(usage) = usage else {
+        return TokenUsage::default();
+    };
+
+    TokenUsage {
+        prompt_tokens: u64::from(usage.input_tokens),
+        completion_tokens: u64::from(usage.output_tokens),
+        reasoning_tokens: u64::from(usage.output_tokens_details.reasoning_tokens),
+        cached_tokens: u64::from(usage.input_tokens_details.cached_tokens),
+    }
+}
+
+/// Request fields baibot may drop when a model rejects them as unsupported.
+///
+/// Reasoning models refuse sampling knobs like `temperature` and `top_p`, and OpenAI-compatible
+/// servers reached via this provider may not know about `max_output_tokens`.
+/// The model cannot honor such a field anyway, so dropping it is the only way to get an answer.
+pub(super) const DROPPABLE_RESPONSE_FIELDS: &[&str] =
+    &["temperature", "top_p", "max_output_tokens"];
+
+impl RecoverableRequest for CreateResponse {
+    fn strip_unsupported_field(&mut self, field: &str) -> bool {
+        if !DROPPABLE_RESPONSE_FIELDS.contains(&field) {
+            return false;
+        }
+
+        match field {
+            "temperature" => self.temperature.take().is_some(),
+            "top_p" => self.top_p.take().is_some(),
+            "max_output_tokens" => self.max_output_tokens.take().is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// Tells which request field (if any) an API error rejects as unsupported.
+///
+/// OpenAI reports it as the error's `param`, while some compatible servers only mention it in the message.
+/// Errors about a field's value (e.g. an out-of-range `temperature`) name the field too, but are not about it being unsupported.
+pub(super) fn rejected_field_from_error(err: &OpenAIError) -> Option<String> {
+    let OpenAIError::ApiError(api_error) = err else {
+        return None;
+    };
+
+    let codes: Vec<&str> = [api_error.code.as_deref(), api_error.r#type.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if !is_unsupported_field_error(&codes, &api_error.message) {
+        return None;
+    }
+
+    api_error
+        .param
+        .clone()
+        .filter(|param| !param.is_empty())
+        .or_else(|| parse_rejected_field(&api_error.message))
+}
@@ -5,10 +5,11 @@ use crate::agent::provider::openai::{
     ImageGenerationConfig as OpenAIImageGenerationConfig,
     SpeechToTextConfig as OpenAISpeechToTextConfig,
     TextGenerationConfig as OpenAITextGenerationConfig,
-    TextToSpeechConfig as OpenAITextToSpeechConfig,
+    TextToSpeechConfig as OpenAITextToSpeechConfig, ToolsConfig as OpenAIToolsConfig,
 };
 
 use crate::agent::provider::ConfigTrait;
+use crate::agent::tool::ToolsConfig;
 
 use super::utils::convert_string_to_enum;
 
@@ -50,6 +51,10 @@ impl ConfigTrait for Config {
             return Err("The base URL must not be empty.".to_owned());
         }
 
+        if let Some(text_generation) = &self.text_generation {
+            text_generation.tools.validate()?;
+        }
+
         Ok(())
     }
 }
@@ -70,6 +75,14 @@ pub struct TextGenerationConfig {
 
     #[serde(default)]
     pub max_context_tokens: u32,
+
+    #[serde(default)]
+    pub tools: ToolsConfig,
+
+    /// Path to the model's HuggingFace `tokenizer.json` file, for counting tokens accurately when trimming
+    /// the conversation to `max_context_tokens`. Without it, tokens are approximated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer_path: Option<String>,
 }
 
 impl Default for TextGenerationConfig {
@@ -80,6 +93,8 @@ impl Default for TextGenerationConfig {
             temperature: super::super::default_temperature(),
             max_response_tokens: Some(4096),
             max_context_tokens: 128_000,
+            tools: ToolsConfig::default(),
+            tokenizer_path: None,
         }
     }
 }
@@ -95,7 +110,11 @@ impl TryInto<OpenAITextGenerationConfig> for TextGenerationConfig {
             max_response_tokens: self.max_response_tokens,
             max_completion_tokens: None,
             max_context_tokens: self.max_context_tokens,
-            tools: Default::default(),
+            tools: OpenAIToolsConfig {
+                enabled: self.tools.enabled,
+                mcp: self.tools.mcp,
+                ..Default::default()
+            },
         })
     }
 }
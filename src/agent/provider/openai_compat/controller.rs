@@ -1,28 +1,38 @@
 use etke_openai_api_rust::audio::{AudioApi, AudioBody};
-use etke_openai_api_rust::chat::{ChatApi, ChatBody};
+use etke_openai_api_rust::chat::ChatBody;
 use etke_openai_api_rust::images::{ImagesApi, ImagesBody};
 use etke_openai_api_rust::{Auth, Message, OpenAI};
+use std::sync::Arc;
+use tokenizers::Tokenizer;
 
 const SMALLEST_IMAGE_SIZE: &str = "256x256";
 
 use super::super::ControllerTrait;
+use super::super::model_listing::{self, ModelList};
+use super::super::recovery::{UnsupportedFieldsCache, parse_unsupported_field};
+use super::super::sse::EventStreamReader;
+use crate::agent::tool::{
+    MAX_TOOL_CALL_ROUNDS, ToolCall, ToolRegistry, chat_completions::chat_tools, mcp::McpToolsConfig,
+};
 use crate::utils::base64::base64_decode;
 use crate::{
     agent::provider::{
-        ImageEditParams, ImageGenerationParams, ImageSource, SpeechToTextParams,
-        SpeechToTextResult,
-        entity::{TextGenerationParams, TextGenerationResult},
+        ImageEditParams, ImageGenerationParams, ImageSource, LearnedUnsupportedFields,
+        ProviderHttpError, SpeechToTextParams, SpeechToTextResult,
+        entity::{TextGenerationParams, TextGenerationResult, TokenUsage},
+        recognize_http_error, retry_after_from_headers,
     },
     conversation::llm::{
-        Author as LLMAuthor, Conversation as LLMConversation, Message as LLMMessage,
-        MessageContent as LLMMessageContent, TokenEstimate, shorten_messages_list_to_context_size,
+        Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost,
+        Message as LLMMessage, MessageContent as LLMMessageContent, TokenEstimate,
+        shorten_messages_list_to_context_size,
     },
 };
 use crate::{
     agent::{
         AgentPurpose,
         provider::entity::{
-            ImageEditResult, ImageGenerationResult, PingResult, TextToSpeechParams,
+            ImageEditResult, ImageGenerationResult, ModelInfo, PingResult, TextToSpeechParams,
             TextToSpeechResult,
         },
     },
@@ -30,11 +40,32 @@ use crate::{
 };
 
 use super::Config;
+use super::utils::ChatCompletionRequestBody;
+
+/// The dialect of the model-listing endpoint to query (see `ControllerTrait::list_models`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ModelListingApi {
+    /// The OpenAI-style `GET /models` endpoint, relative to the base URL.
+    #[default]
+    OpenAI,
+
+    /// Ollama's native `GET /api/tags` endpoint, which (unlike its OpenAI-compatible one) reports model details.
+    Ollama,
+}
 
 #[derive(Debug, Clone)]
 pub struct Controller {
     config: Config,
     client: OpenAI,
+    // Used for text generation, which the `etke_openai_api_rust` library cannot do to the extent we need
+    // (streaming, tool calls, recovering from rejected fields).
+    http: reqwest::Client,
+    // Per-model record of chat fields this server has rejected as unsupported, learned at runtime.
+    unsupported_fields: UnsupportedFieldsCache,
+    tools: ToolRegistry,
+    model_listing_api: ModelListingApi,
+    // Loaded from `text_generation.tokenizer_path`, if configured.
+    tokenizer: Option<Arc<Tokenizer>>,
 }
 
 impl Controller {
@@ -52,36 +83,40 @@ impl Controller {
 
         let client = OpenAI::new(auth, &base_url);
 
-        Self { config, client }
-    }
-}
+        let tools = match &config.text_generation {
+            Some(text_generation_config) => {
+                ToolRegistry::with_builtin_tools(&text_generation_config.tools.enabled)
+            }
+            None => ToolRegistry::default(),
+        };
 
-impl ControllerTrait for Controller {
-    async fn ping(&self) -> anyhow::Result<PingResult> {
-        if !self.supports_purpose(AgentPurpose::TextGeneration) {
-            return Ok(PingResult::Inconclusive);
+        Self {
+            config,
+            client,
+            http: reqwest::Client::new(),
+            unsupported_fields: UnsupportedFieldsCache::default(),
+            tools,
+            model_listing_api: ModelListingApi::default(),
+            tokenizer: None,
         }
+    }
 
-        let messages = vec![LLMMessage {
-            author: LLMAuthor::User,
-            sender_id: None,
-            content: LLMMessageContent::Text("Hello!".to_string()),
-            timestamp: chrono::Utc::now(),
-        }];
-
-        let conversation = LLMConversation { messages };
-
-        self.generate_text(conversation, TextGenerationParams::default())
-            .await?;
+    pub fn with_model_listing_api(mut self, model_listing_api: ModelListingApi) -> Self {
+        self.model_listing_api = model_listing_api;
+        self
+    }
 
-        Ok(PingResult::Successful)
+    pub fn with_tokenizer(mut self, tokenizer: Option<Arc<Tokenizer>>) -> Self {
+        self.tokenizer = tokenizer;
+        self
     }
 
-    async fn generate_text(
+    fn create_chat_body(
         &self,
         conversation: LLMConversation,
         params: TextGenerationParams,
-    ) -> anyhow::Result<TextGenerationResult> {
+        stream: bool,
+    ) -> anyhow::Result<ChatBody> {
         let Some(text_generation_config) = &self.config.text_generation else {
             return Err(anyhow::anyhow!(
                 strings::agent::no_configuration_for_purpose_so_cannot_be_used(
@@ -90,12 +125,12 @@ impl ControllerTrait for Controller {
             ));
         };
 
-        let prompt_text = params.prompt_variables.format(
-            params
-                .prompt_override
-                .unwrap_or(self.text_generation_prompt().unwrap_or("".to_owned()))
-                .trim(),
-        );
+        let model_id = params.model_id(&text_generation_config.model_id);
+
+        let max_response_tokens =
+            params.max_response_tokens(text_generation_config.max_response_tokens);
+
+        let prompt_text = params.prompt_text(self.text_generation_prompt());
 
         let prompt_message = if prompt_text.is_empty() {
             None
@@ -113,11 +148,17 @@ impl ControllerTrait for Controller {
         if params.context_management_enabled {
             tracing::trace!("Shortening messages list to context size");
 
+            let estimate = match &self.tokenizer {
+                Some(tokenizer) => TokenEstimate::HuggingFace(tokenizer),
+                None => TokenEstimate::Approximate,
+            };
+
             conversation_messages = shorten_messages_list_to_context_size(
-                TokenEstimate::Approximate,
+                estimate,
+                self.text_generation_media_token_cost(),
                 &prompt_message,
                 conversation_messages,
-                text_generation_config.max_response_tokens,
+                max_response_tokens,
                 text_generation_config.max_context_tokens,
             );
 
@@ -137,21 +178,17 @@ impl ControllerTrait for Controller {
             .temperature_override
             .unwrap_or(text_generation_config.temperature);
 
-        let max_tokens = text_generation_config
-            .max_response_tokens
-            .map(|max_response_tokens| {
-                max_response_tokens
-                    .try_into()
-                    .expect("Failed converting max_response_tokens from u32 to i32")
-            });
+        // The library's request type cannot carry more than `i32::MAX`, which no model comes close to anyway.
+        let max_tokens = max_response_tokens
+            .map(|max_response_tokens| i32::try_from(max_response_tokens).unwrap_or(i32::MAX));
 
         let request = ChatBody {
-            model: text_generation_config.model_id.clone(),
+            model: model_id,
             max_tokens,
             temperature: Some(temperature),
             top_p: None,
             n: Some(1),
-            stream: Some(false),
+            stream: Some(stream),
             stop: None,
             presence_penalty: None,
             frequency_penalty: None,
@@ -169,42 +206,275 @@ impl ControllerTrait for Controller {
             );
         }
 
-        // This library is not async-aware, so we need to use `spawn_blocking` to run the request on a separate thread.
-        let client = self.client.clone();
-        let response =
-            tokio::task::spawn_blocking(move || client.chat_completion_create(&request)).await?;
+        Ok(request)
+    }
 
-        let response = match response {
-            Ok(response) => response,
-            Err(err) => {
+    fn create_chat_request_body(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+        stream: bool,
+    ) -> anyhow::Result<ChatCompletionRequestBody> {
+        let reasoning_effort = params.reasoning_effort_override;
+
+        let request = self.create_chat_body(conversation, params, stream)?;
+
+        let mut body = serde_json::to_value(&request)?;
+
+        // The library's request type has no field for this. Servers which reject it get it stripped.
+        if let Some(reasoning_effort) = reasoning_effort {
+            body["reasoning_effort"] = serde_json::json!(reasoning_effort.as_str());
+        }
+
+        // Not all servers report usage while streaming unless asked to.
+        // Those which reject this field get it stripped (see `send_chat_completion_request`).
+        if stream {
+            body["stream_options"] = serde_json::json!({ "include_usage": true });
+        }
+
+        Ok(ChatCompletionRequestBody(body))
+    }
+
+    // Talks to the chat completion API directly, bypassing the library.
+    //
+    // Many servers reject optional fields they do not support (instead of ignoring them), so when a
+    // droppable field is the reason for a 400 (for being unsupported, not for its value), it gets stripped (and remembered for this model) and the
+    // request is retried. Each retry strips a distinct field, so the loop is bounded.
+    async fn send_chat_completion_request(
+        &self,
+        body: &mut ChatCompletionRequestBody,
+    ) -> anyhow::Result<reqwest::Response> {
+        let url = format!(
+            "{}/chat/completions",
+            self.config.base_url.trim_end_matches('/')
+        );
+
+        let model_id = body.model_id();
+
+        self.unsupported_fields.strip_known(&model_id, body);
+
+        loop {
+            let mut request_builder = self.http.post(&url).json(&body.0);
+            if let Some(api_key) = &self.config.api_key
+                && !api_key.is_empty()
+            {
+                request_builder = request_builder.bearer_auth(api_key);
+            }
+
+            let response = request_builder.send().await?;
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let retry_after = retry_after_from_headers(response.headers());
+
+            let response_body = response.text().await.unwrap_or_default();
+            tracing::warn!(
+                %status,
+                body = response_body,
+                "OpenAI-compat chat completion request failed"
+            );
+
+            if status == reqwest::StatusCode::BAD_REQUEST
+                && self.unsupported_fields.recover(
+                    "openai-compatible",
+                    &model_id,
+                    body,
+                    parse_unsupported_field(&response_body),
+                )
+            {
+                continue;
+            }
+
+            return Err(anyhow::Error::new(
+                ProviderHttpError::new(
+                    status,
+                    format!("The OpenAI-compat chat completion API responded with status {status}"),
+                )
+                .with_retry_after(retry_after),
+            ));
+        }
+    }
+}
+
+impl ControllerTrait for Controller {
+    async fn ping(&self) -> anyhow::Result<PingResult> {
+        if !self.supports_purpose(AgentPurpose::TextGeneration) {
+            return Ok(PingResult::Inconclusive);
+        }
+
+        let messages = vec![LLMMessage {
+            author: LLMAuthor::User,
+            sender_id: None,
+            content: LLMMessageContent::Text("Hello!".to_string()),
+            timestamp: chrono::Utc::now(),
+        }];
+
+        let conversation = LLMConversation { messages };
+
+        self.generate_text(conversation, TextGenerationParams::default())
+            .await?;
+
+        Ok(PingResult::Successful)
+    }
+
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
+        let models = match self.model_listing_api {
+            ModelListingApi::OpenAI => {
+                let url = format!("{}/models", self.config.base_url.trim_end_matches('/'));
+
+                let mut request_builder = self.http.get(url);
+                if let Some(api_key) = &self.config.api_key
+                    && !api_key.is_empty()
+                {
+                    request_builder = request_builder.bearer_auth(api_key);
+                }
+
+                let list: ModelList =
+                    model_listing::get_json(request_builder, "OpenAI-compat").await?;
+
+                list.into_models()
+            }
+            ModelListingApi::Ollama => {
+                super::super::ollama::list_models(&self.http, &self.config.base_url).await?
+            }
+        };
+
+        Ok(model_listing::sorted(models))
+    }
+
+    async fn generate_text(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+    ) -> anyhow::Result<TextGenerationResult> {
+        let tools = self.tools.merged_with(&params.tools);
+
+        let mut body = self.create_chat_request_body(conversation, params, false)?;
+
+        if !tools.is_empty() {
+            body.0["tools"] = serde_json::to_value(chat_tools(tools.definitions()))?;
+        }
+
+        let mut usage = TokenUsage::default();
+
+        for _ in 0..MAX_TOOL_CALL_ROUNDS {
+            let response = self.send_chat_completion_request(&mut body).await?;
+            let response: super::utils::ChatCompletionResponse = response.json().await?;
+
+            tracing::trace!(
+                ?response,
+                "Got response from the OpenAI-compat chat completion API"
+            );
+
+            if let Some(response_usage) = response.usage {
+                usage += TokenUsage::from(response_usage);
+            }
+
+            // We only request 1 result, so there should only be 1 choice.
+            let Some(choice) = response.choices.into_iter().next() else {
                 return Err(anyhow::anyhow!(
-                    "Failed to get response from the OpenAI-compat chat completion API: {:?}",
-                    err
+                    "No response messages choices were returned from the OpenAI-compat chat completion API"
                 ));
+            };
+
+            let tool_calls = choice.message.tool_calls.unwrap_or_default();
+
+            if tool_calls.is_empty() {
+                let Some(text) = choice.message.content else {
+                    return Err(anyhow::anyhow!(
+                        "No response message content was returned from the OpenAI-compat chat completion API"
+                    ));
+                };
+
+                return Ok(TextGenerationResult { text, usage });
             }
-        };
 
-        tracing::trace!(
-            ?response,
-            "Got response from the OpenAI-compat chat completion API"
-        );
+            let mut messages = vec![super::utils::create_assistant_tool_calls_message(
+                choice.message.content,
+                &tool_calls,
+            )];
 
-        // We only request 1 result, so there should only be 1 choice.
-        if let Some(choice) = response.choices.into_iter().next() {
-            let Some(message) = choice.message else {
+            for tool_call in &tool_calls {
+                let output = tools.call(&ToolCall::from(tool_call)).await;
+                messages.push(super::utils::create_tool_result_message(
+                    &tool_call.id,
+                    output,
+                ));
+            }
+
+            let Some(body_messages) = body.0["messages"].as_array_mut() else {
                 return Err(anyhow::anyhow!(
-                    "No response message in choice was returned from the OpenAI-compat chat completion API"
+                    "The chat completion request unexpectedly has no messages list"
                 ));
             };
+            body_messages.extend(messages);
+        }
 
-            return Ok(TextGenerationResult {
-                text: message.content,
-            });
+        Err(anyhow::anyhow!(strings::agent::tool_call_rounds_exhausted(
+            MAX_TOOL_CALL_ROUNDS
+        )))
+    }
+
+    async fn generate_text_streaming(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> anyhow::Result<TextGenerationResult> {
+        // Tool calls need a request/response loop, so replies which may involve them are not streamed.
+        if !self.tools.is_empty() || !params.tools.is_empty() {
+            return self.generate_text(conversation, params).await;
         }
 
-        Err(anyhow::anyhow!(
-            "No response messages choices were returned from the OpenAI-compat chat completion API"
-        ))
+        let mut body = self.create_chat_request_body(conversation, params, true)?;
+
+        let response = self.send_chat_completion_request(&mut body).await?;
+
+        let mut reader = EventStreamReader::new(response);
+
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+
+        while let Some(data) = reader.next_data().await? {
+            let chunk = super::utils::parse_chat_completion_chunk(&data)?;
+
+            if let Some(chunk_usage) = chunk.usage {
+                usage = chunk_usage.into();
+            }
+
+            // We only request 1 result, so there should only be 1 choice.
+            let Some(content) = chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.delta.content)
+            else {
+                continue;
+            };
+
+            if content.is_empty() {
+                continue;
+            }
+
+            text.push_str(&content);
+            partial_text.send_replace(text.clone());
+        }
+
+        tracing::trace!(
+            ?text,
+            "Got streamed response from the OpenAI-compat chat completion API"
+        );
+
+        if text.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No response text was streamed from the OpenAI-compat chat completion API"
+            ));
+        }
+
+        Ok(TextGenerationResult { text, usage })
     }
 
     async fn speech_to_text(
@@ -269,10 +539,11 @@ impl ControllerTrait for Controller {
         let response = match response {
             Ok(response) => response,
             Err(err) => {
-                return Err(anyhow::anyhow!(
+                // The library does not expose the response's status, which we need for deciding whether to retry.
+                return Err(recognize_http_error(anyhow::anyhow!(
                     "Failed to get response from the OpenAI-compat audio transcription API: {:?}",
                     err
-                ));
+                )));
             }
         };
 
@@ -340,10 +611,11 @@ impl ControllerTrait for Controller {
         let response = match response {
             Ok(response) => response,
             Err(err) => {
-                return Err(anyhow::anyhow!(
+                // The library does not expose the response's status, which we need for deciding whether to retry.
+                return Err(recognize_http_error(anyhow::anyhow!(
                     "Failed to get response from the OpenAI-compat image creation API: {:?}",
                     err
-                ));
+                )));
             }
         };
 
@@ -455,6 +727,36 @@ impl ControllerTrait for Controller {
             .map(|config| config.temperature)
     }
 
+    fn text_generation_max_response_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .and_then(|config| config.max_response_tokens)
+    }
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.max_context_tokens)
+    }
+
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost {
+        // Images and files are not sent to OpenAI-compatible servers (see `convert_llm_messages_to_openai_messages`).
+        MediaTokenCost::None
+    }
+
+    fn text_generation_tools(&self) -> ToolRegistry {
+        self.tools.clone()
+    }
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.tools.mcp.clone())
+    }
+
     fn text_to_speech_voice(&self) -> Option<String> {
         let Some(text_to_speech_config) = &self.config.text_to_speech else {
             return None;
@@ -472,4 +774,8 @@ impl ControllerTrait for Controller {
 
         Some(text_to_speech_config.speed)
     }
+
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields {
+        self.unsupported_fields.learned()
+    }
 }
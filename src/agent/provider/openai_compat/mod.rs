@@ -11,22 +11,39 @@
 // Since this crate is not async-aware, we need to use tokio's `spawn_blocking` to invoke it.
 //
 // Certain features (e.g. text-to-speech) are not supported by `openai_api_rust` yet, so we may try to delegate them to the `openai` provider.
+// Streaming text generation is not supported by it either, so it talks to the chat completion API directly via `reqwest`.
 
 mod config;
 mod controller;
 mod utils;
 
 pub use config::Config;
-pub use controller::Controller;
+pub use controller::{Controller, ModelListingApi};
+
+use std::sync::Arc;
 
 use super::super::AgentInstantiationError;
 use super::super::AgentInstantiationResult;
 use super::ConfigTrait;
 use super::controller::ControllerType;
+use crate::conversation::llm::load_huggingface_tokenizer;
 
 pub fn create_controller_from_yaml_value_config(
     agent_id: &str,
     config: serde_yaml_ng::Value,
+) -> AgentInstantiationResult<ControllerType> {
+    create_controller_from_yaml_value_config_with_model_listing_api(
+        agent_id,
+        config,
+        ModelListingApi::default(),
+    )
+}
+
+/// Like `create_controller_from_yaml_value_config`, but for servers whose models are best listed via another API.
+pub(super) fn create_controller_from_yaml_value_config_with_model_listing_api(
+    agent_id: &str,
+    config: serde_yaml_ng::Value,
+    model_listing_api: ModelListingApi,
 ) -> AgentInstantiationResult<ControllerType> {
     let config = match &config {
         serde_yaml_ng::Value::Mapping(_) => {
@@ -46,9 +63,20 @@ pub fn create_controller_from_yaml_value_config(
         }
     };
 
-    Ok(ControllerType::OpenAICompat(Box::new(Controller::new(
-        config,
-    ))))
+    let tokenizer = config
+        .text_generation
+        .as_ref()
+        .and_then(|text_generation| text_generation.tokenizer_path.as_deref())
+        .map(load_huggingface_tokenizer)
+        .transpose()
+        .map_err(AgentInstantiationError::ConstructionFailed)?
+        .map(Arc::new);
+
+    Ok(ControllerType::OpenAICompat(Box::new(
+        Controller::new(config)
+            .with_model_listing_api(model_listing_api)
+            .with_tokenizer(tokenizer),
+    )))
 }
 
 pub fn default_config() -> Config {
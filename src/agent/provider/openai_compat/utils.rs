@@ -1,6 +1,9 @@
 use etke_openai_api_rust::{Message, Role};
 
+use crate::agent::provider::TokenUsage;
 use crate::agent::provider::openai::Config as OpenAIConfig;
+use crate::agent::provider::recovery::RecoverableRequest;
+use crate::agent::tool::chat_completions::ChatToolCall;
 
 use crate::conversation::llm::{
     Author as LLMAuthor, Message as LLMMessage, MessageContent as LLMMessageContent,
@@ -49,6 +52,179 @@ fn convert_llm_message_to_openai_message(llm_message: LLMMessage) -> Option<Mess
     }
 }
 
+// A single `chat.completion.chunk` event, as streamed by the chat completion API.
+// Only the parts we make use of are modeled.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionChunk {
+    #[serde(default)]
+    pub choices: Vec<ChatCompletionChunkChoice>,
+
+    // Only sent (on the last chunk) by servers which report usage while streaming.
+    #[serde(default)]
+    pub usage: Option<ChatCompletionUsage>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionChunkChoice {
+    pub delta: ChatCompletionChunkDelta,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionChunkDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+// A (non-streamed) chat completion response, as read when tools are involved.
+// Only the parts we make use of are modeled.
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionResponse {
+    #[serde(default)]
+    pub choices: Vec<ChatCompletionChoice>,
+
+    #[serde(default)]
+    pub usage: Option<ChatCompletionUsage>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionChoice {
+    pub message: ChatCompletionResponseMessage,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionResponseMessage {
+    #[serde(default)]
+    pub content: Option<String>,
+
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ChatToolCall>>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub(super) struct ChatCompletionUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    #[serde(default)]
+    pub completion_tokens: u64,
+
+    #[serde(default)]
+    pub prompt_tokens_details: Option<ChatCompletionPromptTokensDetails>,
+
+    #[serde(default)]
+    pub completion_tokens_details: Option<ChatCompletionCompletionTokensDetails>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionPromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: u64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChatCompletionCompletionTokensDetails {
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+}
+
+impl From<ChatCompletionUsage> for TokenUsage {
+    fn from(usage: ChatCompletionUsage) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            reasoning_tokens: usage
+                .completion_tokens_details
+                .map(|details| details.reasoning_tokens)
+                .unwrap_or(0),
+            cached_tokens: usage
+                .prompt_tokens_details
+                .map(|details| details.cached_tokens)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Top-level chat completion fields baibot may drop when a server rejects them as unsupported.
+///
+/// Self-hosted OpenAI-compatible servers (vLLM, llama.cpp, LM Studio, LocalAI, ..) implement the API
+/// to varying degrees, and some reject fields they don't know instead of ignoring them.
+/// Reasoning models commonly refuse sampling knobs like `temperature` too.
+/// Unlike with Venice, even meaning-bearing knobs are droppable here: such a server cannot be told
+/// to honor the field anyway, so the alternative to dropping it is not answering at all.
+///
+/// `model`, `messages` and `tools` are never dropped, as the request makes no sense without them.
+pub(super) const DROPPABLE_CHAT_FIELDS: &[&str] = &[
+    "max_tokens",
+    "max_completion_tokens",
+    "temperature",
+    "top_p",
+    "n",
+    "stop",
+    "presence_penalty",
+    "frequency_penalty",
+    "logit_bias",
+    "user",
+    "stream_options",
+    "reasoning_effort",
+];
+
+/// A `/chat/completions` request body, in its JSON form.
+///
+/// The library's request type cannot carry tool calls, tool results or `stream_options`,
+/// so the request is sent as (and recovered upon) JSON instead.
+pub(super) struct ChatCompletionRequestBody(pub serde_json::Value);
+
+impl ChatCompletionRequestBody {
+    pub(super) fn model_id(&self) -> String {
+        self.0["model"].as_str().unwrap_or_default().to_owned()
+    }
+}
+
+impl RecoverableRequest for ChatCompletionRequestBody {
+    fn strip_unsupported_field(&mut self, field: &str) -> bool {
+        if !DROPPABLE_CHAT_FIELDS.contains(&field) {
+            return false;
+        }
+
+        self.0
+            .as_object_mut()
+            .and_then(|body| body.remove(field))
+            .is_some()
+    }
+}
+
+pub(super) fn create_assistant_tool_calls_message(
+    content: Option<String>,
+    tool_calls: &[ChatToolCall],
+) -> serde_json::Value {
+    serde_json::json!({
+        "role": "assistant",
+        "content": content,
+        "tool_calls": tool_calls,
+    })
+}
+
+pub(super) fn create_tool_result_message(tool_call_id: &str, output: String) -> serde_json::Value {
+    serde_json::json!({
+        "role": "tool",
+        "tool_call_id": tool_call_id,
+        "content": output,
+    })
+}
+
+pub(super) fn parse_chat_completion_chunk(data: &str) -> anyhow::Result<ChatCompletionChunk> {
+    // Errors which happen mid-stream are reported as an `error` object instead of a chunk.
+    let value: serde_json::Value = serde_json::from_str(data)?;
+    if let Some(error) = value.get("error") {
+        return Err(anyhow::anyhow!(
+            "The OpenAI-compat chat completion API reported an error while streaming: {}",
+            error
+        ));
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
 pub(super) fn convert_config_to_openai_config_lossy(config: &super::Config) -> OpenAIConfig {
     let text_generation = config
         .text_generation
@@ -95,3 +271,39 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_droppable_fields_only() {
+        let mut body = ChatCompletionRequestBody(serde_json::json!({
+            "model": "some-model",
+            "messages": [],
+            "temperature": 1.0,
+            "stream_options": {"include_usage": true},
+        }));
+
+        assert_eq!(body.model_id(), "some-model");
+
+        assert!(body.strip_unsupported_field("stream_options"));
+        assert!(body.0.get("stream_options").is_none());
+
+        // Already gone, so no progress is made (which is what stops the retry loop).
+        assert!(!body.strip_unsupported_field("stream_options"));
+
+        // Not sent at all.
+        assert!(!body.strip_unsupported_field("max_completion_tokens"));
+
+        // The request makes no sense without these.
+        assert!(!body.strip_unsupported_field("model"));
+        assert!(!body.strip_unsupported_field("messages"));
+
+        assert!(body.strip_unsupported_field("temperature"));
+        assert_eq!(
+            body.0,
+            serde_json::json!({"model": "some-model", "messages": []})
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Auto-recovery for backends which reject optional request fields they don't support.
+//!
+//! Strict backends (Venice, and many self-hosted OpenAI-compatible servers like vLLM, llama.cpp,
+//! LM Studio or LocalAI) reject the whole request with a 400 when it carries an optional field the
+//! model does not support, instead of ignoring the field. When such a field is the reason for a 400,
+//! the provider strips it and retries, then remembers the rejection per model, so later requests skip
+//! the field (and the wasted round-trip) entirely.
+//!
+//! Which fields are safe to drop is a per-provider decision (see each provider's
+//! [`RecoverableRequest`] implementation). This module only holds the shared machinery: the
+//! per-model cache of learned rejections and the parsing of rejection responses.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use regex::Regex;
+
+/// The fields each model has rejected as unsupported, sorted by model and field.
+pub type LearnedUnsupportedFields = BTreeMap<String, BTreeSet<String>>;
+
+/// A request body which knows which of its optional fields may be dropped to recover from a 400.
+pub(crate) trait RecoverableRequest {
+    /// Clears `field` when it is one the provider may safely drop and it is currently set.
+    ///
+    /// Returns `true` only when a value was actually removed, which is what bounds the retry loops:
+    /// once a field is gone, a repeat rejection for the same name returns `false` and the caller
+    /// stops instead of retrying forever. A field the provider does not consider droppable must
+    /// always return `false`.
+    fn strip_unsupported_field(&mut self, field: &str) -> bool;
+}
+
+/// Per-model record of fields a model has rejected as unsupported, learned at runtime from 400
+/// responses. It lives on the provider's controller, which is part of the (cached) agent instance
+/// (see `agent::Manager`), so a rejection is remembered for as long as the agent's definition stays
+/// the same. The `Arc` is shared across `Controller` clones, so a rejection learned once is seen by
+/// every clone of the same agent. The cache is process-lived only: a restart (or a change to the
+/// agent's configuration) re-learns on the first request to each model, which costs one extra
+/// round-trip and nothing else, so there is no persistence to keep in sync with config changes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UnsupportedFieldsCache {
+    inner: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+}
+
+impl UnsupportedFieldsCache {
+    /// Fields already known unsupported for `model_id`. Returns an empty set on an unknown model or
+    /// a poisoned lock, so a cache failure degrades to "strip nothing proactively" rather than
+    /// breaking the request path.
+    pub(crate) fn known_for(&self, model_id: &str) -> HashSet<String> {
+        self.inner
+            .read()
+            .ok()
+            .and_then(|map| map.get(model_id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Records that `model_id` rejected `field`. A poisoned lock is ignored: failing to memoize only
+    /// means the next request re-discovers the rejection, never a wrong result.
+    pub(crate) fn record(&self, model_id: &str, field: &str) {
+        if let Ok(mut map) = self.inner.write() {
+            map.entry(model_id.to_owned())
+                .or_default()
+                .insert(field.to_owned());
+        }
+    }
+
+    /// The rejections learned so far (for showing to users), for all models the agent has used,
+    /// including those it was told to use instead of its configured one (see `TextGenerationParams::model_id`).
+    pub(crate) fn learned(&self) -> LearnedUnsupportedFields {
+        self.inner
+            .read()
+            .map(|map| {
+                map.iter()
+                    .map(|(model_id, fields)| (model_id.clone(), fields.iter().cloned().collect()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Proactively drops the fields `model_id` has already rejected earlier in this process, so a
+    /// known mismatch costs zero wasted round-trips after the first discovery.
+    pub(crate) fn strip_known(&self, model_id: &str, request: &mut impl RecoverableRequest) {
+        for field in self.known_for(model_id) {
+            request.strip_unsupported_field(&field);
+        }
+    }
+
+    /// Handles a 400 which (possibly) named a `rejected_field`. When the field is one the request may
+    /// drop and it was still set, it gets stripped, the rejection gets remembered for this model and
+    /// `true` is returned to tell the caller to retry. Otherwise the caller should surface the error.
+    pub(crate) fn recover(
+        &self,
+        provider: &str,
+        model_id: &str,
+        request: &mut impl RecoverableRequest,
+        rejected_field: Option<String>,
+    ) -> bool {
+        let Some(field) = rejected_field else {
+            return false;
+        };
+
+        if !request.strip_unsupported_field(&field) {
+            return false;
+        }
+
+        self.record(model_id, &field);
+
+        tracing::info!(
+            provider,
+            model = model_id,
+            field,
+            "The model rejected an unsupported field; dropping it and retrying"
+        );
+
+        true
+    }
+}
+
+/// Parses the offending field name out of a 400 body. Backends report it in different ways:
+/// - an OpenAI-style envelope: `{"error": {"param": "temperature", ...}}`
+/// - Venice: `... field: 'prompt_cache_retention', value: '...'`
+/// - OpenAI-style messages: `Unsupported parameter: 'max_tokens' is not supported with this model.`
+/// - pydantic-based servers (e.g. vLLM): `... 'loc': ('body', 'stream_options') ...`
+///
+/// Returns `None` when the body names no field (a different 400 class, e.g. a missing required
+/// field), so the caller surfaces that error instead.
+///
+/// Returns only the FIRST named field by design. If a backend names several rejected fields in one
+/// body, the retry loop strips this one, retries, and rediscovers the next on the following 400 —
+/// bounded and correct, since each round strips a distinct field.
+pub(crate) fn parse_rejected_field(body: &str) -> Option<String> {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(body.trim())
+        && let Some(param) = value
+            .get("error")
+            .and_then(|e| e.get("param"))
+            .and_then(|p| p.as_str())
+        && !param.is_empty()
+    {
+        return Some(param.to_owned());
+    }
+
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"field: '([^']+)'",
+            r"|[Uu]nsupported param(?:eter)?:? '([^']+)'",
+            r"|'loc': \('body', '([^']+)'\)",
+        ))
+        .expect("rejected-field regex is valid")
+    });
+
+    let caps = re.captures(body)?;
+
+    caps.iter()
+        .skip(1)
+        .flatten()
+        .map(|m| m.as_str().to_owned())
+        .find(|field| !field.is_empty())
+}
+
+/// Error codes (or types) with which backends report a field as unsupported.
+const UNSUPPORTED_FIELD_ERROR_CODES: &[&str] = &[
+    "unsupported_parameter",
+    "unsupported_value",
+    "extra_forbidden",
+];
+
+/// Tells if an error rejects a field as unsupported (by the model or the server),
+/// as opposed to rejecting the value it was given (e.g. an out-of-range `temperature` or a malformed `stop`).
+///
+/// Only the former may be recovered from by dropping the field (and remembering that).
+/// The latter is a configuration mistake, which must be reported instead of silently ignored from then on.
+pub(crate) fn is_unsupported_field_error(codes: &[&str], message: &str) -> bool {
+    if codes
+        .iter()
+        .any(|code| UNSUPPORTED_FIELD_ERROR_CODES.contains(code))
+    {
+        return true;
+    }
+
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)unsupported (?:parameter|value)",
+            r"|extra_forbidden",
+            r"|extra inputs are not permitted",
+            r"|unrecognized (?:request )?(?:argument|field|parameter)",
+            r"|unknown (?:argument|field|parameter)",
+        ))
+        .expect("unsupported-field regex is valid")
+    });
+
+    re.is_match(message)
+}
+
+/// Like [`parse_rejected_field`], but only returns the field when the body rejects it as unsupported
+/// (see [`is_unsupported_field_error`]).
+pub(crate) fn parse_unsupported_field(body: &str) -> Option<String> {
+    let field = parse_rejected_field(body)?;
+
+    let error = serde_json::from_str::<serde_json::Value>(body.trim())
+        .ok()
+        .and_then(|value| value.get("error").cloned());
+
+    let codes: Vec<&str> = ["code", "type"]
+        .iter()
+        .filter_map(|key| error.as_ref()?.get(key)?.as_str())
+        .collect();
+
+    is_unsupported_field_error(&codes, body).then_some(field)
+}
+
+/// Pulls a human-readable message out of an error body for surfacing in the room. The usual
+/// envelopes are `{"error": "..."}` and `{"error": {"message": "..."}}`. Falls back to the trimmed
+/// raw body (length-capped so a large body cannot flood the room) and finally to a fixed string for
+/// an empty body, so the caller always has something to show.
+pub(crate) fn extract_error_message(body: &str) -> String {
+    let trimmed = body.trim();
+    if trimmed.is_empty() {
+        return "no response body".to_owned();
+    }
+
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        if let Some(msg) = value.get("error").and_then(|e| e.as_str()) {
+            return msg.to_owned();
+        }
+        if let Some(msg) = value
+            .get("error")
+            .and_then(|e| e.get("message"))
+            .and_then(|m| m.as_str())
+        {
+            return msg.to_owned();
+        }
+    }
+
+    const MAX: usize = 500;
+    if trimmed.chars().count() > MAX {
+        trimmed.chars().take(MAX).collect::<String>() + "…"
+    } else {
+        trimmed.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Request {
+        reasoning_effort: Option<String>,
+    }
+
+    impl RecoverableRequest for Request {
+        fn strip_unsupported_field(&mut self, field: &str) -> bool {
+            match field {
+                "reasoning_effort" => self.reasoning_effort.take().is_some(),
+                _ => false,
+            }
+        }
+    }
+
+    #[test]
+    fn parses_the_rejected_field_from_various_backends() {
+        let venice = r#"{"error":"Extra inputs are not permitted, field: 'prompt_cache_retention', value: 'default'","request_id":"qM_DmKSXKF07wRxmQJ-hc"}"#;
+        assert_eq!(
+            parse_rejected_field(venice).as_deref(),
+            Some("prompt_cache_retention")
+        );
+
+        let openai = r#"{"error":{"message":"Unsupported parameter: 'temperature' is not supported with this model.","type":"invalid_request_error","param":"temperature","code":"unsupported_parameter"}}"#;
+        assert_eq!(parse_rejected_field(openai).as_deref(), Some("temperature"));
+
+        let message_only = "Unsupported parameter: 'max_tokens' is not supported with this model.";
+        assert_eq!(
+            parse_rejected_field(message_only).as_deref(),
+            Some("max_tokens")
+        );
+
+        let vllm = r#"{"object":"error","message":"[{'type': 'extra_forbidden', 'loc': ('body', 'stream_options'), 'msg': 'Extra inputs are not permitted'}]","type":"BadRequestError","code":400}"#;
+        assert_eq!(
+            parse_rejected_field(vllm).as_deref(),
+            Some("stream_options")
+        );
+    }
+
+    #[test]
+    fn returns_no_field_when_the_body_names_none() {
+        // A different 400 class (e.g. a genuinely malformed request) names no field, so there is
+        // nothing to strip and the caller must surface the error instead.
+        assert_eq!(parse_rejected_field(r#"{"error":"Invalid request"}"#), None);
+        assert_eq!(
+            parse_rejected_field(r#"{"error":{"message":"Bad","param":null}}"#),
+            None
+        );
+        assert_eq!(parse_rejected_field(""), None);
+    }
+
+    #[test]
+    fn cache_records_per_model_and_isolates_models() {
+        let cache = UnsupportedFieldsCache::default();
+        assert!(cache.known_for("venice-uncensored").is_empty());
+
+        cache.record("venice-uncensored", "prompt_cache_retention");
+        cache.record("venice-uncensored", "reasoning_effort");
+
+        let known = cache.known_for("venice-uncensored");
+        assert!(known.contains("prompt_cache_retention"));
+        assert!(known.contains("reasoning_effort"));
+
+        // A rejection learned for one model must not leak to another.
+        assert!(cache.known_for("kimi-k2-5").is_empty());
+
+        assert_eq!(
+            cache.learned(),
+            BTreeMap::from([(
+                "venice-uncensored".to_owned(),
+                BTreeSet::from([
+                    "prompt_cache_retention".to_owned(),
+                    "reasoning_effort".to_owned()
+                ])
+            )])
+        );
+
+        // Each model which rejected something is listed, so a room's model override shows up too.
+        cache.record("kimi-k2-5", "temperature");
+        assert_eq!(
+            cache.learned().keys().collect::<Vec<_>>(),
+            vec!["kimi-k2-5", "venice-uncensored"]
+        );
+    }
+
+    #[test]
+    fn only_unsupported_fields_are_parsed_as_such() {
+        struct TestCase {
+            name: &'static str,
+            body: &'static str,
+            expected: Option<&'static str>,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "OpenAI unsupported parameter",
+                body: r#"{"error":{"message":"Unsupported parameter: 'temperature' is not supported with this model.","type":"invalid_request_error","param":"temperature","code":"unsupported_parameter"}}"#,
+                expected: Some("temperature"),
+            },
+            TestCase {
+                name: "OpenAI unsupported value",
+                body: r#"{"error":{"message":"Unsupported value: 'temperature' does not support 0.7 with this model. Only the default (1) value is supported.","type":"invalid_request_error","param":"temperature","code":"unsupported_value"}}"#,
+                expected: Some("temperature"),
+            },
+            TestCase {
+                name: "vLLM extra field",
+                body: r#"{"object":"error","message":"[{'type': 'extra_forbidden', 'loc': ('body', 'stream_options'), 'msg': 'Extra inputs are not permitted'}]","type":"BadRequestError","code":400}"#,
+                expected: Some("stream_options"),
+            },
+            TestCase {
+                name: "Out-of-range value",
+                body: r#"{"error":{"message":"Invalid 'temperature': decimal above maximum value. Expected a value <= 2, but got 3 instead.","type":"invalid_request_error","param":"temperature","code":"decimal_above_max_value"}}"#,
+                expected: None,
+            },
+            TestCase {
+                name: "Malformed value",
+                body: r#"{"error":{"message":"Invalid type for 'stop': expected a string or an array of strings, but got an object instead.","type":"invalid_request_error","param":"stop","code":"invalid_type"}}"#,
+                expected: None,
+            },
+            TestCase {
+                name: "vLLM value validation",
+                body: r#"{"object":"error","message":"[{'type': 'less_than_equal', 'loc': ('body', 'temperature'), 'msg': 'Input should be less than or equal to 2'}]","type":"BadRequestError","code":400}"#,
+                expected: None,
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                parse_unsupported_field(test_case.body).as_deref(),
+                test_case.expected,
+                "Test case: {}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn recovers_only_by_stripping_a_droppable_field_which_is_set() {
+        let cache = UnsupportedFieldsCache::default();
+        let mut request = Request {
+            reasoning_effort: Some("high".to_owned()),
+        };
+
+        assert!(!cache.recover("test", "model-x", &mut request, None));
+        assert!(!cache.recover(
+            "test",
+            "model-x",
+            &mut request,
+            Some("temperature".to_owned())
+        ));
+        assert!(cache.known_for("model-x").is_empty());
+
+        assert!(cache.recover(
+            "test",
+            "model-x",
+            &mut request,
+            Some("reasoning_effort".to_owned())
+        ));
+        assert!(request.reasoning_effort.is_none());
+        assert!(cache.known_for("model-x").contains("reasoning_effort"));
+
+        // A repeat rejection for the (now absent) field makes no progress, which stops the retry loop.
+        assert!(!cache.recover(
+            "test",
+            "model-x",
+            &mut request,
+            Some("reasoning_effort".to_owned())
+        ));
+
+        // Later requests get the known field stripped before they're even sent.
+        let mut request = Request {
+            reasoning_effort: Some("low".to_owned()),
+        };
+        cache.strip_known("model-x", &mut request);
+        assert!(request.reasoning_effort.is_none());
+    }
+
+    #[test]
+    fn extracts_a_human_message_from_error_envelopes() {
+        assert_eq!(
+            extract_error_message(r#"{"error":"Extra inputs are not permitted","request_id":"x"}"#),
+            "Extra inputs are not permitted"
+        );
+
+        // OpenAI-style nested envelope.
+        assert_eq!(
+            extract_error_message(r#"{"error":{"message":"context length exceeded"}}"#),
+            "context length exceeded"
+        );
+
+        // Unknown shape falls back to the raw body; empty falls back to a fixed string.
+        assert_eq!(
+            extract_error_message("plain text failure"),
+            "plain text failure"
+        );
+        assert_eq!(extract_error_message("   "), "no response body");
+    }
+}
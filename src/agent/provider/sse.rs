@@ -0,0 +1,145 @@
+//! Minimal reader for `text/event-stream` (Server-Sent Events) response bodies, as returned by
+//! OpenAI-shaped `/chat/completions` endpoints when `stream: true` is requested.
+//! MCP servers (see `crate::agent::tool::mcp`) may reply this way too.
+//!
+//! Only the `data` field of each event is surfaced: that is where every provider we talk to puts
+//! its JSON chunk. Event names, ids and `:` comment lines (keep-alives) are ignored. The
+//! conventional `[DONE]` sentinel ends the stream.
+
+use std::collections::VecDeque;
+
+const DONE_SENTINEL: &str = "[DONE]";
+
+pub(crate) struct EventStreamReader {
+    response: reqwest::Response,
+    // Raw bytes rather than a `String`: a chunk boundary may split a multi-byte UTF-8 character,
+    // so bytes are only decoded once a whole event has arrived.
+    buffer: Vec<u8>,
+    pending: VecDeque<String>,
+    finished: bool,
+}
+
+impl EventStreamReader {
+    pub(crate) fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Returns the `data` payload of the next event, or `None` once the stream is over.
+    pub(crate) async fn next_data(&mut self) -> anyhow::Result<Option<String>> {
+        loop {
+            if let Some(data) = self.pending.pop_front() {
+                if data.trim() == DONE_SENTINEL {
+                    self.finished = true;
+                    self.pending.clear();
+                    return Ok(None);
+                }
+
+                return Ok(Some(data));
+            }
+
+            if self.finished {
+                return Ok(None);
+            }
+
+            match self.response.chunk().await? {
+                Some(chunk) => {
+                    self.buffer.extend_from_slice(&chunk);
+                    self.pending.extend(drain_complete_events(&mut self.buffer));
+                }
+                None => {
+                    // Some servers close the connection without a trailing blank line after the
+                    // last event, so whatever is left in the buffer is treated as a final event.
+                    self.finished = true;
+                    if let Some(data) = parse_event_data(&self.buffer) {
+                        self.pending.push_back(data);
+                    }
+                    self.buffer.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Removes every complete (blank-line-terminated) event from the buffer and returns their `data`
+/// payloads in order. An incomplete trailing event is left in the buffer for the next chunk.
+fn drain_complete_events(buffer: &mut Vec<u8>) -> Vec<String> {
+    // Line endings may be `\n` or `\r\n`. JSON payloads never contain a raw carriage return, so
+    // dropping them up front keeps the event-boundary search simple.
+    buffer.retain(|byte| *byte != b'\r');
+
+    let mut events = vec![];
+
+    while let Some(position) = buffer.windows(2).position(|window| window == b"\n\n") {
+        let event: Vec<u8> = buffer.drain(..position + 2).collect();
+
+        if let Some(data) = parse_event_data(&event) {
+            events.push(data);
+        }
+    }
+
+    events
+}
+
+fn parse_event_data(event: &[u8]) -> Option<String> {
+    let event = String::from_utf8_lossy(event);
+
+    let data_lines: Vec<&str> = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|value| value.strip_prefix(' ').unwrap_or(value))
+        .collect();
+
+    if data_lines.is_empty() {
+        return None;
+    }
+
+    Some(data_lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::drain_complete_events;
+
+    #[test]
+    fn drains_complete_events_only() {
+        let mut buffer = b"data: {\"a\":1}\n\ndata: {\"b\":2}\n\ndata: {\"c\"".to_vec();
+
+        let events = drain_complete_events(&mut buffer);
+
+        assert_eq!(events, vec!["{\"a\":1}", "{\"b\":2}"]);
+        assert_eq!(buffer, b"data: {\"c\"".to_vec());
+
+        buffer.extend_from_slice(b":3}\n\n");
+
+        assert_eq!(drain_complete_events(&mut buffer), vec!["{\"c\":3}"]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn handles_crlf_comments_and_multiline_data() {
+        let mut buffer =
+            b": keep-alive\r\n\r\nevent: message\r\ndata: first\r\ndata: second\r\n\r\n".to_vec();
+
+        let events = drain_complete_events(&mut buffer);
+
+        assert_eq!(events, vec!["first\nsecond"]);
+    }
+
+    #[test]
+    fn keeps_multibyte_characters_split_across_chunks() {
+        let payload = "data: Здравей\n\n".as_bytes();
+        // Split in the middle of a 2-byte Cyrillic character.
+        let (first, second) = payload.split_at(7);
+
+        let mut buffer = first.to_vec();
+        assert!(drain_complete_events(&mut buffer).is_empty());
+
+        buffer.extend_from_slice(second);
+        assert_eq!(drain_complete_events(&mut buffer), vec!["Здравей"]);
+    }
+}
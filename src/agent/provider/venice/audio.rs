@@ -1,6 +1,8 @@
 use crate::agent::AgentPurpose;
 use crate::agent::provider::entity::{TextToSpeechParams, TextToSpeechResult};
-use crate::agent::provider::{SpeechToTextParams, SpeechToTextResult};
+use crate::agent::provider::{
+    ProviderHttpError, SpeechToTextParams, SpeechToTextResult, retry_after_from_headers,
+};
 use crate::strings;
 
 use super::config::Config;
@@ -56,11 +58,17 @@ pub async fn speech_to_text(
 
     let status = response.status();
     if !status.is_success() {
+        let retry_after = retry_after_from_headers(response.headers());
+
         // Body to the server log only, not into the returned error (which reaches the Matrix room).
         let body = response.text().await.unwrap_or_default();
         tracing::warn!(%status, body, "Venice audio transcription request failed");
-        return Err(anyhow::anyhow!(
-            "Venice audio transcription request failed with status {status}"
+        return Err(anyhow::Error::new(
+            ProviderHttpError::new(
+                status,
+                format!("Venice audio transcription request failed with status {status}"),
+            )
+            .with_retry_after(retry_after),
         ));
     }
 
@@ -122,11 +130,17 @@ pub async fn text_to_speech(
 
     let status = response.status();
     if !status.is_success() {
+        let retry_after = retry_after_from_headers(response.headers());
+
         // Body to the server log only, not into the returned error (which reaches the Matrix room).
         let body = response.text().await.unwrap_or_default();
         tracing::warn!(%status, body, "Venice text-to-speech request failed");
-        return Err(anyhow::anyhow!(
-            "Venice text-to-speech request failed with status {status}"
+        return Err(anyhow::Error::new(
+            ProviderHttpError::new(
+                status,
+                format!("Venice text-to-speech request failed with status {status}"),
+            )
+            .with_retry_after(retry_after),
         ));
     }
 
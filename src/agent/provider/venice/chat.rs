@@ -3,26 +3,218 @@ use std::hash::{Hash, Hasher};
 use std::sync::OnceLock;
 
 use regex::Regex;
+use tokenizers::Tokenizer;
 
 use crate::agent::AgentPurpose;
-use crate::agent::provider::entity::{TextGenerationParams, TextGenerationResult};
+use crate::agent::provider::entity::{TextGenerationParams, TextGenerationResult, TokenUsage};
+use crate::agent::provider::{ProviderHttpError, retry_after_from_headers};
+use crate::agent::tool::{
+    MAX_TOOL_CALL_ROUNDS, ToolCall, ToolRegistry, chat_completions::chat_tools,
+};
 use crate::conversation::llm::{
-    Author as LLMAuthor, Conversation as LLMConversation, Message as LLMMessage,
+    Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost, Message as LLMMessage,
     MessageContent as LLMMessageContent, TokenEstimate, shorten_messages_list_to_context_size,
 };
 use crate::strings;
 
-use super::config::{Config, WebSearchMode};
+use super::super::recovery::{UnsupportedFieldsCache, extract_error_message, parse_rejected_field};
+use super::super::sse::EventStreamReader;
+use super::config::{Config, TextGenerationConfig, WebSearchMode};
 use super::utils::convert_llm_messages_to_venice;
-use super::wire::{ChatCompletionRequest, ChatCompletionResponse, WebSearchCitation};
+use super::wire::{
+    ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse, ChatMessage,
+    MessageContent, WebSearchCitation,
+};
 
 pub async fn generate_text(
     config: &Config,
     http: &reqwest::Client,
-    unsupported: &super::recovery::UnsupportedFieldsCache,
+    unsupported: &UnsupportedFieldsCache,
+    tools: &ToolRegistry,
+    tokenizer: Option<&Tokenizer>,
+    conversation: LLMConversation,
+    params: TextGenerationParams,
+) -> anyhow::Result<TextGenerationResult> {
+    let (mut request, text_generation_config) =
+        create_chat_completion_request(config, tokenizer, conversation, params)?;
+
+    if !tools.is_empty() {
+        request.tools = Some(chat_tools(tools.definitions()));
+    }
+
+    // Citations from every round are kept: with tools enabled, the round that searched the web is
+    // not necessarily the one that produces the final answer.
+    let mut citations: Vec<WebSearchCitation> = vec![];
+
+    let mut usage = TokenUsage::default();
+
+    // Each round either returns the final answer or asks for tool calls, whose results are appended
+    // to the conversation for the next round. Without tools, the first round always answers.
+    for _ in 0..MAX_TOOL_CALL_ROUNDS {
+        let response =
+            send_chat_completion_request(config, http, unsupported, &mut request).await?;
+
+        let response: ChatCompletionResponse = response.json().await?;
+
+        if let Some(response_usage) = response.usage {
+            usage += TokenUsage::from(response_usage);
+        }
+
+        if let Some(vp) = response.venice_parameters
+            && !vp.web_search_citations.is_empty()
+        {
+            citations = vp.web_search_citations;
+        }
+
+        let Some(choice) = response.choices.into_iter().next() else {
+            return Err(anyhow::anyhow!(
+                "No choices were returned from the Venice chat completion API"
+            ));
+        };
+
+        let tool_calls = choice.message.tool_calls.unwrap_or_default();
+
+        if tool_calls.is_empty() {
+            let Some(content) = choice.message.content else {
+                return Err(anyhow::anyhow!(
+                    "No message content was returned from the Venice chat completion API"
+                ));
+            };
+
+            let text = render_with_citations(content, &citations);
+            let text = append_reasoning(
+                text,
+                choice.message.reasoning_content,
+                text_generation_config.show_reasoning,
+            );
+
+            return Ok(TextGenerationResult { text, usage });
+        }
+
+        let mut tool_messages = Vec::with_capacity(tool_calls.len());
+        for tool_call in &tool_calls {
+            let output = tools.call(&ToolCall::from(tool_call)).await;
+
+            tool_messages.push(ChatMessage {
+                role: "tool".to_owned(),
+                content: MessageContent::Text(output),
+                tool_calls: None,
+                tool_call_id: Some(tool_call.id.clone()),
+            });
+        }
+
+        request.messages.push(ChatMessage {
+            role: "assistant".to_owned(),
+            content: MessageContent::Text(choice.message.content.unwrap_or_default()),
+            tool_calls: Some(tool_calls),
+            tool_call_id: None,
+        });
+        request.messages.extend(tool_messages);
+    }
+
+    Err(anyhow::anyhow!(strings::agent::tool_call_rounds_exhausted(
+        MAX_TOOL_CALL_ROUNDS
+    )))
+}
+
+/// Streaming variant of `generate_text`. Only the answer `content` is published as it arrives;
+/// citations and reasoning are rendered once, on the final text, exactly as in the non-streaming
+/// variant, so the finished message looks the same either way.
+pub async fn generate_text_streaming(
+    config: &Config,
+    http: &reqwest::Client,
+    unsupported: &UnsupportedFieldsCache,
+    tokenizer: Option<&Tokenizer>,
     conversation: LLMConversation,
     params: TextGenerationParams,
+    partial_text: tokio::sync::watch::Sender<String>,
 ) -> anyhow::Result<TextGenerationResult> {
+    let (mut request, text_generation_config) =
+        create_chat_completion_request(config, tokenizer, conversation, params)?;
+
+    request.stream = Some(true);
+
+    // Structured search results are only part of a stream when explicitly asked for. Same as
+    // `return_search_results_as_documents`, an explicit user choice wins.
+    if let Some(vp) = request.venice_parameters.as_mut()
+        && vp.return_search_results_as_documents == Some(true)
+        && vp.include_search_results_in_stream.is_none()
+    {
+        vp.include_search_results_in_stream = Some(true);
+    }
+
+    let response = send_chat_completion_request(config, http, unsupported, &mut request).await?;
+
+    let mut reader = EventStreamReader::new(response);
+
+    let mut content = String::new();
+    let mut reasoning_content = String::new();
+    let mut citations: Vec<WebSearchCitation> = vec![];
+    let mut usage = TokenUsage::default();
+
+    while let Some(data) = reader.next_data().await? {
+        // A failure after the response has started (e.g. the model erroring out mid-answer) can
+        // only be reported in-band, as an `error` event instead of a chunk.
+        let value: serde_json::Value = serde_json::from_str(&data)?;
+        if value.get("error").is_some() {
+            return Err(anyhow::anyhow!(
+                "Venice reported an error while streaming: {}",
+                extract_error_message(&data)
+            ));
+        }
+
+        let chunk: ChatCompletionChunk = serde_json::from_value(value)?;
+
+        if let Some(chunk_usage) = chunk.usage {
+            usage = TokenUsage::from(chunk_usage);
+        }
+
+        if citations.is_empty()
+            && let Some(vp) = chunk.venice_parameters
+        {
+            citations = vp.web_search_citations;
+        }
+
+        let Some(choice) = chunk.choices.into_iter().next() else {
+            continue;
+        };
+
+        if let Some(reasoning) = choice.delta.reasoning_content {
+            reasoning_content.push_str(&reasoning);
+        }
+
+        if let Some(delta) = choice.delta.content
+            && !delta.is_empty()
+        {
+            content.push_str(&delta);
+            partial_text.send_replace(content.clone());
+        }
+    }
+
+    if content.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No message content was streamed from the Venice chat completion API"
+        ));
+    }
+
+    let text = render_with_citations(content, &citations);
+    let text = append_reasoning(
+        text,
+        Some(reasoning_content),
+        text_generation_config.show_reasoning,
+    );
+
+    Ok(TextGenerationResult { text, usage })
+}
+
+/// Builds the `/chat/completions` request body for the given conversation. Shared by the regular
+/// and the streaming variants, which differ only in how the response is read.
+fn create_chat_completion_request<'a>(
+    config: &'a Config,
+    tokenizer: Option<&Tokenizer>,
+    conversation: LLMConversation,
+    params: TextGenerationParams,
+) -> anyhow::Result<(ChatCompletionRequest, &'a TextGenerationConfig)> {
     let Some(text_generation_config) = &config.text_generation else {
         return Err(anyhow::anyhow!(
             strings::agent::no_configuration_for_purpose_so_cannot_be_used(
@@ -31,19 +223,26 @@ pub async fn generate_text(
         ));
     };
 
-    let prompt_text = params.prompt_variables.format(
-        params
-            .prompt_override
-            .unwrap_or(text_generation_config.prompt.clone().unwrap_or_default())
-            .trim(),
-    );
+    let model_id = params.model_id(&text_generation_config.model_id);
+
+    let max_response_tokens =
+        params.max_response_tokens(text_generation_config.max_response_tokens);
+
+    let reasoning_effort = params
+        .reasoning_effort_override
+        .map(|reasoning_effort| reasoning_effort.as_str().to_owned())
+        .or_else(|| text_generation_config.reasoning_effort.clone());
+
+    let prompt_text = params.prompt_text(text_generation_config.prompt.clone());
 
     // Prompt-cache routing key. Hash ONLY conversation-stable inputs: the rendered system prompt
     // and the conversation start time. Folding in anything per-turn (message content, the current
     // time, the message count) would mint a fresh key every turn, miss the cache every lookup, and
     // pay full price plus the hashing cost. The start time is rendered explicitly here so the key
     // stays stable even when the user's prompt template never mentions the time variable; an
-    // unknown start time renders "unknown" and simply keys on the prompt alone.
+    // unknown start time renders "unknown" and simply keys on the prompt alone. A context summary
+    // (part of the rendered prompt) only changes when more of the conversation overflows, so it
+    // rarely moves the key.
     let conversation_start_time = params
         .prompt_variables
         .format("{{ baibot_conversation_start_time_utc }}");
@@ -63,11 +262,18 @@ pub async fn generate_text(
     let mut conversation_messages = conversation.messages;
 
     if params.context_management_enabled {
+        let estimate = match tokenizer {
+            Some(tokenizer) => TokenEstimate::HuggingFace(tokenizer),
+            None => TokenEstimate::Approximate,
+        };
+
         conversation_messages = shorten_messages_list_to_context_size(
-            TokenEstimate::Approximate,
+            estimate,
+            // Venice accepts images and PDFs the same way OpenAI does.
+            MediaTokenCost::OpenAI,
             &prompt_message,
             conversation_messages,
-            text_generation_config.max_response_tokens,
+            max_response_tokens,
             text_generation_config.max_context_tokens,
         );
     }
@@ -99,40 +305,52 @@ pub async fn generate_text(
             vp
         });
 
-    let mut request = ChatCompletionRequest {
-        model: text_generation_config.model_id.clone(),
+    let request = ChatCompletionRequest {
+        model: model_id,
         messages,
         temperature: Some(temperature),
-        // Web search rides entirely inside `venice_parameters`; there is no `tools` array here.
+        // Web search rides entirely inside `venice_parameters`, not the `tools` array (which only
+        // carries client-side tools and is filled in by `generate_text` when any are enabled).
         // `max_tokens` is deprecated on Venice in favor of `max_completion_tokens`.
-        max_completion_tokens: text_generation_config.max_response_tokens,
+        max_completion_tokens: max_response_tokens,
         top_p: text_generation_config.top_p,
         frequency_penalty: text_generation_config.frequency_penalty,
         presence_penalty: text_generation_config.presence_penalty,
         repetition_penalty: text_generation_config.repetition_penalty,
-        reasoning_effort: text_generation_config.reasoning_effort.clone(),
+        reasoning_effort,
         prompt_cache_key: Some(prompt_cache_key),
         prompt_cache_retention: text_generation_config.prompt_cache_retention.clone(),
         venice_parameters,
+        tools: None,
+        stream: None,
     };
 
+    Ok((request, text_generation_config))
+}
+
+/// Sends the request, recovering from rejected unsupported fields along the way. Returns the
+/// (successful) response with its body still unread.
+async fn send_chat_completion_request(
+    config: &Config,
+    http: &reqwest::Client,
+    unsupported: &UnsupportedFieldsCache,
+    request: &mut ChatCompletionRequest,
+) -> anyhow::Result<reqwest::Response> {
     let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
 
-    let model_id = text_generation_config.model_id.clone();
+    let model_id = request.model.clone();
 
     // Proactively drop fields this model has already rejected earlier in this process, so a known
     // mismatch costs zero wasted round-trips after the first discovery. Venice's body is
     // `additionalProperties: false`, so sending a known-unsupported field would 400 again.
-    for field in unsupported.known_for(&model_id) {
-        super::recovery::strip_droppable_field(&mut request, &field);
-    }
+    unsupported.strip_known(&model_id, request);
 
     // Send with bounded auto-recovery. When Venice 400s because a model does not support an optional
     // knob, it names the field (`field: '...'`); if that field is one we may safely drop, we strip
     // it, remember the rejection for this model, and retry. The loop is bounded: each retry clears a
     // distinct droppable field (strip returns false once it is gone), so after at most
     // `DROPPABLE_FIELDS.len()` retries the request either succeeds or surfaces the error.
-    let response = loop {
+    loop {
         tracing::trace!(
             model = model_id,
             messages_count = request.messages.len(),
@@ -142,15 +360,17 @@ pub async fn generate_text(
         let response = http
             .post(&url)
             .bearer_auth(&config.api_key)
-            .json(&request)
+            .json(&*request)
             .send()
             .await?;
 
         let status = response.status();
         if status.is_success() {
-            break response;
+            return Ok(response);
         }
 
+        let retry_after = retry_after_from_headers(response.headers());
+
         // Always log the body server-side: Venice explains a rejected strict body there.
         let body = response.text().await.unwrap_or_default();
         tracing::warn!(%status, body, "Venice chat completion request failed");
@@ -159,15 +379,8 @@ pub async fn generate_text(
         // and retry. Only fields in `DROPPABLE_FIELDS` are eligible, so a meaning-bearing knob (a
         // sampling parameter) is never silently dropped; that case falls through to surface below.
         if status == reqwest::StatusCode::BAD_REQUEST
-            && let Some(field) = super::recovery::parse_rejected_field(&body)
-            && super::recovery::strip_droppable_field(&mut request, &field)
+            && unsupported.recover("venice", &model_id, request, parse_rejected_field(&body))
         {
-            unsupported.record(&model_id, &field);
-            tracing::info!(
-                model = model_id,
-                field,
-                "Venice rejected an unsupported field; dropping it and retrying"
-            );
             continue;
         }
 
@@ -186,42 +399,18 @@ pub async fn generate_text(
         if status == reqwest::StatusCode::BAD_REQUEST {
             return Err(anyhow::anyhow!(
                 "Venice rejected the request (400 Bad Request): {}",
-                super::recovery::extract_error_message(&body)
+                extract_error_message(&body)
             ));
         }
 
-        return Err(anyhow::anyhow!(
-            "Venice chat completion request failed with status {status}"
-        ));
-    };
-
-    let response: ChatCompletionResponse = response.json().await?;
-
-    let citations = response
-        .venice_parameters
-        .map(|vp| vp.web_search_citations)
-        .unwrap_or_default();
-
-    let Some(choice) = response.choices.into_iter().next() else {
-        return Err(anyhow::anyhow!(
-            "No choices were returned from the Venice chat completion API"
-        ));
-    };
-
-    let Some(content) = choice.message.content else {
-        return Err(anyhow::anyhow!(
-            "No message content was returned from the Venice chat completion API"
+        return Err(anyhow::Error::new(
+            ProviderHttpError::new(
+                status,
+                format!("Venice chat completion request failed with status {status}"),
+            )
+            .with_retry_after(retry_after),
         ));
-    };
-
-    let text = render_with_citations(content, &citations);
-    let text = append_reasoning(
-        text,
-        choice.message.reasoning_content,
-        text_generation_config.show_reasoning,
-    );
-
-    Ok(TextGenerationResult { text })
+    }
 }
 
 /// Builds the prompt-cache routing key from conversation-stable inputs. `DefaultHasher::new()` is a
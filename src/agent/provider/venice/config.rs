@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::agent::{default_prompt, provider::ConfigTrait};
+use crate::agent::{default_prompt, provider::ConfigTrait, tool::ToolsConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -40,6 +40,10 @@ impl ConfigTrait for Config {
             return Err("The base URL must not be empty.".to_owned());
         }
 
+        if let Some(text_generation) = &self.text_generation {
+            text_generation.tools.validate()?;
+        }
+
         Ok(())
     }
 }
@@ -98,6 +102,16 @@ pub struct TextGenerationConfig {
     /// wire. Any unset field is omitted, so Venice applies its own server-side default.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub venice_parameters: Option<VeniceParameters>,
+
+    /// Client-side tools (function calling) offered to the model via the standard `tools` array.
+    /// Unrelated to Venice's native web search, which is configured in `venice_parameters`.
+    #[serde(default)]
+    pub tools: ToolsConfig,
+
+    /// Path to the model's HuggingFace `tokenizer.json` file, for counting tokens accurately when trimming
+    /// the conversation to `max_context_tokens`. Without it, tokens are approximated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer_path: Option<String>,
 }
 
 impl Default for TextGenerationConfig {
@@ -130,6 +144,8 @@ impl Default for TextGenerationConfig {
                 enable_e2ee: Some(false),
                 ..Default::default()
             }),
+            tools: ToolsConfig::default(),
+            tokenizer_path: None,
         }
     }
 }
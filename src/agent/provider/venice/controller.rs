@@ -1,19 +1,25 @@
+use std::sync::Arc;
+
+use tokenizers::Tokenizer;
+
 use crate::agent::AgentPurpose;
 use crate::agent::provider::entity::{
-    ImageEditResult, ImageGenerationResult, ImageSource, PingResult, TextGenerationParams,
-    TextGenerationResult, TextToSpeechParams, TextToSpeechResult,
+    ImageEditResult, ImageGenerationResult, ImageSource, ModelInfo, PingResult,
+    TextGenerationParams, TextGenerationResult, TextToSpeechParams, TextToSpeechResult,
 };
 use crate::agent::provider::{
-    ImageEditParams, ImageGenerationParams, SpeechToTextParams, SpeechToTextResult,
+    ImageEditParams, ImageGenerationParams, LearnedUnsupportedFields, SpeechToTextParams,
+    SpeechToTextResult,
 };
 use crate::conversation::llm::{
-    Author as LLMAuthor, Conversation as LLMConversation, Message as LLMMessage,
+    Author as LLMAuthor, Conversation as LLMConversation, MediaTokenCost, Message as LLMMessage,
     MessageContent as LLMMessageContent,
 };
 
 use super::super::ControllerTrait;
+use super::super::recovery::UnsupportedFieldsCache;
 use super::config::Config;
-use super::recovery::UnsupportedFieldsCache;
+use crate::agent::tool::{ToolRegistry, mcp::McpToolsConfig};
 
 #[derive(Debug, Clone)]
 pub struct Controller {
@@ -23,6 +29,11 @@ pub struct Controller {
     // runtime. `Arc`-backed inside, so the `Clone` derive shares one cache across all clones of an
     // agent's controller.
     unsupported_fields: UnsupportedFieldsCache,
+    // Client-side tools enabled via `text_generation.tools`. Empty when none are, which keeps the
+    // request free of a `tools` array.
+    tools: ToolRegistry,
+    // Loaded from `text_generation.tokenizer_path`, if configured. Used for trimming the conversation to the context window.
+    tokenizer: Option<Arc<Tokenizer>>,
 }
 
 impl Controller {
@@ -35,17 +46,26 @@ impl Controller {
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
 
-        // Process-global per-deployment cache rather than a fresh one: dynamic agents rebuild their
-        // controller on every message, so an instance-owned cache would never retain a learned
-        // rejection and the same unsupported field would 400 (and warn) on every turn.
-        let unsupported_fields = UnsupportedFieldsCache::shared_for(&config.base_url);
+        let tools = match &config.text_generation {
+            Some(text_generation_config) => {
+                ToolRegistry::with_builtin_tools(&text_generation_config.tools.enabled)
+            }
+            None => ToolRegistry::default(),
+        };
 
         Self {
             config,
             http,
-            unsupported_fields,
+            unsupported_fields: UnsupportedFieldsCache::default(),
+            tools,
+            tokenizer: None,
         }
     }
+
+    pub fn with_tokenizer(mut self, tokenizer: Option<Arc<Tokenizer>>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
 }
 
 impl ControllerTrait for Controller {
@@ -71,17 +91,49 @@ impl ControllerTrait for Controller {
         Ok(PingResult::Successful)
     }
 
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
+        super::models::list_models(&self.config, &self.http).await
+    }
+
     async fn generate_text(
         &self,
         conversation: LLMConversation,
         params: TextGenerationParams,
     ) -> anyhow::Result<TextGenerationResult> {
+        let tools = self.tools.merged_with(&params.tools);
+
         super::chat::generate_text(
             &self.config,
             &self.http,
             &self.unsupported_fields,
+            &tools,
+            self.tokenizer.as_deref(),
+            conversation,
+            params,
+        )
+        .await
+    }
+
+    async fn generate_text_streaming(
+        &self,
+        conversation: LLMConversation,
+        params: TextGenerationParams,
+        partial_text: tokio::sync::watch::Sender<String>,
+    ) -> anyhow::Result<TextGenerationResult> {
+        // The tool-call loop needs each round's complete response, so with tools enabled the reply
+        // is produced by the non-streaming path and only the final text is shown.
+        if !self.tools.is_empty() || !params.tools.is_empty() {
+            return self.generate_text(conversation, params).await;
+        }
+
+        super::chat::generate_text_streaming(
+            &self.config,
+            &self.http,
+            &self.unsupported_fields,
+            self.tokenizer.as_deref(),
             conversation,
             params,
+            partial_text,
         )
         .await
     }
@@ -151,6 +203,35 @@ impl ControllerTrait for Controller {
             .map(|config| config.temperature)
     }
 
+    fn text_generation_max_response_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .and_then(|config| config.max_response_tokens)
+    }
+
+    fn text_generation_max_context_tokens(&self) -> Option<u32> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.max_context_tokens)
+    }
+
+    fn text_generation_media_token_cost(&self) -> MediaTokenCost {
+        MediaTokenCost::OpenAI
+    }
+
+    fn text_generation_tools(&self) -> ToolRegistry {
+        self.tools.clone()
+    }
+
+    fn text_generation_mcp_config(&self) -> Option<McpToolsConfig> {
+        self.config
+            .text_generation
+            .as_ref()
+            .map(|config| config.tools.mcp.clone())
+    }
+
     fn text_to_speech_voice(&self) -> Option<String> {
         self.config
             .text_to_speech
@@ -164,4 +245,8 @@ impl ControllerTrait for Controller {
             .as_ref()
             .and_then(|config| config.speed)
     }
+
+    fn learned_unsupported_fields(&self) -> LearnedUnsupportedFields {
+        self.unsupported_fields.learned()
+    }
 }
@@ -1,6 +1,8 @@
 use crate::agent::AgentPurpose;
 use crate::agent::provider::entity::{ImageEditResult, ImageGenerationResult, ImageSource};
-use crate::agent::provider::{ImageEditParams, ImageGenerationParams};
+use crate::agent::provider::{
+    ImageEditParams, ImageGenerationParams, ProviderHttpError, retry_after_from_headers,
+};
 use crate::strings;
 use crate::utils::base64::{base64_decode, base64_encode};
 
@@ -70,11 +72,17 @@ pub async fn generate_image(
 
     let status = response.status();
     if !status.is_success() {
+        let retry_after = retry_after_from_headers(response.headers());
+
         // Body to the server log only, not into the returned error (which reaches the Matrix room).
         let body = response.text().await.unwrap_or_default();
         tracing::warn!(%status, body, "Venice image generation request failed");
-        return Err(anyhow::anyhow!(
-            "Venice image generation request failed with status {status}"
+        return Err(anyhow::Error::new(
+            ProviderHttpError::new(
+                status,
+                format!("Venice image generation request failed with status {status}"),
+            )
+            .with_retry_after(retry_after),
         ));
     }
 
@@ -155,11 +163,17 @@ pub async fn create_image_edit(
 
     let status = response.status();
     if !status.is_success() {
+        let retry_after = retry_after_from_headers(response.headers());
+
         // Body to the server log only, not into the returned error (which reaches the Matrix room).
         let body = response.text().await.unwrap_or_default();
         tracing::warn!(%status, body, "Venice image edit request failed");
-        return Err(anyhow::anyhow!(
-            "Venice image edit request failed with status {status}"
+        return Err(anyhow::Error::new(
+            ProviderHttpError::new(
+                status,
+                format!("Venice image edit request failed with status {status}"),
+            )
+            .with_retry_after(retry_after),
         ));
     }
 
@@ -3,6 +3,7 @@ mod chat;
 mod config;
 mod controller;
 mod images;
+mod models;
 mod recovery;
 mod utils;
 mod wire;
@@ -13,10 +14,13 @@ mod tests;
 pub use config::Config;
 pub use controller::Controller;
 
+use std::sync::Arc;
+
 use super::super::AgentInstantiationError;
 use super::super::AgentInstantiationResult;
 use super::ConfigTrait;
 use super::controller::ControllerType;
+use crate::conversation::llm::load_huggingface_tokenizer;
 
 pub fn create_controller_from_yaml_value_config(
     agent_id: &str,
@@ -40,7 +44,18 @@ pub fn create_controller_from_yaml_value_config(
         }
     };
 
-    Ok(ControllerType::Venice(Box::new(Controller::new(config))))
+    let tokenizer = config
+        .text_generation
+        .as_ref()
+        .and_then(|text_generation| text_generation.tokenizer_path.as_deref())
+        .map(load_huggingface_tokenizer)
+        .transpose()
+        .map_err(AgentInstantiationError::ConstructionFailed)?
+        .map(Arc::new);
+
+    Ok(ControllerType::Venice(Box::new(
+        Controller::new(config).with_tokenizer(tokenizer),
+    )))
 }
 
 pub fn default_config() -> Config {
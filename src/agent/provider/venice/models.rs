@@ -0,0 +1,57 @@
+use crate::agent::AgentPurpose;
+use crate::agent::provider::ModelInfo;
+
+use super::super::model_listing;
+use super::config::Config;
+use super::wire::{ModelsResponse, ModelsResponseEntry};
+
+pub async fn list_models(
+    config: &Config,
+    http: &reqwest::Client,
+) -> anyhow::Result<Vec<ModelInfo>> {
+    // Without `type=all`, Venice only lists text models.
+    let url = format!("{}/models?type=all", config.base_url.trim_end_matches('/'));
+
+    let request = http.get(url).bearer_auth(&config.api_key);
+
+    let response: ModelsResponse = model_listing::get_json(request, "Venice").await?;
+
+    Ok(model_listing::sorted(convert_models_response(response)))
+}
+
+pub(super) fn convert_models_response(response: ModelsResponse) -> Vec<ModelInfo> {
+    response.data.into_iter().map(convert_model).collect()
+}
+
+fn convert_model(entry: ModelsResponseEntry) -> ModelInfo {
+    let purposes = match entry.model_type.as_deref() {
+        Some("text") => vec![AgentPurpose::TextGeneration],
+        Some("image") => vec![AgentPurpose::ImageGeneration],
+        Some("tts") => vec![AgentPurpose::TextToSpeech],
+        Some("asr") => vec![AgentPurpose::SpeechToText],
+        // Embedding, upscaling, inpainting, etc. are not purposes we serve.
+        _ => vec![],
+    };
+
+    let spec = entry.model_spec.unwrap_or_default();
+    let capabilities = spec.capabilities.unwrap_or_default();
+
+    let mut notes = vec![];
+    if capabilities.supports_vision {
+        notes.push("vision".to_owned());
+    }
+    if capabilities.supports_function_calling {
+        notes.push("tools".to_owned());
+    }
+    if capabilities.supports_reasoning {
+        notes.push("reasoning".to_owned());
+    }
+    if capabilities.supports_web_search {
+        notes.push("web search".to_owned());
+    }
+    if let Some(context_tokens) = spec.available_context_tokens {
+        notes.push(format!("{context_tokens} tokens of context"));
+    }
+
+    ModelInfo::new(entry.id, purposes).with_details(Some(notes.join(", ")))
+}
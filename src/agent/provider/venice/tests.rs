@@ -6,6 +6,7 @@ use mxlink::mime;
 
 use super::super::ControllerTrait;
 use crate::agent::AgentPurpose;
+use crate::agent::provider::ModelInfo;
 use crate::conversation::llm::{
     Author as LLMAuthor, FileDetails, ImageDetails, Message as LLMMessage,
     MessageContent as LLMMessageContent,
@@ -14,10 +15,12 @@ use crate::conversation::llm::{
 use super::chat::{append_reasoning, derive_prompt_cache_key, render_with_citations};
 use super::config::{Config, TextGenerationConfig, VeniceParameters, WebSearchMode};
 use super::controller::Controller;
+use super::models::convert_models_response;
 use super::utils::convert_llm_messages_to_venice;
 use super::wire::{
-    ChatCompletionRequest, ContentPart, EditImageRequest, GenerateImageRequest, MessageContent,
-    SpeechRequest, WebSearchCitation,
+    ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse, ChatMessage, ContentPart,
+    EditImageRequest, GenerateImageRequest, MessageContent, ModelsResponse, SpeechRequest,
+    WebSearchCitation,
 };
 
 #[test]
@@ -313,6 +316,8 @@ fn request_places_sampling_top_level_and_verbosity_in_the_bag() {
             verbosity: Some("high".to_owned()),
             ..Default::default()
         }),
+        tools: None,
+        stream: None,
     };
 
     let json = serde_json::to_value(&request).expect("serialize request");
@@ -333,6 +338,31 @@ fn request_places_sampling_top_level_and_verbosity_in_the_bag() {
     );
 }
 
+#[test]
+fn stream_chunks_carry_deltas_and_streamed_citations() {
+    // The first chunk of a stream with `include_search_results_in_stream` carries the citations
+    // and no delta; later chunks carry content and/or reasoning deltas only.
+    let first = r#"{"id":"c1","choices":[],"venice_parameters":{"web_search_citations":[{"title":"Rust","url":"https://www.rust-lang.org"}]}}"#;
+    let later =
+        r#"{"id":"c1","choices":[{"index":0,"delta":{"content":"Hi","reasoning_content":null}}]}"#;
+
+    let first: ChatCompletionChunk = serde_json::from_str(first).expect("first chunk parses");
+    assert!(first.choices.is_empty());
+    assert_eq!(
+        first
+            .venice_parameters
+            .expect("citations are present")
+            .web_search_citations[0]
+            .url,
+        "https://www.rust-lang.org"
+    );
+
+    let later: ChatCompletionChunk = serde_json::from_str(later).expect("later chunk parses");
+    let delta = &later.choices[0].delta;
+    assert_eq!(delta.content.as_deref(), Some("Hi"));
+    assert!(delta.reasoning_content.is_none());
+}
+
 #[test]
 fn config_defaults_prompt_cache_retention_to_24h() {
     // The programmatic default.
@@ -547,3 +577,75 @@ fn oversized_file_is_rejected() {
         "a file over the 25MB limit must be rejected"
     );
 }
+
+#[test]
+fn tool_calls_are_read_and_echoed_back_in_the_openai_shape() {
+    // A tool-calling round: no answer content, just the calls the model wants made.
+    let response: ChatCompletionResponse = serde_json::from_str(
+        r#"{"choices":[{"message":{"role":"assistant","content":null,"tool_calls":[{"id":"call_1","type":"function","function":{"name":"calculator","arguments":"{\"expression\":\"6*7\"}"}}]}}]}"#,
+    )
+    .expect("tool-call response should deserialize");
+
+    let message = response.choices.into_iter().next().unwrap().message;
+    assert!(message.content.is_none());
+    let tool_calls = message.tool_calls.expect("tool calls should be present");
+    assert_eq!(tool_calls[0].function.name, "calculator");
+
+    // The follow-up request carries the assistant turn with its calls, then one `tool` message per
+    // call. Neither tool field leaks onto ordinary messages.
+    let assistant = ChatMessage {
+        role: "assistant".to_owned(),
+        content: MessageContent::Text(String::new()),
+        tool_calls: Some(tool_calls),
+        tool_call_id: None,
+    };
+    let tool = ChatMessage {
+        role: "tool".to_owned(),
+        content: MessageContent::Text("42".to_owned()),
+        tool_calls: None,
+        tool_call_id: Some("call_1".to_owned()),
+    };
+
+    let assistant = serde_json::to_value(&assistant).expect("serialize assistant message");
+    assert_eq!(assistant["tool_calls"][0]["id"], "call_1");
+    assert_eq!(assistant["tool_calls"][0]["type"], "function");
+    assert!(assistant.get("tool_call_id").is_none());
+
+    let tool = serde_json::to_value(&tool).expect("serialize tool message");
+    assert_eq!(tool["tool_call_id"], "call_1");
+    assert_eq!(tool["content"], "42");
+    assert!(tool.get("tool_calls").is_none());
+}
+
+#[test]
+fn models_are_grouped_by_type_with_capabilities_as_details() {
+    let body = r#"{"data":[
+        {"id":"llama-3.3-70b","type":"text","object":"model","model_spec":{"availableContextTokens":65536,"capabilities":{"supportsFunctionCalling":true,"supportsReasoning":false,"supportsVision":false,"supportsWebSearch":true},"name":"Llama 3.3 70B"}},
+        {"id":"flux-dev","type":"image","object":"model","model_spec":{"name":"FLUX Standard"}},
+        {"id":"tts-kokoro","type":"tts","object":"model"},
+        {"id":"nvidia/parakeet-tdt-0.6b-v3","type":"asr","object":"model"},
+        {"id":"text-embedding-bge-m3","type":"embedding","object":"model"}
+    ],"object":"list","type":"all"}"#;
+
+    let response: ModelsResponse = serde_json::from_str(body).expect("response should parse");
+
+    assert_eq!(
+        convert_models_response(response),
+        vec![
+            ModelInfo::new(
+                "llama-3.3-70b".to_owned(),
+                vec![AgentPurpose::TextGeneration]
+            )
+            .with_details(Some(
+                "tools, web search, 65536 tokens of context".to_owned()
+            )),
+            ModelInfo::new("flux-dev".to_owned(), vec![AgentPurpose::ImageGeneration]),
+            ModelInfo::new("tts-kokoro".to_owned(), vec![AgentPurpose::TextToSpeech]),
+            ModelInfo::new(
+                "nvidia/parakeet-tdt-0.6b-v3".to_owned(),
+                vec![AgentPurpose::SpeechToText]
+            ),
+            ModelInfo::new("text-embedding-bge-m3".to_owned(), vec![]),
+        ]
+    );
+}
@@ -33,6 +33,8 @@ fn convert_llm_message_to_venice(message: LLMMessage) -> anyhow::Result<ChatMess
         LLMMessageContent::Text(text) => Ok(ChatMessage {
             role: role.to_owned(),
             content: MessageContent::Text(text),
+            tool_calls: None,
+            tool_call_id: None,
         }),
         LLMMessageContent::Image(image_details) => {
             // Inline the image as a base64 data URI, the same shape the OpenAI vision content
@@ -48,6 +50,8 @@ fn convert_llm_message_to_venice(message: LLMMessage) -> anyhow::Result<ChatMess
                 content: MessageContent::Parts(vec![ContentPart::ImageUrl {
                     image_url: ImageUrl { url: data_uri },
                 }]),
+                tool_calls: None,
+                tool_call_id: None,
             })
         }
         LLMMessageContent::File(file_details) => {
@@ -75,6 +79,8 @@ fn convert_llm_message_to_venice(message: LLMMessage) -> anyhow::Result<ChatMess
                         filename: Some(file_details.filename()),
                     },
                 }]),
+                tool_calls: None,
+                tool_call_id: None,
             })
         }
     }
@@ -1,5 +1,5 @@
 //! Serde structs modeling Venice's `/chat/completions`, `/audio/transcriptions`,
-//! `/audio/speech`, `/image/generate`, and `/image/edit` wire shapes. Request types are
+//! `/audio/speech`, `/image/generate`, `/image/edit`, and `/models` wire shapes. Request types are
 //! `Serialize`-only (we build them, Venice never sends them back); response types are
 //! `Deserialize`-only. Keeping the split means the untagged request content enum is never on a
 //! deserialize path, so a surprise response shape can't fail to match it.
@@ -12,6 +12,8 @@
 use serde::{Deserialize, Serialize};
 
 use super::config::VeniceParameters;
+use crate::agent::provider::TokenUsage;
+use crate::agent::tool::chat_completions::{ChatTool, ChatToolCall};
 
 #[derive(Debug, Serialize)]
 pub struct ChatCompletionRequest {
@@ -48,6 +50,14 @@ pub struct ChatCompletionRequest {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub venice_parameters: Option<VeniceParameters>,
+
+    /// Client-side tools (function calling), in the standard OpenAI shape. Omitted when the agent
+    /// has none enabled, so tool-less requests stay byte-identical to what they were before.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ChatTool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -55,6 +65,14 @@ pub struct ChatMessage {
     pub role: String,
 
     pub content: MessageContent,
+
+    /// Set on an `assistant` message that is echoed back after the model asked for tool calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ChatToolCall>>,
+
+    /// Set on a `tool` message, pairing the tool's output with the call it answers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 /// A message body is either a bare string or a list of content parts. Venice accepts both; we
@@ -100,6 +118,9 @@ pub struct ChatCompletionResponse {
 
     #[serde(default)]
     pub venice_parameters: Option<ResponseVeniceParameters>,
+
+    #[serde(default)]
+    pub usage: Option<Usage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -114,6 +135,88 @@ pub struct ResponseMessage {
 
     #[serde(default)]
     pub reasoning_content: Option<String>,
+
+    /// Present (and non-empty) when the model asks for client-side tools to be called instead of
+    /// answering. `null` and a missing key both deserialize to `None`.
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ChatToolCall>>,
+}
+
+/// One `chat.completion.chunk` event of a streamed (`stream: true`) chat completion. Only the
+/// deltas are read; when search results are streamed (`include_search_results_in_stream`), they
+/// arrive in the same `venice_parameters` envelope as on the non-streamed response.
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionChunk {
+    #[serde(default)]
+    pub choices: Vec<ChatChunkChoice>,
+
+    #[serde(default)]
+    pub venice_parameters: Option<ResponseVeniceParameters>,
+
+    /// Only carried by the last chunk, if at all.
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatChunkChoice {
+    #[serde(default)]
+    pub delta: ChatChunkDelta,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ChatChunkDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+}
+
+/// Standard OpenAI-shaped token usage. The details objects are only returned by models (and for
+/// requests) where they apply.
+#[derive(Debug, Deserialize, Default)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    #[serde(default)]
+    pub completion_tokens: u64,
+
+    #[serde(default)]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+
+    #[serde(default)]
+    pub completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct PromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: u64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct CompletionTokensDetails {
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+}
+
+impl From<Usage> for TokenUsage {
+    fn from(usage: Usage) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            reasoning_tokens: usage
+                .completion_tokens_details
+                .map(|details| details.reasoning_tokens)
+                .unwrap_or(0),
+            cached_tokens: usage
+                .prompt_tokens_details
+                .map(|details| details.cached_tokens)
+                .unwrap_or(0),
+        }
+    }
 }
 
 /// The `venice_parameters` envelope on a chat-completion *response*, distinct from the request-side
@@ -271,3 +374,49 @@ pub struct EditImageRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub safe_mode: Option<bool>,
 }
+
+/// `/models` response. Unlike OpenAI's, each entry says what kind of model it is (`type`) and, for
+/// text models, what it is capable of (`model_spec.capabilities`).
+#[derive(Debug, Deserialize)]
+pub struct ModelsResponse {
+    #[serde(default)]
+    pub data: Vec<ModelsResponseEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModelsResponseEntry {
+    pub id: String,
+
+    /// `text`, `image`, `tts`, `asr`, `embedding`, `upscale`, `inpaint`, etc.
+    #[serde(default, rename = "type")]
+    pub model_type: Option<String>,
+
+    #[serde(default)]
+    pub model_spec: Option<ModelSpec>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSpec {
+    #[serde(default)]
+    pub available_context_tokens: Option<u64>,
+
+    #[serde(default)]
+    pub capabilities: Option<ModelCapabilities>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapabilities {
+    #[serde(default)]
+    pub supports_vision: bool,
+
+    #[serde(default)]
+    pub supports_function_calling: bool,
+
+    #[serde(default)]
+    pub supports_reasoning: bool,
+
+    #[serde(default)]
+    pub supports_web_search: bool,
+}
@@ -0,0 +1,488 @@
+use std::time::Duration;
+
+use async_openai::error::OpenAIError;
+use serde::{Deserialize, Serialize};
+
+use super::{AgentInstance, AgentPurpose};
+use crate::agent::provider::{ProviderHttpError, status_from_error_message};
+
+/// The delay before the first retry. Each subsequent retry doubles it (up to `max_backoff_seconds`).
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_max_backoff_seconds() -> u64 {
+    30
+}
+
+/// Controls how an agent retries requests which fail with a retryable error (see [`classify_error`]).
+///
+/// It's read from the (optional) `retry` section of the agent's configuration, regardless of the provider.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct RetryConfig {
+    /// How many times to retry a failed request. Setting it to 0 disables retrying.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// The longest (in seconds) we're willing to wait before retrying.
+    /// When a provider asks us (via `Retry-After`) to wait longer than this, we give up instead.
+    #[serde(default = "default_max_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            max_backoff_seconds: default_max_backoff_seconds(),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn from_agent_config(config: &serde_yaml_ng::Value) -> Result<Self, serde_yaml_ng::Error> {
+        match config.get("retry") {
+            Some(value) if !value.is_null() => serde_yaml_ng::from_value(value.clone()),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn max_backoff(&self) -> Duration {
+        Duration::from_secs(self.max_backoff_seconds)
+    }
+}
+
+/// The kind of transient problem an agent request failed with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryableError {
+    /// HTTP 429, possibly telling us how long to wait before trying again.
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    /// HTTP 5xx
+    ServerError,
+    /// The connection could not be established or was dropped.
+    Connection,
+    Timeout,
+}
+
+/// Tells whether an error returned by an agent is likely transient (rate limiting, server-side trouble, network issues),
+/// so that trying again (or trying another agent) may succeed.
+///
+/// Errors like invalid requests or authentication failures are not retryable - another attempt would fail the same way
+/// and would only hide the real problem.
+pub fn classify_error(err: &anyhow::Error) -> Option<RetryableError> {
+    for cause in err.chain() {
+        if let Some(http_error) = cause.downcast_ref::<ProviderHttpError>() {
+            return classify_status(http_error.status, http_error.retry_after);
+        }
+
+        // async-openai (used by the OpenAI provider) does not expose the response's status,
+        // so we go by the error type and code OpenAI (or a compatible server) reported instead.
+        if let Some(openai_error) = cause.downcast_ref::<OpenAIError>() {
+            match openai_error {
+                OpenAIError::Reqwest(reqwest_error) => {
+                    return classify_reqwest_error(reqwest_error);
+                }
+                OpenAIError::ApiError(_) | OpenAIError::StreamError(_) => {
+                    return status_from_error_message(&format!("{openai_error:?}"))
+                        .and_then(|status| classify_status(status, None));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(reqwest_error) = cause.downcast_ref::<reqwest::Error>()
+            && let Some(error_kind) = classify_reqwest_error(reqwest_error)
+        {
+            return Some(error_kind);
+        }
+
+        if let Some(io_error) = cause.downcast_ref::<std::io::Error>() {
+            return match io_error.kind() {
+                std::io::ErrorKind::TimedOut => Some(RetryableError::Timeout),
+                std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::BrokenPipe => Some(RetryableError::Connection),
+                _ => None,
+            };
+        }
+    }
+
+    None
+}
+
+fn classify_reqwest_error(reqwest_error: &reqwest::Error) -> Option<RetryableError> {
+    if reqwest_error.is_timeout() {
+        return Some(RetryableError::Timeout);
+    }
+
+    if reqwest_error.is_connect() {
+        return Some(RetryableError::Connection);
+    }
+
+    classify_status(reqwest_error.status()?, None)
+}
+
+pub fn is_retryable_error(err: &anyhow::Error) -> bool {
+    classify_error(err).is_some()
+}
+
+fn classify_status(
+    status: reqwest::StatusCode,
+    retry_after: Option<Duration>,
+) -> Option<RetryableError> {
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Some(RetryableError::RateLimited { retry_after });
+    }
+
+    if status.is_server_error() {
+        return Some(RetryableError::ServerError);
+    }
+
+    None
+}
+
+/// Determines how long to wait before the given retry (0 being the first one), or `None` if we should give up.
+///
+/// A `Retry-After` hint from the provider is obeyed as is. Otherwise, the delay grows exponentially (up to the configured maximum),
+/// with `jitter` (between 0 and 1) spreading out retries, so that many requests failing at once do not all retry at the same time.
+pub fn backoff_delay(
+    config: &RetryConfig,
+    retry: u32,
+    error: RetryableError,
+    jitter: f64,
+) -> Option<Duration> {
+    if retry >= config.max_retries {
+        return None;
+    }
+
+    if let RetryableError::RateLimited {
+        retry_after: Some(retry_after),
+    } = error
+    {
+        if retry_after > config.max_backoff() {
+            return None;
+        }
+
+        return Some(retry_after);
+    }
+
+    let exponential = INITIAL_BACKOFF
+        .saturating_mul(2_u32.saturating_pow(retry))
+        .min(config.max_backoff());
+
+    // "Equal jitter": wait at least half of the exponential delay, plus a random part of the other half.
+    let half = exponential / 2;
+
+    Some(half + half.mul_f64(jitter.clamp(0.0, 1.0)))
+}
+
+fn random_jitter() -> f64 {
+    // Jitter does not need to be cryptographically random. The sub-second part of the clock is good enough
+    // to keep concurrent retries from lining up.
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|since| since.subsec_nanos())
+        .unwrap_or(0);
+
+    f64::from(nanos) / 1_000_000_000.0
+}
+
+/// Runs the given operation against the agent, retrying (with backoff) while it fails with a retryable error,
+/// as allowed by the agent's [`RetryConfig`].
+pub async fn run_with_retries<'a, T>(
+    agent: &'a AgentInstance,
+    purpose: AgentPurpose,
+    operation: &mut impl AsyncFnMut(&'a AgentInstance) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let mut retry = 0;
+
+    loop {
+        let result = {
+            // A slot is only held while the request is in progress, not while backing off.
+            let _permit = agent
+                .concurrency_limiter()
+                .acquire(agent.identifier())
+                .await;
+
+            operation(agent).await
+        };
+
+        let err = match result {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        let Some(error_kind) = classify_error(&err) else {
+            return Err(err);
+        };
+
+        let Some(delay) = backoff_delay(agent.retry_config(), retry, error_kind, random_jitter())
+        else {
+            if agent.retry_config().max_retries > 0 {
+                tracing::warn!(
+                    ?purpose,
+                    agent_id = agent.identifier().as_string(),
+                    ?error_kind,
+                    attempts = retry + 1,
+                    "Giving up on retrying the agent request"
+                );
+            }
+
+            return Err(err);
+        };
+
+        retry += 1;
+
+        tracing::warn!(
+            ?purpose,
+            agent_id = agent.identifier().as_string(),
+            ?error_kind,
+            ?err,
+            ?delay,
+            retry,
+            max_retries = agent.retry_config().max_retries,
+            "Agent request failed with a retryable error, so retrying after a delay"
+        );
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_errors_are_classified_by_status() {
+        let err = anyhow::Error::new(
+            ProviderHttpError::new(reqwest::StatusCode::TOO_MANY_REQUESTS, "failed".to_owned())
+                .with_retry_after(Some(Duration::from_secs(3))),
+        );
+        assert_eq!(
+            classify_error(&err),
+            Some(RetryableError::RateLimited {
+                retry_after: Some(Duration::from_secs(3))
+            })
+        );
+
+        let retryable = [
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            reqwest::StatusCode::BAD_GATEWAY,
+            reqwest::StatusCode::SERVICE_UNAVAILABLE,
+        ];
+
+        for status in retryable {
+            let err = anyhow::Error::new(ProviderHttpError::new(status, "failed".to_owned()));
+            assert_eq!(
+                classify_error(&err),
+                Some(RetryableError::ServerError),
+                "{status} should be retryable"
+            );
+        }
+
+        let non_retryable = [
+            reqwest::StatusCode::BAD_REQUEST,
+            reqwest::StatusCode::UNAUTHORIZED,
+            reqwest::StatusCode::NOT_FOUND,
+        ];
+
+        for status in non_retryable {
+            let err = anyhow::Error::new(ProviderHttpError::new(status, "failed".to_owned()));
+            assert!(
+                !is_retryable_error(&err),
+                "{status} should not be retryable"
+            );
+        }
+    }
+
+    #[test]
+    fn context_does_not_hide_the_cause() {
+        let err = anyhow::Error::new(ProviderHttpError::new(
+            reqwest::StatusCode::SERVICE_UNAVAILABLE,
+            "failed".to_owned(),
+        ))
+        .context("Failed generating text");
+
+        assert!(is_retryable_error(&err));
+    }
+
+    #[test]
+    fn network_errors_are_retryable() {
+        let err = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert_eq!(classify_error(&err), Some(RetryableError::Connection));
+
+        let err = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert_eq!(classify_error(&err), Some(RetryableError::Timeout));
+
+        let err = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(!is_retryable_error(&err));
+    }
+
+    fn openai_api_error(value: serde_json::Value) -> anyhow::Error {
+        anyhow::Error::new(OpenAIError::ApiError(
+            serde_json::from_value(value).expect("a valid API error"),
+        ))
+    }
+
+    #[test]
+    fn openai_api_errors_are_classified_by_type_and_code() {
+        let err = openai_api_error(serde_json::json!({
+            "message": "Rate limit reached for gpt-4o in organization org-123 on requests per min (RPM)",
+            "type": "requests",
+            "param": null,
+            "code": "rate_limit_exceeded",
+        }));
+        assert_eq!(
+            classify_error(&err),
+            Some(RetryableError::RateLimited { retry_after: None })
+        );
+
+        let err = openai_api_error(serde_json::json!({
+            "message": "The server had an error while processing your request. Sorry about that!",
+            "type": "server_error",
+            "param": null,
+            "code": null,
+        }));
+        assert_eq!(classify_error(&err), Some(RetryableError::ServerError));
+
+        // Running out of credits is reported with a 429 as well, but waiting does not help.
+        let err = openai_api_error(serde_json::json!({
+            "message": "You exceeded your current quota, please check your plan and billing details.",
+            "type": "insufficient_quota",
+            "param": null,
+            "code": "insufficient_quota",
+        }));
+        assert!(!is_retryable_error(&err));
+
+        let err = openai_api_error(serde_json::json!({
+            "message": "Unsupported parameter: 'temperature' is not supported with this model.",
+            "type": "invalid_request_error",
+            "param": "temperature",
+            "code": "unsupported_parameter",
+        }));
+        assert!(!is_retryable_error(&err));
+    }
+
+    #[test]
+    fn recognized_library_errors_are_retryable() {
+        // Anthropic and OpenAI-compatible providers turn library errors into provider HTTP errors when possible.
+        let err = crate::agent::provider::recognize_http_error(anyhow::anyhow!(
+            r#"ApiError {{ type: "overloaded_error", message: "Overloaded" }}"#
+        ));
+        assert_eq!(classify_error(&err), Some(RetryableError::ServerError));
+
+        let err = crate::agent::provider::recognize_http_error(anyhow::anyhow!(
+            "Failed to get response from the OpenAI-compat image creation API: ApiError(\"Rate limit reached (code: rate_limit_exceeded)\")"
+        ));
+        assert_eq!(
+            classify_error(&err),
+            Some(RetryableError::RateLimited { retry_after: None })
+        );
+    }
+
+    #[test]
+    fn other_errors_are_not_retryable() {
+        assert!(!is_retryable_error(&anyhow::anyhow!(
+            "Something went wrong"
+        )));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_maximum() {
+        let config = RetryConfig {
+            max_retries: 10,
+            max_backoff_seconds: 3,
+        };
+
+        let delays: Vec<Duration> = (0..5)
+            .map(|retry| backoff_delay(&config, retry, RetryableError::ServerError, 1.0).unwrap())
+            .collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(500),
+                Duration::from_millis(1000),
+                Duration::from_millis(2000),
+                Duration::from_millis(3000),
+                Duration::from_millis(3000),
+            ]
+        );
+
+        // Without jitter, only half of the delay remains.
+        assert_eq!(
+            backoff_delay(&config, 1, RetryableError::Connection, 0.0),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn backoff_gives_up_after_max_retries() {
+        let config = RetryConfig {
+            max_retries: 2,
+            max_backoff_seconds: 30,
+        };
+
+        assert!(backoff_delay(&config, 1, RetryableError::Timeout, 0.5).is_some());
+        assert!(backoff_delay(&config, 2, RetryableError::Timeout, 0.5).is_none());
+
+        let config = RetryConfig {
+            max_retries: 0,
+            max_backoff_seconds: 30,
+        };
+        assert!(backoff_delay(&config, 0, RetryableError::Timeout, 0.5).is_none());
+    }
+
+    #[test]
+    fn backoff_obeys_retry_after_within_the_maximum() {
+        let config = RetryConfig {
+            max_retries: 2,
+            max_backoff_seconds: 30,
+        };
+
+        let rate_limited = |seconds| RetryableError::RateLimited {
+            retry_after: Some(Duration::from_secs(seconds)),
+        };
+
+        assert_eq!(
+            backoff_delay(&config, 0, rate_limited(7), 0.5),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(backoff_delay(&config, 0, rate_limited(120), 0.5), None);
+    }
+
+    #[test]
+    fn config_is_read_from_the_agent_configuration() {
+        let config: serde_yaml_ng::Value = serde_yaml_ng::from_str(
+            r#"
+base_url: https://api.example.com
+retry:
+  max_retries: 5
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            RetryConfig::from_agent_config(&config).unwrap(),
+            RetryConfig {
+                max_retries: 5,
+                max_backoff_seconds: default_max_backoff_seconds(),
+            }
+        );
+
+        let config: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str("base_url: https://api.example.com").unwrap();
+        assert_eq!(
+            RetryConfig::from_agent_config(&config).unwrap(),
+            RetryConfig::default()
+        );
+
+        let config: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str("retry:\n  max_retries: many").unwrap();
+        assert!(RetryConfig::from_agent_config(&config).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use super::super::{Tool, ToolDefinition, ToolFuture};
+
+pub(super) const NAME: &str = "calculator";
+
+/// How deeply parentheses, unary operators and powers may nest.
+/// The expression comes from the model and each level of nesting is a level of recursion, so it must not be allowed to overflow the stack.
+const MAX_NESTING_DEPTH: usize = 64;
+
+pub(super) struct Calculator;
+
+impl Tool for Calculator {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: NAME.to_owned(),
+            description: "Evaluates an arithmetic expression and returns the result. Supports `+`, `-`, `*`, `/`, `%`, `^` (power) and parentheses. Use this instead of doing non-trivial arithmetic yourself.".to_owned(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "expression": {
+                        "type": "string",
+                        "description": "The expression to evaluate, e.g. `(1.5 + 2) * 3^2`.",
+                    },
+                },
+                "required": ["expression"],
+            }),
+        }
+    }
+
+    fn call(&self, arguments: serde_json::Value) -> ToolFuture<'_> {
+        Box::pin(async move {
+            let Some(expression) = arguments.get("expression").and_then(|value| value.as_str())
+            else {
+                return Err(anyhow::anyhow!("the `expression` argument is required"));
+            };
+
+            let result = evaluate(expression)?;
+
+            Ok(format_number(result))
+        })
+    }
+}
+
+fn evaluate(expression: &str) -> anyhow::Result<f64> {
+    let mut parser = Parser {
+        chars: expression.chars().filter(|c| !c.is_whitespace()).collect(),
+        position: 0,
+        depth: 0,
+    };
+
+    let result = parser.expression()?;
+
+    if let Some(c) = parser.peek() {
+        return Err(anyhow::anyhow!("unexpected character `{c}`"));
+    }
+
+    if !result.is_finite() {
+        return Err(anyhow::anyhow!("the result is not a finite number"));
+    }
+
+    Ok(result)
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{value}")
+    }
+}
+
+// A small recursive-descent parser. Precedence (lowest to highest): `+ -`, `* / %`, unary `-`, `^`.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            return true;
+        }
+
+        false
+    }
+
+    /// Parses something nested one level deeper than where we are, refusing to go past [`MAX_NESTING_DEPTH`].
+    fn nested(&mut self, parse: fn(&mut Self) -> anyhow::Result<f64>) -> anyhow::Result<f64> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(anyhow::anyhow!(
+                "the expression is nested too deeply (at most {MAX_NESTING_DEPTH} levels are supported)"
+            ));
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    fn expression(&mut self) -> anyhow::Result<f64> {
+        let mut value = self.term()?;
+
+        loop {
+            if self.consume('+') {
+                value += self.term()?;
+            } else if self.consume('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> anyhow::Result<f64> {
+        let mut value = self.unary()?;
+
+        loop {
+            if self.consume('*') {
+                value *= self.unary()?;
+            } else if self.consume('/') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err(anyhow::anyhow!("division by zero"));
+                }
+                value /= divisor;
+            } else if self.consume('%') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err(anyhow::anyhow!("division by zero"));
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> anyhow::Result<f64> {
+        if self.consume('-') {
+            return Ok(-self.nested(Self::unary)?);
+        }
+        if self.consume('+') {
+            return self.nested(Self::unary);
+        }
+
+        self.power()
+    }
+
+    fn power(&mut self) -> anyhow::Result<f64> {
+        let base = self.primary()?;
+
+        // Right-associative: 2^3^2 = 2^(3^2)
+        if self.consume('^') {
+            let exponent = self.nested(Self::unary)?;
+            return Ok(base.powf(exponent));
+        }
+
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> anyhow::Result<f64> {
+        if self.consume('(') {
+            let value = self.nested(Self::expression)?;
+            if !self.consume(')') {
+                return Err(anyhow::anyhow!("missing closing parenthesis"));
+            }
+            return Ok(value);
+        }
+
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || c == '.' {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        if start == self.position {
+            return match self.peek() {
+                Some(c) => Err(anyhow::anyhow!("unexpected character `{c}`")),
+                None => Err(anyhow::anyhow!("unexpected end of expression")),
+            };
+        }
+
+        let number: String = self.chars[start..self.position].iter().collect();
+
+        number
+            .parse::<f64>()
+            .map_err(|_| anyhow::anyhow!("`{number}` is not a valid number"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_expressions() {
+        let cases = [
+            ("1 + 2", "3"),
+            ("2 + 3 * 4", "14"),
+            ("(2 + 3) * 4", "20"),
+            ("10 / 4", "2.5"),
+            ("-3 + 5", "2"),
+            ("2^3^2", "512"),
+            ("-2^2", "-4"),
+            ("10 % 3", "1"),
+            ("1.5 * (2 - -2)", "6"),
+        ];
+
+        for (expression, expected) in cases {
+            assert_eq!(
+                format_number(evaluate(expression).unwrap()),
+                expected,
+                "expression: {expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in ["", "1 +", "(1 + 2", "1 / 0", "2 ** 3", "abc", "1.2.3"] {
+            assert!(evaluate(expression).is_err(), "expression: {expression}");
+        }
+    }
+
+    #[test]
+    fn rejects_deeply_nested_expressions() {
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert_eq!(
+            format_number(evaluate(&nested(MAX_NESTING_DEPTH)).unwrap()),
+            "1"
+        );
+
+        for expression in [
+            nested(MAX_NESTING_DEPTH + 1),
+            nested(100_000),
+            format!("{}1", "-".repeat(100_000)),
+            format!("2{}", "^2".repeat(100_000)),
+        ] {
+            let err = evaluate(&expression).unwrap_err();
+            assert!(
+                err.to_string().contains("nested too deeply"),
+                "unexpected error: {err}"
+            );
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::{FixedOffset, Utc};
+
+use super::super::{Tool, ToolDefinition, ToolFuture};
+use crate::utils::time::parse_utc_offset;
+
+pub(super) const NAME: &str = "current_time";
+
+pub(super) struct CurrentTime;
+
+impl Tool for CurrentTime {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: NAME.to_owned(),
+            description: "Returns the current date and time. Use this whenever the answer depends on the current date or time.".to_owned(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "utc_offset": {
+                        "type": "string",
+                        "description": "An optional UTC offset (e.g. `+02:00` or `-05:30`) to return the local time for. Defaults to UTC.",
+                    },
+                },
+                "required": [],
+            }),
+        }
+    }
+
+    fn call(&self, arguments: serde_json::Value) -> ToolFuture<'_> {
+        Box::pin(async move {
+            let offset = match arguments.get("utc_offset").and_then(|value| value.as_str()) {
+                Some(utc_offset) => parse_utc_offset(utc_offset)
+                    .ok_or_else(|| anyhow::anyhow!("`{utc_offset}` is not a valid UTC offset"))?,
+                None => FixedOffset::east_opt(0).expect("A zero offset is valid"),
+            };
+
+            let now = Utc::now().with_timezone(&offset);
+
+            Ok(now.format("%Y-%m-%d (%A), %H:%M:%S %:z").to_string())
+        })
+    }
+}
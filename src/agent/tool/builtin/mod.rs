@@ -0,0 +1,16 @@
+mod calculator;
+mod current_time;
+
+use std::sync::Arc;
+
+use super::Tool;
+
+pub(super) const NAMES: &[&str] = &[calculator::NAME, current_time::NAME];
+
+pub(super) fn create(name: &str) -> Option<Arc<dyn Tool>> {
+    match name {
+        calculator::NAME => Some(Arc::new(calculator::Calculator)),
+        current_time::NAME => Some(Arc::new(current_time::CurrentTime)),
+        _ => None,
+    }
+}
@@ -0,0 +1,132 @@
+//! Tool-calling wire shapes of OpenAI-style `/chat/completions` APIs, shared by the providers which talk to
+//! such APIs directly (`openai_compat` and `venice`).
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{ToolCall, ToolDefinition};
+
+/// An entry of the request's `tools` list.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatTool {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+
+    pub function: ChatToolFunction,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatToolFunction {
+    pub name: String,
+
+    pub description: String,
+
+    pub parameters: serde_json::Value,
+}
+
+/// An entry of an assistant message's `tool_calls` list.
+///
+/// It's deserialized from responses and serialized back (as part of the assistant message) on the follow-up request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatToolCall {
+    pub id: String,
+
+    #[serde(rename = "type", default = "default_tool_call_kind")]
+    pub kind: String,
+
+    pub function: ChatToolCallFunction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatToolCallFunction {
+    pub name: String,
+
+    /// JSON-encoded arguments. Some servers send an object here instead of a string, so both are accepted.
+    #[serde(default, deserialize_with = "deserialize_arguments")]
+    pub arguments: String,
+}
+
+fn default_tool_call_kind() -> String {
+    "function".to_owned()
+}
+
+fn deserialize_arguments<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    Ok(match value {
+        serde_json::Value::Null => "".to_owned(),
+        serde_json::Value::String(arguments) => arguments,
+        other => other.to_string(),
+    })
+}
+
+pub fn chat_tools(definitions: Vec<ToolDefinition>) -> Vec<ChatTool> {
+    definitions
+        .into_iter()
+        .map(|definition| ChatTool {
+            kind: "function",
+            function: ChatToolFunction {
+                name: definition.name,
+                description: definition.description,
+                parameters: definition.parameters,
+            },
+        })
+        .collect()
+}
+
+impl From<&ChatToolCall> for ToolCall {
+    fn from(value: &ChatToolCall) -> Self {
+        Self {
+            id: value.id.clone(),
+            name: value.function.name.clone(),
+            arguments: value.function.arguments.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_calls_accept_string_and_object_arguments() {
+        let calls: Vec<ChatToolCall> = serde_json::from_str(
+            r#"[
+                {"id": "a", "type": "function", "function": {"name": "calculator", "arguments": "{\"expression\":\"1+1\"}"}},
+                {"id": "b", "function": {"name": "current_time", "arguments": {"utc_offset": "+02:00"}}},
+                {"id": "c", "function": {"name": "current_time"}}
+            ]"#,
+        )
+        .unwrap();
+
+        let calls: Vec<ToolCall> = calls.iter().map(ToolCall::from).collect();
+
+        assert_eq!(calls[0].arguments, r#"{"expression":"1+1"}"#);
+        assert_eq!(calls[1].name, "current_time");
+        assert_eq!(calls[1].arguments, r#"{"utc_offset":"+02:00"}"#);
+        assert_eq!(calls[2].arguments, "");
+    }
+
+    #[test]
+    fn tools_serialize_as_functions() {
+        let tools = chat_tools(vec![ToolDefinition {
+            name: "calculator".to_owned(),
+            description: "Calculates".to_owned(),
+            parameters: serde_json::json!({"type": "object"}),
+        }]);
+
+        assert_eq!(
+            serde_json::to_value(&tools).unwrap(),
+            serde_json::json!([{
+                "type": "function",
+                "function": {
+                    "name": "calculator",
+                    "description": "Calculates",
+                    "parameters": {"type": "object"},
+                },
+            }])
+        );
+    }
+}
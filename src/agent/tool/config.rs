@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use super::mcp::McpToolsConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ToolsConfig {
+    /// Names of the client-side tools made available to the model.
+    #[serde(default)]
+    pub enabled: Vec<String>,
+
+    /// MCP servers whose tools are made available to the model.
+    #[serde(default, skip_serializing_if = "McpToolsConfig::is_default")]
+    pub mcp: McpToolsConfig,
+}
+
+impl ToolsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_enabled_tool_names(&self.enabled)?;
+        self.mcp.validate()
+    }
+}
+
+pub fn validate_enabled_tool_names(names: &[String]) -> Result<(), String> {
+    for name in names {
+        if !super::builtin::NAMES.contains(&name.as_str()) {
+            return Err(format!(
+                "The tool `{}` is not a known tool. Available tools: {}.",
+                name,
+                super::builtin::NAMES
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_known_tools_only() {
+        let config = ToolsConfig {
+            enabled: vec!["current_time".to_owned(), "calculator".to_owned()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = ToolsConfig {
+            enabled: vec!["current_time".to_owned(), "rm_rf".to_owned()],
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("`rm_rf`"));
+    }
+}
@@ -0,0 +1,408 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use super::http::HttpTransport;
+use super::jsonrpc::{Message, Request};
+use super::stdio::StdioTransport;
+use super::{ServerDefinition, Transport};
+
+/// The protocol revision we speak. Servers may negotiate an older one, which is fine for the few methods we use.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// How long to wait for a server to respond. Tools may legitimately take a while (e.g. fetching web pages).
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long to trust a server's list of tools before asking for it again.
+const TOOL_LIST_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// A tool, as described by an MCP server's `tools/list` response.
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct RemoteTool {
+    pub(super) name: String,
+
+    #[serde(default)]
+    pub(super) description: Option<String>,
+
+    #[serde(rename = "inputSchema", default)]
+    pub(super) input_schema: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListToolsResult {
+    #[serde(default)]
+    tools: Vec<RemoteTool>,
+
+    #[serde(rename = "nextCursor", default)]
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallToolResult {
+    #[serde(default)]
+    content: Vec<serde_json::Value>,
+
+    #[serde(rename = "structuredContent", default)]
+    structured_content: Option<serde_json::Value>,
+
+    #[serde(rename = "isError", default)]
+    is_error: bool,
+}
+
+enum Connection {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+}
+
+/// An initialized connection to a single MCP server.
+pub(super) struct Client {
+    server_id: String,
+    connection: Connection,
+    next_request_id: AtomicU64,
+    tools: tokio::sync::Mutex<Option<(Instant, Vec<RemoteTool>)>>,
+}
+
+impl Client {
+    pub(super) async fn connect(server: &ServerDefinition) -> anyhow::Result<Self> {
+        let connection = match server.transport {
+            Transport::Stdio => Connection::Stdio(StdioTransport::spawn(server)?),
+            Transport::Http => Connection::Http(HttpTransport::new(server)?),
+        };
+
+        let client = Self::new(server.id.clone(), connection);
+
+        client.initialize().await.map_err(|err| {
+            anyhow::anyhow!(
+                "Failed initializing the `{}` MCP server: {}",
+                server.id,
+                err
+            )
+        })?;
+
+        Ok(client)
+    }
+
+    fn new(server_id: String, connection: Connection) -> Self {
+        Self {
+            server_id,
+            connection,
+            next_request_id: AtomicU64::new(1),
+            tools: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub(super) fn server_id(&self) -> &str {
+        &self.server_id
+    }
+
+    /// Tells if the connection is gone for good and a new client needs to be created.
+    pub(super) fn is_closed(&self) -> bool {
+        match &self.connection {
+            Connection::Stdio(transport) => transport.is_closed(),
+            Connection::Http(transport) => transport.is_closed(),
+        }
+    }
+
+    async fn initialize(&self) -> anyhow::Result<()> {
+        let result = self
+            .request(
+                "initialize",
+                Some(serde_json::json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "baibot",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                })),
+            )
+            .await?;
+
+        let protocol_version = result
+            .get("protocolVersion")
+            .and_then(|value| value.as_str())
+            .unwrap_or(PROTOCOL_VERSION)
+            .to_owned();
+
+        tracing::debug!(
+            server_id = self.server_id,
+            protocol_version,
+            "Initialized MCP server connection"
+        );
+
+        if let Connection::Http(transport) = &self.connection {
+            transport.set_protocol_version(protocol_version);
+        }
+
+        let notification = Request::notification("notifications/initialized");
+        match &self.connection {
+            Connection::Stdio(transport) => transport.notify(&notification).await,
+            Connection::Http(transport) => transport.notify(&notification).await,
+        }
+    }
+
+    /// Returns the tools the server offers, following pagination. The result is cached for a while.
+    pub(super) async fn list_tools(&self) -> anyhow::Result<Vec<RemoteTool>> {
+        let mut cache = self.tools.lock().await;
+
+        if let Some((fetched_at, tools)) = cache.as_ref()
+            && fetched_at.elapsed() < TOOL_LIST_CACHE_TTL
+        {
+            return Ok(tools.clone());
+        }
+
+        let mut tools = vec![];
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let params = cursor
+                .as_ref()
+                .map(|cursor| serde_json::json!({ "cursor": cursor }));
+
+            let result: ListToolsResult =
+                serde_json::from_value(self.request("tools/list", params).await?)?;
+
+            tools.extend(result.tools);
+
+            match result.next_cursor {
+                Some(next_cursor) if !next_cursor.is_empty() => cursor = Some(next_cursor),
+                _ => break,
+            }
+        }
+
+        *cache = Some((Instant::now(), tools.clone()));
+
+        Ok(tools)
+    }
+
+    pub(super) async fn call_tool(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> anyhow::Result<String> {
+        let result = self
+            .request(
+                "tools/call",
+                Some(serde_json::json!({
+                    "name": name,
+                    "arguments": arguments,
+                })),
+            )
+            .await?;
+
+        let result: CallToolResult = serde_json::from_value(result)?;
+
+        let text = call_tool_result_text(&result);
+
+        if result.is_error {
+            return Err(anyhow::anyhow!(text));
+        }
+
+        Ok(text)
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let request = Request::new(id, method, params);
+
+        let response = match &self.connection {
+            Connection::Stdio(transport) => {
+                tokio::time::timeout(REQUEST_TIMEOUT, transport.request(id, &request)).await
+            }
+            Connection::Http(transport) => {
+                tokio::time::timeout(REQUEST_TIMEOUT, transport.request(id, &request)).await
+            }
+        };
+
+        let response: Message = response.map_err(|_| {
+            anyhow::anyhow!(
+                "The `{}` MCP server did not respond to `{}` in time",
+                self.server_id,
+                method
+            )
+        })??;
+
+        response.into_result()
+    }
+}
+
+/// Turns the content blocks of a `tools/call` result into text for the model.
+///
+/// Models only get text back from tools, so non-text content (images, audio) is merely mentioned.
+fn call_tool_result_text(result: &CallToolResult) -> String {
+    let mut parts: Vec<String> = vec![];
+
+    for block in &result.content {
+        let kind = block.get("type").and_then(|value| value.as_str());
+
+        match kind {
+            Some("text") => {
+                if let Some(text) = block.get("text").and_then(|value| value.as_str()) {
+                    parts.push(text.to_owned());
+                }
+            }
+            Some("resource") => {
+                let resource = block.get("resource");
+
+                match resource
+                    .and_then(|resource| resource.get("text"))
+                    .and_then(|value| value.as_str())
+                {
+                    Some(text) => parts.push(text.to_owned()),
+                    None => parts.push(format!(
+                        "[resource: {}]",
+                        resource
+                            .and_then(|resource| resource.get("uri"))
+                            .and_then(|value| value.as_str())
+                            .unwrap_or("unknown")
+                    )),
+                }
+            }
+            Some(kind) => parts.push(format!("[{kind} content omitted]")),
+            None => {}
+        }
+    }
+
+    if parts.is_empty()
+        && let Some(structured_content) = &result.structured_content
+    {
+        return structured_content.to_string();
+    }
+
+    parts.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    use super::*;
+
+    /// Plays the role of a stdio MCP server on the other end of in-memory pipes.
+    async fn fake_server(
+        reader: tokio::io::DuplexStream,
+        mut writer: tokio::io::DuplexStream,
+    ) -> anyhow::Result<()> {
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            let request: serde_json::Value = serde_json::from_str(&line)?;
+
+            let Some(id) = request.get("id").cloned() else {
+                // A notification
+                continue;
+            };
+
+            let result = match request["method"].as_str() {
+                Some("initialize") => serde_json::json!({
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {"tools": {}},
+                    "serverInfo": {"name": "fake", "version": "1.0"},
+                }),
+                Some("tools/list") => match request["params"]["cursor"].as_str() {
+                    None => serde_json::json!({
+                        "tools": [{"name": "echo", "description": "Echoes", "inputSchema": {"type": "object"}}],
+                        "nextCursor": "page-2",
+                    }),
+                    Some(_) => serde_json::json!({
+                        "tools": [{"name": "fail", "inputSchema": {"type": "object"}}],
+                    }),
+                },
+                Some("tools/call") => match request["params"]["name"].as_str() {
+                    Some("echo") => serde_json::json!({
+                        "content": [{"type": "text", "text": request["params"]["arguments"]["text"]}],
+                    }),
+                    _ => serde_json::json!({
+                        "content": [{"type": "text", "text": "Something broke"}],
+                        "isError": true,
+                    }),
+                },
+                _ => serde_json::Value::Null,
+            };
+
+            // Interleave a notification, which the client is expected to skip over.
+            let notification = serde_json::json!({"jsonrpc": "2.0", "method": "notifications/message", "params": {}});
+            let response = serde_json::json!({"jsonrpc": "2.0", "id": id, "result": result});
+
+            writer
+                .write_all(format!("{notification}\n{response}\n").as_bytes())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn talks_to_a_stdio_server() {
+        let (client_writer, server_reader) = tokio::io::duplex(4096);
+        let (server_writer, client_reader) = tokio::io::duplex(4096);
+
+        tokio::spawn(fake_server(server_reader, server_writer));
+
+        let client = Client::new(
+            "fake".to_owned(),
+            Connection::Stdio(super::super::stdio::from_streams(
+                client_reader,
+                client_writer,
+            )),
+        );
+
+        client.initialize().await.unwrap();
+
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(
+            tools
+                .iter()
+                .map(|tool| tool.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["echo", "fail"]
+        );
+
+        assert_eq!(
+            client
+                .call_tool("echo", serde_json::json!({"text": "hello"}))
+                .await
+                .unwrap(),
+            "hello"
+        );
+
+        let err = client
+            .call_tool("fail", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Something broke");
+
+        assert!(!client.is_closed());
+    }
+
+    #[test]
+    fn non_text_content_is_summarized() {
+        let result: CallToolResult = serde_json::from_value(serde_json::json!({
+            "content": [
+                {"type": "text", "text": "Here you go"},
+                {"type": "image", "data": "...", "mimeType": "image/png"},
+                {"type": "resource", "resource": {"uri": "file:///a.txt", "text": "File contents"}},
+                {"type": "resource", "resource": {"uri": "file:///b.bin", "blob": "..."}},
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            call_tool_result_text(&result),
+            "Here you go\n[image content omitted]\nFile contents\n[resource: file:///b.bin]"
+        );
+
+        let result: CallToolResult = serde_json::from_value(serde_json::json!({
+            "content": [],
+            "structuredContent": {"temperature": 21},
+        }))
+        .unwrap();
+
+        assert_eq!(call_tool_result_text(&result), r#"{"temperature":21}"#);
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// A local process, spoken to over its stdin/stdout (newline-delimited JSON-RPC messages).
+    Stdio,
+    /// A remote server, spoken to via the "Streamable HTTP" transport.
+    Http,
+}
+
+/// Describes how to reach an MCP server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerDefinition {
+    pub id: String,
+
+    pub transport: Transport,
+
+    // stdio only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    // http only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl ServerDefinition {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("MCP server IDs must not be empty.".to_owned());
+        }
+
+        // The ID ends up in the names of the tools we expose to models,
+        // and providers only accept a restricted set of characters there.
+        if !self
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "The MCP server ID `{}` is not valid. It may only contain letters, digits, `-` and `_`.",
+                self.id
+            ));
+        }
+
+        match self.transport {
+            Transport::Stdio => {
+                if self.command.as_deref().unwrap_or("").is_empty() {
+                    return Err(format!(
+                        "The `{}` MCP server uses the stdio transport, so it must specify a `command`.",
+                        self.id
+                    ));
+                }
+
+                if self.url.is_some() || !self.headers.is_empty() {
+                    return Err(format!(
+                        "The `{}` MCP server uses the stdio transport, so `url` and `headers` do not apply to it.",
+                        self.id
+                    ));
+                }
+            }
+            Transport::Http => {
+                let Some(url) = &self.url else {
+                    return Err(format!(
+                        "The `{}` MCP server uses the http transport, so it must specify a `url`.",
+                        self.id
+                    ));
+                };
+
+                match url::Url::parse(url) {
+                    Ok(parsed) if ["http", "https"].contains(&parsed.scheme()) => {}
+                    _ => {
+                        return Err(format!(
+                            "The URL of the `{}` MCP server is not a valid HTTP(S) URL.",
+                            self.id
+                        ));
+                    }
+                }
+
+                if self.command.is_some() || !self.args.is_empty() || !self.env.is_empty() {
+                    return Err(format!(
+                        "The `{}` MCP server uses the http transport, so `command`, `args` and `env` do not apply to it.",
+                        self.id
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn validate_server_definitions(servers: &[ServerDefinition]) -> Result<(), String> {
+    for (index, server) in servers.iter().enumerate() {
+        server.validate()?;
+
+        if servers[..index].iter().any(|other| other.id == server.id) {
+            return Err(format!(
+                "The MCP server ID `{}` is used more than once.",
+                server.id
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Per-agent configuration of the MCP tools offered to the model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpToolsConfig {
+    /// IDs of MCP servers (declared in the static configuration) whose tools the agent may use.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<String>,
+
+    /// Additional MCP servers dedicated to this agent.
+    ///
+    /// Only the http transport is allowed here. Spawning processes is reserved to the static configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_servers: Vec<ServerDefinition>,
+
+    /// Patterns (`server/tool`, `*` matches anything) of tools to offer. An empty list allows all tools.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+
+    /// Patterns (`server/tool`, `*` matches anything) of tools to hide. This takes precedence over `allow`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl McpToolsConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.servers.iter().any(|id| id.is_empty()) {
+            return Err("MCP server IDs must not be empty.".to_owned());
+        }
+
+        validate_server_definitions(&self.extra_servers)?;
+
+        if let Some(server) = self
+            .extra_servers
+            .iter()
+            .find(|server| server.transport != Transport::Http)
+        {
+            return Err(format!(
+                "The `{}` MCP server cannot be declared in an agent's configuration. Only http servers are allowed there. stdio servers must be declared in the bot's static configuration.",
+                server.id
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Tells if the tool called `tool_name` on the `server_id` server passes the allow/deny lists.
+    pub fn is_tool_allowed(&self, server_id: &str, tool_name: &str) -> bool {
+        let qualified_name = format!("{server_id}/{tool_name}");
+
+        if self
+            .deny
+            .iter()
+            .any(|pattern| wildcard_match(pattern, &qualified_name))
+        {
+            return false;
+        }
+
+        self.allow.is_empty()
+            || self
+                .allow
+                .iter()
+                .any(|pattern| wildcard_match(pattern, &qualified_name))
+    }
+}
+
+/// Matches `text` against a `pattern` in which `*` stands for any (possibly empty) sequence of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+
+    // `split` always yields at least one item.
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern, so it must have matched exactly.
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http_server(id: &str) -> ServerDefinition {
+        ServerDefinition {
+            id: id.to_owned(),
+            transport: Transport::Http,
+            command: None,
+            args: vec![],
+            env: BTreeMap::new(),
+            url: Some("https://mcp.example.com/mcp".to_owned()),
+            headers: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn wildcard_matching() {
+        let cases = [
+            ("github/*", "github/create_issue", true),
+            ("github/*", "gitlab/create_issue", false),
+            ("*/delete_*", "fs/delete_file", true),
+            ("*/delete_*", "fs/read_file", false),
+            ("fs/read_file", "fs/read_file", true),
+            ("fs/read_file", "fs/read_file_2", false),
+            ("*", "anything/at_all", true),
+            ("a*a", "a", false),
+        ];
+
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                wildcard_match(pattern, text),
+                expected,
+                "pattern: {pattern}, text: {text}"
+            );
+        }
+    }
+
+    #[test]
+    fn deny_takes_precedence_over_allow() {
+        let config = McpToolsConfig {
+            allow: vec!["fs/*".to_owned()],
+            deny: vec!["*/write_*".to_owned()],
+            ..Default::default()
+        };
+
+        assert!(config.is_tool_allowed("fs", "read_file"));
+        assert!(!config.is_tool_allowed("fs", "write_file"));
+        assert!(!config.is_tool_allowed("github", "create_issue"));
+
+        let config = McpToolsConfig::default();
+        assert!(config.is_tool_allowed("github", "create_issue"));
+    }
+
+    #[test]
+    fn agents_cannot_declare_stdio_servers() {
+        let mut config = McpToolsConfig {
+            extra_servers: vec![http_server("remote")],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.extra_servers.push(ServerDefinition {
+            id: "local".to_owned(),
+            transport: Transport::Stdio,
+            command: Some("/usr/bin/mcp-server".to_owned()),
+            url: None,
+            ..http_server("local")
+        });
+        assert!(config.validate().unwrap_err().contains("`local`"));
+    }
+
+    #[test]
+    fn server_definitions_are_validated() {
+        assert!(validate_server_definitions(&[http_server("a"), http_server("b")]).is_ok());
+        assert!(validate_server_definitions(&[http_server("a"), http_server("a")]).is_err());
+        assert!(validate_server_definitions(&[http_server("a b")]).is_err());
+
+        let server = ServerDefinition {
+            url: Some("ftp://example.com".to_owned()),
+            ..http_server("a")
+        };
+        assert!(server.validate().is_err());
+
+        let server = ServerDefinition {
+            transport: Transport::Stdio,
+            url: None,
+            ..http_server("a")
+        };
+        assert!(server.validate().is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::agent::provider::sse::EventStreamReader;
+
+use super::ServerDefinition;
+use super::jsonrpc::{Message, Request};
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const PROTOCOL_VERSION_HEADER: &str = "MCP-Protocol-Version";
+
+/// Talks to a remote MCP server via the "Streamable HTTP" transport.
+///
+/// Each message is POST-ed separately. The server replies either with a plain JSON body
+/// or with an event stream, which eventually carries the response to our request.
+pub(super) struct HttpTransport {
+    http: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+    session_id: std::sync::Mutex<Option<String>>,
+    protocol_version: std::sync::Mutex<Option<String>>,
+    closed: AtomicBool,
+}
+
+impl HttpTransport {
+    pub(super) fn new(server: &ServerDefinition) -> anyhow::Result<Self> {
+        let Some(url) = &server.url else {
+            return Err(anyhow::anyhow!(
+                "The `{}` MCP server does not specify a URL",
+                server.id
+            ));
+        };
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            url: url.clone(),
+            headers: server.headers.clone(),
+            session_id: std::sync::Mutex::new(None),
+            protocol_version: std::sync::Mutex::new(None),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    pub(super) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Remembers the protocol version negotiated during initialization, to be sent along with subsequent requests.
+    pub(super) fn set_protocol_version(&self, version: String) {
+        *self
+            .protocol_version
+            .lock()
+            .expect("The protocol version lock is poisoned") = Some(version);
+    }
+
+    pub(super) async fn request(&self, id: u64, request: &Request<'_>) -> anyhow::Result<Message> {
+        let response = self.post(request).await?;
+
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+
+        if !is_event_stream {
+            return Ok(response.json().await?);
+        }
+
+        let mut reader = EventStreamReader::new(response);
+
+        while let Some(data) = reader.next_data().await? {
+            let message: Message = match serde_json::from_str(&data) {
+                Ok(message) => message,
+                Err(err) => {
+                    tracing::debug!(?err, data, "Ignoring non-JSON-RPC MCP server event");
+                    continue;
+                }
+            };
+
+            if message.response_id() == Some(id) {
+                return Ok(message);
+            }
+
+            tracing::trace!(method = ?message.method, "Ignoring MCP server message");
+        }
+
+        Err(anyhow::anyhow!(
+            "The MCP server ended the event stream without responding"
+        ))
+    }
+
+    pub(super) async fn notify(&self, notification: &Request<'_>) -> anyhow::Result<()> {
+        self.post(notification).await?;
+
+        Ok(())
+    }
+
+    async fn post(&self, message: &Request<'_>) -> anyhow::Result<reqwest::Response> {
+        let mut request_builder = self
+            .http
+            .post(&self.url)
+            .header(
+                reqwest::header::ACCEPT,
+                "application/json, text/event-stream",
+            )
+            .json(message);
+
+        for (name, value) in &self.headers {
+            request_builder = request_builder.header(name.as_str(), value.as_str());
+        }
+
+        let session_id = self
+            .session_id
+            .lock()
+            .expect("The session ID lock is poisoned")
+            .clone();
+
+        if let Some(session_id) = &session_id {
+            request_builder = request_builder.header(SESSION_ID_HEADER, session_id.as_str());
+        }
+
+        let protocol_version = self
+            .protocol_version
+            .lock()
+            .expect("The protocol version lock is poisoned")
+            .clone();
+
+        if let Some(protocol_version) = protocol_version {
+            request_builder = request_builder.header(PROTOCOL_VERSION_HEADER, protocol_version);
+        }
+
+        let response = request_builder.send().await?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::NOT_FOUND && session_id.is_some() {
+            // The server forgot about our session (e.g. it restarted). A new connection needs to be established.
+            self.closed.store(true, Ordering::Relaxed);
+
+            return Err(anyhow::anyhow!("The MCP server session has expired"));
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            tracing::warn!(%status, body, "MCP server request failed");
+
+            return Err(anyhow::anyhow!(
+                "The MCP server responded with status {status}"
+            ));
+        }
+
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            *self
+                .session_id
+                .lock()
+                .expect("The session ID lock is poisoned") = Some(session_id.to_owned());
+        }
+
+        Ok(response)
+    }
+}
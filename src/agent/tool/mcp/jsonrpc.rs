@@ -0,0 +1,127 @@
+//! The subset of JSON-RPC 2.0 which MCP clients need.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub(super) struct Request<'a> {
+    jsonrpc: &'static str,
+
+    // Notifications carry no ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+
+    method: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<serde_json::Value>,
+}
+
+impl<'a> Request<'a> {
+    pub(super) fn new(id: u64, method: &'a str, params: Option<serde_json::Value>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: Some(id),
+            method,
+            params,
+        }
+    }
+
+    pub(super) fn notification(method: &'a str) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: None,
+            method,
+            params: None,
+        }
+    }
+}
+
+/// Any message a server may send us: a response to one of our requests, a notification or a request of its own.
+#[derive(Debug, Deserialize)]
+pub(super) struct Message {
+    #[serde(default)]
+    pub(super) id: Option<serde_json::Value>,
+
+    #[serde(default)]
+    pub(super) method: Option<String>,
+
+    #[serde(default)]
+    pub(super) result: Option<serde_json::Value>,
+
+    #[serde(default)]
+    pub(super) error: Option<Error>,
+}
+
+impl Message {
+    /// Returns the ID of the request this message responds to, if it's a response at all.
+    pub(super) fn response_id(&self) -> Option<u64> {
+        if self.method.is_some() {
+            return None;
+        }
+
+        self.id.as_ref().and_then(|id| id.as_u64())
+    }
+
+    pub(super) fn into_result(self) -> anyhow::Result<serde_json::Value> {
+        if let Some(error) = self.error {
+            return Err(anyhow::anyhow!(
+                "The MCP server returned an error ({}): {}",
+                error.code,
+                error.message
+            ));
+        }
+
+        Ok(self.result.unwrap_or(serde_json::Value::Null))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Error {
+    pub(super) code: i64,
+    pub(super) message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_and_notifications_serialize() {
+        let request = Request::new(7, "tools/list", Some(serde_json::json!({})));
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({"jsonrpc": "2.0", "id": 7, "method": "tools/list", "params": {}})
+        );
+
+        let notification = Request::notification("notifications/initialized");
+        assert_eq!(
+            serde_json::to_value(&notification).unwrap(),
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"})
+        );
+    }
+
+    #[test]
+    fn responses_are_told_apart_from_server_requests() {
+        let response: Message =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":3,"result":{"tools":[]}}"#).unwrap();
+        assert_eq!(response.response_id(), Some(3));
+        assert!(response.into_result().is_ok());
+
+        let error: Message = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","id":4,"error":{"code":-32601,"message":"Method not found"}}"#,
+        )
+        .unwrap();
+        assert_eq!(error.response_id(), Some(4));
+        assert!(
+            error
+                .into_result()
+                .unwrap_err()
+                .to_string()
+                .contains("Method not found")
+        );
+
+        let server_request: Message =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#).unwrap();
+        assert_eq!(server_request.response_id(), None);
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::agent::{AgentInstance, ControllerTrait, PublicIdentifier};
+
+use super::super::ToolRegistry;
+use super::client::Client;
+use super::{McpTool, McpToolsConfig, ServerDefinition};
+
+/// The MCP tools an agent gets to see.
+#[derive(Default)]
+pub struct ResolvedTools {
+    pub registry: ToolRegistry,
+
+    /// IDs of servers which could not be used. The reasons are only logged, as they may reveal commands, paths, etc.
+    /// These don't prevent the agent from working. It merely doesn't get their tools.
+    pub failures: Vec<String>,
+}
+
+#[derive(Default)]
+struct Connections {
+    // Keyed by the full server definition (not just the ID), so that changing an agent's configuration
+    // results in a new connection instead of reusing one established with stale settings.
+    clients: HashMap<String, Arc<Client>>,
+
+    /// The servers (keyed like `clients`) each agent was last configured to use.
+    server_keys_by_agent: HashMap<String, HashSet<String>>,
+}
+
+impl Connections {
+    /// Records the servers an agent uses and drops the connections no agent uses anymore
+    /// (e.g. ones established with an agent's previous configuration).
+    fn use_servers(&mut self, agent_key: String, server_keys: HashSet<String>) {
+        if server_keys.is_empty() {
+            self.server_keys_by_agent.remove(&agent_key);
+        } else {
+            self.server_keys_by_agent.insert(agent_key, server_keys);
+        }
+
+        let server_keys_in_use: HashSet<&String> =
+            self.server_keys_by_agent.values().flatten().collect();
+
+        self.clients
+            .retain(|key, _| server_keys_in_use.contains(key));
+    }
+}
+
+/// Keeps track of the statically-declared MCP servers and of the connections to all servers in use.
+pub struct Manager {
+    servers: Vec<ServerDefinition>,
+
+    connections: tokio::sync::Mutex<Connections>,
+}
+
+impl Manager {
+    pub fn new(servers: Vec<ServerDefinition>) -> Self {
+        Self {
+            servers,
+            connections: tokio::sync::Mutex::new(Connections::default()),
+        }
+    }
+
+    /// Collects the MCP tools the given agent has access to, connecting to servers as necessary.
+    ///
+    /// Room-local agents can be defined by non-administrators, so they never get access to MCP servers.
+    pub async fn tools_for_agent(&self, agent: &AgentInstance) -> ResolvedTools {
+        let agent_key = agent.identifier().to_string();
+
+        let config = match agent.identifier() {
+            PublicIdentifier::DynamicRoomLocal(_) => None,
+            _ => agent.controller().text_generation_mcp_config(),
+        };
+
+        let Some(config) = config else {
+            // The agent may have used some servers before its configuration changed.
+            self.connections
+                .lock()
+                .await
+                .use_servers(agent_key, HashSet::new());
+
+            return ResolvedTools::default();
+        };
+
+        self.tools(agent_key, &config).await
+    }
+
+    async fn tools(&self, agent_key: String, config: &McpToolsConfig) -> ResolvedTools {
+        let mut resolved = ResolvedTools::default();
+
+        let mut servers: Vec<&ServerDefinition> = vec![];
+
+        for server_id in &config.servers {
+            match self.servers.iter().find(|server| &server.id == server_id) {
+                Some(server) => servers.push(server),
+                None => {
+                    tracing::warn!(server_id, "Skipping unknown MCP server");
+                    resolved.failures.push(server_id.clone());
+                }
+            }
+        }
+
+        servers.extend(config.extra_servers.iter());
+
+        let mut keyed_servers: Vec<(String, &ServerDefinition)> = vec![];
+
+        for server in servers {
+            match serde_json::to_string(server) {
+                Ok(key) => keyed_servers.push((key, server)),
+                Err(err) => {
+                    tracing::warn!(server_id = server.id, ?err, "Failed keying MCP server");
+                    resolved.failures.push(server.id.clone());
+                }
+            }
+        }
+
+        self.connections.lock().await.use_servers(
+            agent_key,
+            keyed_servers.iter().map(|(key, _)| key.clone()).collect(),
+        );
+
+        for (key, server) in keyed_servers {
+            let tools = match self.client(&key, server).await {
+                Ok(client) => match client.list_tools().await {
+                    Ok(tools) => Ok((client, tools)),
+                    Err(err) => {
+                        // The connection may be broken in a way we cannot detect, so we start afresh next time.
+                        self.evict(&key, &client).await;
+                        Err(err)
+                    }
+                },
+                Err(err) => Err(err),
+            };
+
+            let (client, tools) = match tools {
+                Ok(result) => result,
+                Err(err) => {
+                    tracing::warn!(server_id = server.id, ?err, "Failed listing MCP tools");
+                    resolved.failures.push(server.id.clone());
+                    continue;
+                }
+            };
+
+            for tool in tools {
+                if !config.is_tool_allowed(&server.id, &tool.name) {
+                    continue;
+                }
+
+                resolved
+                    .registry
+                    .register(Arc::new(McpTool::new(client.clone(), tool)));
+            }
+        }
+
+        resolved
+    }
+
+    async fn client(&self, key: &str, server: &ServerDefinition) -> anyhow::Result<Arc<Client>> {
+        {
+            let mut connections = self.connections.lock().await;
+
+            match connections.clients.get(key) {
+                Some(client) if !client.is_closed() => return Ok(client.clone()),
+                Some(_) => {
+                    // Dropped right away, so that it's gone even if reconnecting below fails.
+                    connections.clients.remove(key);
+                }
+                None => {}
+            }
+        }
+
+        // Connecting may take a while (e.g. a stdio server being downloaded and started by `npx`),
+        // so we don't hold the lock meanwhile. Racing connections are harmless: the loser is dropped.
+        tracing::info!(server_id = server.id, "Connecting to MCP server");
+
+        let client = Arc::new(Client::connect(server).await?);
+
+        self.connections
+            .lock()
+            .await
+            .clients
+            .insert(key.to_owned(), client.clone());
+
+        Ok(client)
+    }
+
+    /// Forgets the given client, unless it has already been replaced by a newer one.
+    async fn evict(&self, key: &str, client: &Arc<Client>) {
+        let mut connections = self.connections.lock().await;
+
+        if connections
+            .clients
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, client))
+        {
+            connections.clients.remove(key);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Model Context Protocol (MCP) client.
+//
+// MCP servers are declared in the static configuration (and, for http servers, also in the configuration of individual agents).
+// Agents opt into using some of them, optionally narrowing down the offered tools with allow/deny lists.
+// Connections are established lazily (when an agent first needs a server's tools) and are shared by all agents.
+//
+// The tools of MCP servers are exposed to models as regular client-side tools (see `super::Tool`),
+// so every provider's tool-call loop can make use of them without knowing anything about MCP.
+
+mod client;
+mod config;
+mod http;
+mod jsonrpc;
+mod manager;
+mod stdio;
+
+use std::sync::Arc;
+
+use super::{Tool, ToolDefinition, ToolFuture};
+
+pub use config::{McpToolsConfig, ServerDefinition, Transport, validate_server_definitions};
+pub use manager::{Manager, ResolvedTools};
+
+/// Providers reject tool names longer than this.
+const MAX_TOOL_NAME_LENGTH: usize = 64;
+
+/// A tool living on an MCP server.
+struct McpTool {
+    client: Arc<client::Client>,
+    name: String,
+    remote: client::RemoteTool,
+}
+
+impl McpTool {
+    fn new(client: Arc<client::Client>, remote: client::RemoteTool) -> Self {
+        Self {
+            name: exposed_tool_name(client.server_id(), &remote.name),
+            client,
+            remote,
+        }
+    }
+}
+
+impl Tool for McpTool {
+    fn definition(&self) -> ToolDefinition {
+        // Some servers omit the schema for tools which take no arguments, but providers insist on one.
+        let parameters = if self.remote.input_schema.is_object() {
+            self.remote.input_schema.clone()
+        } else {
+            serde_json::json!({"type": "object", "properties": {}})
+        };
+
+        ToolDefinition {
+            name: self.name.clone(),
+            description: self.remote.description.clone().unwrap_or_default(),
+            parameters,
+        }
+    }
+
+    fn call(&self, arguments: serde_json::Value) -> ToolFuture<'_> {
+        Box::pin(self.client.call_tool(&self.remote.name, arguments))
+    }
+}
+
+/// Builds the name a model sees for an MCP tool.
+///
+/// Tools are prefixed with their server's ID, so that same-named tools on different servers do not clash.
+/// Characters which providers do not accept in tool names are replaced.
+fn exposed_tool_name(server_id: &str, tool_name: &str) -> String {
+    format!("{server_id}__{tool_name}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_TOOL_NAME_LENGTH)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposed_tool_names_are_prefixed_and_sanitized() {
+        assert_eq!(
+            exposed_tool_name("github", "create_issue"),
+            "github__create_issue"
+        );
+        assert_eq!(exposed_tool_name("fs", "read.file v2"), "fs__read_file_v2");
+        assert_eq!(
+            exposed_tool_name("server", &"x".repeat(100)).len(),
+            MAX_TOOL_NAME_LENGTH
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::oneshot;
+
+use super::ServerDefinition;
+use super::jsonrpc::{Message, Request};
+
+type PendingRequests = Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<Message>>>>;
+
+/// Talks to a local MCP server process over its stdin/stdout.
+///
+/// Messages are newline-delimited JSON. A background task reads the server's output
+/// and routes responses to whoever is waiting for them (by request ID).
+///
+/// Dropping the transport closes the server's stdin, which is how MCP servers are asked to exit.
+/// The background task owns the process and kills it, should it linger after its output is closed.
+pub(super) struct StdioTransport {
+    writer: tokio::sync::Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    pending: PendingRequests,
+    closed: Arc<AtomicBool>,
+}
+
+impl StdioTransport {
+    pub(super) fn spawn(server: &ServerDefinition) -> anyhow::Result<Self> {
+        let Some(command) = &server.command else {
+            return Err(anyhow::anyhow!(
+                "The `{}` MCP server does not specify a command",
+                server.id
+            ));
+        };
+
+        let mut child = tokio::process::Command::new(command)
+            .args(&server.args)
+            .envs(&server.env)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| {
+                anyhow::anyhow!(
+                    "Failed starting the `{}` MCP server (`{}`): {}",
+                    server.id,
+                    command,
+                    err
+                )
+            })?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(anyhow::anyhow!(
+                "Failed attaching to the standard input/output of the `{}` MCP server",
+                server.id
+            ));
+        };
+
+        // Servers log to stderr. We relay that to our own logs, as it's the only way to tell why a server misbehaves.
+        if let Some(stderr) = child.stderr.take() {
+            let server_id = server.id.clone();
+
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!(server_id, line, "MCP server stderr");
+                }
+            });
+        }
+
+        Ok(Self::new(server.id.clone(), stdout, stdin, Some(child)))
+    }
+
+    fn new(
+        server_id: String,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        writer: impl AsyncWrite + Send + Unpin + 'static,
+        child: Option<tokio::process::Child>,
+    ) -> Self {
+        let pending: PendingRequests = Default::default();
+        let closed = Arc::new(AtomicBool::new(false));
+
+        tokio::spawn(read_messages(
+            server_id,
+            reader,
+            pending.clone(),
+            closed.clone(),
+            child,
+        ));
+
+        Self {
+            writer: tokio::sync::Mutex::new(Box::new(writer)),
+            pending,
+            closed,
+        }
+    }
+
+    pub(super) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    pub(super) async fn request(&self, id: u64, request: &Request<'_>) -> anyhow::Result<Message> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.pending
+            .lock()
+            .expect("The pending requests lock is poisoned")
+            .insert(id, sender);
+
+        if let Err(err) = self.send(request).await {
+            self.pending
+                .lock()
+                .expect("The pending requests lock is poisoned")
+                .remove(&id);
+
+            return Err(err);
+        }
+
+        receiver
+            .await
+            .map_err(|_| anyhow::anyhow!("The MCP server exited before responding"))
+    }
+
+    pub(super) async fn notify(&self, notification: &Request<'_>) -> anyhow::Result<()> {
+        self.send(notification).await
+    }
+
+    async fn send(&self, message: &Request<'_>) -> anyhow::Result<()> {
+        if self.is_closed() {
+            return Err(anyhow::anyhow!("The MCP server is no longer running"));
+        }
+
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&line).await?;
+        writer.flush().await?;
+
+        Ok(())
+    }
+}
+
+async fn read_messages(
+    server_id: String,
+    reader: impl AsyncRead + Unpin,
+    pending: PendingRequests,
+    closed: Arc<AtomicBool>,
+    // Only held, so that the process gets killed (it's configured with `kill_on_drop`) when we're done.
+    _child: Option<tokio::process::Child>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => {
+                tracing::warn!(server_id, ?err, "Failed reading from MCP server");
+                break;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message: Message = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(err) => {
+                tracing::debug!(
+                    server_id,
+                    ?err,
+                    line,
+                    "Ignoring non-JSON-RPC MCP server output"
+                );
+                continue;
+            }
+        };
+
+        let Some(id) = message.response_id() else {
+            // Notifications (logging, progress, list changes) and server-initiated requests are not something we act upon.
+            tracing::trace!(server_id, method = ?message.method, "Ignoring MCP server message");
+            continue;
+        };
+
+        let sender = pending
+            .lock()
+            .expect("The pending requests lock is poisoned")
+            .remove(&id);
+
+        match sender {
+            Some(sender) => {
+                let _ = sender.send(message);
+            }
+            None => tracing::debug!(server_id, id, "Ignoring MCP response to an unknown request"),
+        }
+    }
+
+    tracing::info!(server_id, "MCP server connection closed");
+
+    closed.store(true, Ordering::Relaxed);
+
+    // Dropping the senders wakes up everyone still waiting, with an error.
+    pending
+        .lock()
+        .expect("The pending requests lock is poisoned")
+        .clear();
+}
+
+#[cfg(test)]
+pub(super) fn from_streams(
+    reader: impl AsyncRead + Send + Unpin + 'static,
+    writer: impl AsyncWrite + Send + Unpin + 'static,
+) -> StdioTransport {
+    StdioTransport::new("test".to_owned(), reader, writer, None)
+}
@@ -0,0 +1,54 @@
+// Client-side tools (also known as "function calling").
+//
+// Unlike provider-specific server-side tools (e.g. OpenAI's `web_search`), these are executed by baibot itself.
+// We send the tool definitions along with the conversation, the model asks for some of them to be called,
+// we run them locally and feed the results back, until the model produces a final answer.
+//
+// Everything here is provider-neutral. Each provider maps these types onto its own wire format
+// and runs the tool-call loop (bounded by `MAX_TOOL_CALL_ROUNDS`) in its controller.
+//
+// Besides the built-in tools (see `builtin`), tools may also come from MCP servers (see `mcp`).
+
+mod builtin;
+pub mod chat_completions;
+mod config;
+pub mod mcp;
+mod registry;
+
+use std::future::Future;
+use std::pin::Pin;
+
+pub use config::{ToolsConfig, validate_enabled_tool_names};
+pub use registry::ToolRegistry;
+
+/// The maximum number of model <-> tool round-trips for a single reply.
+///
+/// A model which keeps on calling tools without ever answering would otherwise loop forever (and keep on costing money).
+pub const MAX_TOOL_CALL_ROUNDS: usize = 10;
+
+pub type ToolFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>>;
+
+pub trait Tool: Send + Sync {
+    fn definition(&self) -> ToolDefinition;
+
+    /// Executes the tool with the given (already parsed) arguments and returns the result, as text for the model.
+    fn call(&self, arguments: serde_json::Value) -> ToolFuture<'_>;
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    /// A JSON Schema object describing the arguments the tool accepts.
+    pub parameters: serde_json::Value,
+}
+
+/// A tool invocation requested by the model.
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    /// The provider-assigned identifier, used to match the result to the call.
+    pub id: String,
+    pub name: String,
+    /// The raw JSON-encoded arguments, as returned by the model.
+    pub arguments: String,
+}
@@ -0,0 +1,172 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use super::{Tool, ToolCall, ToolDefinition};
+
+/// The set of tools made available to a model during text-generation.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Vec<Arc<dyn Tool>>,
+}
+
+impl Debug for ToolRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.tools.iter().map(|tool| tool.definition().name))
+            .finish()
+    }
+}
+
+impl ToolRegistry {
+    /// Creates a registry containing the built-in tools with the given names.
+    ///
+    /// Unknown names are skipped. Configuration validation is expected to have rejected them already.
+    pub fn with_builtin_tools(names: &[String]) -> Self {
+        let mut registry = Self::default();
+
+        for name in names {
+            match super::builtin::create(name) {
+                Some(tool) => registry.register(tool),
+                None => tracing::warn!(name, "Skipping unknown built-in tool"),
+            }
+        }
+
+        registry
+    }
+
+    pub fn register(&mut self, tool: Arc<dyn Tool>) {
+        let name = tool.definition().name;
+
+        if self.find(&name).is_some() {
+            tracing::warn!(
+                name,
+                "A tool with this name is already registered, skipping"
+            );
+            return;
+        }
+
+        self.tools.push(tool);
+    }
+
+    /// Returns a registry containing our tools, followed by those of `other`.
+    pub fn merged_with(&self, other: &ToolRegistry) -> ToolRegistry {
+        let mut registry = self.clone();
+
+        for tool in &other.tools {
+            registry.register(tool.clone());
+        }
+
+        registry
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools.iter().map(|tool| tool.definition()).collect()
+    }
+
+    /// Executes the given tool call and returns the text to feed back to the model.
+    ///
+    /// Failures (unknown tool, malformed arguments, tool errors) are not fatal.
+    /// They're reported back to the model, which can usually recover by retrying or answering without the tool.
+    pub async fn call(&self, call: &ToolCall) -> String {
+        tracing::debug!(name = call.name, id = call.id, "Executing tool call");
+
+        let Some(tool) = self.find(&call.name) else {
+            tracing::warn!(name = call.name, "The model requested an unknown tool");
+            return format!("Error: there is no tool named `{}`.", call.name);
+        };
+
+        let arguments = if call.arguments.trim().is_empty() {
+            serde_json::Value::Object(Default::default())
+        } else {
+            match serde_json::from_str(&call.arguments) {
+                Ok(arguments) => arguments,
+                Err(err) => {
+                    tracing::warn!(name = call.name, ?err, "Failed parsing tool call arguments");
+                    return format!("Error: the arguments are not valid JSON: {err}");
+                }
+            }
+        };
+
+        match tool.call(arguments).await {
+            Ok(output) => {
+                tracing::trace!(name = call.name, output, "Tool call succeeded");
+                output
+            }
+            Err(err) => {
+                tracing::warn!(name = call.name, ?err, "Tool call failed");
+                format!("Error: {err}")
+            }
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&Arc<dyn Tool>> {
+        self.tools
+            .iter()
+            .find(|tool| tool.definition().name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            id: "call_1".to_owned(),
+            name: name.to_owned(),
+            arguments: arguments.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_are_routed_by_name() {
+        let registry = ToolRegistry::with_builtin_tools(&["calculator".to_owned()]);
+
+        assert_eq!(registry.definitions().len(), 1);
+        assert_eq!(
+            registry
+                .call(&call("calculator", r#"{"expression": "2 + 3 * 4"}"#))
+                .await,
+            "14"
+        );
+    }
+
+    #[tokio::test]
+    async fn failures_are_reported_to_the_model() {
+        let registry = ToolRegistry::with_builtin_tools(&["calculator".to_owned()]);
+
+        assert!(
+            registry
+                .call(&call("unknown", "{}"))
+                .await
+                .starts_with("Error:")
+        );
+        assert!(
+            registry
+                .call(&call("calculator", "not json"))
+                .await
+                .starts_with("Error:")
+        );
+        assert!(
+            registry
+                .call(&call("calculator", r#"{"expression": "1 / 0"}"#))
+                .await
+                .starts_with("Error:")
+        );
+    }
+
+    #[test]
+    fn duplicate_and_unknown_names_are_skipped() {
+        let registry = ToolRegistry::with_builtin_tools(&[
+            "calculator".to_owned(),
+            "calculator".to_owned(),
+            "unknown".to_owned(),
+        ]);
+
+        assert_eq!(registry.definitions().len(), 1);
+    }
+}
@@ -9,9 +9,22 @@ use crate::{
 #[derive(Debug)]
 pub struct AgentForPurposeDeterminationInfo {
     pub instance: AgentInstance,
+
+    /// Agents to fail over to (in order) when the previous one fails with a retryable error.
+    pub fallback_instances: Vec<AgentInstance>,
+
     pub configuration_source: AgentForPurposeDeterminationInfoConfigurationSource,
 }
 
+impl AgentForPurposeDeterminationInfo {
+    /// Returns the whole chain of agents - the primary one followed by its fallbacks.
+    pub fn chain(self) -> Vec<AgentInstance> {
+        let mut chain = vec![self.instance];
+        chain.extend(self.fallback_instances);
+        chain
+    }
+}
+
 #[derive(Debug)]
 pub enum AgentForPurposeDeterminationInfoConfigurationSource {
     Room,
@@ -29,90 +42,120 @@ pub enum AgentForPurposeDeterminationError {
     ConfiguredButLacksSupport(PublicIdentifier),
 }
 
+/// Determines the agent (and its fallback agents) which handle the given purpose.
+///
+/// Agents in the chain which are missing or do not support the purpose are skipped.
+/// An error is only returned when none of the agents in the chain is usable, in which case it describes the problem with the first one.
 pub async fn get_effective_agent_for_purpose(
     agent_manager: &AgentManager,
     room_config_context: &RoomConfigContext,
     agent_purpose: AgentPurpose,
 ) -> Result<AgentForPurposeDeterminationInfo, AgentForPurposeDeterminationError> {
-    let (agent_identifier, configuration_source) =
-        match get_effective_room_agent_identifier_for_purpose(room_config_context, agent_purpose)
+    let (agent_identifiers, configuration_source) =
+        match get_effective_room_agent_identifiers_for_purpose(room_config_context, agent_purpose)
             .await
         {
-            Ok((agent_identifier, configuration_source)) => {
-                (agent_identifier, configuration_source)
+            Ok((agent_identifiers, configuration_source)) => {
+                (agent_identifiers, configuration_source)
             }
             Err(err) => {
                 return Err(AgentForPurposeDeterminationError::Unknown(err));
             }
         };
 
-    let Some(agent_identifier) = agent_identifier else {
+    if agent_identifiers.is_empty() {
         return Err(AgentForPurposeDeterminationError::NoneConfigured);
-    };
+    }
 
     let agents = agent_manager.available_room_agents_by_room_config_context(room_config_context);
 
-    let Some(agent_instance) = agents.iter().find(|a| *a.identifier() == agent_identifier) else {
-        return Err(AgentForPurposeDeterminationError::ConfiguredButMissing(
-            agent_identifier,
-        ));
-    };
+    let mut usable_agents: Vec<AgentInstance> = vec![];
+    let mut first_error: Option<AgentForPurposeDeterminationError> = None;
+
+    for agent_identifier in agent_identifiers {
+        let Some(agent_instance) = agents.iter().find(|a| *a.identifier() == agent_identifier)
+        else {
+            tracing::warn!(
+                ?agent_purpose,
+                agent_id = agent_identifier.as_string(),
+                "Skipping agent in handler chain, as it does not exist"
+            );
+
+            first_error.get_or_insert(AgentForPurposeDeterminationError::ConfiguredButMissing(
+                agent_identifier,
+            ));
+            continue;
+        };
 
-    let agent_instance = agent_instance.clone();
+        if !agent_instance.controller().supports_purpose(agent_purpose) {
+            tracing::warn!(
+                ?agent_purpose,
+                agent_id = agent_identifier.as_string(),
+                "Skipping agent in handler chain, as it does not support the purpose"
+            );
 
-    let supports_purpose = agent_instance.controller().supports_purpose(agent_purpose);
+            first_error.get_or_insert(
+                AgentForPurposeDeterminationError::ConfiguredButLacksSupport(agent_identifier),
+            );
+            continue;
+        }
 
-    if !supports_purpose {
-        return Err(AgentForPurposeDeterminationError::ConfiguredButLacksSupport(agent_identifier));
+        usable_agents.push(agent_instance.clone());
     }
 
+    let mut usable_agents = usable_agents.into_iter();
+
+    let Some(agent_instance) = usable_agents.next() else {
+        return Err(first_error.unwrap_or(AgentForPurposeDeterminationError::NoneConfigured));
+    };
+
     Ok(AgentForPurposeDeterminationInfo {
         instance: agent_instance,
+        fallback_instances: usable_agents.collect(),
         configuration_source,
     })
 }
 
-async fn get_effective_room_agent_identifier_for_purpose(
+async fn get_effective_room_agent_identifiers_for_purpose(
     room_config_context: &RoomConfigContext,
     purpose: AgentPurpose,
 ) -> Result<
     (
-        Option<PublicIdentifier>,
+        Vec<PublicIdentifier>,
         AgentForPurposeDeterminationInfoConfigurationSource,
     ),
     String,
 > {
-    let (agent_id, configuration_source) =
-        get_effective_room_agent_raw_id_for_purpose(room_config_context, purpose).await;
-
-    let Some(agent_id) = agent_id else {
-        return Ok((None, configuration_source));
-    };
-
-    let agent_identifier = match PublicIdentifier::from_str(agent_id.as_str()) {
-        Some(agent_identifier) => agent_identifier,
-        None => return Err(strings::agent::invalid_id_generic()),
-    };
+    let (agent_ids, configuration_source) =
+        get_effective_room_agent_raw_ids_for_purpose(room_config_context, purpose).await;
+
+    let mut agent_identifiers = vec![];
+    for agent_id in agent_ids {
+        match PublicIdentifier::from_str(agent_id.as_str()) {
+            Some(agent_identifier) => agent_identifiers.push(agent_identifier),
+            None => return Err(strings::agent::invalid_id_generic()),
+        };
+    }
 
-    Ok((Some(agent_identifier), configuration_source))
+    Ok((agent_identifiers, configuration_source))
 }
 
-async fn get_effective_room_agent_raw_id_for_purpose(
+async fn get_effective_room_agent_raw_ids_for_purpose(
     room_config_context: &RoomConfigContext,
     purpose: AgentPurpose,
 ) -> (
-    Option<String>,
+    Vec<String>,
     AgentForPurposeDeterminationInfoConfigurationSource,
 ) {
-    let agent_id = room_config_context
+    let agent_ids = room_config_context
         .room_config
         .settings
         .handler
-        .get_by_purpose_with_catch_all_fallback(purpose);
+        .get_chain_by_purpose_with_catch_all_fallback(purpose);
 
-    if let Some(agent_id) = agent_id {
+    if !agent_ids.is_empty() {
         return (
-            Some(agent_id),
+            agent_ids,
             AgentForPurposeDeterminationInfoConfigurationSource::Room,
         );
     }
@@ -123,18 +166,18 @@ async fn get_effective_room_agent_raw_id_for_purpose(
     );
 
     (
-        get_global_agent_id_for_purpose(room_config_context, purpose).await,
+        get_global_agent_ids_for_purpose(room_config_context, purpose).await,
         AgentForPurposeDeterminationInfoConfigurationSource::Global,
     )
 }
 
-async fn get_global_agent_id_for_purpose(
+async fn get_global_agent_ids_for_purpose(
     room_config_context: &RoomConfigContext,
     purpose: AgentPurpose,
-) -> Option<String> {
+) -> Vec<String> {
     room_config_context
         .global_config
         .fallback_room_settings
         .handler
-        .get_by_purpose_with_catch_all_fallback(purpose)
+        .get_chain_by_purpose_with_catch_all_fallback(purpose)
 }
@@ -21,21 +21,30 @@ use mxlink::helpers::account_data_config::{
 use mxlink::helpers::encryption::Manager as EncryptionManager;
 use mxlink::mime::Mime;
 
+use crate::agent::ConcurrencyLimiter;
 use crate::agent::Manager as AgentManager;
+use crate::agent::tool::mcp::Manager as McpManager;
+use crate::entity::InFlightGenerationTracker;
 use crate::entity::catch_up_marker::{
     CatchUpMarker, CatchUpMarkerManager, DelayedCatchUpMarkerManager,
 };
 use crate::entity::cfg::{Avatar, Config, ConfigUserAuth};
 use crate::entity::globalconfig::{GlobalConfig, GlobalConfigurationManager};
 use crate::entity::roomconfig::{RoomConfig, RoomConfigurationManager};
+use crate::entity::usage::{RoomUsage, RoomUsageManager, UsageTracker};
 
 use crate::agent::Manager;
 
+use crate::controller::Scheduler;
+use crate::conversation::llm::ConversationSummaryCache;
 use crate::conversation::matrix::{RoomDisplayNameFetcher, RoomEventFetcher};
 
 const ROOM_EVENT_FETCHER_LRU_CACHE_SIZE: usize = 1000;
 const ROOM_DISPLAY_NAME_FETCHER_LRU_CACHE_SIZE: usize = 1000;
 const ROOM_CONFIG_MANAGER_LRU_CACHE_SIZE: usize = 1000;
+const ROOM_USAGE_MANAGER_LRU_CACHE_SIZE: usize = 1000;
+const DYNAMIC_AGENTS_LRU_CACHE_SIZE: usize = 1000;
+const CONVERSATION_SUMMARY_LRU_CACHE_SIZE: usize = 1000;
 
 const LOGO_BYTES: &[u8] = include_bytes!("../../etc/assets/baibot-torso-768.png");
 const LOGO_MIME_TYPE: &str = "image/png";
@@ -58,9 +67,14 @@ struct BotInner {
     delayed_catch_up_marker_manager: DelayedCatchUpMarkerManager,
     global_config_manager: tokio::sync::Mutex<GlobalConfigurationManager>,
     room_config_manager: tokio::sync::Mutex<RoomConfigurationManager>,
+    usage_tracker: UsageTracker,
     room_event_fetcher: Arc<RoomEventFetcher>,
     room_display_name_fetcher: Arc<RoomDisplayNameFetcher>,
+    conversation_summary_cache: ConversationSummaryCache,
+    in_flight_generation_tracker: InFlightGenerationTracker,
+    scheduler: Scheduler,
     agent_manager: Manager,
+    mcp_manager: McpManager,
     admin_pattern_regexes: Vec<regex::Regex>,
 }
 
@@ -83,7 +97,13 @@ impl Bot {
         let admin_pattern_regexes = config.access.admin_pattern_regexes()?;
         let persistence_config_encryption_key = config.persistence.config_encryption_key()?;
 
-        let agent_manager = AgentManager::new(config.agents.static_definitions.clone())?;
+        let agent_manager = AgentManager::new(
+            config.agents.static_definitions.clone(),
+            DYNAMIC_AGENTS_LRU_CACHE_SIZE,
+            ConcurrencyLimiter::new(config.scheduling.max_concurrent_requests_per_agent),
+        )?;
+
+        let mcp_manager = McpManager::new(config.mcp.servers.clone());
 
         let encryption_manager = EncryptionManager::new(persistence_config_encryption_key);
 
@@ -108,6 +128,11 @@ impl Bot {
             encryption_manager.clone(),
         ));
 
+        let usage_tracker = UsageTracker::new(create_room_usage_manager(
+            matrix_link.clone(),
+            encryption_manager.clone(),
+        ));
+
         let room_event_fetcher = RoomEventFetcher::new(Some(ROOM_EVENT_FETCHER_LRU_CACHE_SIZE));
 
         let room_display_name_fetcher = RoomDisplayNameFetcher::new(
@@ -115,6 +140,14 @@ impl Bot {
             Some(ROOM_DISPLAY_NAME_FETCHER_LRU_CACHE_SIZE),
         );
 
+        let conversation_summary_cache =
+            ConversationSummaryCache::new(CONVERSATION_SUMMARY_LRU_CACHE_SIZE);
+
+        let scheduler = Scheduler::new(
+            config.scheduling.max_concurrent_requests,
+            config.scheduling.coalescing_window(),
+        );
+
         Ok(Self {
             inner: Arc::new(BotInner {
                 config,
@@ -123,9 +156,14 @@ impl Bot {
                 delayed_catch_up_marker_manager,
                 global_config_manager,
                 room_config_manager,
+                usage_tracker,
                 room_event_fetcher: Arc::new(room_event_fetcher),
                 room_display_name_fetcher: Arc::new(room_display_name_fetcher),
+                conversation_summary_cache,
+                in_flight_generation_tracker: InFlightGenerationTracker::new(),
+                scheduler,
                 agent_manager,
+                mcp_manager,
                 admin_pattern_regexes,
             }),
         })
@@ -159,6 +197,10 @@ impl Bot {
         &self.inner.room_config_manager
     }
 
+    pub(crate) fn usage_tracker(&self) -> &UsageTracker {
+        &self.inner.usage_tracker
+    }
+
     pub(crate) fn room_event_fetcher(&self) -> Arc<RoomEventFetcher> {
         self.inner.room_event_fetcher.clone()
     }
@@ -167,10 +209,26 @@ impl Bot {
         self.inner.room_display_name_fetcher.clone()
     }
 
+    pub(crate) fn conversation_summary_cache(&self) -> &ConversationSummaryCache {
+        &self.inner.conversation_summary_cache
+    }
+
+    pub(crate) fn in_flight_generation_tracker(&self) -> &InFlightGenerationTracker {
+        &self.inner.in_flight_generation_tracker
+    }
+
+    pub(crate) fn scheduler(&self) -> &Scheduler {
+        &self.inner.scheduler
+    }
+
     pub(crate) fn agent_manager(&self) -> &Manager {
         &self.inner.agent_manager
     }
 
+    pub(crate) fn mcp_manager(&self) -> &McpManager {
+        &self.inner.mcp_manager
+    }
+
     pub(crate) fn matrix_link(&self) -> &MatrixLink {
         &self.inner.matrix_link
     }
@@ -480,6 +538,25 @@ async fn create_initial_room_config(room: Room) -> RoomConfig {
     RoomConfig::default().with_room(room).await
 }
 
+pub fn create_room_usage_manager(
+    matrix_link: MatrixLink,
+    encryption_manager: EncryptionManager,
+) -> RoomUsageManager {
+    let initial_room_usage_callback = |_room: Room| {
+        let future = async { RoomUsage::default() };
+
+        // Explicitly box the future to match the expected type
+        Box::pin(future) as Pin<Box<dyn Future<Output = RoomUsage> + Send>>
+    };
+
+    AccountDataRoomConfigManager::new(
+        matrix_link.user_id().clone(),
+        encryption_manager,
+        initial_room_usage_callback,
+        Some(ROOM_USAGE_MANAGER_LRU_CACHE_SIZE),
+    )
+}
+
 pub fn create_catch_up_marker_manager(matrix_link: MatrixLink) -> CatchUpMarkerManager {
     let initial_global_config_callback = || {
         let future = create_initial_catch_up_marker();
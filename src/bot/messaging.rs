@@ -1,22 +1,29 @@
 use mxlink::matrix_sdk::{
-    Room,
+    Room, RoomMemberships,
     room::edit::EditedContent,
     ruma::{
-        EventId, OwnedEventId,
+        EventId, OwnedEventId, UserId,
         api::client::receipt::create_receipt::v3::ReceiptType,
-        events::room::message::{
-            OriginalSyncRoomMessageEvent, RoomMessageEventContentWithoutRelation,
+        events::{
+            AnySyncMessageLikeEvent, AnySyncTimelineEvent, SyncMessageLikeEvent,
+            relation::Replacement,
+            room::message::{
+                OriginalSyncRoomMessageEvent, Relation, RoomMessageEventContentWithoutRelation,
+            },
+            room::redaction::OriginalSyncRoomRedactionEvent,
         },
     },
 };
 
-use mxlink::{CallbackError, MessageResponseType};
+use mxlink::{CallbackError, MessageResponseType, ThreadInfo};
 
 use tracing::Instrument;
 
 use crate::{
     conversation::matrix::determine_interaction_context_for_room_event,
-    entity::{MessageContext, MessagePayload, RoomConfigContext, TriggerEventInfo},
+    entity::{
+        CancellationOutcome, MessageContext, MessagePayload, RoomConfigContext, TriggerEventInfo,
+    },
 };
 
 #[derive(Clone)]
@@ -133,6 +140,53 @@ impl Messaging {
         }
     }
 
+    /// Returns a direct-message room which only the given user and the bot are part of,
+    /// creating it (and inviting the user to it) if there's no such room yet.
+    ///
+    /// Any room can be flagged as a direct-message one (in `m.direct`) and it may have gained other members
+    /// (or lost the user) since, so existing rooms are only reused after checking who's in them.
+    pub async fn direct_message_room(&self, user_id: &UserId) -> anyhow::Result<Room> {
+        let client = self.bot.matrix_link().client();
+
+        if let Some(room) = client.get_dm_room(user_id) {
+            match self.room_is_private_to(&room, user_id).await {
+                Ok(true) => return Ok(room),
+                Ok(false) => {
+                    tracing::info!(
+                        room_id = room.room_id().as_str(),
+                        "Not reusing direct-message room, as others than the user and us are (or the user is not) part of it"
+                    );
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        room_id = room.room_id().as_str(),
+                        ?err,
+                        "Failed to determine the members of the direct-message room, so not reusing it"
+                    );
+                }
+            }
+        }
+
+        Ok(client.create_dm(user_id).await?)
+    }
+
+    /// Tells if the given user and the bot are the only ones who are (or are invited to be) part of the room.
+    async fn room_is_private_to(&self, room: &Room, user_id: &UserId) -> anyhow::Result<bool> {
+        let members = room.members(RoomMemberships::ACTIVE).await?;
+
+        let mut member_ids: Vec<&UserId> = members.iter().map(|member| member.user_id()).collect();
+        member_ids.sort();
+        member_ids.dedup();
+
+        let bot_user_id: &UserId = self.bot.user_id();
+
+        let mut expected_member_ids = vec![user_id, bot_user_id];
+        expected_member_ids.sort();
+        expected_member_ids.dedup();
+
+        Ok(member_ids == expected_member_ids)
+    }
+
     pub async fn send_notice_markdown_no_fail(
         &self,
         room: &Room,
@@ -240,6 +294,46 @@ impl Messaging {
         matrix_link_messaging.on_actionable_room_message(|event, room| async move {
             this.on_actionable_message(event, room).await
         });
+
+        // mxlink does not route redactions to us, so we subscribe to them directly.
+        let this = self.clone();
+        self.bot.matrix_link().client().add_event_handler(
+            move |event: OriginalSyncRoomRedactionEvent, room: Room| {
+                let this = this.clone();
+                async move { this.on_redaction(event, room).await }
+            },
+        );
+    }
+
+    /// Cancels the generation (if any) which the redacted message triggered.
+    #[tracing::instrument(name = "bot_on_redaction", skip_all, fields(room_id = room.room_id().as_str(), event_id = event.event_id.as_str()))]
+    async fn on_redaction(&self, event: OriginalSyncRoomRedactionEvent, room: Room) {
+        if event.sender == *self.bot.user_id() {
+            return;
+        }
+
+        // Depending on the room version, the redacted event is specified either at the top level or in the content.
+        let Some(redacted_event_id) = event.content.redacts.as_ref().or(event.redacts.as_ref())
+        else {
+            return;
+        };
+
+        let sender_is_admin =
+            mxidwc::match_user_id(event.sender.as_str(), self.bot.admin_pattern_regexes());
+
+        let outcome = self.bot.in_flight_generation_tracker().cancel(
+            room.room_id(),
+            redacted_event_id,
+            &event.sender,
+            sender_is_admin,
+        );
+
+        if outcome != CancellationOutcome::NotFound {
+            tracing::debug!(
+                ?outcome,
+                "Handled redaction of a message with a generation in progress"
+            );
+        }
     }
 
     #[tracing::instrument(name = "bot_on_actionable_message", skip_all, fields(room_id = room.room_id().as_str(), event_id = event.event_id.as_str()))]
@@ -248,6 +342,9 @@ impl Messaging {
         event: OriginalSyncRoomMessageEvent,
         room: Room,
     ) -> Result<(), CallbackError> {
+        // Coalescing windows are measured from the moment messages reach us, not from when we get to look at them.
+        let received_at = tokio::time::Instant::now();
+
         if self
             .bot
             .is_caught_up(event.origin_server_ts)
@@ -266,6 +363,13 @@ impl Messaging {
             return Ok(());
         }
 
+        if let Some(Relation::Replacement(replacement)) = &event.content.relates_to {
+            // Fetched events carry their latest edit, so a cached copy of the edited event is now stale.
+            self.bot
+                .room_event_fetcher()
+                .forget_event(&replacement.event_id);
+        }
+
         tracing::info!("Processing message");
 
         let global_config = self
@@ -307,6 +411,27 @@ impl Messaging {
             return Ok(());
         }
 
+        if let Some(Relation::Replacement(replacement)) = &event.content.relates_to {
+            let room_config_context = RoomConfigContext::new(
+                room.room_id().to_owned(),
+                global_config.clone(),
+                room_config.clone(),
+            );
+
+            self.on_actionable_message_edit(
+                &event,
+                replacement,
+                room,
+                room_config_context,
+                trigger_event_sender_is_admin,
+            )
+            .await;
+
+            self.bot.catch_up(event.origin_server_ts).await;
+
+            return Ok(());
+        }
+
         let payload: Result<MessagePayload, String> = event.content.msgtype.clone().try_into();
         let payload = match payload {
             Ok(payload) => payload,
@@ -347,8 +472,11 @@ impl Messaging {
             return Ok(());
         };
 
-        let room_config_context =
-            RoomConfigContext::new(global_config.clone(), room_config.clone());
+        let room_config_context = RoomConfigContext::new(
+            room.room_id().to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
 
         let trigger_event_info = TriggerEventInfo::new(
             event.event_id.clone(),
@@ -386,9 +514,14 @@ impl Messaging {
 
         let event_span = tracing::error_span!("message_controller", ?controller_type);
 
-        crate::controller::dispatch_controller(&controller_type, &message_context, &self.bot)
-            .instrument(event_span)
-            .await;
+        crate::controller::dispatch_controller_when_scheduled(
+            &controller_type,
+            &message_context,
+            &self.bot,
+            received_at,
+        )
+        .instrument(event_span)
+        .await;
 
         let duration = std::time::Instant::now().duration_since(start_time);
 
@@ -398,4 +531,153 @@ impl Messaging {
 
         return Ok(());
     }
+
+    /// Handles an edit (`m.replace`) of an earlier message, regenerating the bot's reply to it if the room wishes so.
+    ///
+    /// Only edits of the last user message in a threaded conversation handled via text generation are acted upon.
+    async fn on_actionable_message_edit(
+        &self,
+        event: &OriginalSyncRoomMessageEvent,
+        replacement: &Replacement<RoomMessageEventContentWithoutRelation>,
+        room: Room,
+        room_config_context: RoomConfigContext,
+        trigger_event_sender_is_admin: bool,
+    ) {
+        if !room_config_context.text_generation_regenerate_on_edit_enabled() {
+            tracing::debug!("Ignoring message edit, as regenerating on edit is disabled");
+            return;
+        }
+
+        let payload: Result<MessagePayload, String> =
+            replacement.new_content.msgtype.clone().try_into();
+        let Ok(payload @ MessagePayload::Text(_)) = payload else {
+            tracing::debug!("Ignoring edit which does not result in a text message");
+            return;
+        };
+
+        let edited_event = match self
+            .bot
+            .room_event_fetcher()
+            .fetch_event_in_room(&replacement.event_id, &room)
+            .await
+        {
+            Ok(value) => value,
+            Err(err) => {
+                tracing::error!(
+                    edited_event_id = replacement.event_id.as_str(),
+                    ?err,
+                    "Failed to fetch edited event",
+                );
+                return;
+            }
+        };
+
+        let Ok(AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(
+            SyncMessageLikeEvent::Original(edited_event),
+        ))) = edited_event.raw().deserialize()
+        else {
+            tracing::debug!("Ignoring edit of an event which is not a room message");
+            return;
+        };
+
+        // Only the sender of a message may edit it. Clients are expected to disregard other "edits" too.
+        if edited_event.sender != event.sender {
+            tracing::debug!("Ignoring edit sent by someone other than the original sender");
+            return;
+        }
+
+        // Only threads can be regenerated. Top-level messages may be the root of a thread.
+        if !matches!(
+            edited_event.content.relates_to,
+            None | Some(Relation::Thread(_))
+        ) {
+            tracing::debug!("Ignoring edit of a message which is not part of a thread");
+            return;
+        }
+
+        let Ok(edited_event_payload) = edited_event.content.msgtype.clone().try_into() else {
+            tracing::debug!("Ignoring edit of a message not supported by us");
+            return;
+        };
+
+        let bot_display_name = self.bot.user_display_name_in_room(&room).await;
+
+        let interaction_context = determine_interaction_context_for_room_event(
+            self.bot.user_id(),
+            &bot_display_name,
+            &room,
+            &edited_event,
+            &edited_event_payload,
+            &self.bot.room_event_fetcher(),
+        )
+        .await;
+
+        let interaction_context = match interaction_context {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                tracing::debug!("Ignoring edit of a message which is not a message for us");
+                return;
+            }
+            Err(err) => {
+                tracing::error!(?err, "Failed to determine interaction context for edit");
+                return;
+            }
+        };
+
+        // Our replies go to the thread, so the edit event (which is never a thread root) is what we consider the thread's last event.
+        let thread_info = ThreadInfo::new(
+            interaction_context.thread_info.root_event_id.clone(),
+            event.event_id.clone(),
+        );
+
+        let trigger_event_info = TriggerEventInfo::new(
+            event.event_id.clone(),
+            event.sender.clone(),
+            payload,
+            trigger_event_sender_is_admin,
+        );
+
+        let message_context = MessageContext::new(
+            room.clone(),
+            room_config_context,
+            self.bot.admin_pattern_regexes().clone(),
+            trigger_event_info,
+            thread_info,
+        )
+        .with_bot_display_name(bot_display_name);
+
+        let controller_type = crate::controller::determine_controller(
+            self.bot.command_prefix(),
+            &interaction_context.trigger,
+            &message_context,
+        );
+
+        let crate::controller::ControllerType::ChatCompletion(_) = controller_type else {
+            tracing::debug!(
+                ?controller_type,
+                "Ignoring edit of a message in a conversation not handled via text generation"
+            );
+            return;
+        };
+
+        let event_span = tracing::error_span!("message_edit_controller");
+
+        // The edit waits for whatever is still being generated in the thread, so it regenerates the final answer.
+        let result = crate::controller::run_when_scheduled(
+            &self.bot,
+            &message_context,
+            crate::controller::chat_completion::regenerate::handle_edit(
+                &self.bot,
+                self.bot.matrix_link().clone(),
+                &message_context,
+                &replacement.event_id,
+            ),
+        )
+        .instrument(event_span)
+        .await;
+
+        if let Err(err) = result {
+            tracing::error!(?err, "Failed to regenerate reply to edited message");
+        }
+    }
 }
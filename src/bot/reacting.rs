@@ -14,7 +14,9 @@ use mxlink::ThreadInfo;
 
 use tracing::Instrument;
 
-use crate::entity::{MessageContext, MessagePayload, RoomConfigContext, TriggerEventInfo};
+use crate::entity::{
+    CancellationOutcome, MessageContext, MessagePayload, RoomConfigContext, TriggerEventInfo,
+};
 
 #[derive(Clone)]
 pub struct Reacting {
@@ -121,6 +123,26 @@ impl Reacting {
 
         let reacted_to_event_id = &reaction_event_content.relates_to.event_id;
 
+        if crate::entity::roomconfig::reaction_keys_match(
+            &reaction_event_content.relates_to.key,
+            crate::strings::CANCEL_EMOJI,
+        ) {
+            let outcome = self.bot.in_flight_generation_tracker().cancel(
+                room.room_id(),
+                reacted_to_event_id,
+                event.sender(),
+                trigger_event_sender_is_admin,
+            );
+
+            tracing::debug!(?outcome, "Handled cancellation reaction");
+
+            if outcome != CancellationOutcome::NotFound {
+                return Ok(());
+            }
+
+            // Nothing in progress to cancel, so the reaction may be mapped to some action.
+        }
+
         let reacted_to_event = self
             .bot
             .room_event_fetcher()
@@ -219,8 +241,11 @@ impl Reacting {
 
         tracing::trace!(?room_config, "Room config");
 
-        let room_config_context =
-            RoomConfigContext::new(global_config.clone(), room_config.clone());
+        let room_config_context = RoomConfigContext::new(
+            room.room_id().to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
 
         let trigger_event_info = TriggerEventInfo::new(
             event.event_id().to_owned(),
@@ -246,14 +271,10 @@ impl Reacting {
 
         let event_span = tracing::error_span!("reaction_controller");
 
-        crate::controller::reaction::handle(
-            &self.bot,
-            self.bot.matrix_link().clone(),
-            &message_context,
-        )
-        .instrument(event_span)
-        .await
-        .map_err(|err| CallbackError::Unknown(err.into()))?;
+        crate::controller::handle_reaction_when_scheduled(&self.bot, &message_context)
+            .instrument(event_span)
+            .await
+            .map_err(|err| CallbackError::Unknown(err.into()))?;
 
         self.bot.catch_up(event.origin_server_ts()).await;
 
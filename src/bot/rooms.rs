@@ -115,7 +115,8 @@ impl Rooms {
             .await
             .map_err(|e| CallbackError::Unknown(e.into()))?;
 
-        let room_config_context = RoomConfigContext::new(global_config, room_config);
+        let room_config_context =
+            RoomConfigContext::new(room.room_id().to_owned(), global_config, room_config);
 
         let event_span = tracing::error_span!("join_controller");
 
@@ -263,7 +263,7 @@ async fn send_guide(
     Ok(())
 }
 
-fn parse_from_message_to_yaml_value(text: &str) -> Result<serde_yaml_ng::Value, String> {
+pub(super) fn parse_from_message_to_yaml_value(text: &str) -> Result<serde_yaml_ng::Value, String> {
     let mut text = text.trim();
 
     if text.starts_with("```") {
@@ -314,6 +314,7 @@ async fn parse_agent_config_from_message_or_complain(
         provider,
         agent_identifier,
         config_yaml_value.clone(),
+        bot.agent_manager().concurrency_limiter().clone(),
     );
 
     let agent = match agent {
@@ -337,7 +338,7 @@ async fn parse_agent_config_from_message_or_complain(
     Some(agent)
 }
 
-async fn try_to_ping_agent_or_complain(
+pub(super) async fn try_to_ping_agent_or_complain(
     bot: &Bot,
     message_context: &MessageContext,
     agent_instance: &AgentInstance,
@@ -18,7 +18,7 @@ pub async fn handle(
 
     let agent = agents.iter().find(|a| a.identifier() == agent_identifier);
 
-    let Some(_) = agent else {
+    let Some(agent) = agent else {
         bot.messaging()
             .send_error_markdown_no_fail(
                 message_context.room(),
@@ -46,8 +46,18 @@ pub async fn handle(
                 return Ok(());
             }
 
-            delete_room_local_agent(bot, room_config_manager, message_context, agent_identifier)
-                .await
+            let result = delete_room_local_agent(
+                bot,
+                room_config_manager,
+                message_context,
+                agent_identifier,
+            )
+            .await;
+
+            bot.agent_manager()
+                .forget_dynamic_agent(message_context.room_config_context(), agent);
+
+            result
         }
         PublicIdentifier::DynamicGlobal(_) => {
             if !message_context.sender_can_manage_global_config() {
@@ -64,13 +74,18 @@ pub async fn handle(
                 return Ok(());
             }
 
-            delete_global_agent(
+            let result = delete_global_agent(
                 bot,
                 global_config_manager,
                 message_context,
                 agent_identifier,
             )
-            .await
+            .await;
+
+            bot.agent_manager()
+                .forget_dynamic_agent(message_context.room_config_context(), agent);
+
+            result
         }
         PublicIdentifier::Static(_) => {
             bot.messaging()
@@ -124,7 +139,7 @@ async fn delete_room_local_agent(
     let room_config_manager = room_config_manager.lock().await;
 
     // We may unset all handlers in the room config which refer to this agent.
-    // We intentionally do not do this, because we do not support "agent edit" yet and ask people to do "agent delete" and "agent create" instead.
+    // We intentionally do not do this, because people may delete an agent and re-create it (under the same ID) right after.
     // We'd rather not magically reconfigure the room on agent deletion and obstruct this use case.
 
     room_config_manager
@@ -1,6 +1,11 @@
 use mxlink::MessageResponseType;
 
-use crate::{Bot, agent::PublicIdentifier, entity::MessageContext, strings};
+use crate::{
+    Bot,
+    agent::{AgentInstance, ControllerTrait, PublicIdentifier, secrets},
+    entity::MessageContext,
+    strings,
+};
 
 pub async fn handle(
     bot: &Bot,
@@ -64,21 +69,152 @@ pub async fn handle(
         PublicIdentifier::Static(_) => {}
     };
 
-    let config_yaml_pretty = serde_yaml_ng::to_string(&agent.definition().config)?;
+    // Secrets are masked, as rooms (and their history) are visible to all members.
+    let config = secrets::redact(&agent.definition().config);
+
+    let mut message = build_details_message(agent, &config)?;
+
+    if message_context.sender_can_manage_global_config() {
+        message.push_str("\n\n");
+        message.push_str(&strings::agent::details_secrets_redacted_reveal_hint(
+            bot.command_prefix(),
+            agent_identifier,
+        ));
+    }
 
     bot.messaging()
         .send_text_markdown_no_fail(
             message_context.room(),
-            format!(
-                "Configuration for agent `{}` (powered by the `{}` provider):\n```yml\n{}\n```",
-                agent_identifier,
-                agent.definition().provider.to_static_str(),
-                config_yaml_pretty.trim(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+/// Sends the full (unredacted) configuration of an agent to the requester via a direct message.
+/// Only administrators can do this, regardless of the type of the agent.
+pub async fn handle_reveal(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent_identifier: &PublicIdentifier,
+) -> anyhow::Result<()> {
+    if !message_context.sender_can_manage_global_config() {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::global_config::no_permissions_to_administrate(),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    let agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    let Some(agent) = agents.iter().find(|a| a.identifier() == agent_identifier) else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::agent_with_given_identifier_missing(agent_identifier),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    let message = build_details_message(agent, &agent.definition().config)?;
+
+    let dm_room = match bot
+        .messaging()
+        .direct_message_room(message_context.sender_id())
+        .await
+    {
+        Ok(dm_room) => dm_room,
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                "Failed to get a direct-message room with the requester"
+            );
+
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::agent::details_reveal_failed_to_reach_requester(),
+                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    // The command came from the private room with the requester, so that's where the answer goes.
+    // Only a room which nobody else is part of can be this one (see `direct_message_room`).
+    if dm_room.room_id() == message_context.room().room_id() {
+        bot.messaging()
+            .send_text_markdown_no_fail(
+                &dm_room,
+                message,
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
             )
-            .to_owned(),
+            .await;
+
+        return Ok(());
+    }
+
+    let sent = bot
+        .messaging()
+        .send_text_markdown_no_fail(&dm_room, message, MessageResponseType::InRoom)
+        .await;
+
+    let notice = if sent.is_some() {
+        strings::agent::details_revealed_via_direct_message(agent_identifier)
+    } else {
+        strings::agent::details_reveal_failed_to_reach_requester()
+    };
+
+    bot.messaging()
+        .send_notice_markdown_no_fail(
+            message_context.room(),
+            notice,
             MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
         )
         .await;
 
     Ok(())
 }
+
+fn build_details_message(
+    agent: &AgentInstance,
+    config: &serde_yaml_ng::Value,
+) -> anyhow::Result<String> {
+    let config_yaml_pretty = serde_yaml_ng::to_string(config)?;
+
+    let mut message = format!(
+        "Configuration for agent `{}` (powered by the `{}` provider):\n```yml\n{}\n```",
+        agent.identifier(),
+        agent.definition().provider.to_static_str(),
+        config_yaml_pretty.trim(),
+    );
+
+    let learned_unsupported_fields = agent.controller().learned_unsupported_fields();
+    if !learned_unsupported_fields.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(strings::agent::learned_unsupported_fields_intro());
+
+        for (model_id, fields) in &learned_unsupported_fields {
+            message.push('\n');
+            message.push_str(&strings::agent::learned_unsupported_fields_entry(
+                model_id, fields,
+            ));
+        }
+    }
+
+    Ok(message)
+}
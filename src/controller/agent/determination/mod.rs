@@ -7,8 +7,12 @@ use crate::{agent::PublicIdentifier, controller::ControllerType, strings};
 pub enum AgentControllerType {
     List,
     Details(PublicIdentifier),
+    RevealDetails(PublicIdentifier),
+    Tools(PublicIdentifier),
+    Models(PublicIdentifier),
     CreateRoomLocal { provider: String, agent_id: String },
     CreateGlobal { provider: String, agent_id: String },
+    Update(PublicIdentifier),
     Delete(PublicIdentifier),
     Help,
 }
@@ -17,8 +21,14 @@ pub fn determine_controller(command_prefix: &str, text: &str) -> ControllerType
     if text.starts_with("list") {
         return ControllerType::Agent(AgentControllerType::List);
     }
-    if let Some(agent_id_string) = text.strip_prefix("details") {
-        let agent_id_string = agent_id_string.trim();
+    if let Some(remaining_text) = text.strip_prefix("details") {
+        // `remaining_text` should be something like: `ID` or `ID --reveal`
+        let remaining_text = remaining_text.trim();
+
+        let (agent_id_string, reveal) = match remaining_text.strip_suffix("--reveal") {
+            Some(agent_id_string) => (agent_id_string.trim(), true),
+            None => (remaining_text, false),
+        };
 
         if agent_id_string.is_empty() || agent_id_string.contains(" ") {
             return ControllerType::Error(
@@ -30,9 +40,45 @@ pub fn determine_controller(command_prefix: &str, text: &str) -> ControllerType
             return ControllerType::Error(strings::agent::invalid_id_generic());
         };
 
+        if reveal {
+            return ControllerType::Agent(AgentControllerType::RevealDetails(agent_identifier));
+        }
+
         return ControllerType::Agent(AgentControllerType::Details(agent_identifier));
     }
 
+    if let Some(agent_id_string) = text.strip_prefix("tools") {
+        let agent_id_string = agent_id_string.trim();
+
+        if agent_id_string.is_empty() || agent_id_string.contains(" ") {
+            return ControllerType::Error(
+                strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            );
+        }
+
+        let Some(agent_identifier) = PublicIdentifier::from_str(agent_id_string) else {
+            return ControllerType::Error(strings::agent::invalid_id_generic());
+        };
+
+        return ControllerType::Agent(AgentControllerType::Tools(agent_identifier));
+    }
+
+    if let Some(agent_id_string) = text.strip_prefix("models") {
+        let agent_id_string = agent_id_string.trim();
+
+        if agent_id_string.is_empty() || agent_id_string.contains(" ") {
+            return ControllerType::Error(
+                strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            );
+        }
+
+        let Some(agent_identifier) = PublicIdentifier::from_str(agent_id_string) else {
+            return ControllerType::Error(strings::agent::invalid_id_generic());
+        };
+
+        return ControllerType::Agent(AgentControllerType::Models(agent_identifier));
+    }
+
     if let Some(remaining_text) = text.strip_prefix("create-room-local") {
         // `remaining_text` should be something like: `PROVIDER ID`
         let remaining_text = remaining_text.trim();
@@ -79,6 +125,22 @@ pub fn determine_controller(command_prefix: &str, text: &str) -> ControllerType
         });
     }
 
+    if let Some(agent_id_string) = text.strip_prefix("update") {
+        let agent_id_string = agent_id_string.trim();
+
+        if agent_id_string.is_empty() || agent_id_string.contains(" ") {
+            return ControllerType::Error(
+                strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            );
+        }
+
+        let Some(agent_identifier) = PublicIdentifier::from_str(agent_id_string) else {
+            return ControllerType::Error(strings::agent::invalid_id_generic());
+        };
+
+        return ControllerType::Agent(AgentControllerType::Update(agent_identifier));
+    }
+
     if let Some(agent_id_string) = text.strip_prefix("delete") {
         let agent_id_string = agent_id_string.trim();
 
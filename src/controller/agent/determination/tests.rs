@@ -33,11 +33,67 @@ fn determine_controller() {
                 PublicIdentifier::Static("agent-id".to_owned()),
             )),
         },
+        TestCase {
+            name: "details with reveal",
+            input: "details global/agent-id --reveal",
+            expected: super::ControllerType::Agent(super::AgentControllerType::RevealDetails(
+                PublicIdentifier::DynamicGlobal("agent-id".to_owned()),
+            )),
+        },
+        TestCase {
+            name: "details with reveal but without agent identifier",
+            input: "details --reveal",
+            expected: super::ControllerType::Error(
+                crate::strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            ),
+        },
         TestCase {
             name: "details with invalid agent identifier",
             input: "details agent-id",
             expected: super::ControllerType::Error(crate::strings::agent::invalid_id_generic()),
         },
+        TestCase {
+            name: "tools",
+            input: "tools global/agent-id",
+            expected: super::ControllerType::Agent(super::AgentControllerType::Tools(
+                PublicIdentifier::DynamicGlobal("agent-id".to_owned()),
+            )),
+        },
+        TestCase {
+            name: "tools without agent identifier",
+            input: "tools",
+            expected: super::ControllerType::Error(
+                crate::strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            ),
+        },
+        TestCase {
+            name: "models",
+            input: "models room-local/agent-id",
+            expected: super::ControllerType::Agent(super::AgentControllerType::Models(
+                PublicIdentifier::DynamicRoomLocal("agent-id".to_owned()),
+            )),
+        },
+        TestCase {
+            name: "models with too many arguments",
+            input: "models room-local/agent-id extra",
+            expected: super::ControllerType::Error(
+                crate::strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            ),
+        },
+        TestCase {
+            name: "update",
+            input: "update global/agent-id",
+            expected: super::ControllerType::Agent(super::AgentControllerType::Update(
+                PublicIdentifier::DynamicGlobal("agent-id".to_owned()),
+            )),
+        },
+        TestCase {
+            name: "update without agent identifier",
+            input: "update",
+            expected: super::ControllerType::Error(
+                crate::strings::agent::incorrect_invocation_expects_agent_id_arg(command_prefix),
+            ),
+        },
         TestCase {
             name: "create-room-local no arguments",
             input: "create-room-local",
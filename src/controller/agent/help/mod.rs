@@ -53,11 +53,20 @@ pub async fn handle(bot: &Bot, message_context: &MessageContext) -> anyhow::Resu
         ));
         message.push('\n');
 
-        message.push_str(&strings::help::agent::delete_agent(bot.command_prefix()));
+        message.push_str(&strings::help::agent::show_agent_tools(
+            bot.command_prefix(),
+        ));
+        message.push('\n');
 
-        message.push_str("\n\n");
+        message.push_str(&strings::help::agent::list_agent_models(
+            bot.command_prefix(),
+        ));
+        message.push('\n');
 
-        message.push_str(strings::help::agent::available_commands_outro_update_note());
+        message.push_str(&strings::help::agent::update_agent(bot.command_prefix()));
+        message.push('\n');
+
+        message.push_str(&strings::help::agent::delete_agent(bot.command_prefix()));
     } else {
         message.push_str("\n\n");
 
@@ -6,6 +6,9 @@ pub mod details;
 pub mod determination;
 pub mod help;
 pub mod list;
+pub mod models;
+pub mod tools;
+pub mod update;
 
 pub use determination::{AgentControllerType, determine_controller};
 
@@ -39,6 +42,25 @@ pub async fn dispatch_controller(
         AgentControllerType::Details(agent_identifier) => {
             details::handle(bot, message_context, agent_identifier).await
         }
+        AgentControllerType::RevealDetails(agent_identifier) => {
+            details::handle_reveal(bot, message_context, agent_identifier).await
+        }
+        AgentControllerType::Tools(agent_identifier) => {
+            tools::handle(bot, message_context, agent_identifier).await
+        }
+        AgentControllerType::Models(agent_identifier) => {
+            models::handle(bot, message_context, agent_identifier).await
+        }
+        AgentControllerType::Update(agent_identifier) => {
+            update::handle(
+                bot,
+                bot.room_config_manager(),
+                bot.global_config_manager(),
+                message_context,
+                agent_identifier,
+            )
+            .await
+        }
         AgentControllerType::Delete(agent_identifier) => {
             delete::handle(
                 bot,
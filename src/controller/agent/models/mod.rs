@@ -0,0 +1,69 @@
+use mxlink::MessageResponseType;
+
+use crate::{
+    Bot,
+    agent::{ControllerTrait, PublicIdentifier},
+    entity::MessageContext,
+    strings,
+};
+
+pub async fn handle(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent_identifier: &PublicIdentifier,
+) -> anyhow::Result<()> {
+    let agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    let Some(agent) = agents.iter().find(|a| a.identifier() == agent_identifier) else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::agent_with_given_identifier_missing(agent_identifier),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    // Like `tools`, this doesn't reveal any configuration (API keys, etc.), so there are no access checks.
+
+    let models = match agent.controller().list_models().await {
+        Ok(models) => models,
+        Err(err) => {
+            tracing::warn!(?err, agent_id = %agent_identifier, "Failed to list models");
+
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::agent::models_listing_failed(agent_identifier, err),
+                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    let message = if models.is_empty() {
+        strings::agent::models_none(agent_identifier)
+    } else {
+        format!(
+            "{}\n\n{}",
+            strings::agent::models_intro(agent_identifier),
+            strings::provider::models_grouped_by_purpose_block(&models),
+        )
+    };
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use mxlink::MessageResponseType;
+
+use crate::{
+    Bot,
+    agent::{ControllerTrait, PublicIdentifier},
+    entity::MessageContext,
+    strings,
+};
+
+pub async fn handle(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent_identifier: &PublicIdentifier,
+) -> anyhow::Result<()> {
+    let agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    let Some(agent) = agents.iter().find(|a| a.identifier() == agent_identifier) else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::agent_with_given_identifier_missing(agent_identifier),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    // Access checks
+    //
+    // Listing tools doesn't reveal any configuration (API keys, etc.), but it does connect to the agent's MCP servers
+    // (possibly spawning processes), so only those who manage the agent may trigger it.
+
+    match &agent_identifier {
+        PublicIdentifier::DynamicRoomLocal(_) => {
+            if !message_context.sender_can_manage_room_local_agents()? {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &strings::agent::not_allowed_to_manage_room_local_agents_in_room(),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+        }
+        PublicIdentifier::DynamicGlobal(_) | PublicIdentifier::Static(_) => {
+            if !message_context.sender_can_manage_global_config() {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        strings::global_config::no_permissions_to_administrate(),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let builtin_tools = agent.controller().text_generation_tools().definitions();
+
+    // This connects to the agent's MCP servers (if not already connected), just like text-generation would.
+    let mcp_tools = bot.mcp_manager().tools_for_agent(agent).await;
+    let mcp_tool_definitions = mcp_tools.registry.definitions();
+
+    let mut message = String::new();
+
+    if builtin_tools.is_empty() && mcp_tool_definitions.is_empty() {
+        message.push_str(&strings::agent::tools_none(agent_identifier));
+    } else {
+        message.push_str(&strings::agent::tools_intro(agent_identifier));
+
+        if !builtin_tools.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(strings::agent::tools_builtin_heading());
+            for definition in &builtin_tools {
+                message.push('\n');
+                message.push_str(&strings::agent::tools_list_entry(definition));
+            }
+        }
+
+        if !mcp_tool_definitions.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(strings::agent::tools_mcp_heading());
+            for definition in &mcp_tool_definitions {
+                message.push('\n');
+                message.push_str(&strings::agent::tools_list_entry(definition));
+            }
+        }
+    }
+
+    if !mcp_tools.failures.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(strings::agent::tools_mcp_failures_intro());
+        for server_id in &mcp_tools.failures {
+            message.push('\n');
+            message.push_str(&strings::agent::tools_mcp_failure_entry(server_id));
+        }
+    }
+
+    let has_mcp_config = agent
+        .controller()
+        .text_generation_mcp_config()
+        .is_some_and(|config| !config.is_default());
+
+    if has_mcp_config && matches!(agent_identifier, PublicIdentifier::DynamicRoomLocal(_)) {
+        message.push_str("\n\n");
+        message.push_str(strings::agent::tools_mcp_not_available_to_room_local_agents());
+    }
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,426 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+
+use mxlink::MessageResponseType;
+
+use crate::agent::{
+    AgentDefinition, AgentInstance, PublicIdentifier, create_from_provider_and_yaml_value_config,
+    secrets,
+};
+use crate::controller::utils::get_text_body_or_complain;
+use crate::entity::globalconfig::GlobalConfigurationManager;
+use crate::entity::roomconfig::RoomConfigurationManager;
+use crate::strings;
+use crate::{Bot, entity::MessageContext};
+
+use super::create::{parse_from_message_to_yaml_value, try_to_ping_agent_or_complain};
+
+pub async fn handle(
+    bot: &Bot,
+    room_config_manager: &tokio::sync::Mutex<RoomConfigurationManager>,
+    global_config_manager: &tokio::sync::Mutex<GlobalConfigurationManager>,
+    message_context: &MessageContext,
+    agent_identifier: &PublicIdentifier,
+) -> anyhow::Result<()> {
+    let agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    let Some(agent) = agents.iter().find(|a| a.identifier() == agent_identifier) else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::agent_with_given_identifier_missing(agent_identifier),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    // Access checks
+
+    match &agent_identifier {
+        PublicIdentifier::DynamicRoomLocal(_) => {
+            if !message_context.sender_can_manage_room_local_agents()? {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &strings::agent::not_allowed_to_manage_room_local_agents_in_room(),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+        }
+        PublicIdentifier::DynamicGlobal(_) => {
+            if !message_context.sender_can_manage_global_config() {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        strings::global_config::no_permissions_to_administrate(),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+        }
+        PublicIdentifier::Static(_) => {
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::agent::not_allowed_to_manage_static_agents(),
+                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    if message_context.thread_info().is_thread_root_only() {
+        return send_guide(bot, message_context, agent).await;
+    }
+
+    let Some(text_message_content) = get_text_body_or_complain(bot, message_context).await else {
+        return Ok(());
+    };
+
+    let patch = match parse_from_message_to_yaml_value(text_message_content) {
+        Ok(patch) => patch,
+        Err(err) => {
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::agent::configuration_not_a_valid_yaml_hashmap(err),
+                    MessageResponseType::InThread(message_context.thread_info().clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    let definition = agent.definition();
+
+    let mut config = definition.config.clone();
+    merge_patch(&mut config, patch);
+
+    let changes = diff(&definition.config, &config);
+    if changes.is_empty() {
+        bot.messaging()
+            .send_notice_markdown_no_fail(
+                message_context.room(),
+                strings::agent::update_changes_nothing(agent_identifier),
+                MessageResponseType::InThread(message_context.thread_info().clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    // Instantiating validates the new configuration (see `ConfigTrait::validate`).
+    let updated_agent = match create_from_provider_and_yaml_value_config(
+        &definition.provider,
+        agent_identifier,
+        config.clone(),
+        bot.agent_manager().concurrency_limiter().clone(),
+    ) {
+        Ok(updated_agent) => updated_agent,
+        Err(err) => {
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::provider::invalid_configuration_for_provider(
+                        &definition.provider,
+                        err,
+                    ),
+                    MessageResponseType::InThread(message_context.thread_info().clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    if !try_to_ping_agent_or_complain(bot, message_context, &updated_agent).await {
+        return Ok(());
+    }
+
+    // The definition is replaced in place, keeping the agent's identifier.
+    // Handlers refer to agents by identifier, so all of them keep pointing at the updated agent.
+    let was_updated = match agent_identifier {
+        PublicIdentifier::DynamicRoomLocal(_) => {
+            let mut room_config = message_context.room_config().clone();
+
+            let was_updated = replace_agent_config(
+                &mut room_config.agents,
+                &agent_identifier.prefixless(),
+                config,
+            );
+
+            if was_updated {
+                room_config_manager
+                    .lock()
+                    .await
+                    .persist(message_context.room(), &room_config)
+                    .await?;
+            }
+
+            was_updated
+        }
+        PublicIdentifier::DynamicGlobal(_) => {
+            let mut global_config = message_context.global_config().clone();
+
+            let was_updated = replace_agent_config(
+                &mut global_config.agents,
+                &agent_identifier.prefixless(),
+                config,
+            );
+
+            if was_updated {
+                global_config_manager
+                    .lock()
+                    .await
+                    .persist(&global_config)
+                    .await?;
+            }
+
+            was_updated
+        }
+        PublicIdentifier::Static(_) => false,
+    };
+
+    if !was_updated {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::agent_with_given_identifier_missing(agent_identifier),
+                MessageResponseType::InThread(message_context.thread_info().clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    // The new definition gets a fresh instance on its next use anyway, but there's no point in keeping the old one around.
+    bot.agent_manager()
+        .forget_dynamic_agent(message_context.room_config_context(), agent);
+
+    bot.messaging()
+        .send_success_markdown_no_fail(
+            message_context.room(),
+            &strings::agent::updated(agent_identifier, &render_diff(&changes)),
+            MessageResponseType::InThread(message_context.thread_info().clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+async fn send_guide(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent: &AgentInstance,
+) -> anyhow::Result<()> {
+    let config_pretty_yaml =
+        serde_yaml_ng::to_string(&secrets::redact(&agent.definition().config))?;
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            strings::agent::update_guide(agent.identifier(), &config_pretty_yaml),
+            MessageResponseType::InThread(message_context.thread_info().clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+fn replace_agent_config(
+    definitions: &mut [AgentDefinition],
+    agent_id_prefixless: &str,
+    config: serde_yaml_ng::Value,
+) -> bool {
+    let Some(definition) = definitions
+        .iter_mut()
+        .find(|definition| definition.id == agent_id_prefixless)
+    else {
+        return false;
+    };
+
+    definition.config = config;
+
+    true
+}
+
+/// Merges a partial configuration into `target`, in the spirit of [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396):
+/// mappings are merged recursively, `null` removes a key and everything else (including lists) replaces what was there.
+fn merge_patch(target: &mut serde_yaml_ng::Value, patch: serde_yaml_ng::Value) {
+    let serde_yaml_ng::Value::Mapping(patch) = patch else {
+        *target = patch;
+        return;
+    };
+
+    if !target.is_mapping() {
+        *target = serde_yaml_ng::Value::Mapping(serde_yaml_ng::Mapping::new());
+    }
+
+    let serde_yaml_ng::Value::Mapping(target_mapping) = target else {
+        unreachable!("target was just made a mapping");
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target_mapping.remove(&key);
+            continue;
+        }
+
+        match target_mapping.get_mut(&key) {
+            Some(existing) => merge_patch(existing, value),
+            None => {
+                let mut new_value = serde_yaml_ng::Value::Null;
+                merge_patch(&mut new_value, value);
+                target_mapping.insert(key, new_value);
+            }
+        }
+    }
+}
+
+/// A change of a single (leaf) configuration value, addressed by its dotted path (e.g. `text_generation.model_id`).
+#[derive(Debug, PartialEq)]
+enum ConfigChange {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        old: String,
+        new: String,
+    },
+}
+
+/// Lists the changes between two configurations.
+///
+/// Changes are detected on the actual values, but reported with secrets masked (see [`secrets::redact`]),
+/// as the result ends up in the room. A changed API key thus shows up as a change from `***` to `***`.
+fn diff(old: &serde_yaml_ng::Value, new: &serde_yaml_ng::Value) -> Vec<ConfigChange> {
+    let old_shown = flatten(&secrets::redact(old));
+    let new_shown = flatten(&secrets::redact(new));
+
+    let old = flatten(old);
+    let new = flatten(new);
+
+    // Redaction keeps the structure intact, so the paths are the same with and without it.
+    let shown = |values: &BTreeMap<String, String>, path: &str| -> String {
+        values.get(path).cloned().unwrap_or_default()
+    };
+
+    let mut changes = vec![];
+
+    for (path, old_value) in &old {
+        match new.get(path) {
+            None => changes.push(ConfigChange::Removed {
+                path: path.clone(),
+                value: shown(&old_shown, path),
+            }),
+            Some(new_value) if new_value != old_value => changes.push(ConfigChange::Changed {
+                path: path.clone(),
+                old: shown(&old_shown, path),
+                new: shown(&new_shown, path),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for path in new.keys() {
+        if !old.contains_key(path) {
+            changes.push(ConfigChange::Added {
+                path: path.clone(),
+                value: shown(&new_shown, path),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+
+    changes
+}
+
+impl ConfigChange {
+    fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Changed { path, .. } => {
+                path
+            }
+        }
+    }
+}
+
+/// Renders the changes in the unified diff style (`-` for old values, `+` for new ones).
+fn render_diff(changes: &[ConfigChange]) -> String {
+    let mut lines = vec![];
+
+    for change in changes {
+        match change {
+            ConfigChange::Added { path, value } => lines.push(format!("+ {path}: {value}")),
+            ConfigChange::Removed { path, value } => lines.push(format!("- {path}: {value}")),
+            ConfigChange::Changed { path, old, new } => {
+                lines.push(format!("- {path}: {old}"));
+                lines.push(format!("+ {path}: {new}"));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Flattens a configuration into its leaf values (rendered on a single line), keyed by their dotted path.
+/// Lists are treated as leaves, as they are replaced as a whole when patching.
+fn flatten(value: &serde_yaml_ng::Value) -> BTreeMap<String, String> {
+    fn walk(value: &serde_yaml_ng::Value, path: String, out: &mut BTreeMap<String, String>) {
+        match value {
+            serde_yaml_ng::Value::Mapping(mapping) if !mapping.is_empty() => {
+                for (key, value) in mapping {
+                    let key = match key {
+                        serde_yaml_ng::Value::String(key) => key.clone(),
+                        other => render(other),
+                    };
+
+                    let path = if path.is_empty() {
+                        key
+                    } else {
+                        format!("{path}.{key}")
+                    };
+
+                    walk(value, path, out);
+                }
+            }
+            _ => {
+                out.insert(path, render(value));
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    walk(value, String::new(), &mut out);
+    out
+}
+
+fn render(value: &serde_yaml_ng::Value) -> String {
+    // JSON is YAML's single-line (flow) form.
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{value:?}"))
+}
@@ -1,8 +1,9 @@
 use crate::{
-    agent::{AgentPurpose, PublicIdentifier},
+    agent::{AgentPurpose, PublicIdentifier, provider::ReasoningEffort},
     entity::roomconfig::{
-        SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
-        TextGenerationAutoUsage, TextGenerationPrefixRequirementType,
+        QuotaLimit, ReactionAction, SpeechToTextFlowType,
+        SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages, TextGenerationAutoUsage,
+        TextGenerationContextManagementStrategy, TextGenerationPrefixRequirementType,
         TextGenerationSenderContextMode, TextToSpeechBotMessagesFlowType,
         TextToSpeechUserMessagesFlowType,
     },
@@ -26,11 +27,14 @@ pub enum ConfigControllerType {
 #[derive(Debug, PartialEq)]
 pub enum ConfigSettingRelatedControllerType {
     GetHandler(AgentPurpose),
-    SetHandler(AgentPurpose, Option<PublicIdentifier>),
+    /// Sets the ordered chain of agents handling the purpose (an empty chain unsets the handler).
+    SetHandler(AgentPurpose, Vec<PublicIdentifier>),
 
     TextGeneration(ConfigTextGenerationSettingRelatedControllerType),
     SpeechToText(ConfigSpeechToTextSettingRelatedControllerType),
     TextToSpeech(ConfigTextToSpeechSettingRelatedControllerType),
+    Quota(ConfigQuotaSettingRelatedControllerType),
+    Reactions(ConfigReactionsSettingRelatedControllerType),
 }
 
 #[derive(Debug, PartialEq)]
@@ -38,9 +42,21 @@ pub enum ConfigTextGenerationSettingRelatedControllerType {
     GetContextManagementEnabled,
     SetContextManagementEnabled(Option<bool>),
 
+    GetContextManagementStrategy,
+    SetContextManagementStrategy(Option<TextGenerationContextManagementStrategy>),
+
+    GetContextSummaryAgent,
+    SetContextSummaryAgent(Option<PublicIdentifier>),
+
     GetThinkingNoticeEnabled,
     SetThinkingNoticeEnabled(Option<bool>),
 
+    GetStreamingEnabled,
+    SetStreamingEnabled(Option<bool>),
+
+    GetRegenerateOnEditEnabled,
+    SetRegenerateOnEditEnabled(Option<bool>),
+
     GetPrefixRequirementType,
     SetPrefixRequirementType(Option<TextGenerationPrefixRequirementType>),
 
@@ -50,9 +66,24 @@ pub enum ConfigTextGenerationSettingRelatedControllerType {
     GetPromptOverride,
     SetPromptOverride(Option<String>),
 
+    GetPromptName,
+    SetPromptName(Option<String>),
+
+    GetTimezone,
+    SetTimezone(Option<String>),
+
     GetTemperatureOverride,
     SetTemperatureOverride(Option<f32>),
 
+    GetModelOverride,
+    SetModelOverride(Option<String>),
+
+    GetMaxResponseTokensOverride,
+    SetMaxResponseTokensOverride(Option<u32>),
+
+    GetReasoningEffortOverride,
+    SetReasoningEffortOverride(Option<ReasoningEffort>),
+
     GetSenderContextMode,
     SetSenderContextMode(Option<TextGenerationSenderContextMode>),
 }
@@ -85,3 +116,20 @@ pub enum ConfigTextToSpeechSettingRelatedControllerType {
     GetVoiceOverride,
     SetVoiceOverride(Option<String>),
 }
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigQuotaSettingRelatedControllerType {
+    GetLimit(QuotaLimit),
+    SetLimit(QuotaLimit, Option<f64>),
+
+    GetCostPerMillionTokens,
+    SetCostPerMillionTokens(Option<f64>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigReactionsSettingRelatedControllerType {
+    /// Gets the action for the given reaction key (emoji).
+    GetAction(String),
+    /// Sets the action for the given reaction key (emoji). `None` unsets it.
+    SetAction(String, Option<ReactionAction>),
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests;
 
+mod quota;
+mod reactions;
 mod speech_to_text;
 mod text_generation;
 mod text_to_speech;
@@ -8,6 +10,7 @@ mod text_to_speech;
 use crate::{
     agent::{AgentPurpose, PublicIdentifier},
     controller::ControllerType,
+    entity::roomconfig::split_agent_chain,
     strings,
 };
 
@@ -72,6 +75,7 @@ fn do_determine_controller(
     if let Some(remaining_text) = text.strip_prefix("set-handler") {
         // Something like:
         // - `PURPOSE ID`
+        // - `PURPOSE ID,ID,..` (a chain of agents to fail over between)
         // - `PURPOSE`
         let remaining_text = remaining_text.trim();
 
@@ -97,22 +101,25 @@ fn do_determine_controller(
             ));
         };
 
-        let agent_identifier = match agent_id_string_option {
-            Some(agent_id_string) => {
-                let Some(agent_identifier) = PublicIdentifier::from_str(&agent_id_string) else {
-                    return Err(ControllerType::Error(
-                        strings::agent::invalid_id_generic().to_owned(),
-                    ));
-                };
-
-                Some(agent_identifier)
-            }
-            None => None,
+        let agent_id_strings = match agent_id_string_option {
+            Some(agent_id_string) => split_agent_chain(&agent_id_string),
+            None => vec![],
         };
 
+        let mut agent_identifiers = vec![];
+        for agent_id_string in agent_id_strings {
+            let Some(agent_identifier) = PublicIdentifier::from_str(&agent_id_string) else {
+                return Err(ControllerType::Error(
+                    strings::agent::invalid_id_generic().to_owned(),
+                ));
+            };
+
+            agent_identifiers.push(agent_identifier);
+        }
+
         return Ok(ConfigSettingRelatedControllerType::SetHandler(
             purpose,
-            agent_identifier,
+            agent_identifiers,
         ));
     }
 
@@ -137,5 +144,19 @@ fn do_determine_controller(
         };
     }
 
+    if let Some(remaining_text) = text.strip_prefix("quota") {
+        return match quota::determine(remaining_text.trim()) {
+            Ok(handler) => Ok(ConfigSettingRelatedControllerType::Quota(handler)),
+            Err(controller_type) => Err(controller_type),
+        };
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("reactions") {
+        return match reactions::determine(remaining_text.trim()) {
+            Ok(handler) => Ok(ConfigSettingRelatedControllerType::Reactions(handler)),
+            Err(controller_type) => Err(controller_type),
+        };
+    }
+
     Err(ControllerType::Unknown)
 }
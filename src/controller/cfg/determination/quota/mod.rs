@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{controller::ControllerType, entity::roomconfig::QuotaLimit, strings};
+
+use super::super::controller_type::ConfigQuotaSettingRelatedControllerType;
+
+const COST_PER_MILLION_TOKENS: &str = "cost-per-million-tokens";
+
+pub(super) fn determine(
+    text: &str,
+) -> Result<ConfigQuotaSettingRelatedControllerType, ControllerType> {
+    // Something like:
+    // - `user-daily-tokens`
+    // - `set-user-daily-tokens 100000`
+    // - `set-user-daily-tokens`
+    let (name, value_string) = match text.split_once(' ') {
+        Some((name, value_string)) => (name, value_string.trim()),
+        None => (text, ""),
+    };
+
+    if let Some(setter_name) = name.strip_prefix("set-") {
+        if setter_name == COST_PER_MILLION_TOKENS {
+            let value = parse_cost(value_string)?;
+
+            return Ok(ConfigQuotaSettingRelatedControllerType::SetCostPerMillionTokens(value));
+        }
+
+        let Some(limit) = QuotaLimit::from_str(setter_name) else {
+            return Err(ControllerType::Unknown);
+        };
+
+        let value = if limit.metric.is_count() {
+            parse_count(value_string)?
+        } else {
+            parse_cost(value_string)?
+        };
+
+        return Ok(ConfigQuotaSettingRelatedControllerType::SetLimit(
+            limit, value,
+        ));
+    }
+
+    let getter = if name == COST_PER_MILLION_TOKENS {
+        ConfigQuotaSettingRelatedControllerType::GetCostPerMillionTokens
+    } else if let Some(limit) = QuotaLimit::from_str(name) {
+        ConfigQuotaSettingRelatedControllerType::GetLimit(limit)
+    } else {
+        return Err(ControllerType::Unknown);
+    };
+
+    if !value_string.is_empty() {
+        return Err(ControllerType::Error(
+            strings::cfg::configuration_getter_used_with_extra_text(name, value_string).to_owned(),
+        ));
+    }
+
+    Ok(getter)
+}
+
+fn parse_count(value_string: &str) -> Result<Option<f64>, ControllerType> {
+    if value_string.is_empty() {
+        return Ok(None);
+    }
+
+    let Ok(value) = value_string.parse::<u64>() else {
+        return Err(ControllerType::Error(
+            strings::cfg::configuration_value_not_whole_number(value_string).to_owned(),
+        ));
+    };
+
+    Ok(Some(value as f64))
+}
+
+fn parse_cost(value_string: &str) -> Result<Option<f64>, ControllerType> {
+    if value_string.is_empty() {
+        return Ok(None);
+    }
+
+    match value_string.parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(Some(value)),
+        _ => Err(ControllerType::Error(
+            strings::cfg::configuration_value_not_non_negative_number(value_string).to_owned(),
+        )),
+    }
+}
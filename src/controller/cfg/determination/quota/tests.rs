@@ -0,0 +1,154 @@
+#[test]
+fn determine_controller_other() {
+    use super::ConfigQuotaSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigQuotaSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "Unknown",
+            input: "whatever",
+            expected: Err(ControllerType::Unknown),
+        },
+        TestCase {
+            name: "Unknown setter",
+            input: "set-user-yearly-tokens 5",
+            expected: Err(ControllerType::Unknown),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_limits() {
+    use super::ConfigQuotaSettingRelatedControllerType;
+    use super::ControllerType;
+    use crate::entity::roomconfig::{QuotaLimit, QuotaMetric, QuotaPeriod, QuotaScope};
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigQuotaSettingRelatedControllerType, ControllerType>,
+    }
+
+    let user_daily_tokens =
+        QuotaLimit::new(QuotaScope::User, QuotaPeriod::Daily, QuotaMetric::Tokens);
+    let room_monthly_cost =
+        QuotaLimit::new(QuotaScope::Room, QuotaPeriod::Monthly, QuotaMetric::Cost);
+
+    let test_cases = vec![
+        TestCase {
+            name: "limit getter ok",
+            input: "user-daily-tokens",
+            expected: Ok(ConfigQuotaSettingRelatedControllerType::GetLimit(
+                user_daily_tokens,
+            )),
+        },
+        TestCase {
+            name: "limit getter extra args",
+            input: "user-daily-tokens 5000",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text(
+                    "user-daily-tokens",
+                    "5000",
+                ),
+            )),
+        },
+        TestCase {
+            name: "count limit setter",
+            input: "set-user-daily-tokens 5000",
+            expected: Ok(ConfigQuotaSettingRelatedControllerType::SetLimit(
+                user_daily_tokens,
+                Some(5000.0),
+            )),
+        },
+        TestCase {
+            name: "count limit setter with fractional value",
+            input: "set-user-daily-tokens 1.5",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_not_whole_number("1.5"),
+            )),
+        },
+        TestCase {
+            name: "count limit unsetter",
+            input: "set-user-daily-tokens",
+            expected: Ok(ConfigQuotaSettingRelatedControllerType::SetLimit(
+                user_daily_tokens,
+                None,
+            )),
+        },
+        TestCase {
+            name: "cost limit setter",
+            input: "set-room-monthly-cost 12.5",
+            expected: Ok(ConfigQuotaSettingRelatedControllerType::SetLimit(
+                room_monthly_cost,
+                Some(12.5),
+            )),
+        },
+        TestCase {
+            name: "cost limit setter with negative value",
+            input: "set-room-monthly-cost -1",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_not_non_negative_number("-1"),
+            )),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_cost_per_million_tokens() {
+    use super::ConfigQuotaSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigQuotaSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "cost-per-million-tokens getter ok",
+            input: "cost-per-million-tokens",
+            expected: Ok(ConfigQuotaSettingRelatedControllerType::GetCostPerMillionTokens),
+        },
+        TestCase {
+            name: "cost-per-million-tokens setter",
+            input: "set-cost-per-million-tokens 0.6",
+            expected: Ok(
+                ConfigQuotaSettingRelatedControllerType::SetCostPerMillionTokens(Some(0.6)),
+            ),
+        },
+        TestCase {
+            name: "cost-per-million-tokens setter with invalid value",
+            input: "set-cost-per-million-tokens unknown-Value",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_not_non_negative_number("unknown-Value"),
+            )),
+        },
+        TestCase {
+            name: "cost-per-million-tokens unsetter",
+            input: "set-cost-per-million-tokens",
+            expected: Ok(ConfigQuotaSettingRelatedControllerType::SetCostPerMillionTokens(None)),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{controller::ControllerType, entity::roomconfig::ReactionAction, strings};
+
+use super::super::controller_type::ConfigReactionsSettingRelatedControllerType;
+
+pub(super) fn determine(
+    text: &str,
+) -> Result<ConfigReactionsSettingRelatedControllerType, ControllerType> {
+    // Something like:
+    // - `🌍`
+    // - `set-🌍 translate`
+    // - `set-🌍`
+    let (name, value_string) = match text.split_once(' ') {
+        Some((name, value_string)) => (name, value_string.trim()),
+        None => (text, ""),
+    };
+
+    if let Some(key) = name.strip_prefix("set-") {
+        if key.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_invocation_incorrect_more_values_expected(),
+            ));
+        }
+
+        if value_string.is_empty() {
+            return Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+                key.to_owned(),
+                None,
+            ));
+        }
+
+        let Some(action) = ReactionAction::from_str(value_string) else {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_value_unrecognized(value_string),
+            ));
+        };
+
+        return Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+            key.to_owned(),
+            Some(action),
+        ));
+    }
+
+    if name.is_empty() {
+        return Err(ControllerType::Error(
+            strings::cfg::configuration_invocation_incorrect_more_values_expected(),
+        ));
+    }
+
+    if !value_string.is_empty() {
+        return Err(ControllerType::Error(
+            strings::cfg::configuration_getter_used_with_extra_text(name, value_string).to_owned(),
+        ));
+    }
+
+    Ok(ConfigReactionsSettingRelatedControllerType::GetAction(
+        name.to_owned(),
+    ))
+}
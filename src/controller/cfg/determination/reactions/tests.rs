@@ -0,0 +1,95 @@
+#[test]
+fn determine_controller() {
+    use super::ConfigReactionsSettingRelatedControllerType;
+    use super::ControllerType;
+    use crate::entity::roomconfig::ReactionAction;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigReactionsSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "No key",
+            input: "",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_invocation_incorrect_more_values_expected(),
+            )),
+        },
+        TestCase {
+            name: "Getter ok",
+            input: "🌍",
+            expected: Ok(ConfigReactionsSettingRelatedControllerType::GetAction(
+                "🌍".to_owned(),
+            )),
+        },
+        TestCase {
+            name: "Getter extra args",
+            input: "🌍 translate",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text("🌍", "translate"),
+            )),
+        },
+        TestCase {
+            name: "Setter with built-in action",
+            input: "set-🌍 translate",
+            expected: Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+                "🌍".to_owned(),
+                Some(ReactionAction::Translate(None)),
+            )),
+        },
+        TestCase {
+            name: "Setter with parameterized built-in action",
+            input: "set-🇩🇪 translate:German",
+            expected: Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+                "🇩🇪".to_owned(),
+                Some(ReactionAction::Translate(Some("German".to_owned()))),
+            )),
+        },
+        TestCase {
+            name: "Setter with named prompt",
+            input: "set-📝   prompt:tldr ",
+            expected: Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+                "📝".to_owned(),
+                Some(ReactionAction::Prompt("tldr".to_owned())),
+            )),
+        },
+        TestCase {
+            name: "Setter disabling a key",
+            input: "set-🗣️ none",
+            expected: Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+                "🗣️".to_owned(),
+                Some(ReactionAction::Disabled),
+            )),
+        },
+        TestCase {
+            name: "Setter unsetting",
+            input: "set-🌍",
+            expected: Ok(ConfigReactionsSettingRelatedControllerType::SetAction(
+                "🌍".to_owned(),
+                None,
+            )),
+        },
+        TestCase {
+            name: "Setter with unknown action",
+            input: "set-🌍 dance",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("dance"),
+            )),
+        },
+        TestCase {
+            name: "Setter without key",
+            input: "set- translate",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_invocation_incorrect_more_values_expected(),
+            )),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
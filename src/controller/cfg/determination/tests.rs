@@ -70,9 +70,9 @@ fn determine_controller() {
             input: "room set-handler catch-all static/agent-id",
             expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
                 controller_type::SettingsStorageSource::Room,
-                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::CatchAll, Some(
+                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::CatchAll, vec![
                     PublicIdentifier::Static("agent-id".to_owned())
-                )),
+                ]),
             )),
         },
         TestCase {
@@ -87,7 +87,7 @@ fn determine_controller() {
             input: "room set-handler catch-all",
             expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
                 controller_type::SettingsStorageSource::Room,
-                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::CatchAll, None),
+                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::CatchAll, vec![]),
             )),
         },
         TestCase {
@@ -95,11 +95,29 @@ fn determine_controller() {
             input: "room set-handler text-generation room-local/agent-id",
             expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
                 controller_type::SettingsStorageSource::Room,
-                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, Some(
+                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, vec![
                     PublicIdentifier::DynamicRoomLocal("agent-id".to_owned())
-                )),
+                ]),
             )),
         },
+        TestCase {
+            name: "per-room handler setter - text-generation chain",
+            input: "room set-handler text-generation static/agent-id, global/other-agent-id",
+            expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
+                controller_type::SettingsStorageSource::Room,
+                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, vec![
+                    PublicIdentifier::Static("agent-id".to_owned()),
+                    PublicIdentifier::DynamicGlobal("other-agent-id".to_owned()),
+                ]),
+            )),
+        },
+        TestCase {
+            name: "per-room handler setter - chain with bare agent id",
+            input: "room set-handler text-generation static/agent-id,agent-id",
+            expected: super::ControllerType::Error(
+                crate::strings::agent::invalid_id_generic().to_owned()
+            ),
+        },
         TestCase {
             name: "per-room handler setter - too many values",
             input: "room set-handler text-generation agent-id more values here",
@@ -123,9 +141,9 @@ fn determine_controller() {
             input: "global set-handler text-generation global/agent-id",
             expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
                 controller_type::SettingsStorageSource::Global,
-                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, Some(
+                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, vec![
                     PublicIdentifier::DynamicGlobal("agent-id".to_owned())
-                )),
+                ]),
             )),
         },
         // This test case passes, even though the handler function will subsequently reject using room-local agents for global handlers.
@@ -134,9 +152,9 @@ fn determine_controller() {
             input: "global set-handler text-generation room-local/agent-id",
             expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
                 controller_type::SettingsStorageSource::Global,
-                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, Some(
+                controller_type::ConfigSettingRelatedControllerType::SetHandler(AgentPurpose::TextGeneration, vec![
                     PublicIdentifier::DynamicRoomLocal("agent-id".to_owned())
-                )),
+                ]),
             )),
         },
 
@@ -223,6 +241,39 @@ fn determine_controller() {
                 ),
             )),
         },
+        TestCase {
+            name: "room quota/cost-per-million-tokens getter",
+            input: "room quota cost-per-million-tokens",
+            expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
+                controller_type::SettingsStorageSource::Room,
+                controller_type::ConfigSettingRelatedControllerType::Quota(
+                    controller_type::ConfigQuotaSettingRelatedControllerType::GetCostPerMillionTokens,
+                ),
+            )),
+        },
+        TestCase {
+            name: "global quota/cost-per-million-tokens setter",
+            input: "global quota set-cost-per-million-tokens 2.5",
+            expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
+                controller_type::SettingsStorageSource::Global,
+                controller_type::ConfigSettingRelatedControllerType::Quota(
+                    controller_type::ConfigQuotaSettingRelatedControllerType::SetCostPerMillionTokens(Some(2.5)),
+                ),
+            )),
+        },
+        TestCase {
+            name: "room reactions setter",
+            input: "room reactions set-🌍 translate",
+            expected: super::ControllerType::Config(controller_type::ConfigControllerType::SettingsRelated(
+                controller_type::SettingsStorageSource::Room,
+                controller_type::ConfigSettingRelatedControllerType::Reactions(
+                    controller_type::ConfigReactionsSettingRelatedControllerType::SetAction(
+                        "🌍".to_owned(),
+                        Some(crate::entity::roomconfig::ReactionAction::Translate(None)),
+                    ),
+                ),
+            )),
+        },
     ];
 
     for test_case in test_cases {
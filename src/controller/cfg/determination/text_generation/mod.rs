@@ -2,16 +2,26 @@
 mod tests;
 
 use crate::{
+    agent::{PublicIdentifier, provider::ReasoningEffort},
     controller::ControllerType,
-    entity::roomconfig::{
-        TextGenerationAutoUsage, TextGenerationPrefixRequirementType,
-        TextGenerationSenderContextMode,
+    entity::{
+        globalconfig::PromptDefinition,
+        roomconfig::{
+            TextGenerationAutoUsage, TextGenerationContextManagementStrategy,
+            TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
+        },
     },
     strings,
+    utils::time::parse_utc_offset,
 };
 
 use super::super::controller_type::ConfigTextGenerationSettingRelatedControllerType;
 
+const MAX_RESPONSE_TOKENS_OVERRIDE_MIN: u32 = 1;
+
+/// Some providers' request types hold the maximum number of response tokens as an `i32`.
+const MAX_RESPONSE_TOKENS_OVERRIDE_MAX: u32 = i32::MAX as u32;
+
 pub(super) fn determine(
     text: &str,
 ) -> Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType> {
@@ -55,6 +65,82 @@ pub(super) fn determine(
         );
     }
 
+    if let Some(remaining_text) = text.strip_prefix("context-management-strategy") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "context-management-strategy",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetContextManagementStrategy);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-context-management-strategy") {
+        let value_string = value_string.trim().to_owned();
+        let value_choice = if value_string.is_empty() {
+            None
+        } else {
+            let value_choice =
+                TextGenerationContextManagementStrategy::from_str(&value_string.to_lowercase());
+
+            if value_choice.is_none() {
+                return Err(ControllerType::Error(
+                    strings::cfg::configuration_value_unrecognized(&value_string).to_owned(),
+                ));
+            }
+
+            value_choice
+        };
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetContextManagementStrategy(
+                value_choice,
+            ),
+        );
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("context-summary-agent") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "context-summary-agent",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetContextSummaryAgent);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-context-summary-agent") {
+        let value_string = value_string.trim();
+
+        let value_opt = if value_string.is_empty() {
+            None
+        } else {
+            let Some(agent_identifier) = PublicIdentifier::from_str(value_string) else {
+                return Err(ControllerType::Error(
+                    strings::agent::invalid_id_generic().to_owned(),
+                ));
+            };
+
+            Some(agent_identifier)
+        };
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetContextSummaryAgent(value_opt),
+        );
+    }
+
     if let Some(remaining_text) = text.strip_prefix("thinking-notice-enabled") {
         let remaining_text = remaining_text.trim();
 
@@ -93,6 +179,82 @@ pub(super) fn determine(
         );
     }
 
+    if let Some(remaining_text) = text.strip_prefix("streaming-enabled") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "streaming-enabled",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetStreamingEnabled);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-streaming-enabled") {
+        let value_string = value_string.trim().to_owned();
+        let value_opt = if value_string.is_empty() {
+            None
+        } else {
+            let value_string_lowercase = value_string.to_lowercase();
+            Some(match value_string_lowercase.as_str() {
+                "true" => true,
+                "false" => false,
+                _ => {
+                    return Err(ControllerType::Error(
+                        strings::cfg::configuration_value_unrecognized(&value_string).to_owned(),
+                    ));
+                }
+            })
+        };
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetStreamingEnabled(value_opt),
+        );
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("regenerate-on-edit-enabled") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "regenerate-on-edit-enabled",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetRegenerateOnEditEnabled);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-regenerate-on-edit-enabled") {
+        let value_string = value_string.trim().to_owned();
+        let value_opt = if value_string.is_empty() {
+            None
+        } else {
+            let value_string_lowercase = value_string.to_lowercase();
+            Some(match value_string_lowercase.as_str() {
+                "true" => true,
+                "false" => false,
+                _ => {
+                    return Err(ControllerType::Error(
+                        strings::cfg::configuration_value_unrecognized(&value_string).to_owned(),
+                    ));
+                }
+            })
+        };
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetRegenerateOnEditEnabled(value_opt),
+        );
+    }
+
     if let Some(remaining_text) = text.strip_prefix("prefix-requirement-type") {
         let remaining_text = remaining_text.trim();
 
@@ -198,6 +360,72 @@ pub(super) fn determine(
         );
     }
 
+    if let Some(remaining_text) = text.strip_prefix("prompt-name") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "prompt-name",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetPromptName);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-prompt-name") {
+        let value_string = value_string.trim().to_owned();
+
+        if value_string.is_empty() {
+            return Ok(ConfigTextGenerationSettingRelatedControllerType::SetPromptName(None));
+        }
+
+        if let Err(err) = PromptDefinition::validate_name(&value_string) {
+            return Err(ControllerType::Error(
+                strings::prompt::invalid_name_validation_error(err),
+            ));
+        }
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetPromptName(Some(value_string)),
+        );
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("timezone") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text("timezone", remaining_text)
+                    .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetTimezone);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-timezone") {
+        let value_string = value_string.trim().to_owned();
+
+        if value_string.is_empty() {
+            return Ok(ConfigTextGenerationSettingRelatedControllerType::SetTimezone(None));
+        }
+
+        // Offsets are stored normalized (e.g. `+0200` becomes `+02:00`)
+        let Some(offset) = parse_utc_offset(&value_string) else {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_value_unrecognized(&value_string).to_owned(),
+            ));
+        };
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetTimezone(Some(offset.to_string())),
+        );
+    }
+
     if let Some(remaining_text) = text.strip_prefix("temperature-override") {
         let remaining_text = remaining_text.trim();
 
@@ -238,6 +466,132 @@ pub(super) fn determine(
         );
     }
 
+    if let Some(remaining_text) = text.strip_prefix("model-override") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "model-override",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetModelOverride);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-model-override") {
+        let value_string = value_string.trim().to_owned();
+
+        if value_string.is_empty() {
+            return Ok(ConfigTextGenerationSettingRelatedControllerType::SetModelOverride(None));
+        }
+
+        if value_string.contains(char::is_whitespace) {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_value_unrecognized(&value_string).to_owned(),
+            ));
+        }
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetModelOverride(Some(value_string)),
+        );
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("max-response-tokens-override") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "max-response-tokens-override",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetMaxResponseTokensOverride);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-max-response-tokens-override") {
+        let value_string = value_string.trim().to_owned();
+
+        if value_string.is_empty() {
+            return Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetMaxResponseTokensOverride(
+                    None,
+                ),
+            );
+        }
+
+        let Ok(value_u32) = value_string.parse::<u32>() else {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_value_not_whole_number(&value_string).to_owned(),
+            ));
+        };
+
+        if !(MAX_RESPONSE_TOKENS_OVERRIDE_MIN..=MAX_RESPONSE_TOKENS_OVERRIDE_MAX)
+            .contains(&value_u32)
+        {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_value_out_of_range(
+                    &value_string,
+                    MAX_RESPONSE_TOKENS_OVERRIDE_MIN,
+                    MAX_RESPONSE_TOKENS_OVERRIDE_MAX,
+                ),
+            ));
+        }
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetMaxResponseTokensOverride(Some(
+                value_u32,
+            )),
+        );
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("reasoning-effort-override") {
+        let remaining_text = remaining_text.trim();
+
+        if !remaining_text.is_empty() {
+            return Err(ControllerType::Error(
+                strings::cfg::configuration_getter_used_with_extra_text(
+                    "reasoning-effort-override",
+                    remaining_text,
+                )
+                .to_owned(),
+            ));
+        }
+
+        return Ok(ConfigTextGenerationSettingRelatedControllerType::GetReasoningEffortOverride);
+    }
+
+    if let Some(value_string) = text.strip_prefix("set-reasoning-effort-override") {
+        let value_string = value_string.trim().to_owned();
+
+        let value_choice = if value_string.is_empty() {
+            None
+        } else {
+            let value_choice = ReasoningEffort::from_str(&value_string.to_lowercase());
+
+            if value_choice.is_none() {
+                return Err(ControllerType::Error(
+                    strings::cfg::configuration_value_unrecognized(&value_string).to_owned(),
+                ));
+            }
+
+            value_choice
+        };
+
+        return Ok(
+            ConfigTextGenerationSettingRelatedControllerType::SetReasoningEffortOverride(
+                value_choice,
+            ),
+        );
+    }
+
     if let Some(remaining_text) = text.strip_prefix("sender-context-mode") {
         let remaining_text = remaining_text.trim();
 
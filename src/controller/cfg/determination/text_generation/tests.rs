@@ -90,6 +90,244 @@ fn determine_controller_context_management() {
     }
 }
 
+#[test]
+fn determine_controller_context_management_strategy() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+    use crate::entity::roomconfig::TextGenerationContextManagementStrategy;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "context-management-strategy getter ok",
+            input: "context-management-strategy",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::GetContextManagementStrategy,
+            ),
+        },
+        TestCase {
+            name: "context-management-strategy getter extra args",
+            input: "context-management-strategy some values here",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text(
+                    "context-management-strategy",
+                    "some values here",
+                ),
+            )),
+        },
+        TestCase {
+            name: "context-management-strategy setter",
+            input: "set-context-management-strategy Summarize",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetContextManagementStrategy(
+                    Some(TextGenerationContextManagementStrategy::Summarize),
+                ),
+            ),
+        },
+        TestCase {
+            name: "context-management-strategy setter invalid",
+            input: "set-context-management-strategy forget",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("forget"),
+            )),
+        },
+        TestCase {
+            name: "context-management-strategy unsetter",
+            input: "set-context-management-strategy",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetContextManagementStrategy(
+                    None,
+                ),
+            ),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_context_summary_agent() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+    use crate::agent::PublicIdentifier;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "context-summary-agent getter ok",
+            input: "context-summary-agent",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::GetContextSummaryAgent),
+        },
+        TestCase {
+            name: "context-summary-agent getter extra args",
+            input: "context-summary-agent some values here",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text(
+                    "context-summary-agent",
+                    "some values here",
+                ),
+            )),
+        },
+        TestCase {
+            name: "context-summary-agent setter",
+            input: "set-context-summary-agent global/cheap",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetContextSummaryAgent(Some(
+                    PublicIdentifier::DynamicGlobal("cheap".to_owned()),
+                )),
+            ),
+        },
+        TestCase {
+            name: "context-summary-agent setter invalid",
+            input: "set-context-summary-agent not an/agent/id",
+            expected: Err(ControllerType::Error(
+                crate::strings::agent::invalid_id_generic(),
+            )),
+        },
+        TestCase {
+            name: "context-summary-agent unsetter",
+            input: "set-context-summary-agent",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetContextSummaryAgent(None),
+            ),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_streaming() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "streaming-enabled getter ok",
+            input: "streaming-enabled",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::GetStreamingEnabled),
+        },
+        TestCase {
+            name: "streaming-enabled getter extra args",
+            input: "streaming-enabled some values here",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text(
+                    "streaming-enabled",
+                    "some values here",
+                ),
+            )),
+        },
+        TestCase {
+            name: "streaming-enabled setter",
+            input: "set-streaming-enabled false",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetStreamingEnabled(Some(false)),
+            ),
+        },
+        TestCase {
+            name: "streaming-enabled setter non-bool",
+            input: "set-streaming-enabled yes",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("yes"),
+            )),
+        },
+        TestCase {
+            name: "streaming-enabled unsetter",
+            input: "set-streaming-enabled",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetStreamingEnabled(None),
+            ),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_regenerate_on_edit() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "regenerate-on-edit-enabled getter ok",
+            input: "regenerate-on-edit-enabled",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::GetRegenerateOnEditEnabled,
+            ),
+        },
+        TestCase {
+            name: "regenerate-on-edit-enabled getter extra args",
+            input: "regenerate-on-edit-enabled true",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text(
+                    "regenerate-on-edit-enabled",
+                    "true",
+                ),
+            )),
+        },
+        TestCase {
+            name: "regenerate-on-edit-enabled setter",
+            input: "set-regenerate-on-edit-enabled TRUE",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetRegenerateOnEditEnabled(Some(
+                    true,
+                )),
+            ),
+        },
+        TestCase {
+            name: "regenerate-on-edit-enabled setter non-bool",
+            input: "set-regenerate-on-edit-enabled yes",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("yes"),
+            )),
+        },
+        TestCase {
+            name: "regenerate-on-edit-enabled unsetter",
+            input: "set-regenerate-on-edit-enabled",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetRegenerateOnEditEnabled(None),
+            ),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
 #[test]
 fn determine_controller_sender_context() {
     use super::ConfigTextGenerationSettingRelatedControllerType;
@@ -343,6 +581,111 @@ fn determine_controller_prompt_override() {
     }
 }
 
+#[test]
+fn determine_controller_prompt_name() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "prompt-name getter ok",
+            input: "prompt-name",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::GetPromptName),
+        },
+        TestCase {
+            name: "prompt-name getter extra args",
+            input: "prompt-name code-reviewer",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_getter_used_with_extra_text(
+                    "prompt-name",
+                    "code-reviewer",
+                ),
+            )),
+        },
+        TestCase {
+            name: "prompt-name setter ok",
+            input: "set-prompt-name code-reviewer",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetPromptName(Some(
+                    "code-reviewer".to_owned(),
+                )),
+            ),
+        },
+        TestCase {
+            name: "prompt-name setter with an invalid name",
+            input: "set-prompt-name code reviewer",
+            expected: Err(ControllerType::Error(
+                crate::strings::prompt::invalid_name_validation_error(
+                    crate::entity::globalconfig::PromptDefinition::validate_name("code reviewer")
+                        .unwrap_err(),
+                ),
+            )),
+        },
+        TestCase {
+            name: "prompt-name unsetter",
+            input: "set-prompt-name",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::SetPromptName(None)),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_timezone() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "timezone getter ok",
+            input: "timezone",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::GetTimezone),
+        },
+        TestCase {
+            name: "timezone setter normalizes the offset",
+            input: "set-timezone +0530",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetTimezone(Some(
+                    "+05:30".to_owned(),
+                )),
+            ),
+        },
+        TestCase {
+            name: "timezone setter with an invalid offset",
+            input: "set-timezone Europe/Sofia",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("Europe/Sofia"),
+            )),
+        },
+        TestCase {
+            name: "timezone unsetter",
+            input: "set-timezone",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::SetTimezone(None)),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
 #[test]
 fn determine_controller_temperature_override() {
     use super::ConfigTextGenerationSettingRelatedControllerType;
@@ -398,3 +741,175 @@ fn determine_controller_temperature_override() {
         assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
     }
 }
+
+#[test]
+fn determine_controller_model_override() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "model-override getter ok",
+            input: "model-override",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::GetModelOverride),
+        },
+        TestCase {
+            name: "model-override setter",
+            input: "set-model-override gpt-5.4-mini",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetModelOverride(Some(
+                    "gpt-5.4-mini".to_owned(),
+                )),
+            ),
+        },
+        TestCase {
+            name: "model-override setter with spaces",
+            input: "set-model-override gpt 5.4",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("gpt 5.4"),
+            )),
+        },
+        TestCase {
+            name: "model-override unsetter",
+            input: "set-model-override",
+            expected: Ok(ConfigTextGenerationSettingRelatedControllerType::SetModelOverride(None)),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_max_response_tokens_override() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "max-response-tokens-override getter ok",
+            input: "max-response-tokens-override",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::GetMaxResponseTokensOverride,
+            ),
+        },
+        TestCase {
+            name: "max-response-tokens-override setter",
+            input: "set-max-response-tokens-override 2048",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetMaxResponseTokensOverride(
+                    Some(2048),
+                ),
+            ),
+        },
+        TestCase {
+            name: "max-response-tokens-override setter with invalid value",
+            input: "set-max-response-tokens-override 2.5",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_not_whole_number("2.5"),
+            )),
+        },
+        TestCase {
+            name: "max-response-tokens-override setter with zero",
+            input: "set-max-response-tokens-override 0",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_out_of_range("0", 1, 2147483647),
+            )),
+        },
+        TestCase {
+            name: "max-response-tokens-override setter with a value too large for providers",
+            input: "set-max-response-tokens-override 3000000000",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_out_of_range("3000000000", 1, 2147483647),
+            )),
+        },
+        TestCase {
+            name: "max-response-tokens-override setter with the largest allowed value",
+            input: "set-max-response-tokens-override 2147483647",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetMaxResponseTokensOverride(
+                    Some(2147483647),
+                ),
+            ),
+        },
+        TestCase {
+            name: "max-response-tokens-override unsetter",
+            input: "set-max-response-tokens-override",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetMaxResponseTokensOverride(
+                    None,
+                ),
+            ),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_controller_reasoning_effort_override() {
+    use super::ConfigTextGenerationSettingRelatedControllerType;
+    use super::ControllerType;
+    use crate::agent::provider::ReasoningEffort;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: Result<ConfigTextGenerationSettingRelatedControllerType, ControllerType>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "reasoning-effort-override getter ok",
+            input: "reasoning-effort-override",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::GetReasoningEffortOverride,
+            ),
+        },
+        TestCase {
+            name: "reasoning-effort-override setter",
+            input: "set-reasoning-effort-override High",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetReasoningEffortOverride(Some(
+                    ReasoningEffort::High,
+                )),
+            ),
+        },
+        TestCase {
+            name: "reasoning-effort-override setter with unknown value",
+            input: "set-reasoning-effort-override extreme",
+            expected: Err(ControllerType::Error(
+                crate::strings::cfg::configuration_value_unrecognized("extreme"),
+            )),
+        },
+        TestCase {
+            name: "reasoning-effort-override unsetter",
+            input: "set-reasoning-effort-override",
+            expected: Ok(
+                ConfigTextGenerationSettingRelatedControllerType::SetReasoningEffortOverride(None),
+            ),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
@@ -6,6 +6,8 @@ use super::controller_type::{
     ConfigControllerType, ConfigSettingRelatedControllerType, SettingsStorageSource,
 };
 
+mod quota;
+mod reactions;
 mod speech_to_text;
 mod text_generation;
 mod text_to_speech;
@@ -120,5 +122,25 @@ async fn dispatch_config_related_handler(
             )
             .await
         }
+        ConfigSettingRelatedControllerType::Quota(controller_type) => {
+            quota::dispatch(
+                controller_type,
+                message_context,
+                bot,
+                room_settings,
+                config_type,
+            )
+            .await
+        }
+        ConfigSettingRelatedControllerType::Reactions(controller_type) => {
+            reactions::dispatch(
+                controller_type,
+                message_context,
+                bot,
+                room_settings,
+                config_type,
+            )
+            .await
+        }
     }
 }
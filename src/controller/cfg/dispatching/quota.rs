@@ -0,0 +1,83 @@
+use mxlink::MessageResponseType;
+
+use crate::entity::roomconfig::RoomSettings;
+use crate::{Bot, entity::MessageContext, strings};
+
+use super::super::controller_type::{
+    ConfigQuotaSettingRelatedControllerType, SettingsStorageSource,
+};
+
+use super::super::common::generic_setting::handle_get as setting_get;
+
+use super::super::global_config::generic_setting::handle_set as global_setting_set;
+
+use super::super::room_config::generic_setting::handle_set as room_setting_set;
+
+pub(super) async fn dispatch(
+    handler: &ConfigQuotaSettingRelatedControllerType,
+    message_context: &MessageContext,
+    bot: &Bot,
+    room_settings: &RoomSettings,
+    config_type: &SettingsStorageSource,
+) -> anyhow::Result<()> {
+    // Anyone may change the room configuration, so if it weren't for this check,
+    // anyone could lift a quota by overriding it in the room.
+    if let ConfigQuotaSettingRelatedControllerType::SetLimit(_, _)
+    | ConfigQuotaSettingRelatedControllerType::SetCostPerMillionTokens(_) = handler
+        && !message_context.sender_can_manage_global_config()
+    {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::quota::no_permissions_to_change_quotas(),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+        return Ok(());
+    }
+
+    match handler {
+        ConfigQuotaSettingRelatedControllerType::GetLimit(limit) => {
+            let value = limit.get(&room_settings.quota);
+            setting_get::<f64>(bot, message_context, &value).await
+        }
+        ConfigQuotaSettingRelatedControllerType::SetLimit(limit, value) => {
+            let limit = *limit;
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                limit.set(&mut room_settings.quota, value);
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<f64>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<f64>(bot, message_context, &value, setter_callback).await
+                }
+            }
+        }
+
+        ConfigQuotaSettingRelatedControllerType::GetCostPerMillionTokens => {
+            let value = &room_settings.quota.cost_per_million_tokens;
+            setting_get::<f64>(bot, message_context, value).await
+        }
+        ConfigQuotaSettingRelatedControllerType::SetCostPerMillionTokens(value) => {
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.quota.cost_per_million_tokens = value;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<f64>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<f64>(bot, message_context, &value, setter_callback).await
+                }
+            }
+        }
+    }
+}
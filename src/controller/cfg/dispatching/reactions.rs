@@ -0,0 +1,82 @@
+use mxlink::MessageResponseType;
+
+use crate::entity::roomconfig::{ReactionAction, RoomSettings};
+use crate::{Bot, entity::MessageContext, strings};
+
+use super::super::controller_type::{
+    ConfigReactionsSettingRelatedControllerType, SettingsStorageSource,
+};
+
+use super::super::common::generic_setting::handle_get as setting_get;
+
+use super::super::global_config::generic_setting::handle_set as global_setting_set;
+
+use super::super::room_config::generic_setting::handle_set as room_setting_set;
+
+pub(super) async fn dispatch(
+    handler: &ConfigReactionsSettingRelatedControllerType,
+    message_context: &MessageContext,
+    bot: &Bot,
+    room_settings: &RoomSettings,
+    config_type: &SettingsStorageSource,
+) -> anyhow::Result<()> {
+    match handler {
+        ConfigReactionsSettingRelatedControllerType::GetAction(key) => {
+            let value = room_settings.reactions.get(key).cloned();
+            setting_get::<ReactionAction>(bot, message_context, &value).await
+        }
+        ConfigReactionsSettingRelatedControllerType::SetAction(key, value) => {
+            // Referencing a prompt which is not (yet) in the library is most likely a typo.
+            if let Some(ReactionAction::Prompt(name)) = value
+                && message_context
+                    .global_config()
+                    .prompt_by_name(name)
+                    .is_none()
+            {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &strings::prompt::prompt_with_given_name_missing(
+                            name,
+                            bot.command_prefix(),
+                        ),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+
+            let key = key.to_owned();
+            let value = value.to_owned();
+
+            let value_setter = value.clone();
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.reactions.set(&key, value_setter);
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<ReactionAction>(
+                        bot,
+                        message_context,
+                        &value,
+                        setter_callback,
+                    )
+                    .await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<ReactionAction>(
+                        bot,
+                        message_context,
+                        &value,
+                        setter_callback,
+                    )
+                    .await
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use mxlink::MessageResponseType;
+
+use crate::agent::provider::{ReasoningEffort, TextGenerationPromptVariables};
 use crate::entity::roomconfig::{
-    RoomSettings, TextGenerationAutoUsage, TextGenerationPrefixRequirementType,
-    TextGenerationSenderContextMode,
+    RoomSettings, TextGenerationAutoUsage, TextGenerationContextManagementStrategy,
+    TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
 };
-use crate::{Bot, entity::MessageContext};
+use crate::{Bot, entity::MessageContext, strings};
 
 use super::super::controller_type::{
     ConfigTextGenerationSettingRelatedControllerType, SettingsStorageSource,
@@ -43,6 +46,89 @@ pub(super) async fn dispatch(
             }
         }
 
+        ConfigTextGenerationSettingRelatedControllerType::GetContextManagementStrategy => {
+            let value = &room_settings.text_generation.context_management_strategy;
+            setting_get::<TextGenerationContextManagementStrategy>(bot, message_context, value)
+                .await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetContextManagementStrategy(value) => {
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.context_management_strategy = value;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<TextGenerationContextManagementStrategy>(
+                        bot,
+                        message_context,
+                        &value,
+                        setter_callback,
+                    )
+                    .await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<TextGenerationContextManagementStrategy>(
+                        bot,
+                        message_context,
+                        &value,
+                        setter_callback,
+                    )
+                    .await
+                }
+            }
+        }
+
+        ConfigTextGenerationSettingRelatedControllerType::GetContextSummaryAgent => {
+            let value = &room_settings.text_generation.context_summary_agent;
+            setting_get::<String>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetContextSummaryAgent(value) => {
+            if let Some(agent_identifier) = value {
+                let agent_exists = bot
+                    .agent_manager()
+                    .available_room_agents_by_room_config_context(
+                        message_context.room_config_context(),
+                    )
+                    .iter()
+                    .any(|agent| agent.identifier() == agent_identifier);
+
+                if !agent_exists {
+                    bot.messaging()
+                        .send_error_markdown_no_fail(
+                            message_context.room(),
+                            &strings::agent::agent_with_given_identifier_missing(agent_identifier),
+                            MessageResponseType::Reply(
+                                message_context.thread_info().root_event_id.clone(),
+                            ),
+                        )
+                        .await;
+
+                    return Ok(());
+                }
+            }
+
+            let value = value
+                .as_ref()
+                .map(|agent_identifier| agent_identifier.as_string());
+
+            let value_setter = value.clone();
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.context_summary_agent = value_setter;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<String>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<String>(bot, message_context, &value, setter_callback)
+                        .await
+                }
+            }
+        }
+
         ConfigTextGenerationSettingRelatedControllerType::GetThinkingNoticeEnabled => {
             let value = &room_settings.text_generation.thinking_notice_enabled;
             setting_get::<bool>(bot, message_context, value).await
@@ -64,6 +150,48 @@ pub(super) async fn dispatch(
             }
         }
 
+        ConfigTextGenerationSettingRelatedControllerType::GetStreamingEnabled => {
+            let value = &room_settings.text_generation.streaming_enabled;
+            setting_get::<bool>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetStreamingEnabled(value) => {
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.streaming_enabled = value;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<bool>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<bool>(bot, message_context, &value, setter_callback).await
+                }
+            }
+        }
+
+        ConfigTextGenerationSettingRelatedControllerType::GetRegenerateOnEditEnabled => {
+            let value = &room_settings.text_generation.regenerate_on_edit_enabled;
+            setting_get::<bool>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetRegenerateOnEditEnabled(value) => {
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.regenerate_on_edit_enabled = value;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<bool>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<bool>(bot, message_context, &value, setter_callback).await
+                }
+            }
+        }
+
         ConfigTextGenerationSettingRelatedControllerType::GetPrefixRequirementType => {
             let value = &room_settings.text_generation.prefix_requirement_type;
             setting_get::<TextGenerationPrefixRequirementType>(bot, message_context, value).await
@@ -135,6 +263,22 @@ pub(super) async fn dispatch(
             setting_get::<String>(bot, message_context, value).await
         }
         ConfigTextGenerationSettingRelatedControllerType::SetPromptOverride(value) => {
+            if let Some(value) = value
+                && let Err(err) = TextGenerationPromptVariables::validate(value)
+            {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &strings::prompt::template_invalid(&err),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+
             let value = value.to_owned();
 
             let value_setter = value.clone();
@@ -153,6 +297,75 @@ pub(super) async fn dispatch(
             }
         }
 
+        ConfigTextGenerationSettingRelatedControllerType::GetPromptName => {
+            let value = &room_settings.text_generation.prompt_name;
+            setting_get::<String>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetPromptName(value) => {
+            // Referencing a prompt which is not (yet) in the library is most likely a typo.
+            if let Some(name) = value
+                && message_context
+                    .global_config()
+                    .prompt_by_name(name)
+                    .is_none()
+            {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &strings::prompt::prompt_with_given_name_missing(
+                            name,
+                            bot.command_prefix(),
+                        ),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+
+            let value = value.to_owned();
+
+            let value_setter = value.clone();
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.prompt_name = value_setter;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<String>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<String>(bot, message_context, &value, setter_callback)
+                        .await
+                }
+            }
+        }
+
+        ConfigTextGenerationSettingRelatedControllerType::GetTimezone => {
+            let value = &room_settings.text_generation.timezone;
+            setting_get::<String>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetTimezone(value) => {
+            let value = value.to_owned();
+
+            let value_setter = value.clone();
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.timezone = value_setter;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<String>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<String>(bot, message_context, &value, setter_callback)
+                        .await
+                }
+            }
+        }
+
         ConfigTextGenerationSettingRelatedControllerType::GetTemperatureOverride => {
             let value = &room_settings.text_generation.temperature_override;
             setting_get::<f32>(bot, message_context, value).await
@@ -174,6 +387,83 @@ pub(super) async fn dispatch(
             }
         }
 
+        ConfigTextGenerationSettingRelatedControllerType::GetModelOverride => {
+            let value = &room_settings.text_generation.model_override;
+            setting_get::<String>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetModelOverride(value) => {
+            let value = value.to_owned();
+
+            let value_setter = value.clone();
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.model_override = value_setter;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<String>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<String>(bot, message_context, &value, setter_callback)
+                        .await
+                }
+            }
+        }
+
+        ConfigTextGenerationSettingRelatedControllerType::GetMaxResponseTokensOverride => {
+            let value = &room_settings.text_generation.max_response_tokens_override;
+            setting_get::<u32>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetMaxResponseTokensOverride(value) => {
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.max_response_tokens_override = value;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<u32>(bot, message_context, &value, setter_callback).await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<u32>(bot, message_context, &value, setter_callback).await
+                }
+            }
+        }
+
+        ConfigTextGenerationSettingRelatedControllerType::GetReasoningEffortOverride => {
+            let value = &room_settings.text_generation.reasoning_effort_override;
+            setting_get::<ReasoningEffort>(bot, message_context, value).await
+        }
+        ConfigTextGenerationSettingRelatedControllerType::SetReasoningEffortOverride(value) => {
+            let value = value.to_owned();
+
+            let setter_callback = Box::new(move |room_settings: &mut RoomSettings| {
+                room_settings.text_generation.reasoning_effort_override = value;
+            });
+
+            match config_type {
+                SettingsStorageSource::Room => {
+                    room_setting_set::<ReasoningEffort>(
+                        bot,
+                        message_context,
+                        &value,
+                        setter_callback,
+                    )
+                    .await
+                }
+                SettingsStorageSource::Global => {
+                    global_setting_set::<ReasoningEffort>(
+                        bot,
+                        message_context,
+                        &value,
+                        setter_callback,
+                    )
+                    .await
+                }
+            }
+        }
+
         ConfigTextGenerationSettingRelatedControllerType::GetSenderContextMode => {
             let value = &room_settings.text_generation.sender_context_mode;
             setting_get::<TextGenerationSenderContextMode>(bot, message_context, value).await
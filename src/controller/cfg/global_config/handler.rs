@@ -3,7 +3,11 @@ use mxlink::MessageResponseType;
 use crate::{
     Bot,
     agent::{AgentPurpose, PublicIdentifier},
-    entity::{MessageContext, globalconfig::GlobalConfigurationManager},
+    entity::{
+        MessageContext,
+        globalconfig::GlobalConfigurationManager,
+        roomconfig::{join_agent_chain, split_agent_chain},
+    },
     strings,
 };
 
@@ -30,53 +34,54 @@ pub async fn handle_get(
         return Ok(());
     };
 
-    let agent_identifier = match PublicIdentifier::from_str(agent_id.as_str()) {
-        Some(agent_identifier) => agent_identifier,
-        None => {
-            bot.messaging()
-                .send_error_markdown_no_fail(
-                    message_context.room(),
-                    &strings::agent::invalid_id_generic(),
-                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
-                )
-                .await;
-
-            return Ok(());
-        }
-    };
-
-    let agent_exists = bot
-        .agent_manager()
-        .available_room_agents_by_room_config_context(message_context.room_config_context())
+    let agent_identifiers: Option<Vec<PublicIdentifier>> = split_agent_chain(&agent_id)
         .iter()
-        .any(|agent| *agent.identifier() == agent_identifier);
+        .map(|agent_id| PublicIdentifier::from_str(agent_id))
+        .collect();
 
-    if agent_exists {
+    let Some(agent_identifiers) = agent_identifiers else {
         bot.messaging()
-            .send_text_markdown_no_fail(
-                message_context.room(),
-                strings::global_config::configured_to_use_agent_for_purpose(
-                    &agent_identifier,
-                    purpose,
-                ),
-                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
-            )
-            .await;
-    } else {
-        bot.messaging()
-            .send_text_markdown_no_fail(
+            .send_error_markdown_no_fail(
                 message_context.room(),
-                strings::global_config::configures_agent_for_purpose_but_does_not_exist(
-                    &agent_identifier,
-                    purpose,
-                ),
+                &strings::agent::invalid_id_generic(),
                 MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
             )
             .await;
 
         return Ok(());
+    };
+
+    let available_agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    let mut message =
+        strings::global_config::configured_to_use_agent_for_purpose(&agent_identifiers, purpose);
+
+    for agent_identifier in &agent_identifiers {
+        let agent_exists = available_agents
+            .iter()
+            .any(|agent| agent.identifier() == agent_identifier);
+
+        if !agent_exists {
+            message.push_str("\n\n");
+            message.push_str(
+                &strings::global_config::configures_agent_for_purpose_but_does_not_exist(
+                    agent_identifier,
+                    purpose,
+                ),
+            );
+        }
     }
 
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
     Ok(())
 }
 
@@ -85,9 +90,13 @@ pub async fn handle_set(
     global_config_manager: &tokio::sync::Mutex<GlobalConfigurationManager>,
     message_context: &MessageContext,
     purpose: AgentPurpose,
-    agent_identifier: &Option<PublicIdentifier>,
+    agent_identifiers: &[PublicIdentifier],
 ) -> anyhow::Result<()> {
-    if let Some(agent_identifier) = agent_identifier {
+    let available_agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    for agent_identifier in agent_identifiers {
         let is_allowed = match &agent_identifier {
             PublicIdentifier::Static(_) => true,
             PublicIdentifier::DynamicGlobal(_) => true,
@@ -108,9 +117,7 @@ pub async fn handle_set(
             return Ok(());
         }
 
-        let agent_exists = bot
-            .agent_manager()
-            .available_room_agents_by_room_config_context(message_context.room_config_context())
+        let agent_exists = available_agents
             .iter()
             .any(|agent| agent.identifier() == agent_identifier);
 
@@ -127,9 +134,12 @@ pub async fn handle_set(
         }
     }
 
-    let agent_id = agent_identifier
-        .as_ref()
-        .map(|agent_identifier| agent_identifier.as_string());
+    let agent_id = join_agent_chain(
+        &agent_identifiers
+            .iter()
+            .map(|agent_identifier| agent_identifier.as_string())
+            .collect::<Vec<_>>(),
+    );
 
     let mut global_config = global_config_manager.lock().await.get_or_create().await?;
 
@@ -144,11 +154,10 @@ pub async fn handle_set(
         .persist(&global_config)
         .await?;
 
-    let message = match agent_identifier {
-        Some(agent_identifier) => {
-            strings::global_config::reconfigured_to_use_agent_for_purpose(agent_identifier, purpose)
-        }
-        None => strings::global_config::reconfigured_to_not_specify_agent_for_purpose(purpose),
+    let message = if agent_identifiers.is_empty() {
+        strings::global_config::reconfigured_to_not_specify_agent_for_purpose(purpose)
+    } else {
+        strings::global_config::reconfigured_to_use_agent_for_purpose(agent_identifiers, purpose)
     };
 
     bot.messaging()
@@ -2,11 +2,13 @@ use mxlink::MessageResponseType;
 
 use crate::{
     Bot,
+    agent::provider::ReasoningEffort,
     entity::{
         MessageContext,
         roomconfig::{
-            SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
-            TextGenerationAutoUsage, TextGenerationPrefixRequirementType,
+            QuotaLimit, ReactionAction, SpeechToTextFlowType,
+            SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages, TextGenerationAutoUsage,
+            TextGenerationContextManagementStrategy, TextGenerationPrefixRequirementType,
             TextGenerationSenderContextMode, TextToSpeechBotMessagesFlowType,
             TextToSpeechUserMessagesFlowType,
         },
@@ -43,6 +45,14 @@ pub async fn handle(bot: &Bot, message_context: &MessageContext) -> anyhow::Resu
     message.push_str("\n---\n");
     message.push_str(&build_section_image_generation());
 
+    message.push_str("\n\n");
+    message.push_str("\n---\n");
+    message.push_str(&build_section_quota(bot.command_prefix()));
+
+    message.push_str("\n\n");
+    message.push_str("\n---\n");
+    message.push_str(&build_section_reactions(bot.command_prefix()));
+
     bot.messaging()
         .send_text_markdown_no_fail(
             message_context.room(),
@@ -231,6 +241,80 @@ fn build_section_text_generation(command_prefix: &str, bot_username: &str) -> St
     ));
     message.push_str("\n\n");
 
+    // Context Management Strategy
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_context_management_strategy_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_context_management_strategy_intro());
+    message.push('\n');
+    message.push_str(
+        &strings::help::cfg::the_following_configuration_values_are_recognized(
+            TextGenerationContextManagementStrategy::choices(),
+        ),
+    );
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(
+            command_prefix,
+            "text-generation context-management-strategy"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-context-management-strategy VALUE"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-context-management-strategy"
+        )
+    ));
+    message.push_str("\n\n");
+
+    // Context Summary Agent
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_context_summary_agent_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_context_summary_agent_intro());
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(
+            command_prefix,
+            "text-generation context-summary-agent"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-context-summary-agent AGENT_ID"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-context-summary-agent"
+        )
+    ));
+    message.push_str("\n\n");
+
     // Thinking Notice
 
     message.push_str(&format!(
@@ -269,6 +353,82 @@ fn build_section_text_generation(command_prefix: &str, bot_username: &str) -> St
     ));
     message.push_str("\n\n");
 
+    // Streaming
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_streaming_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_streaming_intro());
+    message.push('\n');
+    message.push_str(
+        &strings::help::cfg::the_following_configuration_values_are_recognized(vec![true, false]),
+    );
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(
+            command_prefix,
+            "text-generation streaming-enabled"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-streaming-enabled VALUE"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-streaming-enabled"
+        )
+    ));
+    message.push_str("\n\n");
+
+    // Regenerate on Edit
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_regenerate_on_edit_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_regenerate_on_edit_intro());
+    message.push('\n');
+    message.push_str(
+        &strings::help::cfg::the_following_configuration_values_are_recognized(vec![true, false]),
+    );
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(
+            command_prefix,
+            "text-generation regenerate-on-edit-enabled"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-regenerate-on-edit-enabled VALUE"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-regenerate-on-edit-enabled"
+        )
+    ));
+    message.push_str("\n\n");
+
     // Sender Context
 
     message.push_str(&format!(
@@ -340,6 +500,67 @@ fn build_section_text_generation(command_prefix: &str, bot_username: &str) -> St
     ));
     message.push_str("\n\n");
 
+    // Timezone
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_timezone_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_timezone_intro());
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(command_prefix, "text-generation timezone")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-timezone +02:00"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(command_prefix, "text-generation set-timezone")
+    ));
+    message.push_str("\n\n");
+
+    // Prompt name
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_prompt_name_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_prompt_name_intro(
+        command_prefix,
+    ));
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(command_prefix, "text-generation prompt-name")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-prompt-name NAME"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-prompt-name"
+        )
+    ));
+    message.push_str("\n\n");
+
     // Speed override
 
     message.push_str(&format!(
@@ -372,6 +593,111 @@ fn build_section_text_generation(command_prefix: &str, bot_username: &str) -> St
             "text-generation set-temperature-override"
         )
     ));
+    message.push_str("\n\n");
+
+    // Model override
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_model_override_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_model_override_intro());
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(command_prefix, "text-generation model-override")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-model-override MODEL_ID"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-model-override"
+        )
+    ));
+    message.push_str("\n\n");
+
+    // Max response tokens override
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_max_response_tokens_override_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_max_response_tokens_override_intro());
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(
+            command_prefix,
+            "text-generation max-response-tokens-override"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-max-response-tokens-override VALUE"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-max-response-tokens-override"
+        )
+    ));
+    message.push_str("\n\n");
+
+    // Reasoning effort override
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::text_generation_reasoning_effort_override_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::text_generation_reasoning_effort_override_intro());
+    message.push('\n');
+    message.push_str(
+        &strings::help::cfg::the_following_configuration_values_are_recognized(
+            ReasoningEffort::choices(),
+        ),
+    );
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(
+            command_prefix,
+            "text-generation reasoning-effort-override"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "text-generation set-reasoning-effort-override VALUE"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "text-generation set-reasoning-effort-override"
+        )
+    ));
 
     message
 }
@@ -660,3 +986,108 @@ fn build_section_image_generation() -> String {
 
     message
 }
+
+fn build_section_quota(command_prefix: &str) -> String {
+    let mut message = String::new();
+
+    message.push_str(&format!("### {}", strings::help::cfg::quota_heading()));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::quota_common());
+    message.push_str("\n\n");
+
+    // Limits
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::quota_limits_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::quota_limits_intro());
+    message.push('\n');
+    message.push_str(
+        &strings::help::cfg::the_following_configuration_values_are_recognized(
+            QuotaLimit::choices(),
+        ),
+    );
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(command_prefix, "quota LIMIT")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(command_prefix, "quota set-LIMIT VALUE")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(command_prefix, "quota set-LIMIT")
+    ));
+    message.push_str("\n\n");
+
+    // Cost per million tokens
+
+    message.push_str(&format!(
+        "#### {}",
+        strings::help::cfg::quota_cost_per_million_tokens_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::quota_cost_per_million_tokens_intro());
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(command_prefix, "quota cost-per-million-tokens")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(
+            command_prefix,
+            "quota set-cost-per-million-tokens VALUE"
+        )
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(
+            command_prefix,
+            "quota set-cost-per-million-tokens"
+        )
+    ));
+
+    message
+}
+
+fn build_section_reactions(command_prefix: &str) -> String {
+    let mut message = String::new();
+
+    message.push_str(&format!("### {}", strings::help::cfg::reactions_heading()));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::reactions_common());
+    message.push_str("\n\n");
+    message.push_str(&strings::help::cfg::reactions_actions_intro());
+    message.push('\n');
+    message.push_str(
+        &strings::help::cfg::the_following_configuration_values_are_recognized(
+            ReactionAction::choices(),
+        ),
+    );
+    message.push_str("\n\n");
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_show(command_prefix, "reactions EMOJI")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_set(command_prefix, "reactions set-EMOJI ACTION")
+    ));
+    message.push('\n');
+    message.push_str(&format!(
+        "- {}",
+        strings::help::cfg::current_setting_unset(command_prefix, "reactions set-EMOJI")
+    ));
+
+    message
+}
@@ -7,7 +7,7 @@ use crate::{
     strings,
 };
 
-use crate::entity::roomconfig::RoomConfigurationManager;
+use crate::entity::roomconfig::{RoomConfigurationManager, join_agent_chain, split_agent_chain};
 
 pub async fn handle_get(
     bot: &Bot,
@@ -32,7 +32,12 @@ pub async fn handle_get(
         return Ok(());
     };
 
-    let Some(agent_identifier) = PublicIdentifier::from_str(agent_id.as_str()) else {
+    let agent_identifiers: Option<Vec<PublicIdentifier>> = split_agent_chain(&agent_id)
+        .iter()
+        .map(|agent_id| PublicIdentifier::from_str(agent_id))
+        .collect();
+
+    let Some(agent_identifiers) = agent_identifiers else {
         bot.messaging()
             .send_error_markdown_no_fail(
                 message_context.room(),
@@ -44,38 +49,37 @@ pub async fn handle_get(
         return Ok(());
     };
 
-    let agent_exists = bot
+    let available_agents = bot
         .agent_manager()
-        .available_room_agents_by_room_config_context(message_context.room_config_context())
-        .iter()
-        .any(|agent| *agent.identifier() == agent_identifier);
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
 
-    if agent_exists {
-        bot.messaging()
-            .send_text_markdown_no_fail(
-                message_context.room(),
-                strings::room_config::configured_to_use_agent_for_purpose(
-                    &agent_identifier,
-                    purpose,
-                ),
-                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
-            )
-            .await;
-    } else {
-        bot.messaging()
-            .send_text_markdown_no_fail(
-                message_context.room(),
-                strings::room_config::configures_agent_for_purpose_but_does_not_exist(
-                    &agent_identifier,
+    let mut message =
+        strings::room_config::configured_to_use_agent_for_purpose(&agent_identifiers, purpose);
+
+    for agent_identifier in &agent_identifiers {
+        let agent_exists = available_agents
+            .iter()
+            .any(|agent| agent.identifier() == agent_identifier);
+
+        if !agent_exists {
+            message.push_str("\n\n");
+            message.push_str(
+                &strings::room_config::configures_agent_for_purpose_but_does_not_exist(
+                    agent_identifier,
                     purpose,
                 ),
-                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
-            )
-            .await;
-
-        return Ok(());
+            );
+        }
     }
 
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
     Ok(())
 }
 
@@ -84,12 +88,14 @@ pub async fn handle_set(
     room_config_manager: &tokio::sync::Mutex<RoomConfigurationManager>,
     message_context: &MessageContext,
     purpose: AgentPurpose,
-    agent_identifier: &Option<PublicIdentifier>,
+    agent_identifiers: &[PublicIdentifier],
 ) -> anyhow::Result<()> {
-    if let Some(agent_identifier) = agent_identifier {
-        let agent_exists = bot
-            .agent_manager()
-            .available_room_agents_by_room_config_context(message_context.room_config_context())
+    let available_agents = bot
+        .agent_manager()
+        .available_room_agents_by_room_config_context(message_context.room_config_context());
+
+    for agent_identifier in agent_identifiers {
+        let agent_exists = available_agents
             .iter()
             .any(|agent| agent.identifier() == agent_identifier);
 
@@ -108,9 +114,12 @@ pub async fn handle_set(
 
     let mut new_room_config = message_context.room_config().clone();
 
-    let agent_id = agent_identifier
-        .as_ref()
-        .map(|agent_identifier| agent_identifier.as_string());
+    let agent_id = join_agent_chain(
+        &agent_identifiers
+            .iter()
+            .map(|agent_identifier| agent_identifier.as_string())
+            .collect::<Vec<_>>(),
+    );
 
     new_room_config
         .settings
@@ -123,11 +132,10 @@ pub async fn handle_set(
         .persist(message_context.room(), &new_room_config)
         .await?;
 
-    let message = match agent_identifier {
-        Some(agent_identifier) => {
-            strings::room_config::reconfigured_to_use_agent_for_purpose(agent_identifier, purpose)
-        }
-        None => strings::room_config::reconfigured_to_not_specify_agent_for_purpose(purpose),
+    let message = if agent_identifiers.is_empty() {
+        strings::room_config::reconfigured_to_not_specify_agent_for_purpose(purpose)
+    } else {
+        strings::room_config::reconfigured_to_use_agent_for_purpose(agent_identifiers, purpose)
     };
 
     bot.messaging()
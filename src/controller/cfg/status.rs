@@ -8,7 +8,10 @@ use crate::{
     },
     entity::{
         MessageContext, RoomConfigContext,
-        roomconfig::{RoomConfig, RoomSettingsHandler},
+        roomconfig::{
+            QuotaLimit, RoomConfig, RoomSettingsHandler, default_reaction_actions,
+            reaction_keys_match, split_agent_chain,
+        },
     },
     strings,
 };
@@ -76,6 +79,18 @@ pub async fn handle(bot: &Bot, message_context: &MessageContext) -> anyhow::Resu
     );
     message.push_str("\n\n");
 
+    // Quotas
+    message.push_str(&generate_quota_section(
+        message_context.room_config_context(),
+    ));
+    message.push_str("\n\n");
+
+    // Reactions
+    message.push_str(&generate_reactions_section(
+        message_context.room_config_context(),
+    ));
+    message.push_str("\n\n");
+
     bot.messaging()
         .send_text_markdown_no_fail(
             message_context.room(),
@@ -165,11 +180,18 @@ fn generate_handler_line_for_purpose(
 
     match agent_id {
         Some(agent_id) => {
-            let agent = agents
-                .iter()
-                .find(|a| *a.identifier().as_string() == agent_id);
-
-            strings::cfg::status_handler_line_agent_found(purpose, &agent_id, agent)
+            let agent_chain: Vec<(String, Option<&AgentInstance>)> = split_agent_chain(&agent_id)
+                .into_iter()
+                .map(|agent_id| {
+                    let agent = agents
+                        .iter()
+                        .find(|a| *a.identifier().as_string() == agent_id);
+
+                    (agent_id, agent)
+                })
+                .collect();
+
+            strings::cfg::status_handler_line_agent_found(purpose, &agent_chain)
         }
         None => match purpose {
             AgentPurpose::CatchAll => {
@@ -260,6 +282,7 @@ async fn generate_text_generation_section(
         Ok(text_generation_agent_info) => {
             message.push_str(&strings::cfg::status_entry_effective_agent(
                 text_generation_agent_info.instance.identifier(),
+                &text_generation_agent_info.fallback_instances,
                 text_generation_agent_info.configuration_source,
             ));
 
@@ -359,6 +382,71 @@ async fn generate_text_generation_section(
         ),
     );
 
+    // Context Management Strategy
+
+    let effective_context_management_strategy =
+        room_config_context.text_generation_context_management_strategy();
+    let room_config_context_management_strategy = room_config_context
+        .room_config
+        .settings
+        .text_generation
+        .context_management_strategy;
+    let global_config_context_management_strategy = room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .context_management_strategy;
+
+    let context_management_strategy_set_where = if room_config_context_management_strategy.is_some()
+    {
+        strings::cfg::status_badge_set_in_room_config()
+    } else if global_config_context_management_strategy.is_some() {
+        strings::cfg::status_badge_set_in_global_config()
+    } else {
+        strings::cfg::status_badge_using_hardcoded_default()
+    };
+
+    message.push_str(
+        &strings::cfg::status_text_generation_entry_context_management_strategy(
+            effective_context_management_strategy,
+            context_management_strategy_set_where,
+        ),
+    );
+
+    // Context Summary Agent
+
+    let (context_summary_agent, context_summary_agent_set_where) = if let Some(agent_id) =
+        &room_config_context
+            .room_config
+            .settings
+            .text_generation
+            .context_summary_agent
+    {
+        (
+            Some(agent_id.as_str()),
+            strings::cfg::status_badge_set_in_room_config(),
+        )
+    } else if let Some(agent_id) = &room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .context_summary_agent
+    {
+        (
+            Some(agent_id.as_str()),
+            strings::cfg::status_badge_set_in_global_config(),
+        )
+    } else {
+        (None, "")
+    };
+
+    message.push_str(
+        &strings::cfg::status_text_generation_entry_context_summary_agent(
+            context_summary_agent,
+            context_summary_agent_set_where,
+        ),
+    );
+
     // Thinking Notice
 
     let effective_thinking_notice = room_config_context.text_generation_thinking_notice_enabled();
@@ -386,6 +474,63 @@ async fn generate_text_generation_section(
         thinking_notice_set_where,
     ));
 
+    // Streaming
+
+    let effective_streaming = room_config_context.text_generation_streaming_enabled();
+    let room_config_streaming = room_config_context
+        .room_config
+        .settings
+        .text_generation
+        .streaming_enabled;
+    let global_config_streaming = room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .streaming_enabled;
+
+    let streaming_set_where = if room_config_streaming.is_some() {
+        strings::cfg::status_badge_set_in_room_config()
+    } else if global_config_streaming.is_some() {
+        strings::cfg::status_badge_set_in_global_config()
+    } else {
+        strings::cfg::status_badge_using_hardcoded_default()
+    };
+
+    message.push_str(&strings::cfg::status_text_generation_entry_streaming(
+        effective_streaming,
+        streaming_set_where,
+    ));
+
+    // Regenerate on Edit
+
+    let effective_regenerate_on_edit =
+        room_config_context.text_generation_regenerate_on_edit_enabled();
+    let room_config_regenerate_on_edit = room_config_context
+        .room_config
+        .settings
+        .text_generation
+        .regenerate_on_edit_enabled;
+    let global_config_regenerate_on_edit = room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .regenerate_on_edit_enabled;
+
+    let regenerate_on_edit_set_where = if room_config_regenerate_on_edit.is_some() {
+        strings::cfg::status_badge_set_in_room_config()
+    } else if global_config_regenerate_on_edit.is_some() {
+        strings::cfg::status_badge_set_in_global_config()
+    } else {
+        strings::cfg::status_badge_using_hardcoded_default()
+    };
+
+    message.push_str(
+        &strings::cfg::status_text_generation_entry_regenerate_on_edit(
+            effective_regenerate_on_edit,
+            regenerate_on_edit_set_where,
+        ),
+    );
+
     // Sender Context
 
     let effective_sender_context = room_config_context.text_generation_sender_context_mode();
@@ -421,40 +566,85 @@ async fn generate_text_generation_section(
         None
     };
 
-    let room_config_prompt_override = room_config_context
-        .room_config
-        .settings
-        .text_generation
-        .prompt_override
-        .clone();
-    let global_config_prompt_override = room_config_context
+    let room_config_text_generation = &room_config_context.room_config.settings.text_generation;
+    let global_config_text_generation = &room_config_context
         .global_config
         .fallback_room_settings
-        .text_generation
-        .prompt_override
-        .clone();
+        .text_generation;
 
-    let (prompt, prompt_set_where) =
-        if let Some(room_config_prompt_override) = room_config_prompt_override {
+    // Mirrors `RoomConfigContext::text_generation_prompt_override()`, while also keeping track of where the prompt comes from.
+    let mut prompt_from_config = None;
+    for (text_generation, set_where) in [
+        (
+            room_config_text_generation,
+            strings::cfg::status_badge_set_in_room_config(),
+        ),
+        (
+            global_config_text_generation,
+            strings::cfg::status_badge_set_in_global_config(),
+        ),
+    ] {
+        if let Some(prompt_override) = &text_generation.prompt_override {
+            prompt_from_config = Some((prompt_override.clone(), set_where.to_owned()));
+            break;
+        }
+
+        let Some(prompt_name) = &text_generation.prompt_name else {
+            continue;
+        };
+
+        match room_config_context
+            .global_config
+            .prompt_by_name(prompt_name)
+        {
+            Some(prompt) => {
+                prompt_from_config = Some((
+                    prompt.text.clone(),
+                    strings::cfg::status_badge_from_prompt_library(&prompt.name, set_where),
+                ));
+                break;
+            }
+            None => {
+                message.push_str(&strings::cfg::status_text_generation_entry_prompt_missing(
+                    prompt_name,
+                    set_where,
+                ));
+            }
+        }
+    }
+
+    let (prompt, prompt_set_where) = prompt_from_config.unwrap_or_else(|| {
+        (
+            text_agent_prompt.unwrap_or("".to_owned()),
+            strings::cfg::status_badge_set_in_agent_config().to_owned(),
+        )
+    });
+
+    message.push_str(&strings::cfg::status_text_generation_entry_prompt(
+        &prompt,
+        &prompt_set_where,
+    ));
+
+    // Timezone
+
+    let (timezone, timezone_set_where) =
+        if let Some(timezone) = &room_config_text_generation.timezone {
             (
-                room_config_prompt_override,
+                Some(timezone.as_str()),
                 strings::cfg::status_badge_set_in_room_config(),
             )
-        } else if let Some(global_config_prompt_override) = global_config_prompt_override {
+        } else if let Some(timezone) = &global_config_text_generation.timezone {
             (
-                global_config_prompt_override,
+                Some(timezone.as_str()),
                 strings::cfg::status_badge_set_in_global_config(),
             )
         } else {
-            (
-                text_agent_prompt.unwrap_or("".to_owned()),
-                strings::cfg::status_badge_set_in_agent_config(),
-            )
+            (None, "")
         };
 
-    message.push_str(&strings::cfg::status_text_generation_entry_prompt(
-        &prompt,
-        prompt_set_where,
+    message.push_str(&strings::cfg::status_text_generation_entry_timezone(
+        timezone,
+        timezone_set_where,
     ));
 
     // Temperature
@@ -502,6 +692,108 @@ async fn generate_text_generation_section(
         set_where,
     ));
 
+    // Model override
+
+    let text_agent_model_id = if let Some(text_generation_agent) = &text_generation_agent {
+        text_generation_agent
+            .controller()
+            .text_generation_model_id()
+    } else {
+        None
+    };
+
+    let room_config_model_override = room_config_context
+        .room_config
+        .settings
+        .text_generation
+        .model_override
+        .clone();
+    let global_config_model_override = room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .model_override
+        .clone();
+
+    let (effective_model_id, set_where) =
+        if let Some(room_config_model_override) = room_config_model_override {
+            (
+                Some(room_config_model_override),
+                strings::cfg::status_badge_set_in_room_config(),
+            )
+        } else if let Some(global_config_model_override) = global_config_model_override {
+            (
+                Some(global_config_model_override),
+                strings::cfg::status_badge_set_in_global_config(),
+            )
+        } else {
+            (
+                text_agent_model_id,
+                strings::cfg::status_badge_set_in_agent_config(),
+            )
+        };
+
+    message.push_str(&strings::cfg::status_text_generation_entry_model(
+        effective_model_id.as_deref(),
+        set_where,
+    ));
+
+    // Max response tokens override
+
+    let room_config_max_response_tokens_override = room_config_context
+        .room_config
+        .settings
+        .text_generation
+        .max_response_tokens_override;
+    let global_config_max_response_tokens_override = room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .max_response_tokens_override;
+
+    let max_response_tokens_set_where = if room_config_max_response_tokens_override.is_some() {
+        strings::cfg::status_badge_set_in_room_config()
+    } else if global_config_max_response_tokens_override.is_some() {
+        strings::cfg::status_badge_set_in_global_config()
+    } else {
+        strings::cfg::status_badge_set_in_agent_config()
+    };
+
+    message.push_str(
+        &strings::cfg::status_text_generation_entry_max_response_tokens(
+            room_config_context.text_generation_max_response_tokens_override(),
+            max_response_tokens_set_where,
+        ),
+    );
+
+    // Reasoning effort override
+
+    let room_config_reasoning_effort_override = room_config_context
+        .room_config
+        .settings
+        .text_generation
+        .reasoning_effort_override;
+    let global_config_reasoning_effort_override = room_config_context
+        .global_config
+        .fallback_room_settings
+        .text_generation
+        .reasoning_effort_override;
+
+    let reasoning_effort_set_where = if room_config_reasoning_effort_override.is_some() {
+        strings::cfg::status_badge_set_in_room_config()
+    } else if global_config_reasoning_effort_override.is_some() {
+        strings::cfg::status_badge_set_in_global_config()
+    } else {
+        strings::cfg::status_badge_set_in_agent_config()
+    };
+
+    message.push_str(
+        &strings::cfg::status_text_generation_entry_reasoning_effort(
+            room_config_context.text_generation_reasoning_effort_override(),
+            reasoning_effort_set_where,
+        ),
+    );
+
     message
 }
 
@@ -526,6 +818,7 @@ async fn generate_speech_to_text_section(
         Ok(speech_to_text_agent_info) => {
             message.push_str(&strings::cfg::status_entry_effective_agent(
                 speech_to_text_agent_info.instance.identifier(),
+                &speech_to_text_agent_info.fallback_instances,
                 speech_to_text_agent_info.configuration_source,
             ));
         }
@@ -642,6 +935,7 @@ async fn generate_text_to_speech_section(
         Ok(text_to_speech_agent_info) => {
             message.push_str(&strings::cfg::status_entry_effective_agent(
                 text_to_speech_agent_info.instance.identifier(),
+                &text_to_speech_agent_info.fallback_instances,
                 text_to_speech_agent_info.configuration_source,
             ));
             Some(text_to_speech_agent_info.instance)
@@ -828,6 +1122,7 @@ async fn generate_image_generation_section(
         Ok(image_generation_agent_info) => {
             message.push_str(&strings::cfg::status_entry_effective_agent(
                 image_generation_agent_info.instance.identifier(),
+                &image_generation_agent_info.fallback_instances,
                 image_generation_agent_info.configuration_source,
             ));
             Some(image_generation_agent_info.instance)
@@ -841,3 +1136,112 @@ async fn generate_image_generation_section(
 
     message
 }
+
+fn generate_quota_section(room_config_context: &RoomConfigContext) -> String {
+    let mut message = String::new();
+
+    message.push_str(format!("## {}\n", strings::cfg::status_quota_heading()).as_str());
+
+    let mut any_limit_set = false;
+
+    for limit in QuotaLimit::choices() {
+        let Some(effective_value) = room_config_context.quota_limit(&limit) else {
+            continue;
+        };
+
+        any_limit_set = true;
+
+        let set_where = if limit
+            .get(&room_config_context.room_config.settings.quota)
+            .is_some()
+        {
+            strings::cfg::status_badge_set_in_room_config()
+        } else {
+            strings::cfg::status_badge_set_in_global_config()
+        };
+
+        message.push_str(&strings::cfg::status_quota_entry_limit(
+            &limit,
+            effective_value,
+            set_where,
+        ));
+    }
+
+    if !any_limit_set {
+        message.push_str(strings::cfg::status_quota_entry_no_limits());
+    }
+
+    let room_config_cost_per_million_tokens = room_config_context
+        .room_config
+        .settings
+        .quota
+        .cost_per_million_tokens;
+
+    let cost_per_million_tokens_set_where = if room_config_cost_per_million_tokens.is_some() {
+        strings::cfg::status_badge_set_in_room_config()
+    } else {
+        strings::cfg::status_badge_set_in_global_config()
+    };
+
+    message.push_str(&strings::cfg::status_quota_entry_cost_per_million_tokens(
+        room_config_context.quota_cost_per_million_tokens(),
+        cost_per_million_tokens_set_where,
+    ));
+
+    message
+}
+
+fn generate_reactions_section(room_config_context: &RoomConfigContext) -> String {
+    let mut message = String::new();
+
+    message.push_str(format!("## {}\n", strings::cfg::status_reactions_heading()).as_str());
+
+    let room_reactions = &room_config_context.room_config.settings.reactions;
+    let global_reactions = &room_config_context
+        .global_config
+        .fallback_room_settings
+        .reactions;
+
+    let mut keys: Vec<String> = vec![];
+    for key in default_reaction_actions()
+        .into_iter()
+        .map(|(key, _)| key.to_owned())
+        .chain(global_reactions.actions.keys().cloned())
+        .chain(room_reactions.actions.keys().cloned())
+    {
+        if !keys
+            .iter()
+            .any(|existing| reaction_keys_match(existing, &key))
+        {
+            keys.push(key);
+        }
+    }
+
+    for key in keys {
+        // Disabled keys are listed too, so that it's clear why a default mapping doesn't apply.
+        let (action, set_where) = if let Some(action) = room_reactions.get(&key) {
+            (
+                action.clone(),
+                strings::cfg::status_badge_set_in_room_config(),
+            )
+        } else if let Some(action) = global_reactions.get(&key) {
+            (
+                action.clone(),
+                strings::cfg::status_badge_set_in_global_config(),
+            )
+        } else if let Some((_, action)) = default_reaction_actions()
+            .into_iter()
+            .find(|(default_key, _)| reaction_keys_match(default_key, &key))
+        {
+            (action, strings::cfg::status_badge_using_hardcoded_default())
+        } else {
+            continue;
+        };
+
+        message.push_str(&strings::cfg::status_reactions_entry(
+            &key, &action, set_where,
+        ));
+    }
+
+    message
+}
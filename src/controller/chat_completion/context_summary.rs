@@ -0,0 +1,291 @@
+//! Condensing the oldest messages of long conversations into a running summary
+//! (the `summarize` context management strategy), instead of dropping them.
+
+use crate::Bot;
+use crate::agent::failover::run_with_failover;
+use crate::agent::provider::TextGenerationParams;
+use crate::agent::{AgentInstance, AgentPurpose, ControllerTrait, PublicIdentifier};
+use crate::conversation::llm::{
+    Author, Conversation, ConversationSummary, ConversationSummaryCache, Message, MessageContent,
+    TokenEstimate, approximate_token_size_for_message, split_messages_list_to_context_size,
+};
+use crate::entity::MessageContext;
+use crate::entity::usage::UsageCounters;
+
+/// The most tokens a summary may take.
+/// Room for it is reserved in the context window of the agent generating the answer, as the summary becomes part of its prompt.
+const SUMMARY_MAX_TOKENS: u32 = 1024;
+
+/// Tokens reserved (in the context window of the summarizing agent) for the summarization instructions.
+const SUMMARIZATION_INSTRUCTIONS_TOKENS: u32 = 512;
+
+const SUMMARIZATION_PROMPT: &str = "You maintain a running summary of a chat conversation, whose oldest messages no longer fit in the context window of the assistant taking part in it.
+Write a concise summary which lets the assistant carry on the conversation: the original task or question, decisions made, facts and preferences shared by the participants, open questions and anything the assistant promised to do.
+When given an existing summary, extend it with the new messages instead of starting over. Drop details which no longer matter.
+Reply with the summary alone, written in the language of the conversation.";
+
+/// Returns a summary of those of the conversation's oldest messages which do not fit in the context window of the given agent.
+///
+/// Summaries are cached per conversation and only extended with the messages which overflowed since.
+/// Returns `None` when everything fits or when nothing could be summarized,
+/// in which case the overflowing messages simply get dropped (like with the `truncate` strategy).
+pub(super) async fn summarize_overflowing_messages(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent: &AgentInstance,
+    params: &TextGenerationParams,
+    conversation: &Conversation,
+) -> Option<String> {
+    let controller = agent.controller();
+
+    let max_context_tokens = controller.text_generation_max_context_tokens()?;
+
+    let prompt_message = Message {
+        author: Author::Prompt,
+        sender_id: None,
+        content: MessageContent::Text(params.prompt_text(controller.text_generation_prompt())),
+        timestamp: chrono::Utc::now(),
+    };
+
+    let max_response_tokens = params
+        .max_response_tokens(controller.text_generation_max_response_tokens())
+        .unwrap_or(0)
+        + SUMMARY_MAX_TOKENS;
+
+    // Providers count tokens more precisely (for some models), but the approximation leans high,
+    // so whatever it considers fitting is very unlikely to be dropped by them.
+    let (overflowing_messages, _) = split_messages_list_to_context_size(
+        TokenEstimate::Approximate,
+        controller.text_generation_media_token_cost(),
+        &Some(prompt_message),
+        conversation.messages.clone(),
+        Some(max_response_tokens),
+        max_context_tokens,
+    );
+
+    if overflowing_messages.is_empty() {
+        return None;
+    }
+
+    let cache_key = ConversationSummaryCache::key(
+        message_context.room_id().as_str(),
+        message_context.thread_info().root_event_id.as_str(),
+    );
+
+    let cached_summary = bot.conversation_summary_cache().get(&cache_key);
+
+    let (mut summary_text, mut summarized_messages_count) = match &cached_summary {
+        Some(summary) if summary.uncovered_messages(&overflowing_messages).is_some() => {
+            (Some(summary.text.clone()), summary.messages_count)
+        }
+        _ => (None, 0),
+    };
+
+    if summarized_messages_count == overflowing_messages.len() {
+        return summary_text;
+    }
+
+    let summarizers = determine_summarizer_agents(bot, message_context, agent);
+
+    // Chunks need to fit whichever summarizer ends up being used.
+    let chunk_max_tokens = summarizers
+        .iter()
+        .map(|summarizer| {
+            summarizer
+                .controller()
+                .text_generation_max_context_tokens()
+                .unwrap_or(max_context_tokens)
+        })
+        .min()
+        .unwrap_or(max_context_tokens)
+        .saturating_sub(2 * SUMMARY_MAX_TOKENS + SUMMARIZATION_INSTRUCTIONS_TOKENS);
+
+    while summarized_messages_count < overflowing_messages.len() {
+        let remaining_messages = &overflowing_messages[summarized_messages_count..];
+        let chunk = take_chunk(remaining_messages, chunk_max_tokens);
+
+        tracing::debug!(
+            agent_id = summarizers[0].identifier().as_string(),
+            messages_count = chunk.len(),
+            "Summarizing messages which do not fit in the context window.."
+        );
+
+        let result = summarize_chunk(
+            bot,
+            message_context,
+            &summarizers,
+            summary_text.as_deref(),
+            chunk,
+        )
+        .await;
+
+        match result {
+            Ok(text) => {
+                summary_text = Some(text);
+                summarized_messages_count += chunk.len();
+            }
+            Err(err) => {
+                // Whatever got summarized so far is still useful. The rest is dropped for this turn.
+                tracing::warn!(
+                    ?err,
+                    "Failed summarizing messages which do not fit in the context window"
+                );
+                break;
+            }
+        }
+    }
+
+    if let Some(text) = &summary_text
+        && let Some(summary) = ConversationSummary::new(
+            text.clone(),
+            &overflowing_messages[..summarized_messages_count],
+        )
+    {
+        bot.conversation_summary_cache().insert(cache_key, summary);
+    }
+
+    summary_text
+}
+
+/// Returns the agents to summarize with, in order of preference: the one configured to summarize (if usable),
+/// followed by the given (text-generation) agent.
+fn determine_summarizer_agents(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent: &AgentInstance,
+) -> Vec<AgentInstance> {
+    let Some(agent_id) = message_context
+        .room_config_context()
+        .text_generation_context_summary_agent()
+    else {
+        return vec![agent.clone()];
+    };
+
+    let summarizer = PublicIdentifier::from_str(&agent_id).and_then(|agent_identifier| {
+        bot.agent_manager()
+            .available_room_agents_by_room_config_context(message_context.room_config_context())
+            .into_iter()
+            .find(|agent| *agent.identifier() == agent_identifier)
+    });
+
+    match summarizer {
+        Some(summarizer)
+            if summarizer
+                .controller()
+                .supports_purpose(AgentPurpose::TextGeneration) =>
+        {
+            if summarizer.identifier() == agent.identifier() {
+                vec![summarizer]
+            } else {
+                vec![summarizer, agent.clone()]
+            }
+        }
+        _ => {
+            tracing::warn!(
+                agent_id = agent_id.as_str(),
+                "The context summary agent is missing or does not support text generation. Summarizing via the text-generation agent instead"
+            );
+
+            vec![agent.clone()]
+        }
+    }
+}
+
+/// Takes as many messages from the start as fit in the given token budget (but always at least one).
+fn take_chunk(messages: &[Message], max_tokens: u32) -> &[Message] {
+    let mut tokens: u32 = 0;
+    let mut count = 0;
+
+    for message in messages {
+        tokens += approximate_token_size_for_message(message);
+
+        if tokens > max_tokens && count > 0 {
+            break;
+        }
+
+        count += 1;
+    }
+
+    &messages[..count]
+}
+
+async fn summarize_chunk(
+    bot: &Bot,
+    message_context: &MessageContext,
+    summarizers: &[AgentInstance],
+    previous_summary: Option<&str>,
+    messages: &[Message],
+) -> anyhow::Result<String> {
+    let transcript = messages
+        .iter()
+        .map(transcript_entry)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let request = match previous_summary {
+        Some(previous_summary) => format!(
+            "Existing summary:\n\n{previous_summary}\n\nMessages which followed:\n\n{transcript}"
+        ),
+        None => format!("Messages:\n\n{transcript}"),
+    };
+
+    let conversation = Conversation {
+        messages: vec![Message {
+            author: Author::User,
+            sender_id: None,
+            content: MessageContent::Text(request),
+            timestamp: chrono::Utc::now(),
+        }],
+    };
+
+    let (summarizer, result) =
+        run_with_failover(summarizers, AgentPurpose::TextGeneration, async |agent| {
+            let params = TextGenerationParams {
+                prompt_override: Some(SUMMARIZATION_PROMPT.to_owned()),
+                max_response_tokens_override: Some(SUMMARY_MAX_TOKENS),
+                ..Default::default()
+            };
+
+            agent
+                .controller()
+                .generate_text(conversation.clone(), params)
+                .await
+        })
+        .await;
+
+    let result = result?;
+
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &summarizer.identifier().as_string(),
+            UsageCounters::for_text_generation(&result.usage),
+        )
+        .await;
+
+    let text = result.text.trim();
+
+    if text.is_empty() {
+        return Err(anyhow::anyhow!("The summary came out empty"));
+    }
+
+    Ok(text.to_owned())
+}
+
+fn transcript_entry(message: &Message) -> String {
+    let author = match (&message.author, &message.sender_id) {
+        (Author::User, Some(sender_id)) => format!("User ({sender_id})"),
+        (Author::User, None) => "User".to_owned(),
+        (Author::Assistant, _) => "Assistant".to_owned(),
+        (Author::Prompt, _) => "System".to_owned(),
+    };
+
+    let content = match &message.content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Image(details) => format!("[image: {}]", details.filename()),
+        MessageContent::File(details) => format!("[file: {}]", details.filename()),
+    };
+
+    format!("{author}: {content}")
+}
@@ -1,3 +1,6 @@
+mod context_summary;
+pub mod regenerate;
+
 use mxlink::matrix_sdk::ruma::OwnedEventId;
 use mxlink::matrix_sdk::ruma::events::room::message::AudioMessageEventContent;
 use mxlink::{MatrixLink, MessageResponseType};
@@ -7,17 +10,23 @@ use tracing::Instrument;
 use crate::agent::AgentInstance;
 use crate::agent::AgentPurpose;
 use crate::agent::ControllerTrait;
+use crate::agent::failover::run_with_failover;
 use crate::agent::provider::{
-    SpeechToTextParams, TextGenerationParams, TextGenerationPromptVariables,
+    SpeechToTextParams, TextGenerationParams, TextGenerationPromptVariables, TextGenerationResult,
+};
+use crate::controller::utils::agent::{
+    get_effective_agent_chain_for_purpose_or_complain, get_effective_agent_for_purpose_or_complain,
+    notify_if_served_by_fallback_agent,
 };
-use crate::controller::utils::agent::get_effective_agent_for_purpose_or_complain;
+use crate::controller::utils::without_conversation_commands;
 use crate::conversation::matrix::MatrixMessageProcessingParams;
 use crate::entity::MessagePayload;
 use crate::entity::roomconfig::{
     SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
-    TextGenerationSenderContextMode, TextToSpeechBotMessagesFlowType,
-    TextToSpeechUserMessagesFlowType,
+    TextGenerationContextManagementStrategy, TextGenerationSenderContextMode,
+    TextToSpeechBotMessagesFlowType, TextToSpeechUserMessagesFlowType,
 };
+use crate::entity::usage::UsageCounters;
 use crate::strings;
 use crate::utils::text_to_speech::create_transcribed_message_text;
 use crate::{
@@ -37,6 +46,10 @@ const THINKING_NOTICE_FIRST_DELAY: std::time::Duration = std::time::Duration::fr
 /// How often the "thinking…" placeholder is refreshed once it has appeared.
 const THINKING_NOTICE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
 
+/// Minimum spacing between edits of a message whose text is being streamed into the room.
+/// Each edit is a new Matrix event, so editing on every token would quickly get rate-limited.
+const STREAMING_EDIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1500);
+
 #[derive(Debug, PartialEq)]
 pub enum ChatCompletionControllerType {
     // Invoked via a command prefix (e.g. `!bai Hello!`)
@@ -316,7 +329,7 @@ async fn handle_stage_speech_to_text(
     response_type: MessageResponseType,
     msg_type: SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
 ) -> Option<OwnedEventId> {
-    let agent = get_effective_agent_for_purpose_or_complain(
+    let agents = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::SpeechToText,
@@ -325,11 +338,6 @@ async fn handle_stage_speech_to_text(
     )
     .await?;
 
-    tracing::debug!(
-        agent_id = agent.identifier().as_string(),
-        "Handling speech-to-text",
-    );
-
     let reaction_event_response = bot
         .reacting()
         .react_no_fail(
@@ -339,15 +347,24 @@ async fn handle_stage_speech_to_text(
         )
         .await;
 
-    let speech_to_text_created_event_id = handle_stage_speech_to_text_actual_transcribing(
-        bot,
-        message_context,
-        &agent,
-        audio_content,
-        response_type.clone(),
-        msg_type,
-    )
-    .await;
+    let (agent, speech_to_text_created_event_id) =
+        run_with_failover(&agents, AgentPurpose::SpeechToText, async |agent| {
+            tracing::debug!(
+                agent_id = agent.identifier().as_string(),
+                "Handling speech-to-text",
+            );
+
+            handle_stage_speech_to_text_actual_transcribing(
+                bot,
+                message_context,
+                agent,
+                audio_content,
+                response_type.clone(),
+                msg_type,
+            )
+            .await
+        })
+        .await;
 
     if let Some(reaction_event_response) = reaction_event_response {
         let redaction_reason = if speech_to_text_created_event_id.is_ok() {
@@ -391,6 +408,16 @@ async fn handle_stage_speech_to_text(
         }
     };
 
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::SpeechToText,
+        response_type,
+    )
+    .await;
+
     Some(speech_to_text_created_event_id)
 }
 
@@ -401,7 +428,7 @@ async fn handle_stage_text_generation(
     controller_type: &ChatCompletionControllerType,
     response_type: MessageResponseType,
 ) -> Option<TextToSpeechEligiblePayload> {
-    let agent = get_effective_agent_for_purpose_or_complain(
+    let agents = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::TextGeneration,
@@ -410,6 +437,8 @@ async fn handle_stage_text_generation(
     )
     .await?;
 
+    let primary_agent = &agents[0];
+
     // We only strip text from the first message if we're invoked via a command prefix.
     // Otherwise, we do bot-user mentions stripping on all messages below.
     let first_message_prefixes_to_strip = match controller_type {
@@ -481,7 +510,7 @@ async fn handle_stage_text_generation(
                 .send_error_markdown_no_fail(
                     message_context.room(),
                     &strings::agent::error_while_serving_purpose(
-                        agent.identifier(),
+                        primary_agent.identifier(),
                         &AgentPurpose::TextGeneration,
                         &err,
                     ),
@@ -494,71 +523,77 @@ async fn handle_stage_text_generation(
     };
 
     let conversation = inject_sender_context(
-        conversation,
+        without_conversation_commands(
+            conversation,
+            bot.command_prefix(),
+            message_context.bot_display_name(),
+        ),
         message_context
             .room_config_context()
             .text_generation_sender_context_mode(),
     );
 
-    tracing::debug!(
-        agent_id = agent.identifier().as_string(),
-        provider = format!("{}", agent.definition().provider.clone()),
-        "Invoking LLM for text generation with conversation.."
-    );
-
-    let span = tracing::debug_span!(
-        "text_generation",
-        agent_id = agent.identifier().as_string(),
-        provider = format!("{}", agent.definition().provider.clone()),
+    // Lets the sender (or an administrator) cancel the generation, by reacting to (or redacting) their message or our placeholder.
+    let mut in_flight_generation = bot.in_flight_generation_tracker().start(
+        message_context.room_id(),
+        message_context.event_id(),
+        message_context.sender_id(),
     );
 
     let start_time = std::time::Instant::now();
 
-    let controller = agent.controller();
-
-    let prompt_variables = TextGenerationPromptVariables::new(
-        bot.name(),
-        &controller
-            .text_generation_model_id()
-            .unwrap_or("unknown-model".to_owned()),
-        chrono::Utc::now(),
-        conversation.start_time(),
-    );
-
-    // Cloned only when the thinking-notice is enabled; the original is moved into `params` below.
+    // Only needed when the thinking-notice is enabled. We cannot know in advance which agent in the chain
+    // will end up answering, so the notice is based on the primary one.
     let notice_prompt_variables = if message_context
         .room_config_context()
         .text_generation_thinking_notice_enabled()
     {
-        Some(prompt_variables.clone())
+        Some(
+            create_prompt_variables(
+                bot,
+                message_context,
+                primary_agent,
+                text_generation_model_override(message_context, primary_agent, primary_agent),
+                &conversation,
+            )
+            .await,
+        )
     } else {
         None
     };
 
-    let params = TextGenerationParams {
-        context_management_enabled: message_context
-            .room_config_context()
-            .text_generation_context_management_enabled(),
-
-        prompt_override: message_context
-            .room_config_context()
-            .text_generation_prompt_override(),
-
-        temperature_override: message_context
-            .room_config_context()
-            .text_generation_temperature_override(),
-
-        prompt_variables,
-    };
-
-    // When the thinking-notice is enabled, race generation against a timer that posts and then
-    // periodically edits a "thinking…" placeholder. `biased;` makes generation win a tie, and the
-    // loop exits the instant generation resolves, so there is no detached task and no late edit can
-    // ever clobber the real answer. `placeholder` is the event we must finalize in every exit path.
-    let (result, placeholder) = if let Some(notice_prompt_variables) = notice_prompt_variables {
-        let generation = controller
-            .generate_text(conversation, params)
-            .instrument(span);
+    let streaming_enabled = message_context
+        .room_config_context()
+        .text_generation_streaming_enabled();
+
+    // When the thinking-notice or streaming is enabled, race generation against timers that post and
+    // then periodically edit a placeholder (either "thinking…" flavor text or the partial answer
+    // streamed so far). `biased;` makes cancellation (then generation) win a tie, and the loop exits
+    // the instant either resolves, so there is no detached task and no late edit can ever clobber
+    // the real answer. `placeholder` is the event we must finalize in every exit path.
+    // Cancelling drops the generation future, which aborts the provider request.
+    let (result, placeholder) = if notice_prompt_variables.is_some() || streaming_enabled {
+        let (partial_text_sender, mut partial_text_receiver) =
+            tokio::sync::watch::channel(String::new());
+
+        // Providers which cannot stream never publish partial text, so for them this behaves
+        // exactly like the non-streaming variant (and the thinking-notice keeps working).
+        // When failing over, the next agent publishes into the same channel, replacing any partial
+        // text the failed agent managed to stream.
+        let generation = run_with_failover(&agents, AgentPurpose::TextGeneration, async |agent| {
+            let partial_text_sender = streaming_enabled.then(|| partial_text_sender.clone());
+
+            generate_text_via_agent(
+                bot,
+                message_context,
+                agent,
+                text_generation_model_override(message_context, agent, primary_agent),
+                None,
+                conversation.clone(),
+                partial_text_sender,
+            )
+            .await
+        });
         tokio::pin!(generation);
 
         let mut placeholder: Option<OwnedEventId> = None;
@@ -575,11 +610,70 @@ async fn handle_stage_text_generation(
         // If an edit runs long, hold ~INTERVAL spacing rather than bursting the missed ticks.
         tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
+        // The first streamed text is posted right away; later updates are coalesced so that we
+        // edit at most once per STREAMING_EDIT_INTERVAL. Once anything was streamed, the
+        // placeholder holds the partial answer and thinking notices stop.
+        let mut stream_open = streaming_enabled;
+        let mut stream_update_pending = false;
+        let mut has_streamed = false;
+        let mut next_stream_update = tokio::time::Instant::now();
+
         let result = loop {
             tokio::select! {
                 biased;
-                generation_result = &mut generation => break generation_result,
-                _ = tick.tick() => {
+                _ = in_flight_generation.cancelled() => break None,
+                generation_result = &mut generation => break Some(generation_result),
+                changed = partial_text_receiver.changed(), if stream_open => {
+                    match changed {
+                        Ok(()) => stream_update_pending = true,
+                        // The provider is done publishing (generation resolves right after).
+                        Err(_) => stream_open = false,
+                    }
+                }
+                _ = tokio::time::sleep_until(next_stream_update), if stream_update_pending => {
+                    stream_update_pending = false;
+                    has_streamed = true;
+
+                    let message = format!(
+                        "{} {}",
+                        partial_text_receiver.borrow_and_update().trim_end(),
+                        strings::PROGRESS_INDICATOR_EMOJI,
+                    );
+
+                    match &placeholder {
+                        None => {
+                            placeholder = bot
+                                .messaging()
+                                .send_text_markdown_no_fail_quietly(
+                                    message_context.room(),
+                                    message,
+                                    response_type.clone(),
+                                )
+                                .await
+                                .map(|response| response.event_id);
+
+                            if let Some(event_id) = &placeholder {
+                                in_flight_generation.track_event(event_id.clone());
+                            }
+                        }
+                        Some(event_id) => {
+                            bot.messaging()
+                                .edit_text_markdown_no_fail(
+                                    message_context.room(),
+                                    event_id,
+                                    message,
+                                )
+                                .await;
+                        }
+                    }
+
+                    next_stream_update = tokio::time::Instant::now() + STREAMING_EDIT_INTERVAL;
+                }
+                _ = tick.tick(), if notice_prompt_variables.is_some() && !has_streamed => {
+                    let Some(notice_prompt_variables) = &notice_prompt_variables else {
+                        continue;
+                    };
+
                     let message = notice_prompt_variables.format(
                         strings::thinking::pick_message(start_time.elapsed(), notice_sequence),
                     );
@@ -596,6 +690,10 @@ async fn handle_stage_text_generation(
                                 )
                                 .await
                                 .map(|response| response.event_id);
+
+                            if let Some(event_id) = &placeholder {
+                                in_flight_generation.track_event(event_id.clone());
+                            }
                         }
                         Some(event_id) => {
                             bot.messaging()
@@ -613,14 +711,45 @@ async fn handle_stage_text_generation(
 
         (result, placeholder)
     } else {
-        let result = controller
-            .generate_text(conversation, params)
-            .instrument(span)
+        let result = in_flight_generation
+            .run(run_with_failover(
+                &agents,
+                AgentPurpose::TextGeneration,
+                async |agent| {
+                    generate_text_via_agent(
+                        bot,
+                        message_context,
+                        agent,
+                        text_generation_model_override(message_context, agent, primary_agent),
+                        None,
+                        conversation.clone(),
+                        None,
+                    )
+                    .await
+                },
+            ))
             .await;
 
         (result, None)
     };
 
+    drop(in_flight_generation);
+
+    let Some((agent, result)) = result else {
+        tracing::info!("Text generation got cancelled");
+
+        finalize_thinking_notice_with_error(
+            bot,
+            message_context,
+            placeholder.as_ref(),
+            strings::cancellation::cancelled(),
+            response_type,
+        )
+        .await;
+
+        return None;
+    };
+
     let duration = std::time::Instant::now().duration_since(start_time);
 
     tracing::debug!(
@@ -659,6 +788,15 @@ async fn handle_stage_text_generation(
         }
     };
 
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_text_generation(&result.usage),
+        )
+        .await;
+
     let text = result.text.clone().trim().to_owned();
     if text.is_empty() {
         tracing::warn!(
@@ -695,15 +833,193 @@ async fn handle_stage_text_generation(
         }
         _ => {
             bot.messaging()
-                .send_text_markdown_no_fail(message_context.room(), text.clone(), response_type)
+                .send_text_markdown_no_fail(
+                    message_context.room(),
+                    text.clone(),
+                    response_type.clone(),
+                )
                 .await?
                 .event_id
         }
     };
 
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::TextGeneration,
+        response_type,
+    )
+    .await;
+
     Some(TextToSpeechEligiblePayload { text, event_id })
 }
 
+/// Returns the model (if any) which the room's configuration wants to be used instead of the agent's own.
+///
+/// The override only applies to the primary agent of a handler's fallback chain.
+/// Fallback agents may be of another provider, which likely doesn't offer the same model.
+pub(crate) fn text_generation_model_override(
+    message_context: &MessageContext,
+    agent: &AgentInstance,
+    primary_agent: &AgentInstance,
+) -> Option<String> {
+    if agent.identifier() != primary_agent.identifier() {
+        return None;
+    }
+
+    message_context
+        .room_config_context()
+        .text_generation_model_override()
+}
+
+async fn create_prompt_variables(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent: &AgentInstance,
+    model_override: Option<String>,
+    conversation: &Conversation,
+) -> TextGenerationPromptVariables {
+    let room = message_context.room();
+
+    let is_dm = room.is_direct().await.unwrap_or_else(|err| {
+        tracing::warn!(?err, "Failed determining if the room is a DM room");
+        false
+    });
+
+    let sender_display_name = match room.get_member_no_sync(message_context.sender_id()).await {
+        Ok(Some(member)) => member.display_name().map(|name| name.to_owned()),
+        Ok(None) => None,
+        Err(err) => {
+            tracing::warn!(?err, "Failed fetching the sender's room membership");
+            None
+        }
+    };
+
+    TextGenerationPromptVariables::new(
+        bot.name(),
+        &model_override
+            .or_else(|| agent.controller().text_generation_model_id())
+            .unwrap_or("unknown-model".to_owned()),
+        chrono::Utc::now(),
+        conversation.start_time(),
+    )
+    .with_room_details(
+        room.name(),
+        room.topic(),
+        room.joined_members_count(),
+        is_dm,
+        room.encryption_state().is_encrypted(),
+        message_context
+            .room_config_context()
+            .text_generation_timezone(),
+    )
+    .with_sender_details(message_context.sender_id().as_str(), sender_display_name)
+}
+
+/// Generates text for the conversation via the given agent, streaming partial text into the sender (if one is provided).
+///
+/// Unless a `prompt_override` is provided, the room's configured prompt (if any) applies.
+pub(crate) async fn generate_text_via_agent(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent: &AgentInstance,
+    model_override: Option<String>,
+    prompt_override: Option<String>,
+    conversation: Conversation,
+    partial_text_sender: Option<tokio::sync::watch::Sender<String>>,
+) -> anyhow::Result<TextGenerationResult> {
+    // MCP servers which fail are skipped (and logged), so the agent still answers, just without their tools.
+    let mcp_tools = bot.mcp_manager().tools_for_agent(agent).await.registry;
+
+    tracing::debug!(
+        agent_id = agent.identifier().as_string(),
+        provider = format!("{}", agent.definition().provider.clone()),
+        "Invoking LLM for text generation with conversation.."
+    );
+
+    let span = tracing::debug_span!(
+        "text_generation",
+        agent_id = agent.identifier().as_string(),
+        provider = format!("{}", agent.definition().provider.clone()),
+    );
+
+    let prompt_variables = create_prompt_variables(
+        bot,
+        message_context,
+        agent,
+        model_override.clone(),
+        &conversation,
+    )
+    .await;
+
+    let mut params = TextGenerationParams {
+        context_management_enabled: message_context
+            .room_config_context()
+            .text_generation_context_management_enabled(),
+
+        prompt_override: prompt_override.or_else(|| {
+            message_context
+                .room_config_context()
+                .text_generation_prompt_override()
+        }),
+
+        temperature_override: message_context
+            .room_config_context()
+            .text_generation_temperature_override(),
+
+        max_response_tokens_override: message_context
+            .room_config_context()
+            .text_generation_max_response_tokens_override(),
+
+        reasoning_effort_override: message_context
+            .room_config_context()
+            .text_generation_reasoning_effort_override(),
+
+        prompt_variables,
+
+        model_override,
+
+        context_summary: None,
+
+        tools: mcp_tools,
+    };
+
+    if params.context_management_enabled
+        && message_context
+            .room_config_context()
+            .text_generation_context_management_strategy()
+            == TextGenerationContextManagementStrategy::Summarize
+    {
+        params.context_summary = context_summary::summarize_overflowing_messages(
+            bot,
+            message_context,
+            agent,
+            &params,
+            &conversation,
+        )
+        .await;
+    }
+
+    let controller = agent.controller();
+
+    match partial_text_sender {
+        Some(partial_text_sender) => {
+            controller
+                .generate_text_streaming(conversation, params, partial_text_sender)
+                .instrument(span)
+                .await
+        }
+        None => {
+            controller
+                .generate_text(conversation, params)
+                .instrument(span)
+                .await
+        }
+    }
+}
+
 /// Finalizes a thinking-notice placeholder (if one was posted) with error/notice text, so a
 /// failed or empty generation never leaves an orphaned "thinking…" message behind. With no
 /// placeholder, this is the original behavior: a fresh error notice.
@@ -732,7 +1048,7 @@ async fn finalize_thinking_notice_with_error(
     }
 }
 
-async fn handle_stage_speech_to_text_actual_transcribing(
+pub(crate) async fn handle_stage_speech_to_text_actual_transcribing(
     bot: &Bot,
     message_context: &MessageContext,
     agent: &AgentInstance,
@@ -779,6 +1095,17 @@ async fn handle_stage_speech_to_text_actual_transcribing(
         .instrument(span)
         .await?;
 
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_speech_to_text(
+                audio_content.info.as_ref().and_then(|info| info.duration),
+            ),
+        )
+        .await;
+
     // Only use the `> 🦻 Transcribed text` format if we're posting in a thread.
     //
     // If we're dealing with a regular reply (which would be the case in "Transcribe-only mode" = speech-to-text/flow-type=only_transcribe),
@@ -873,7 +1200,7 @@ async fn generate_and_send_tts_for_message(
     event_id: OwnedEventId,
     text: &str,
 ) -> bool {
-    let speech_agent = get_effective_agent_for_purpose_or_complain(
+    let speech_agents = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::TextToSpeech,
@@ -882,7 +1209,7 @@ async fn generate_and_send_tts_for_message(
     )
     .await;
 
-    let Some(speech_agent) = speech_agent else {
+    let Some(speech_agents) = speech_agents else {
         return false;
     };
 
@@ -891,7 +1218,7 @@ async fn generate_and_send_tts_for_message(
         matrix_link,
         message_context,
         response_type,
-        &speech_agent,
+        &speech_agents,
         &event_id,
         text,
     )
@@ -0,0 +1,422 @@
+use mxlink::matrix_sdk::ruma::OwnedEventId;
+use mxlink::{MatrixLink, MessageResponseType};
+
+use crate::agent::failover::run_with_failover;
+use crate::agent::{AgentInstance, AgentPurpose, ControllerTrait, PublicIdentifier};
+use crate::controller::utils::agent::{
+    get_effective_agent_chain_for_purpose_or_complain, notify_if_served_by_fallback_agent,
+};
+use crate::controller::utils::quota::ensure_within_quotas;
+use crate::controller::utils::without_conversation_commands;
+use crate::conversation::create_llm_conversation_for_matrix_thread_before_event;
+use crate::conversation::matrix::{
+    MatrixMessageProcessingParams, create_list_of_bot_user_prefixes_to_strip,
+    find_bot_answer_to_last_user_message_in_thread, find_last_text_message_in_thread_by_sender,
+};
+use crate::entity::roomconfig::QuotaMetric;
+use crate::entity::usage::UsageCounters;
+use crate::strings;
+use crate::{Bot, entity::MessageContext};
+
+/// Generates a new answer in place of an earlier one, based on the thread's messages preceding it.
+///
+/// The earlier answer is replaced via an edit, so its previous versions remain available in the message's edit history.
+///
+/// When `answer_event_id` is not provided, the bot's last answer in the thread is regenerated.
+/// When `agent_identifier` is provided, that agent is used instead of the room's text-generation handler (and its fallbacks).
+pub async fn handle(
+    bot: &Bot,
+    matrix_link: MatrixLink,
+    message_context: &MessageContext,
+    answer_event_id: Option<&OwnedEventId>,
+    agent_identifier: Option<&PublicIdentifier>,
+) -> anyhow::Result<()> {
+    handle_cancellable_via(
+        bot,
+        matrix_link,
+        message_context,
+        answer_event_id,
+        agent_identifier,
+        None,
+    )
+    .await
+}
+
+/// Like [`handle`], but the regeneration can also be cancelled via the given event (besides the triggering event and the answer).
+async fn handle_cancellable_via(
+    bot: &Bot,
+    matrix_link: MatrixLink,
+    message_context: &MessageContext,
+    answer_event_id: Option<&OwnedEventId>,
+    agent_identifier: Option<&PublicIdentifier>,
+    cancellable_via_event_id: Option<&OwnedEventId>,
+) -> anyhow::Result<()> {
+    let response_type = MessageResponseType::InThread(message_context.thread_info().clone());
+
+    if message_context.thread_info().is_thread_root_only() {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::regenerate::not_in_thread(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    let answer_event_id = match answer_event_id {
+        Some(answer_event_id) => Some(answer_event_id.clone()),
+        None => {
+            find_last_text_message_in_thread_by_sender(
+                &matrix_link,
+                message_context.room(),
+                message_context.thread_info().root_event_id.clone(),
+                bot.user_id(),
+            )
+            .await?
+        }
+    };
+
+    let Some(answer_event_id) = answer_event_id else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::regenerate::nothing_to_regenerate(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    if !ensure_within_quotas(
+        bot,
+        message_context,
+        &[QuotaMetric::Tokens, QuotaMetric::Cost],
+        response_type.clone(),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    let agents = match agent_identifier {
+        Some(agent_identifier) => {
+            let agent = bot
+                .agent_manager()
+                .available_room_agents_by_room_config_context(message_context.room_config_context())
+                .into_iter()
+                .find(|agent| agent.identifier() == agent_identifier);
+
+            let error_message = match &agent {
+                None => Some(strings::agent::agent_with_given_identifier_missing(
+                    agent_identifier,
+                )),
+                Some(agent)
+                    if !agent
+                        .controller()
+                        .supports_purpose(AgentPurpose::TextGeneration) =>
+                {
+                    Some(strings::regenerate::agent_does_not_support_text_generation(
+                        agent_identifier,
+                    ))
+                }
+                Some(_) => None,
+            };
+
+            if let Some(error_message) = error_message {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &error_message,
+                        response_type,
+                    )
+                    .await;
+
+                return Ok(());
+            }
+
+            agent.into_iter().collect()
+        }
+        None => {
+            let Some(agents) = get_effective_agent_chain_for_purpose_or_complain(
+                bot,
+                message_context,
+                AgentPurpose::TextGeneration,
+                response_type.clone(),
+                true,
+            )
+            .await
+            else {
+                return Ok(());
+            };
+
+            agents
+        }
+    };
+
+    let _typing_notice_guard = bot.start_typing_notice(message_context.room()).await;
+
+    let progress_reaction_event_id = bot
+        .reacting()
+        .react_no_fail(
+            message_context.room(),
+            answer_event_id.clone(),
+            strings::PROGRESS_INDICATOR_EMOJI.to_owned(),
+        )
+        .await
+        .map(|response| response.event_id);
+
+    // Lets the sender (or an administrator) cancel the regeneration, by reacting to (or redacting) the triggering message or the answer.
+    let mut in_flight_generation = bot.in_flight_generation_tracker().start(
+        message_context.room_id(),
+        message_context.event_id(),
+        message_context.sender_id(),
+    );
+    in_flight_generation.track_event(answer_event_id.clone());
+    if let Some(event_id) = cancellable_via_event_id {
+        in_flight_generation.track_event(event_id.clone());
+    }
+
+    let result = in_flight_generation
+        .run(regenerate_text(
+            bot,
+            &matrix_link,
+            message_context,
+            &answer_event_id,
+            &agents,
+            agent_identifier.is_none(),
+        ))
+        .await;
+
+    drop(in_flight_generation);
+
+    if let Some(progress_reaction_event_id) = progress_reaction_event_id {
+        bot.messaging()
+            .redact_event_no_fail(
+                message_context.room(),
+                progress_reaction_event_id,
+                Some("Done".to_owned()),
+            )
+            .await;
+    }
+
+    let Some(result) = result else {
+        tracing::info!("Regeneration got cancelled");
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::cancellation::cancelled(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    let Some((agent, text)) = result? else {
+        return Ok(());
+    };
+
+    let edited = bot
+        .messaging()
+        .edit_text_markdown_no_fail(message_context.room(), &answer_event_id, text.clone())
+        .await
+        .is_some();
+
+    if !edited {
+        // Rather than losing the new answer, post it as a new message.
+        bot.messaging()
+            .send_text_markdown_no_fail(message_context.room(), text, response_type.clone())
+            .await;
+    }
+
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::TextGeneration,
+        response_type,
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Regenerates the bot's answer to a message which just got edited, so that the answer reflects the edited content.
+///
+/// Only the answer to the last user message in the thread is regenerated, as answers further up the thread
+/// are followed by messages which relied on them.
+pub async fn handle_edit(
+    bot: &Bot,
+    matrix_link: MatrixLink,
+    message_context: &MessageContext,
+    edited_event_id: &OwnedEventId,
+) -> anyhow::Result<()> {
+    let answer_event_id = find_bot_answer_to_last_user_message_in_thread(
+        &matrix_link,
+        message_context.room(),
+        message_context.thread_info().root_event_id.clone(),
+        bot.user_id(),
+        edited_event_id,
+    )
+    .await?;
+
+    let Some(answer_event_id) = answer_event_id else {
+        tracing::debug!(
+            ?edited_event_id,
+            "Not regenerating, as the edited message is not the last one we've answered in the thread"
+        );
+        return Ok(());
+    };
+
+    // Clients show reactions on the edited message (not on the edit event), so that's where a ❌ would go.
+    handle_cancellable_via(
+        bot,
+        matrix_link,
+        message_context,
+        Some(&answer_event_id),
+        None,
+        Some(edited_event_id),
+    )
+    .await
+}
+
+/// Generates the new answer's text, complaining to the room (and returning `None`) when generation fails.
+async fn regenerate_text<'a>(
+    bot: &Bot,
+    matrix_link: &MatrixLink,
+    message_context: &MessageContext,
+    answer_event_id: &OwnedEventId,
+    agents: &'a [AgentInstance],
+    apply_model_override: bool,
+) -> anyhow::Result<Option<(&'a AgentInstance, String)>> {
+    let response_type = MessageResponseType::InThread(message_context.thread_info().clone());
+
+    let primary_agent = &agents[0];
+
+    // We don't know how the thread was started, so we strip the command prefix from its first message if it's there.
+    // Like regular (non-mention) text generation, only messages from allowed users are considered.
+    let params = MatrixMessageProcessingParams::new(
+        bot.user_id().to_owned(),
+        Some(message_context.combined_admin_and_user_regexes()),
+    )
+    .with_first_message_prefixes_to_strip(vec![bot.command_prefix().to_owned()])
+    .with_bot_user_prefixes_to_strip(create_list_of_bot_user_prefixes_to_strip(
+        bot.user_id(),
+        message_context.bot_display_name(),
+    ));
+
+    let conversation = create_llm_conversation_for_matrix_thread_before_event(
+        matrix_link,
+        message_context.room(),
+        message_context.thread_info().root_event_id.clone(),
+        answer_event_id,
+        &params,
+    )
+    .await?;
+
+    let Some(conversation) = conversation.filter(|conversation| !conversation.messages.is_empty())
+    else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::regenerate::nothing_to_regenerate(),
+                response_type,
+            )
+            .await;
+
+        return Ok(None);
+    };
+
+    let conversation = super::inject_sender_context(
+        without_conversation_commands(
+            conversation,
+            bot.command_prefix(),
+            message_context.bot_display_name(),
+        ),
+        message_context
+            .room_config_context()
+            .text_generation_sender_context_mode(),
+    );
+
+    let (agent, result) = run_with_failover(agents, AgentPurpose::TextGeneration, async |agent| {
+        // An explicitly chosen agent may well be of another provider, which likely doesn't offer the overridden model.
+        let model_override = if apply_model_override {
+            super::text_generation_model_override(message_context, agent, primary_agent)
+        } else {
+            None
+        };
+
+        super::generate_text_via_agent(
+            bot,
+            message_context,
+            agent,
+            model_override,
+            None,
+            conversation.clone(),
+            None,
+        )
+        .await
+    })
+    .await;
+
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => {
+            tracing::warn!(
+                "Error in room {} while trying to regenerate text via agent {}: {:?}",
+                message_context.room_id(),
+                agent.identifier(),
+                err,
+            );
+
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::agent::error_while_serving_purpose(
+                        agent.identifier(),
+                        &AgentPurpose::TextGeneration,
+                        &err,
+                    ),
+                    response_type,
+                )
+                .await;
+
+            return Ok(None);
+        }
+    };
+
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_text_generation(&result.usage),
+        )
+        .await;
+
+    let text = result.text.trim().to_owned();
+
+    if text.is_empty() {
+        tracing::warn!(
+            agent_id = agent.identifier().as_string(),
+            "Agent returned empty text",
+        );
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::empty_response_returned(agent.identifier()),
+                response_type,
+            )
+            .await;
+
+        return Ok(None);
+    }
+
+    Ok(Some((agent, text)))
+}
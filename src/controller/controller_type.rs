@@ -13,13 +13,26 @@ pub enum ControllerType {
     ErrorInThread(String, mxlink::ThreadInfo),
 
     ProviderHelp,
+    ProviderModels {
+        provider: String,
+        base_url: Option<String>,
+    },
 
     Access(super::access::AccessControllerType),
 
     Agent(super::agent::AgentControllerType),
 
+    Prompt(super::prompt::PromptControllerType),
+
     Config(super::cfg::ConfigControllerType),
 
+    Usage(super::usage::UsageControllerType),
+
+    Export(super::export::ExportControllerType),
+
+    // Regenerates the bot's last answer in the thread, optionally via the given agent.
+    Regenerate(Option<crate::agent::PublicIdentifier>),
+
     ChatCompletion(super::chat_completion::ChatCompletionControllerType),
 
     ImageGeneration(String),
@@ -1,8 +1,12 @@
 #[cfg(test)]
 mod tests;
 
+use mxlink::ThreadInfo;
+use mxlink::matrix_sdk::ruma::events::room::message::sanitize::remove_plain_reply_fallback;
+
 use super::chat_completion::ChatCompletionControllerType;
 use crate::{
+    agent::PublicIdentifier,
     entity::{
         InteractionTrigger, MessageContext, MessagePayload,
         roomconfig::TextGenerationPrefixRequirementType,
@@ -16,6 +20,122 @@ pub fn determine_controller(
     command_prefix: &str,
     first_thread_message: &InteractionTrigger,
     message_context: &MessageContext,
+) -> ControllerType {
+    let controller_type =
+        determine_thread_controller(command_prefix, first_thread_message, message_context);
+
+    // Conversation commands (exporting, regenerating) are about the conversation they're posted in (unlike other commands, which are thread roots),
+    // so they're detected based on the current message, but only in conversations we'd otherwise be taking part in.
+    if controller_type != ControllerType::Ignore
+        && let MessagePayload::Text(text_message_content) = message_context.payload()
+        && let Some(arguments) = conversation_command_arguments(
+            command_prefix,
+            "export",
+            &text_message_content.body,
+            message_context.bot_display_name(),
+        )
+    {
+        return super::export::determine_controller(
+            arguments,
+            &first_thread_message.payload,
+            message_context.thread_info(),
+        );
+    }
+
+    if controller_type != ControllerType::Ignore
+        && let MessagePayload::Text(text_message_content) = message_context.payload()
+        && let Some(arguments) = conversation_command_arguments(
+            command_prefix,
+            "regenerate",
+            &text_message_content.body,
+            message_context.bot_display_name(),
+        )
+    {
+        return determine_regenerate_controller(
+            arguments,
+            &first_thread_message.payload,
+            message_context.thread_info(),
+        );
+    }
+
+    controller_type
+}
+
+fn determine_regenerate_controller(
+    arguments: &str,
+    first_thread_message_payload: &MessagePayload,
+    thread_info: &ThreadInfo,
+) -> ControllerType {
+    if thread_info.is_thread_root_only()
+        || matches!(
+            first_thread_message_payload,
+            MessagePayload::SynthethicChatCompletionTriggerForReply
+        )
+    {
+        return ControllerType::Error(strings::regenerate::not_in_thread().to_owned());
+    }
+
+    if arguments.is_empty() {
+        return ControllerType::Regenerate(None);
+    }
+
+    match PublicIdentifier::from_str(arguments) {
+        Some(agent_identifier) => ControllerType::Regenerate(Some(agent_identifier)),
+        None => ControllerType::Error(strings::agent::invalid_id_generic()),
+    }
+}
+
+/// Commands which act on the conversation they're posted in, instead of starting a thread of their own.
+const CONVERSATION_COMMANDS: &[&str] = &["export", "regenerate"];
+
+/// Tells if the given message body is one of the [`CONVERSATION_COMMANDS`].
+pub(super) fn is_conversation_command(
+    command_prefix: &str,
+    body: &str,
+    bot_display_name: &Option<String>,
+) -> bool {
+    CONVERSATION_COMMANDS.iter().any(|command| {
+        conversation_command_arguments(command_prefix, command, body, bot_display_name).is_some()
+    })
+}
+
+/// Extracts the arguments of a command (e.g. `json` from `!bai export json`) from the body of a message
+/// posted within a conversation (as opposed to commands which start threads).
+///
+/// Such messages are replies (in threads or reply chains), so the body may carry a reply fallback.
+/// In reply chains, the command needs to mention the bot, so it may also be preceded by a mention (e.g. `@baibot: !bai export`).
+pub(super) fn conversation_command_arguments<'a>(
+    command_prefix: &str,
+    command: &str,
+    body: &'a str,
+    bot_display_name: &Option<String>,
+) -> Option<&'a str> {
+    let body = remove_plain_reply_fallback(body).trim();
+
+    let (before, after) = body.split_once(&format!("{command_prefix} {command}"))?;
+
+    // Something like `!bai exports` is not our command
+    if after.starts_with(|c: char| !c.is_whitespace()) {
+        return None;
+    }
+
+    let before = before.trim().trim_end_matches(':').trim_start_matches('@');
+
+    let is_preceded_by_mention = before.is_empty()
+        || !before.contains(char::is_whitespace)
+        || bot_display_name.as_deref() == Some(before);
+
+    if !is_preceded_by_mention {
+        return None;
+    }
+
+    Some(after.trim())
+}
+
+fn determine_thread_controller(
+    command_prefix: &str,
+    first_thread_message: &InteractionTrigger,
+    message_context: &MessageContext,
 ) -> ControllerType {
     match &first_thread_message.payload {
         MessagePayload::SynthethicChatCompletionTriggerInThread => {
@@ -96,13 +216,17 @@ fn determine_text_controller(
     }
 
     if let Some(remaining) = text.strip_prefix(&format!("{command_prefix} provider")) {
-        return super::provider::determine_controller(remaining.trim());
+        return super::provider::determine_controller(command_prefix, remaining.trim());
     }
 
     if let Some(remaining) = text.strip_prefix(&format!("{command_prefix} agent")) {
         return super::agent::determine_controller(command_prefix, remaining.trim());
     }
 
+    if let Some(remaining) = text.strip_prefix(&format!("{command_prefix} prompt")) {
+        return super::prompt::determine_controller(command_prefix, remaining.trim());
+    }
+
     if let Some(remaining) = text.strip_prefix(&format!("{command_prefix} config")) {
         return super::cfg::determine_controller(remaining.trim());
     }
@@ -59,6 +59,38 @@ fn determine_text_controller() {
                 super::TextGenerationPrefixRequirementType::No,
             expected: ControllerType::ProviderHelp,
         },
+        TestCase {
+            name: "Provider models",
+            input: "!bai provider models ollama http://ollama:11434/v1",
+            is_mentioning_bot: false,
+            room_text_generation_prefix_requirement_type:
+                super::TextGenerationPrefixRequirementType::No,
+            expected: ControllerType::ProviderModels {
+                provider: "ollama".to_owned(),
+                base_url: Some("http://ollama:11434/v1".to_owned()),
+            },
+        },
+        TestCase {
+            name: "Provider models without base URL",
+            input: "!bai provider models openrouter",
+            is_mentioning_bot: false,
+            room_text_generation_prefix_requirement_type:
+                super::TextGenerationPrefixRequirementType::No,
+            expected: ControllerType::ProviderModels {
+                provider: "openrouter".to_owned(),
+                base_url: None,
+            },
+        },
+        TestCase {
+            name: "Provider models without provider",
+            input: "!bai provider models",
+            is_mentioning_bot: false,
+            room_text_generation_prefix_requirement_type:
+                super::TextGenerationPrefixRequirementType::No,
+            expected: ControllerType::Error(crate::strings::provider::models_incorrect_invocation(
+                "!bai",
+            )),
+        },
         TestCase {
             name: "Usage",
             input: "!bai usage",
@@ -67,6 +99,14 @@ fn determine_text_controller() {
                 super::TextGenerationPrefixRequirementType::No,
             expected: ControllerType::UsageHelp,
         },
+        TestCase {
+            name: "Usage room",
+            input: "!bai usage room",
+            is_mentioning_bot: false,
+            room_text_generation_prefix_requirement_type:
+                super::TextGenerationPrefixRequirementType::No,
+            expected: ControllerType::Usage(controller::usage::UsageControllerType::Room),
+        },
         TestCase {
             name: "Agent top-level",
             input: "!bai agent",
@@ -75,6 +115,22 @@ fn determine_text_controller() {
                 super::TextGenerationPrefixRequirementType::No,
             expected: ControllerType::Agent(controller::agent::AgentControllerType::Help),
         },
+        TestCase {
+            name: "Prompt top-level",
+            input: "!bai prompt",
+            is_mentioning_bot: false,
+            room_text_generation_prefix_requirement_type:
+                super::TextGenerationPrefixRequirementType::No,
+            expected: ControllerType::Prompt(controller::prompt::PromptControllerType::Help),
+        },
+        TestCase {
+            name: "Prompt list",
+            input: "!bai prompt list",
+            is_mentioning_bot: false,
+            room_text_generation_prefix_requirement_type:
+                super::TextGenerationPrefixRequirementType::No,
+            expected: ControllerType::Prompt(controller::prompt::PromptControllerType::List),
+        },
         TestCase {
             name: "Config top-level",
             input: "!bai config",
@@ -169,3 +225,140 @@ fn determine_text_controller() {
         assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
     }
 }
+
+#[test]
+fn conversation_command_arguments() {
+    let bot_display_name = Some("Bai Bot".to_owned());
+
+    struct TestCase {
+        name: &'static str,
+        body: &'static str,
+        expected: Option<&'static str>,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "Command only",
+            body: "!bai export",
+            expected: Some(""),
+        },
+        TestCase {
+            name: "Command with format",
+            body: "  !bai export json ",
+            expected: Some("json"),
+        },
+        TestCase {
+            name: "Preceded by a user ID mention",
+            body: "@baibot:example.com: !bai export md",
+            expected: Some("md"),
+        },
+        TestCase {
+            name: "Preceded by a display name mention",
+            body: "Bai Bot: !bai export",
+            expected: Some(""),
+        },
+        TestCase {
+            name: "With a reply fallback",
+            body: "> <@alice:example.com> Hello\n\n!bai export",
+            expected: Some(""),
+        },
+        TestCase {
+            name: "Other command",
+            body: "!bai exports",
+            expected: None,
+        },
+        TestCase {
+            name: "Mentioned within a sentence",
+            body: "How does !bai export work?",
+            expected: None,
+        },
+        TestCase {
+            name: "Regular message",
+            body: "Hello",
+            expected: None,
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::conversation_command_arguments(
+            "!bai",
+            "export",
+            test_case.body,
+            &bot_display_name,
+        );
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
+
+#[test]
+fn determine_regenerate_controller() {
+    use mxlink::ThreadInfo;
+    use mxlink::matrix_sdk::ruma::OwnedEventId;
+
+    use super::ControllerType;
+    use crate::agent::PublicIdentifier;
+    use crate::entity::MessagePayload;
+
+    let root_event_id = OwnedEventId::try_from("$root:example.com").unwrap();
+    let reply_event_id = OwnedEventId::try_from("$reply:example.com").unwrap();
+
+    let in_thread = ThreadInfo::new(root_event_id.clone(), reply_event_id);
+    let top_level = ThreadInfo::new(root_event_id.clone(), root_event_id);
+
+    struct TestCase {
+        name: &'static str,
+        arguments: &'static str,
+        payload: MessagePayload,
+        thread_info: ThreadInfo,
+        expected: ControllerType,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "Regenerating via the room's handler",
+            arguments: "",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: in_thread.clone(),
+            expected: ControllerType::Regenerate(None),
+        },
+        TestCase {
+            name: "Regenerating via another agent",
+            arguments: "global/claude",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: in_thread.clone(),
+            expected: ControllerType::Regenerate(Some(PublicIdentifier::DynamicGlobal(
+                "claude".to_owned(),
+            ))),
+        },
+        TestCase {
+            name: "Invalid agent ID",
+            arguments: "claude",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: in_thread.clone(),
+            expected: ControllerType::Error(crate::strings::agent::invalid_id_generic()),
+        },
+        TestCase {
+            name: "Reply chains are not supported",
+            arguments: "",
+            payload: MessagePayload::SynthethicChatCompletionTriggerForReply,
+            thread_info: in_thread,
+            expected: ControllerType::Error(crate::strings::regenerate::not_in_thread().to_owned()),
+        },
+        TestCase {
+            name: "Top-level messages have nothing to regenerate",
+            arguments: "",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: top_level,
+            expected: ControllerType::Error(crate::strings::regenerate::not_in_thread().to_owned()),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine_regenerate_controller(
+            test_case.arguments,
+            &test_case.payload,
+            &test_case.thread_info,
+        );
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
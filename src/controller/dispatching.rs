@@ -1,6 +1,6 @@
 use mxlink::MessageResponseType;
 
-use crate::{Bot, entity::MessageContext, strings};
+use crate::{Bot, entity::MessageContext, entity::roomconfig::QuotaMetric, strings};
 
 use super::ControllerType;
 
@@ -9,6 +9,18 @@ pub async fn dispatch_controller(
     message_context: &MessageContext,
     bot: &Bot,
 ) {
+    if let Some(metrics) = quota_metrics_for_controller(controller_type)
+        && !super::utils::quota::ensure_within_quotas(
+            bot,
+            message_context,
+            metrics,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await
+    {
+        return;
+    }
+
     let result = match controller_type {
         ControllerType::Access(controller_type) => {
             super::access::dispatch_controller(controller_type, message_context, bot).await
@@ -16,6 +28,9 @@ pub async fn dispatch_controller(
         ControllerType::Agent(controller_type) => {
             super::agent::dispatch_controller(controller_type, message_context, bot).await
         }
+        ControllerType::Prompt(controller_type) => {
+            super::prompt::dispatch_controller(controller_type, message_context, bot).await
+        }
         ControllerType::Config(controller_type) => {
             super::cfg::dispatch_controller(controller_type, message_context, bot).await
         }
@@ -32,7 +47,27 @@ pub async fn dispatch_controller(
             Ok(())
         }
         ControllerType::ProviderHelp => super::provider::handle_help(message_context, bot).await,
+        ControllerType::ProviderModels { provider, base_url } => {
+            super::provider::handle_models(message_context, bot, provider, base_url.as_deref())
+                .await
+        }
         ControllerType::UsageHelp => super::usage::handle_help(message_context, bot).await,
+        ControllerType::Usage(controller_type) => {
+            super::usage::dispatch_controller(controller_type, message_context, bot).await
+        }
+        ControllerType::Export(controller_type) => {
+            super::export::handle(bot, message_context, controller_type).await
+        }
+        ControllerType::Regenerate(agent_identifier) => {
+            super::chat_completion::regenerate::handle(
+                bot,
+                bot.matrix_link().clone(),
+                message_context,
+                None,
+                agent_identifier.as_ref(),
+            )
+            .await
+        }
         ControllerType::ChatCompletion(controller_type) => {
             super::chat_completion::handle(
                 bot,
@@ -110,3 +145,20 @@ pub async fn dispatch_controller(
             .await;
     }
 }
+
+/// Returns the quota metrics that handling the given controller would consume, if any.
+///
+/// Text-to-speech is not dispatched from here (it follows text-generation or a reaction),
+/// so its quotas are enforced where speech is generated.
+/// Likewise, regenerating answers may be triggered via a reaction, so the regeneration handler enforces its own quotas.
+fn quota_metrics_for_controller(
+    controller_type: &ControllerType,
+) -> Option<&'static [QuotaMetric]> {
+    match controller_type {
+        ControllerType::ChatCompletion(_) => Some(&[QuotaMetric::Tokens, QuotaMetric::Cost]),
+        ControllerType::ImageGeneration(_)
+        | ControllerType::ImageEdit(_)
+        | ControllerType::StickerGeneration(_) => Some(&[QuotaMetric::Images]),
+        _ => None,
+    }
+}
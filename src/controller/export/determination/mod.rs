@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests;
+
+use mxlink::ThreadInfo;
+
+use super::super::ControllerType;
+use crate::{entity::MessagePayload, strings};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn choices() -> Vec<&'static str> {
+        vec!["markdown", "json"]
+    }
+
+    fn from_str(text: &str) -> Option<Self> {
+        match text {
+            "" | "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExportControllerType {
+    Thread(ExportFormat),
+    ReplyChain(ExportFormat),
+}
+
+/// Determines what to export, based on the arguments of the export command (`text`)
+/// and on where the command was posted (a thread or a reply chain).
+pub fn determine_controller(
+    text: &str,
+    first_thread_message_payload: &MessagePayload,
+    thread_info: &ThreadInfo,
+) -> ControllerType {
+    let Some(format) = ExportFormat::from_str(text) else {
+        return ControllerType::Error(strings::export::unknown_format(text));
+    };
+
+    if let MessagePayload::SynthethicChatCompletionTriggerForReply = first_thread_message_payload {
+        return ControllerType::Export(ExportControllerType::ReplyChain(format));
+    }
+
+    if thread_info.is_thread_root_only() {
+        return ControllerType::Error(strings::export::not_in_conversation().to_owned());
+    }
+
+    ControllerType::Export(ExportControllerType::Thread(format))
+}
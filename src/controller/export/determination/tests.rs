@@ -0,0 +1,74 @@
+use mxlink::ThreadInfo;
+use mxlink::matrix_sdk::ruma::OwnedEventId;
+
+use crate::entity::MessagePayload;
+
+#[test]
+fn determine_controller() {
+    use super::{ControllerType, ExportControllerType, ExportFormat};
+
+    let root_event_id = OwnedEventId::try_from("$root:example.com").unwrap();
+    let reply_event_id = OwnedEventId::try_from("$reply:example.com").unwrap();
+
+    let in_thread = ThreadInfo::new(root_event_id.clone(), reply_event_id.clone());
+    let top_level = ThreadInfo::new(root_event_id.clone(), root_event_id.clone());
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        payload: MessagePayload,
+        thread_info: ThreadInfo,
+        expected: ControllerType,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "Thread defaults to Markdown",
+            input: "",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: in_thread.clone(),
+            expected: ControllerType::Export(ExportControllerType::Thread(ExportFormat::Markdown)),
+        },
+        TestCase {
+            name: "Thread as JSON",
+            input: "json",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: in_thread.clone(),
+            expected: ControllerType::Export(ExportControllerType::Thread(ExportFormat::Json)),
+        },
+        TestCase {
+            name: "Reply chain as Markdown",
+            input: "md",
+            payload: MessagePayload::SynthethicChatCompletionTriggerForReply,
+            thread_info: in_thread.clone(),
+            expected: ControllerType::Export(ExportControllerType::ReplyChain(
+                ExportFormat::Markdown,
+            )),
+        },
+        TestCase {
+            name: "Top-level messages have nothing to export",
+            input: "",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: top_level,
+            expected: ControllerType::Error(
+                crate::strings::export::not_in_conversation().to_owned(),
+            ),
+        },
+        TestCase {
+            name: "Unknown format",
+            input: "pdf",
+            payload: MessagePayload::SynthethicChatCompletionTriggerInThread,
+            thread_info: in_thread,
+            expected: ControllerType::Error(crate::strings::export::unknown_format("pdf")),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine_controller(
+            test_case.input,
+            &test_case.payload,
+            &test_case.thread_info,
+        );
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
@@ -0,0 +1,144 @@
+mod determination;
+mod render;
+
+use std::collections::HashMap;
+
+use mxlink::MessageResponseType;
+use mxlink::mime::Mime;
+
+use crate::controller::utils::without_conversation_commands;
+use crate::conversation::matrix::MatrixMessageProcessingParams;
+use crate::conversation::{
+    create_llm_conversation_for_matrix_reply_chain, create_llm_conversation_for_matrix_thread,
+};
+use crate::{Bot, entity::MessageContext, strings};
+
+pub use determination::{ExportControllerType, ExportFormat, determine_controller};
+
+pub async fn handle(
+    bot: &Bot,
+    message_context: &MessageContext,
+    controller_type: &ExportControllerType,
+) -> anyhow::Result<()> {
+    let room = message_context.room();
+
+    // Exports are meant to be complete, so messages from all senders are included (not just from allowed users).
+    let params = MatrixMessageProcessingParams::new(bot.user_id().to_owned(), None);
+
+    let (conversation, format, response_type) = match controller_type {
+        ExportControllerType::Thread(format) => {
+            let conversation = create_llm_conversation_for_matrix_thread(
+                bot.matrix_link(),
+                room,
+                message_context.thread_info().root_event_id.clone(),
+                &params,
+            )
+            .await?;
+
+            (
+                conversation,
+                format,
+                MessageResponseType::InThread(message_context.thread_info().clone()),
+            )
+        }
+        ExportControllerType::ReplyChain(format) => {
+            // The reply chain is fetched starting from the message being replied to,
+            // so the export command itself is not part of it.
+            let conversation = create_llm_conversation_for_matrix_reply_chain(
+                bot.matrix_link(),
+                &bot.room_event_fetcher().clone(),
+                room,
+                message_context.thread_info().root_event_id.clone(),
+                &params,
+            )
+            .await?;
+
+            (
+                conversation,
+                format,
+                MessageResponseType::Reply(message_context.event_id().clone()),
+            )
+        }
+    };
+
+    let conversation = without_conversation_commands(
+        conversation,
+        bot.command_prefix(),
+        message_context.bot_display_name(),
+    );
+
+    if conversation.messages.is_empty() {
+        bot.messaging()
+            .send_error_markdown_no_fail(room, strings::export::nothing_to_export(), response_type)
+            .await;
+
+        return Ok(());
+    }
+
+    let exported_at = chrono::Utc::now();
+
+    let (data, mime, extension) = match format {
+        ExportFormat::Markdown => {
+            let mut display_names = HashMap::new();
+
+            for sender_id in conversation
+                .messages
+                .iter()
+                .filter_map(|message| message.sender_id.as_ref())
+            {
+                if display_names.contains_key(sender_id) {
+                    continue;
+                }
+
+                match room.get_member_no_sync(sender_id).await {
+                    Ok(Some(member)) => {
+                        if let Some(display_name) = member.display_name() {
+                            display_names.insert(sender_id.clone(), display_name.to_owned());
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        tracing::warn!(?err, ?sender_id, "Failed fetching room membership");
+                    }
+                }
+            }
+
+            let details = render::ExportDetails {
+                room_name: room.name().unwrap_or_else(|| room.room_id().to_string()),
+                exported_at,
+                display_names,
+            };
+
+            (
+                render::render_markdown(&conversation, &details),
+                "text/markdown; charset=utf-8".parse::<Mime>()?,
+                "md",
+            )
+        }
+        ExportFormat::Json => (
+            render::render_json(&conversation)?,
+            mxlink::mime::APPLICATION_JSON,
+            "json",
+        ),
+    };
+
+    let file_name = format!(
+        "conversation-{}.{}",
+        exported_at.format("%Y%m%d-%H%M%S"),
+        extension
+    );
+
+    let mut event_content = bot
+        .matrix_link()
+        .media()
+        .upload_and_prepare_event_content(room, &mime, data.into_bytes(), &file_name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to upload and prepare event: {}", e))?;
+
+    bot.matrix_link()
+        .messaging()
+        .send_event(room, &mut event_content, response_type)
+        .await?;
+
+    Ok(())
+}
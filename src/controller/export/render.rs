@@ -0,0 +1,292 @@
+//! Rendering conversations for use outside of Matrix: as a human-readable Markdown transcript,
+//! or as JSON in the shape of an OpenAI chat completion request's `messages` list.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use mxlink::matrix_sdk::ruma::OwnedUserId;
+use mxlink::matrix_sdk::ruma::events::room::MediaSource;
+use serde::Serialize;
+
+use crate::conversation::llm::{Author, Conversation, Message, MessageContent};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+/// Details about where a conversation comes from, shown at the top of exports.
+pub struct ExportDetails {
+    pub room_name: String,
+    pub exported_at: DateTime<Utc>,
+    /// Display names of the conversation's participants, where known.
+    pub display_names: HashMap<OwnedUserId, String>,
+}
+
+impl ExportDetails {
+    fn sender_label(&self, message: &Message) -> String {
+        let Some(sender_id) = &message.sender_id else {
+            return match message.author {
+                Author::Assistant => "Assistant".to_owned(),
+                Author::User => "User".to_owned(),
+                Author::Prompt => "System".to_owned(),
+            };
+        };
+
+        match self.display_names.get(sender_id) {
+            Some(display_name) if display_name != sender_id.as_str() => {
+                format!("{display_name} ({sender_id})")
+            }
+            _ => sender_id.to_string(),
+        }
+    }
+}
+
+pub fn render_markdown(conversation: &Conversation, details: &ExportDetails) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Conversation export\n\n");
+    output.push_str(&format!("- Room: {}\n", details.room_name));
+    output.push_str(&format!(
+        "- Exported at: {}\n",
+        details.exported_at.format(TIMESTAMP_FORMAT)
+    ));
+    output.push_str(&format!("- Messages: {}\n", conversation.messages.len()));
+
+    for message in &conversation.messages {
+        output.push_str("\n---\n\n");
+        output.push_str(&format!(
+            "**{}** · {}\n\n",
+            details.sender_label(message),
+            message.timestamp.format(TIMESTAMP_FORMAT)
+        ));
+
+        let content = match &message.content {
+            MessageContent::Text(text) => text.trim().to_owned(),
+            MessageContent::Image(image_details) => format!(
+                "🖼️ [{}]({})",
+                image_details.filename(),
+                media_url(&image_details.event_content.source)
+            ),
+            MessageContent::File(file_details) => format!(
+                "📎 [{}]({})",
+                file_details.filename(),
+                media_url(&file_details.event_content.source)
+            ),
+        };
+
+        output.push_str(&content);
+        output.push('\n');
+    }
+
+    output
+}
+
+#[derive(Serialize)]
+struct JsonExport {
+    messages: Vec<JsonMessage>,
+}
+
+#[derive(Serialize)]
+struct JsonMessage {
+    role: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    content: JsonContent,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum JsonContent {
+    Text(String),
+    Parts(Vec<JsonContentPart>),
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonContentPart {
+    Text { text: String },
+    ImageUrl { image_url: JsonImageUrl },
+}
+
+#[derive(Serialize)]
+struct JsonImageUrl {
+    url: String,
+}
+
+/// Renders the conversation as `{"messages": [...]}`, which can be used as (the beginning of) a chat completion request.
+///
+/// Images point to their `mxc://` URIs, while files (which have no URL-based equivalent) are mentioned in text parts.
+pub fn render_json(conversation: &Conversation) -> anyhow::Result<String> {
+    let messages = conversation
+        .messages
+        .iter()
+        .map(|message| {
+            let role = match message.author {
+                Author::Assistant => "assistant",
+                Author::User => "user",
+                Author::Prompt => "system",
+            };
+
+            let name = match message.author {
+                Author::User => message
+                    .sender_id
+                    .as_ref()
+                    .map(|id| openai_name(id.as_str())),
+                Author::Assistant | Author::Prompt => None,
+            };
+
+            let content = match &message.content {
+                MessageContent::Text(text) => JsonContent::Text(text.clone()),
+                MessageContent::Image(image_details) => {
+                    JsonContent::Parts(vec![JsonContentPart::ImageUrl {
+                        image_url: JsonImageUrl {
+                            url: media_url(&image_details.event_content.source),
+                        },
+                    }])
+                }
+                MessageContent::File(file_details) => {
+                    JsonContent::Parts(vec![JsonContentPart::Text {
+                        text: format!(
+                            "[file: {}]({})",
+                            file_details.filename(),
+                            media_url(&file_details.event_content.source)
+                        ),
+                    }])
+                }
+            };
+
+            JsonMessage {
+                role,
+                name,
+                content,
+            }
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&JsonExport { messages })?)
+}
+
+fn media_url(source: &MediaSource) -> String {
+    match source {
+        MediaSource::Plain(uri) => uri.to_string(),
+        MediaSource::Encrypted(file) => file.url.to_string(),
+    }
+}
+
+/// Turns a Matrix user ID into a participant name accepted by OpenAI (`^[a-zA-Z0-9_-]{1,64}$`).
+fn openai_name(user_id: &str) -> String {
+    user_id
+        .trim_start_matches('@')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use mxlink::matrix_sdk::ruma::OwnedMxcUri;
+    use mxlink::matrix_sdk::ruma::events::room::message::FileMessageEventContent;
+
+    use super::*;
+    use crate::conversation::llm::FileDetails;
+
+    fn conversation() -> Conversation {
+        let alice = OwnedUserId::try_from("@alice:example.com").unwrap();
+        let bot = OwnedUserId::try_from("@baibot:example.com").unwrap();
+
+        Conversation {
+            messages: vec![
+                Message {
+                    author: Author::User,
+                    sender_id: Some(alice.clone()),
+                    content: MessageContent::Text("What's in this file?".to_owned()),
+                    timestamp: DateTime::from_timestamp(0, 0).unwrap(),
+                },
+                Message {
+                    author: Author::User,
+                    sender_id: Some(alice),
+                    content: MessageContent::File(FileDetails::new(
+                        FileMessageEventContent::plain(
+                            "notes.txt".to_owned(),
+                            OwnedMxcUri::from("mxc://example.com/notes"),
+                        ),
+                        mxlink::mime::TEXT_PLAIN,
+                        vec![],
+                    )),
+                    timestamp: DateTime::from_timestamp(60, 0).unwrap(),
+                },
+                Message {
+                    author: Author::Assistant,
+                    sender_id: Some(bot),
+                    content: MessageContent::Text("Some notes.".to_owned()),
+                    timestamp: DateTime::from_timestamp(120, 0).unwrap(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn render_markdown_works() {
+        let details = ExportDetails {
+            room_name: "Test room".to_owned(),
+            exported_at: DateTime::from_timestamp(180, 0).unwrap(),
+            display_names: HashMap::from([(
+                OwnedUserId::try_from("@alice:example.com").unwrap(),
+                "Alice".to_owned(),
+            )]),
+        };
+
+        let expected = "# Conversation export
+
+- Room: Test room
+- Exported at: 1970-01-01 00:03:00 UTC
+- Messages: 3
+
+---
+
+**Alice (@alice:example.com)** · 1970-01-01 00:00:00 UTC
+
+What's in this file?
+
+---
+
+**Alice (@alice:example.com)** · 1970-01-01 00:01:00 UTC
+
+📎 [notes.txt](mxc://example.com/notes)
+
+---
+
+**@baibot:example.com** · 1970-01-01 00:02:00 UTC
+
+Some notes.
+";
+
+        assert_eq!(expected, render_markdown(&conversation(), &details));
+    }
+
+    #[test]
+    fn render_json_works() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&conversation()).unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "messages": [
+                    {"role": "user", "name": "alice_example_com", "content": "What's in this file?"},
+                    {"role": "user", "name": "alice_example_com", "content": [
+                        {"type": "text", "text": "[file: notes.txt](mxc://example.com/notes)"},
+                    ]},
+                    {"role": "assistant", "content": "Some notes."},
+                ],
+            })
+        );
+    }
+}
@@ -54,6 +54,17 @@ pub async fn handle(bot: &Bot, message_context: &MessageContext) -> anyhow::Resu
     ));
     message.push_str("\n\n");
 
+    // Prompt library
+    message.push_str(&format!("## {}", strings::help::prompt::heading()));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::prompt::intro());
+    message.push_str("\n\n");
+    message.push_str(&strings::help::learn_more_send_a_command(
+        bot.command_prefix(),
+        "prompt",
+    ));
+    message.push_str("\n\n");
+
     // Configuration
     message.push_str(&format!("## {}", strings::help::cfg::heading()));
     message.push_str("\n\n");
@@ -65,6 +76,12 @@ pub async fn handle(bot: &Bot, message_context: &MessageContext) -> anyhow::Resu
     ));
     message.push_str("\n\n");
 
+    // Export
+    message.push_str(&format!("## {}", strings::help::export::heading()));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::export::intro(bot.command_prefix()));
+    message.push_str("\n\n");
+
     // Usage
     message.push_str(&format!("## {}", strings::help::usage::heading()));
     message.push_str("\n\n");
@@ -4,14 +4,20 @@ use tracing::Instrument;
 
 use crate::agent::AgentPurpose;
 use crate::agent::ControllerTrait;
+use crate::agent::failover::run_with_failover;
 use crate::agent::provider::ImageEditParams;
 use crate::agent::provider::ImageSource;
-use crate::controller::utils::agent::get_effective_agent_for_purpose_or_complain;
+use crate::controller::utils::agent::{
+    get_effective_agent_chain_for_purpose_or_complain, notify_if_served_by_fallback_agent,
+};
 use crate::conversation::create_llm_conversation_for_matrix_thread;
 use crate::conversation::matrix::MatrixMessageProcessingParams;
 use crate::strings;
 use crate::utils::mime::get_file_extension;
-use crate::{Bot, entity::MessageContext};
+use crate::{
+    Bot,
+    entity::{MessageContext, usage::UsageCounters},
+};
 
 pub async fn handle(
     bot: &Bot,
@@ -21,7 +27,7 @@ pub async fn handle(
 ) -> anyhow::Result<()> {
     let response_type = MessageResponseType::InThread(message_context.thread_info().clone());
 
-    let Some(agent) = get_effective_agent_for_purpose_or_complain(
+    let Some(agents) = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::ImageGeneration,
@@ -87,14 +93,46 @@ pub async fn handle(
         return Ok(());
     }
 
-    let span = tracing::debug_span!("image_edit", agent_id = agent.identifier().as_string());
+    // Lets the sender (or an administrator) cancel the generation, by reacting to (or redacting) their message.
+    let in_flight_generation = bot.in_flight_generation_tracker().start(
+        message_context.room_id(),
+        message_context.event_id(),
+        message_context.sender_id(),
+    );
 
-    let result = agent
-        .controller()
-        .create_image_edit(&prompt, image_sources, ImageEditParams::default())
-        .instrument(span)
+    let result = in_flight_generation
+        .run(run_with_failover(
+            &agents,
+            AgentPurpose::ImageGeneration,
+            async |agent| {
+                let span =
+                    tracing::debug_span!("image_edit", agent_id = agent.identifier().as_string());
+
+                agent
+                    .controller()
+                    .create_image_edit(&prompt, image_sources.clone(), ImageEditParams::default())
+                    .instrument(span)
+                    .await
+            },
+        ))
         .await;
 
+    drop(in_flight_generation);
+
+    let Some((agent, result)) = result else {
+        tracing::info!("Image generation got cancelled");
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::cancellation::cancelled(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    };
+
     let response = match result {
         Ok(response) => response,
         Err(err) => {
@@ -121,6 +159,15 @@ pub async fn handle(
         }
     };
 
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_image_generation(1),
+        )
+        .await;
+
     let attachment_body_text = format!(
         "generated-image-edit.{}",
         get_file_extension(&response.mime_type)
@@ -146,6 +193,16 @@ pub async fn handle(
         )
         .await?;
 
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::ImageGeneration,
+        response_type,
+    )
+    .await;
+
     Ok(())
 }
 
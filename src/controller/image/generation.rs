@@ -4,13 +4,19 @@ use tracing::Instrument;
 
 use crate::agent::AgentPurpose;
 use crate::agent::ControllerTrait;
+use crate::agent::failover::run_with_failover;
 use crate::agent::provider::ImageGenerationParams;
-use crate::controller::utils::agent::get_effective_agent_for_purpose_or_complain;
+use crate::controller::utils::agent::{
+    get_effective_agent_chain_for_purpose_or_complain, notify_if_served_by_fallback_agent,
+};
 use crate::conversation::create_llm_conversation_for_matrix_thread;
 use crate::conversation::matrix::MatrixMessageProcessingParams;
 use crate::strings;
 use crate::utils::mime::get_file_extension;
-use crate::{Bot, entity::MessageContext};
+use crate::{
+    Bot,
+    entity::{MessageContext, usage::UsageCounters},
+};
 
 pub async fn handle_image(
     bot: &Bot,
@@ -20,7 +26,7 @@ pub async fn handle_image(
 ) -> anyhow::Result<()> {
     let response_type = MessageResponseType::InThread(message_context.thread_info().clone());
 
-    let Some(agent) = get_effective_agent_for_purpose_or_complain(
+    let Some(agents) = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::ImageGeneration,
@@ -56,17 +62,48 @@ pub async fn handle_image(
         original_prompt.to_owned()
     };
 
-    let span = tracing::debug_span!(
-        "image_generation",
-        agent_id = agent.identifier().as_string()
+    // Lets the sender (or an administrator) cancel the generation, by reacting to (or redacting) their message.
+    let in_flight_generation = bot.in_flight_generation_tracker().start(
+        message_context.room_id(),
+        message_context.event_id(),
+        message_context.sender_id(),
     );
 
-    let result = agent
-        .controller()
-        .generate_image(&prompt, ImageGenerationParams::default())
-        .instrument(span)
+    let result = in_flight_generation
+        .run(run_with_failover(
+            &agents,
+            AgentPurpose::ImageGeneration,
+            async |agent| {
+                let span = tracing::debug_span!(
+                    "image_generation",
+                    agent_id = agent.identifier().as_string()
+                );
+
+                agent
+                    .controller()
+                    .generate_image(&prompt, ImageGenerationParams::default())
+                    .instrument(span)
+                    .await
+            },
+        ))
         .await;
 
+    drop(in_flight_generation);
+
+    let Some((agent, result)) = result else {
+        tracing::info!("Image generation got cancelled");
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::cancellation::cancelled(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    };
+
     let response = match result {
         Ok(response) => response,
         Err(err) => {
@@ -93,6 +130,15 @@ pub async fn handle_image(
         }
     };
 
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_image_generation(1),
+        )
+        .await;
+
     let actual_prompt = response.revised_prompt.as_deref().unwrap_or(&prompt);
 
     if *actual_prompt.trim() != *prompt.trim() {
@@ -130,6 +176,16 @@ pub async fn handle_image(
         )
         .await?;
 
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::ImageGeneration,
+        response_type.clone(),
+    )
+    .await;
+
     if conversation.messages.len() == 1 {
         // If this is the beginning of the thread, send helpful instructions
         bot.messaging()
@@ -154,7 +210,7 @@ pub async fn handle_sticker(
     let response_type =
         MessageResponseType::Reply(message_context.thread_info().root_event_id.clone());
 
-    let Some(agent) = get_effective_agent_for_purpose_or_complain(
+    let Some(agents) = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::ImageGeneration,
@@ -168,22 +224,53 @@ pub async fn handle_sticker(
 
     let _typing_notice_guard = bot.start_typing_notice(message_context.room()).await;
 
-    let span = tracing::debug_span!(
-        "sticker_generation",
-        agent_id = agent.identifier().as_string()
+    // Lets the sender (or an administrator) cancel the generation, by reacting to (or redacting) their message.
+    let in_flight_generation = bot.in_flight_generation_tracker().start(
+        message_context.room_id(),
+        message_context.event_id(),
+        message_context.sender_id(),
     );
 
-    let params = ImageGenerationParams::default()
-        .with_smallest_size_possible(true)
-        .with_cheaper_model_switching_allowed(true)
-        .with_cheaper_quality_switching_allowed(true);
-
-    let result = agent
-        .controller()
-        .generate_image(original_prompt, params)
-        .instrument(span)
+    let result = in_flight_generation
+        .run(run_with_failover(
+            &agents,
+            AgentPurpose::ImageGeneration,
+            async |agent| {
+                let span = tracing::debug_span!(
+                    "sticker_generation",
+                    agent_id = agent.identifier().as_string()
+                );
+
+                let params = ImageGenerationParams::default()
+                    .with_smallest_size_possible(true)
+                    .with_cheaper_model_switching_allowed(true)
+                    .with_cheaper_quality_switching_allowed(true);
+
+                agent
+                    .controller()
+                    .generate_image(original_prompt, params)
+                    .instrument(span)
+                    .await
+            },
+        ))
         .await;
 
+    drop(in_flight_generation);
+
+    let Some((agent, result)) = result else {
+        tracing::info!("Image generation got cancelled");
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::cancellation::cancelled(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    };
+
     let response = match result {
         Ok(response) => response,
         Err(err) => {
@@ -210,6 +297,15 @@ pub async fn handle_sticker(
         }
     };
 
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_image_generation(1),
+        )
+        .await;
+
     let attachment_body_text = format!(
         "generated-sticker.{}",
         get_file_extension(&response.mime_type)
@@ -228,8 +324,22 @@ pub async fn handle_sticker(
 
     matrix_link
         .messaging()
-        .send_event(message_context.room(), &mut event_content, response_type)
+        .send_event(
+            message_context.room(),
+            &mut event_content,
+            response_type.clone(),
+        )
         .await?;
 
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::ImageGeneration,
+        response_type,
+    )
+    .await;
+
     Ok(())
 }
@@ -6,14 +6,21 @@ pub mod cfg;
 pub mod chat_completion;
 mod determination;
 mod dispatching;
+pub mod export;
 pub mod help;
 pub mod image;
 pub mod join;
+pub mod prompt;
 pub mod provider;
 pub mod reaction;
+mod scheduling;
 pub mod usage;
 mod utils;
 
 pub use controller_type::ControllerType;
 pub use determination::determine_controller;
 pub use dispatching::dispatch_controller;
+pub use scheduling::{
+    Scheduler, dispatch_controller_when_scheduled, handle_reaction_when_scheduled,
+    run_when_scheduled,
+};
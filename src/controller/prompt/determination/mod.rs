@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{entity::globalconfig::PromptDefinition, strings};
+
+use super::super::ControllerType;
+
+#[derive(Debug, PartialEq)]
+pub enum PromptControllerType {
+    Help,
+
+    List,
+    Show(String),
+    Add(PromptDefinition),
+    Delete(String),
+}
+
+pub fn determine_controller(command_prefix: &str, text: &str) -> ControllerType {
+    if text.starts_with("list") {
+        return ControllerType::Prompt(PromptControllerType::List);
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("show") {
+        return match parse_name(command_prefix, remaining_text) {
+            Ok(name) => ControllerType::Prompt(PromptControllerType::Show(name)),
+            Err(controller_type) => controller_type,
+        };
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("delete") {
+        return match parse_name(command_prefix, remaining_text) {
+            Ok(name) => ControllerType::Prompt(PromptControllerType::Delete(name)),
+            Err(controller_type) => controller_type,
+        };
+    }
+
+    if let Some(remaining_text) = text.strip_prefix("add") {
+        return match parse_definition(command_prefix, remaining_text) {
+            Ok(definition) => ControllerType::Prompt(PromptControllerType::Add(definition)),
+            Err(controller_type) => controller_type,
+        };
+    }
+
+    ControllerType::Prompt(PromptControllerType::Help)
+}
+
+/// Parses a single prompt name argument (e.g. ` code-reviewer`).
+fn parse_name(command_prefix: &str, text: &str) -> Result<String, ControllerType> {
+    let mut parts = text.split_whitespace();
+
+    let (Some(name), None) = (parts.next(), parts.next()) else {
+        return Err(ControllerType::Error(
+            strings::prompt::incorrect_invocation_expects_name_arg(command_prefix),
+        ));
+    };
+
+    if let Err(err) = PromptDefinition::validate_name(name) {
+        return Err(ControllerType::Error(
+            strings::prompt::invalid_name_validation_error(err),
+        ));
+    }
+
+    Ok(name.to_owned())
+}
+
+/// Parses the arguments of the `add` command:
+/// the name and description on the first line, followed by the prompt text on the next lines.
+fn parse_definition(command_prefix: &str, text: &str) -> Result<PromptDefinition, ControllerType> {
+    let text = text.trim_start();
+
+    let (first_line, prompt_text) = text.split_once('\n').unwrap_or((text, ""));
+
+    let first_line = first_line.trim();
+    let (name, description) = first_line
+        .split_once(char::is_whitespace)
+        .unwrap_or((first_line, ""));
+
+    let description = description.trim();
+    let prompt_text = prompt_text.trim();
+
+    if name.is_empty() || description.is_empty() || prompt_text.is_empty() {
+        return Err(ControllerType::Error(
+            strings::prompt::incorrect_add_invocation(command_prefix),
+        ));
+    }
+
+    if let Err(err) = PromptDefinition::validate_name(name) {
+        return Err(ControllerType::Error(
+            strings::prompt::invalid_name_validation_error(err),
+        ));
+    }
+
+    Ok(PromptDefinition {
+        name: name.to_owned(),
+        description: description.to_owned(),
+        text: prompt_text.to_owned(),
+    })
+}
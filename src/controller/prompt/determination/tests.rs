@@ -0,0 +1,89 @@
+#[test]
+fn determine_controller() {
+    use super::{ControllerType, PromptControllerType};
+    use crate::entity::globalconfig::PromptDefinition;
+
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: ControllerType,
+    }
+
+    let command_prefix = "!bai";
+
+    let test_cases = vec![
+        TestCase {
+            name: "Top-level is the help",
+            input: "",
+            expected: ControllerType::Prompt(PromptControllerType::Help),
+        },
+        TestCase {
+            name: "List",
+            input: "list",
+            expected: ControllerType::Prompt(PromptControllerType::List),
+        },
+        TestCase {
+            name: "Show",
+            input: "show code-reviewer",
+            expected: ControllerType::Prompt(PromptControllerType::Show(
+                "code-reviewer".to_owned(),
+            )),
+        },
+        TestCase {
+            name: "Show without a name",
+            input: "show",
+            expected: ControllerType::Error(
+                crate::strings::prompt::incorrect_invocation_expects_name_arg(command_prefix),
+            ),
+        },
+        TestCase {
+            name: "Delete",
+            input: "delete code-reviewer",
+            expected: ControllerType::Prompt(PromptControllerType::Delete(
+                "code-reviewer".to_owned(),
+            )),
+        },
+        TestCase {
+            name: "Delete with extra arguments",
+            input: "delete code-reviewer translator",
+            expected: ControllerType::Error(
+                crate::strings::prompt::incorrect_invocation_expects_name_arg(command_prefix),
+            ),
+        },
+        TestCase {
+            name: "Add",
+            input: "add code-reviewer Reviews code strictly, but fairly\nYou are a code reviewer.\n\nBe concise.\n",
+            expected: ControllerType::Prompt(PromptControllerType::Add(PromptDefinition {
+                name: "code-reviewer".to_owned(),
+                description: "Reviews code strictly, but fairly".to_owned(),
+                text: "You are a code reviewer.\n\nBe concise.".to_owned(),
+            })),
+        },
+        TestCase {
+            name: "Add without a prompt",
+            input: "add code-reviewer Reviews code strictly, but fairly",
+            expected: ControllerType::Error(crate::strings::prompt::incorrect_add_invocation(
+                command_prefix,
+            )),
+        },
+        TestCase {
+            name: "Add without a description",
+            input: "add code-reviewer\nYou are a code reviewer.",
+            expected: ControllerType::Error(crate::strings::prompt::incorrect_add_invocation(
+                command_prefix,
+            )),
+        },
+        TestCase {
+            name: "Add with an invalid name",
+            input: "add code/reviewer Reviews code\nYou are a code reviewer.",
+            expected: ControllerType::Error(crate::strings::prompt::invalid_name_validation_error(
+                PromptDefinition::validate_name("code/reviewer").unwrap_err(),
+            )),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine_controller(command_prefix, test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
@@ -0,0 +1,46 @@
+use mxlink::MessageResponseType;
+
+use crate::{Bot, entity::MessageContext, strings};
+
+use super::PromptControllerType;
+
+pub async fn dispatch_controller(
+    handler: &PromptControllerType,
+    message_context: &MessageContext,
+    bot: &Bot,
+) -> anyhow::Result<()> {
+    // Anyone can browse the prompt library (to pick a prompt for their room), but only administrators can change it.
+    match handler {
+        PromptControllerType::Help | PromptControllerType::List | PromptControllerType::Show(_) => {
+        }
+        PromptControllerType::Add(_) | PromptControllerType::Delete(_) => {
+            if !message_context.sender_can_manage_global_config() {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        strings::global_config::no_permissions_to_administrate(),
+                        MessageResponseType::Reply(
+                            message_context.thread_info().root_event_id.clone(),
+                        ),
+                    )
+                    .await;
+
+                return Ok(());
+            }
+        }
+    };
+
+    match handler {
+        PromptControllerType::Help => super::help::handle(bot, message_context).await,
+        PromptControllerType::List => super::library::handle_list(bot, message_context).await,
+        PromptControllerType::Show(name) => {
+            super::library::handle_show(bot, message_context, name).await
+        }
+        PromptControllerType::Add(definition) => {
+            super::library::handle_add(bot, message_context, definition).await
+        }
+        PromptControllerType::Delete(name) => {
+            super::library::handle_delete(bot, message_context, name).await
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use mxlink::MessageResponseType;
+
+use crate::{Bot, entity::MessageContext, strings};
+
+pub async fn handle(bot: &Bot, message_context: &MessageContext) -> anyhow::Result<()> {
+    let command_prefix = bot.command_prefix();
+
+    let mut message = String::new();
+
+    message.push_str(&format!("## {}", strings::help::prompt::heading()));
+    message.push_str("\n\n");
+    message.push_str(&strings::help::prompt::intro());
+    message.push_str("\n\n");
+    message.push_str(&strings::help::prompt::usage_in_rooms(command_prefix));
+    message.push_str("\n\n");
+
+    message.push_str(strings::the_following_commands_are_available());
+    message.push('\n');
+    message.push_str(&strings::help::prompt::command_list(command_prefix));
+    message.push('\n');
+    message.push_str(&strings::help::prompt::command_show(command_prefix));
+
+    if message_context.sender_can_manage_global_config() {
+        message.push('\n');
+        message.push_str(&strings::help::prompt::command_add(command_prefix));
+        message.push('\n');
+        message.push_str(&strings::help::prompt::command_delete(command_prefix));
+        message.push_str("\n\n");
+        message.push_str(&strings::help::prompt::example_add(command_prefix));
+    }
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
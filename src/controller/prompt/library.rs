@@ -0,0 +1,161 @@
+use mxlink::MessageResponseType;
+
+use crate::{
+    Bot,
+    agent::provider::TextGenerationPromptVariables,
+    entity::{MessageContext, globalconfig::PromptDefinition},
+    strings,
+};
+
+pub async fn handle_list(bot: &Bot, message_context: &MessageContext) -> anyhow::Result<()> {
+    let prompts = &message_context.global_config().prompts;
+
+    let message = if prompts.is_empty() {
+        strings::prompt::library_empty(bot.command_prefix())
+    } else {
+        let mut message = String::new();
+        message.push_str(strings::prompt::library_intro());
+        message.push_str("\n\n");
+
+        for prompt in prompts {
+            message.push_str(&strings::prompt::library_entry(prompt));
+            message.push('\n');
+        }
+
+        message.push('\n');
+        message.push_str(&strings::prompt::library_outro(bot.command_prefix()));
+
+        message
+    };
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+pub async fn handle_show(
+    bot: &Bot,
+    message_context: &MessageContext,
+    name: &str,
+) -> anyhow::Result<()> {
+    let Some(prompt) = message_context.global_config().prompt_by_name(name) else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::prompt::prompt_with_given_name_missing(name, bot.command_prefix()),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            strings::prompt::details(prompt, bot.command_prefix()),
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+pub async fn handle_add(
+    bot: &Bot,
+    message_context: &MessageContext,
+    definition: &PromptDefinition,
+) -> anyhow::Result<()> {
+    if let Err(err) = TextGenerationPromptVariables::validate(&definition.text) {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::prompt::template_invalid(&err),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    let mut global_config_manager_guard = bot.global_config_manager().lock().await;
+
+    let mut global_config = global_config_manager_guard.get_or_create().await?;
+
+    // Adding a prompt with an existing name replaces it in place, so that rooms using it pick up the new version.
+    let replaced = match global_config
+        .prompts
+        .iter_mut()
+        .find(|prompt| prompt.name == definition.name)
+    {
+        Some(prompt) => {
+            *prompt = definition.clone();
+            true
+        }
+        None => {
+            global_config.prompts.push(definition.clone());
+            false
+        }
+    };
+
+    global_config_manager_guard.persist(&global_config).await?;
+
+    let message = if replaced {
+        strings::prompt::updated(&definition.name)
+    } else {
+        strings::prompt::added(&definition.name, bot.command_prefix())
+    };
+
+    bot.messaging()
+        .send_success_markdown_no_fail(
+            message_context.room(),
+            &message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+pub async fn handle_delete(
+    bot: &Bot,
+    message_context: &MessageContext,
+    name: &str,
+) -> anyhow::Result<()> {
+    let mut global_config_manager_guard = bot.global_config_manager().lock().await;
+
+    let mut global_config = global_config_manager_guard.get_or_create().await?;
+
+    let prompts_count = global_config.prompts.len();
+    global_config.prompts.retain(|prompt| prompt.name != name);
+
+    if global_config.prompts.len() == prompts_count {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::prompt::prompt_with_given_name_missing(name, bot.command_prefix()),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    global_config_manager_guard.persist(&global_config).await?;
+
+    bot.messaging()
+        .send_success_markdown_no_fail(
+            message_context.room(),
+            &strings::prompt::deleted(name),
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+mod determination;
+mod dispatching;
+mod help;
+mod library;
+
+pub use determination::{PromptControllerType, determine_controller};
+pub use dispatching::dispatch_controller;
@@ -1,10 +1,42 @@
 use mxlink::MessageResponseType;
 
-use crate::{Bot, agent::AgentProvider, entity::MessageContext, strings};
+use crate::{
+    Bot,
+    agent::{
+        AgentProvider, ConcurrencyLimiter, ControllerTrait, PublicIdentifier,
+        create_from_provider_and_yaml_value_config, default_config_for_provider,
+    },
+    entity::MessageContext,
+    strings,
+};
 
 use super::ControllerType;
 
-pub fn determine_controller(_text: &str) -> ControllerType {
+pub fn determine_controller(command_prefix: &str, text: &str) -> ControllerType {
+    if let Some(remaining_text) = text.strip_prefix("models") {
+        // `remaining_text` should be something like: `PROVIDER [BASE_URL]`
+        let mut parts = remaining_text.split_whitespace();
+
+        let Some(provider) = parts.next() else {
+            return ControllerType::Error(strings::provider::models_incorrect_invocation(
+                command_prefix,
+            ));
+        };
+
+        let base_url = parts.next().map(|s| s.to_owned());
+
+        if parts.next().is_some() {
+            return ControllerType::Error(strings::provider::models_incorrect_invocation(
+                command_prefix,
+            ));
+        }
+
+        return ControllerType::ProviderModels {
+            provider: provider.to_owned(),
+            base_url,
+        };
+    }
+
     ControllerType::ProviderHelp
 }
 
@@ -42,6 +74,17 @@ pub async fn handle_help(message_context: &MessageContext, bot: &Bot) -> anyhow:
     ));
     message.push_str("\n\n");
 
+    // How to find models
+    message.push_str(&format!(
+        "### {}",
+        strings::provider::help_how_to_find_models_heading()
+    ));
+    message.push_str("\n\n");
+    message.push_str(&strings::provider::help_how_to_find_models_description(
+        bot.command_prefix(),
+    ));
+    message.push_str("\n\n");
+
     for provider in AgentProvider::choices() {
         let provider_info = provider.info();
 
@@ -94,3 +137,112 @@ pub async fn handle_help(message_context: &MessageContext, bot: &Bot) -> anyhow:
 
     Ok(())
 }
+
+pub async fn handle_models(
+    message_context: &MessageContext,
+    bot: &Bot,
+    provider: &str,
+    base_url: Option<&str>,
+) -> anyhow::Result<()> {
+    // This makes the bot send requests to any URL it's given, which is no more than what agent creators can do anyway.
+    if !message_context.sender_can_manage_room_local_agents()? {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::provider::models_not_allowed(),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    let Ok(provider) = AgentProvider::from_string(provider) else {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::provider::invalid(provider),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    // A throwaway agent based on the provider's sample configuration, without the placeholder API key
+    // (sending it would make servers which don't require a key reject the request).
+    let mut config = default_config_for_provider(&provider);
+    if let serde_yaml_ng::Value::Mapping(mapping) = &mut config {
+        if let Some(base_url) = base_url {
+            mapping.insert("base_url".into(), base_url.into());
+        }
+
+        if mapping.contains_key("api_key") {
+            mapping.insert("api_key".into(), "".into());
+        }
+    }
+
+    let identifier = PublicIdentifier::DynamicRoomLocal("model-listing".to_owned());
+
+    // This throwaway agent is only used for a single request, so it's not worth bounding.
+    let agent = match create_from_provider_and_yaml_value_config(
+        &provider,
+        &identifier,
+        config,
+        ConcurrencyLimiter::unlimited(),
+    ) {
+        Ok(agent) => agent,
+        Err(err) => {
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::provider::models_cannot_be_listed_without_agent(
+                        &provider,
+                        err,
+                        bot.command_prefix(),
+                    ),
+                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    let models = match agent.controller().list_models().await {
+        Ok(models) => models,
+        Err(err) => {
+            tracing::warn!(?err, %provider, "Failed to list models");
+
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::provider::models_listing_failed(&provider, err),
+                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    let message = if models.is_empty() {
+        strings::provider::models_none(&provider)
+    } else {
+        format!(
+            "{}\n\n{}",
+            strings::provider::models_intro(&provider, base_url),
+            strings::provider::models_grouped_by_purpose_block(&models),
+        )
+    };
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
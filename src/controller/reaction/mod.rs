@@ -1,14 +1,16 @@
 use std::ops::Deref;
 
-use mxlink::MatrixLink;
+use mxlink::matrix_sdk::ruma::OwnedEventId;
+use mxlink::{MatrixLink, MessageResponseType};
 
 use crate::{
     Bot,
-    agent::AgentPurpose,
-    entity::{MessageContext, MessagePayload},
+    entity::{MessageContext, MessagePayload, roomconfig::ReactionAction},
 };
 
+mod text_generation;
 mod text_to_speech;
+mod transcribe;
 
 pub async fn handle(
     bot: &Bot,
@@ -22,9 +24,14 @@ pub async fn handle(
             reacted_to_event_id,
             reacted_to_event_sender_id,
         } => {
-            if key == AgentPurpose::TextToSpeech.emoji() {
-                if let MessagePayload::Text(text_content) = reacted_to_event_payload.deref() {
-                    return text_to_speech::handle(
+            let Some(action) = message_context.room_config_context().reaction_action(key) else {
+                tracing::debug!("Ignoring reaction which is not mapped to an action");
+                return Ok(());
+            };
+
+            match (action, reacted_to_event_payload.deref()) {
+                (ReactionAction::TextToSpeech, MessagePayload::Text(text_content)) => {
+                    text_to_speech::handle(
                         bot,
                         matrix_link,
                         message_context,
@@ -32,19 +39,67 @@ pub async fn handle(
                         reacted_to_event_sender_id,
                         text_content,
                     )
-                    .await;
+                    .await
+                }
+                (ReactionAction::Regenerate, MessagePayload::Text(_))
+                    if *reacted_to_event_sender_id == *bot.user_id() =>
+                {
+                    crate::controller::chat_completion::regenerate::handle(
+                        bot,
+                        matrix_link,
+                        message_context,
+                        Some(reacted_to_event_id),
+                        None,
+                    )
+                    .await
+                }
+                (ReactionAction::Transcribe, MessagePayload::Audio(audio_content)) => {
+                    transcribe::handle(bot, message_context, reacted_to_event_id, audio_content)
+                        .await
+                }
+                (
+                    action @ (ReactionAction::Translate(_)
+                    | ReactionAction::Summarize
+                    | ReactionAction::Explain
+                    | ReactionAction::ImagePrompt
+                    | ReactionAction::Prompt(_)),
+                    MessagePayload::Text(text_content),
+                ) => {
+                    text_generation::handle(
+                        bot,
+                        message_context,
+                        reacted_to_event_id,
+                        &text_content.body,
+                        &action,
+                    )
+                    .await
+                }
+                (action, _) => {
+                    tracing::debug!(
+                        %action,
+                        "Ignoring reaction whose action does not apply to the reacted-to message"
+                    );
+                    Ok(())
                 }
-
-                tracing::debug!("Ignoring text-to-speech reaction to non-text message");
-                return Ok(());
             }
-
-            tracing::debug!("Ignoring unknown reaction");
-
-            Ok(())
         }
         _ => Err(anyhow::anyhow!(
             "Reaction controller called with a non-reaction message"
         )),
     }
 }
+
+/// Determines where to post the result of an action performed on the reacted-to message.
+///
+/// If we're in a thread, we should respond in the thread.
+/// Otherwise, we should reply to the reacted-to message and avoid starting threads.
+fn response_type_for_reaction(
+    message_context: &MessageContext,
+    reacted_to_event_id: &OwnedEventId,
+) -> MessageResponseType {
+    if message_context.thread_info().is_thread_root_only() {
+        MessageResponseType::Reply(reacted_to_event_id.clone())
+    } else {
+        MessageResponseType::InThread(message_context.thread_info().clone())
+    }
+}
@@ -0,0 +1,243 @@
+//! Performs text-generation-based reaction actions (translating, summarizing, running a library prompt, etc.)
+//! on the text of the reacted-to message.
+
+use mxlink::matrix_sdk::ruma::OwnedEventId;
+
+use crate::{
+    Bot,
+    agent::{AgentPurpose, failover::run_with_failover},
+    controller::chat_completion::{generate_text_via_agent, text_generation_model_override},
+    controller::utils::{
+        agent::{
+            get_effective_agent_chain_for_purpose_or_complain, notify_if_served_by_fallback_agent,
+        },
+        quota::ensure_within_quotas,
+    },
+    conversation::llm::{Author, Conversation, Message, MessageContent},
+    entity::{
+        MessageContext,
+        roomconfig::{QuotaMetric, ReactionAction},
+        usage::UsageCounters,
+    },
+    strings,
+};
+
+const TRANSLATE_PROMPT_DEFAULT_LANGUAGE: &str = "English";
+
+const SUMMARIZE_PROMPT: &str = "Summarize the message you're given. Be brief and capture its key points. Reply only with the summary, in the language of the message.";
+
+const EXPLAIN_PROMPT: &str = "Explain the message you're given in simple terms, clarifying any jargon, abbreviations or references it contains. Reply in the language of the message.";
+
+const IMAGE_PROMPT_PROMPT: &str = "Turn the message you're given into a detailed prompt for an image-generation model, describing the subject, setting, style, composition and lighting. Reply only with the prompt, in English.";
+
+pub(super) async fn handle(
+    bot: &Bot,
+    message_context: &MessageContext,
+    reacted_to_event_id: &OwnedEventId,
+    text: &str,
+    action: &ReactionAction,
+) -> anyhow::Result<()> {
+    let response_type = super::response_type_for_reaction(message_context, reacted_to_event_id);
+
+    let prompt = match action {
+        ReactionAction::Translate(language) => format!(
+            "Translate the message you're given into {}. Reply only with the translation.",
+            language
+                .as_deref()
+                .unwrap_or(TRANSLATE_PROMPT_DEFAULT_LANGUAGE)
+        ),
+        ReactionAction::Summarize => SUMMARIZE_PROMPT.to_owned(),
+        ReactionAction::Explain => EXPLAIN_PROMPT.to_owned(),
+        ReactionAction::ImagePrompt => IMAGE_PROMPT_PROMPT.to_owned(),
+        ReactionAction::Prompt(name) => {
+            // The prompt may have been removed from the library after the reaction got mapped to it.
+            let Some(prompt) = message_context.global_config().prompt_by_name(name) else {
+                bot.messaging()
+                    .send_error_markdown_no_fail(
+                        message_context.room(),
+                        &strings::prompt::prompt_with_given_name_missing(
+                            name,
+                            bot.command_prefix(),
+                        ),
+                        response_type,
+                    )
+                    .await;
+
+                return Ok(());
+            };
+
+            prompt.text.clone()
+        }
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Reaction action {action} is not a text-generation action"
+            ));
+        }
+    };
+
+    if !ensure_within_quotas(
+        bot,
+        message_context,
+        &[QuotaMetric::Tokens, QuotaMetric::Cost],
+        response_type.clone(),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    let Some(agents) = get_effective_agent_chain_for_purpose_or_complain(
+        bot,
+        message_context,
+        AgentPurpose::TextGeneration,
+        response_type.clone(),
+        true,
+    )
+    .await
+    else {
+        // We've already complained about this in get_effective_agent_chain_for_purpose_or_complain
+        return Ok(());
+    };
+
+    let primary_agent = &agents[0];
+
+    let conversation = Conversation {
+        messages: vec![Message {
+            author: Author::User,
+            sender_id: None,
+            timestamp: chrono::Utc::now(),
+            content: MessageContent::Text(text.to_owned()),
+        }],
+    };
+
+    let _typing_notice_guard = bot.start_typing_notice(message_context.room()).await;
+
+    let progress_reaction_event_id = bot
+        .reacting()
+        .react_no_fail(
+            message_context.room(),
+            reacted_to_event_id.clone(),
+            strings::PROGRESS_INDICATOR_EMOJI.to_owned(),
+        )
+        .await
+        .map(|response| response.event_id);
+
+    // Lets the reacting user (or an administrator) cancel the generation, by reacting with ❌ to the reacted-to message.
+    let mut in_flight_generation = bot.in_flight_generation_tracker().start(
+        message_context.room_id(),
+        message_context.event_id(),
+        message_context.sender_id(),
+    );
+    in_flight_generation.track_event(reacted_to_event_id.clone());
+
+    let result = in_flight_generation
+        .run(run_with_failover(
+            &agents,
+            AgentPurpose::TextGeneration,
+            async |agent| {
+                generate_text_via_agent(
+                    bot,
+                    message_context,
+                    agent,
+                    text_generation_model_override(message_context, agent, primary_agent),
+                    Some(prompt.clone()),
+                    conversation.clone(),
+                    None,
+                )
+                .await
+            },
+        ))
+        .await;
+
+    drop(in_flight_generation);
+
+    if let Some(progress_reaction_event_id) = progress_reaction_event_id {
+        bot.messaging()
+            .redact_event_no_fail(
+                message_context.room(),
+                progress_reaction_event_id,
+                Some("Done".to_owned()),
+            )
+            .await;
+    }
+
+    let Some((agent, result)) = result else {
+        tracing::info!(%action, "Reaction action got cancelled");
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::cancellation::cancelled(),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    };
+
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => {
+            tracing::warn!(
+                "Error in room {} while trying to perform reaction action {} via agent {}: {:?}",
+                message_context.room_id(),
+                action,
+                agent.identifier(),
+                err,
+            );
+
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    &strings::agent::error_while_serving_purpose(
+                        agent.identifier(),
+                        &AgentPurpose::TextGeneration,
+                        &err,
+                    ),
+                    response_type,
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &agent.identifier().as_string(),
+            UsageCounters::for_text_generation(&result.usage),
+        )
+        .await;
+
+    let text = result.text.trim().to_owned();
+
+    if text.is_empty() {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::empty_response_returned(agent.identifier()),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    bot.messaging()
+        .send_text_markdown_no_fail(message_context.room(), text, response_type.clone())
+        .await;
+
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::TextGeneration,
+        response_type,
+    )
+    .await;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use mxlink::{MatrixLink, MessageResponseType};
+use mxlink::MatrixLink;
 
 use mxlink::matrix_sdk::ruma::{
     OwnedEventId, OwnedUserId, events::room::message::TextMessageEventContent,
@@ -10,7 +10,8 @@ use crate::entity::roomconfig::{
 
 use crate::{
     Bot, agent::AgentPurpose,
-    controller::utils::agent::get_effective_agent_for_purpose_or_complain, entity::MessageContext,
+    controller::utils::agent::get_effective_agent_chain_for_purpose_or_complain,
+    entity::MessageContext,
 };
 
 pub(super) async fn handle(
@@ -23,11 +24,7 @@ pub(super) async fn handle(
 ) -> anyhow::Result<()> {
     // If we're in a thread, we're likely dealing with a bot message, so we should start in the thread.
     // Otherwise, we're likely operating in "TTS user messages" mode, so we should reply to the reacted-to message and avoid threads.
-    let response_type = if message_context.thread_info().is_thread_root_only() {
-        MessageResponseType::Reply(reacted_to_event_id.clone())
-    } else {
-        MessageResponseType::InThread(message_context.thread_info().clone())
-    };
+    let response_type = super::response_type_for_reaction(message_context, reacted_to_event_id);
 
     if !is_allowed_to_tts_for_event(
         message_context,
@@ -40,7 +37,7 @@ pub(super) async fn handle(
         return Ok(());
     }
 
-    let speech_agent = get_effective_agent_for_purpose_or_complain(
+    let speech_agents = get_effective_agent_chain_for_purpose_or_complain(
         bot,
         message_context,
         AgentPurpose::TextToSpeech,
@@ -49,7 +46,7 @@ pub(super) async fn handle(
     )
     .await;
 
-    let Some(speech_agent) = speech_agent else {
+    let Some(speech_agents) = speech_agents else {
         // We've already complained about this in get_effective_agent_or_complain
         return Ok(());
     };
@@ -61,7 +58,7 @@ pub(super) async fn handle(
         matrix_link,
         message_context,
         response_type,
-        &speech_agent,
+        &speech_agents,
         reacted_to_event_id,
         &text_content.body,
     )
@@ -0,0 +1,114 @@
+use mxlink::matrix_sdk::ruma::{OwnedEventId, events::room::message::AudioMessageEventContent};
+
+use crate::{
+    Bot,
+    agent::{AgentPurpose, failover::run_with_failover},
+    controller::chat_completion::handle_stage_speech_to_text_actual_transcribing,
+    controller::utils::agent::{
+        get_effective_agent_chain_for_purpose_or_complain, notify_if_served_by_fallback_agent,
+    },
+    entity::MessageContext,
+    strings,
+};
+
+pub(super) async fn handle(
+    bot: &Bot,
+    message_context: &MessageContext,
+    reacted_to_event_id: &OwnedEventId,
+    audio_content: &AudioMessageEventContent,
+) -> anyhow::Result<()> {
+    let response_type = super::response_type_for_reaction(message_context, reacted_to_event_id);
+
+    let Some(agents) = get_effective_agent_chain_for_purpose_or_complain(
+        bot,
+        message_context,
+        AgentPurpose::SpeechToText,
+        response_type.clone(),
+        true,
+    )
+    .await
+    else {
+        // We've already complained about this in get_effective_agent_chain_for_purpose_or_complain
+        return Ok(());
+    };
+
+    let msg_type = message_context
+        .room_config_context()
+        .speech_to_text_msg_type_for_non_threaded_only_transcribed_messages();
+
+    let _typing_notice_guard = bot.start_typing_notice(message_context.room()).await;
+
+    let progress_reaction_event_id = bot
+        .reacting()
+        .react_no_fail(
+            message_context.room(),
+            reacted_to_event_id.clone(),
+            strings::PROGRESS_INDICATOR_EMOJI.to_owned(),
+        )
+        .await
+        .map(|response| response.event_id);
+
+    let (agent, result) = run_with_failover(&agents, AgentPurpose::SpeechToText, async |agent| {
+        handle_stage_speech_to_text_actual_transcribing(
+            bot,
+            message_context,
+            agent,
+            audio_content,
+            response_type.clone(),
+            msg_type,
+        )
+        .await
+    })
+    .await;
+
+    if let Some(progress_reaction_event_id) = progress_reaction_event_id {
+        let redaction_reason = if result.is_ok() {
+            strings::speech_to_text::redaction_reason_done()
+        } else {
+            strings::speech_to_text::redaction_reason_failed()
+        };
+
+        bot.messaging()
+            .redact_event_no_fail(
+                message_context.room(),
+                progress_reaction_event_id,
+                Some(redaction_reason.to_owned()),
+            )
+            .await;
+    }
+
+    if let Err(err) = result {
+        tracing::warn!(
+            "Error in room {} while trying to transcribe (via reaction) via agent {}: {:?}",
+            message_context.room_id(),
+            agent.identifier(),
+            err,
+        );
+
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                &strings::agent::error_while_serving_purpose(
+                    agent.identifier(),
+                    &AgentPurpose::SpeechToText,
+                    &err,
+                ),
+                response_type,
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    notify_if_served_by_fallback_agent(
+        bot,
+        message_context,
+        &agents,
+        agent,
+        AgentPurpose::SpeechToText,
+        response_type,
+    )
+    .await;
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+mod scheduler;
+
+pub use scheduler::Scheduler;
+
+use scheduler::{Admission, ScheduledRequest};
+
+use std::future::Future;
+
+use mxlink::MessageResponseType;
+use mxlink::matrix_sdk::ruma::OwnedEventId;
+use tokio::time::Instant;
+
+use crate::{
+    Bot,
+    entity::{MessageContext, MessagePayload, roomconfig::ReactionAction},
+    strings,
+};
+
+use super::ControllerType;
+use super::chat_completion::ChatCompletionControllerType;
+
+#[derive(Debug, PartialEq)]
+enum Scheduling {
+    /// The request is handled right away (e.g. commands, which are quick and do not call providers).
+    Bypass,
+
+    /// The request waits for its turn in the thread and for a free slot.
+    Queue,
+
+    /// Like [`Scheduling::Queue`], but the request may also be answered together with newer messages in the thread.
+    QueueCoalescable,
+}
+
+fn scheduling_for_controller(controller_type: &ControllerType) -> Scheduling {
+    match controller_type {
+        ControllerType::ChatCompletion(controller_type) => match controller_type {
+            ChatCompletionControllerType::TextCommand
+            | ChatCompletionControllerType::TextMention
+            | ChatCompletionControllerType::TextDirect
+            | ChatCompletionControllerType::Image
+            | ChatCompletionControllerType::File
+            | ChatCompletionControllerType::ThreadMention => Scheduling::QueueCoalescable,
+            // Audio gets transcribed (and possibly posted) by itself, so it cannot be answered as part of another message.
+            // Reply chains are not threads, so a newer reply does not necessarily see the older message.
+            ChatCompletionControllerType::Audio | ChatCompletionControllerType::ReplyMention => {
+                Scheduling::Queue
+            }
+        },
+        ControllerType::Regenerate(_)
+        | ControllerType::ImageGeneration(_)
+        | ControllerType::ImageEdit(_)
+        | ControllerType::StickerGeneration(_) => Scheduling::Queue,
+        _ => Scheduling::Bypass,
+    }
+}
+
+fn scheduling_for_reaction_action(action: Option<&ReactionAction>) -> Scheduling {
+    match action {
+        None | Some(ReactionAction::Disabled) => Scheduling::Bypass,
+        // All actions call providers, so they wait their turn like any other request.
+        Some(_) => Scheduling::Queue,
+    }
+}
+
+/// Dispatches the controller once the bot's [`Scheduler`] lets the request through.
+///
+/// Requests which have to wait get a notice telling them their position in the queue,
+/// which is redacted once they start being handled.
+pub async fn dispatch_controller_when_scheduled(
+    controller_type: &ControllerType,
+    message_context: &MessageContext,
+    bot: &Bot,
+    received_at: Instant,
+) {
+    let coalescable = match scheduling_for_controller(controller_type) {
+        Scheduling::Bypass => {
+            super::dispatch_controller(controller_type, message_context, bot).await;
+            return;
+        }
+        Scheduling::Queue => false,
+        Scheduling::QueueCoalescable => true,
+    };
+
+    let Some(_scheduled_request) = admit(bot, message_context, coalescable, received_at).await
+    else {
+        tracing::info!("Request coalesced with a newer message in the thread");
+        return;
+    };
+
+    super::dispatch_controller(controller_type, message_context, bot).await;
+}
+
+/// Handles a reaction via the reaction controller, queueing it (like [`dispatch_controller_when_scheduled`] does for messages)
+/// if it's mapped to an action.
+pub async fn handle_reaction_when_scheduled(
+    bot: &Bot,
+    message_context: &MessageContext,
+) -> anyhow::Result<()> {
+    let action = match message_context.payload() {
+        MessagePayload::Reaction { key, .. } => {
+            message_context.room_config_context().reaction_action(key)
+        }
+        _ => None,
+    };
+
+    let handling = super::reaction::handle(bot, bot.matrix_link().clone(), message_context);
+
+    match scheduling_for_reaction_action(action.as_ref()) {
+        Scheduling::Bypass => handling.await,
+        Scheduling::Queue | Scheduling::QueueCoalescable => {
+            run_when_scheduled(bot, message_context, handling).await
+        }
+    }
+}
+
+/// Runs the given future once the bot's [`Scheduler`] lets the request (which is never coalesced) through.
+pub async fn run_when_scheduled<F: Future>(
+    bot: &Bot,
+    message_context: &MessageContext,
+    future: F,
+) -> F::Output {
+    // Only coalescable requests can be turned away, so we always get our turn.
+    let _scheduled_request = admit(bot, message_context, false, Instant::now()).await;
+
+    future.await
+}
+
+/// Waits for the request's turn, keeping the room informed about its position in the queue.
+///
+/// Returns `None` if the request got coalesced with a newer one and is not to be handled.
+async fn admit(
+    bot: &Bot,
+    message_context: &MessageContext,
+    coalescable: bool,
+    received_at: Instant,
+) -> Option<ScheduledRequest> {
+    let thread_info = message_context.thread_info();
+
+    let thread_key = format!(
+        "{}/{}",
+        message_context.room_id(),
+        thread_info.root_event_id
+    );
+
+    let response_type = if thread_info.is_thread_root_only() {
+        MessageResponseType::Reply(thread_info.root_event_id.clone())
+    } else {
+        MessageResponseType::InThread(thread_info.clone())
+    };
+
+    let mut queued_notice_event_id: Option<OwnedEventId> = None;
+    let mut queued_notice_sent = false;
+
+    let admission = bot
+        .scheduler()
+        .admit(&thread_key, coalescable, received_at, async |position| {
+            tracing::info!(position, "Request queued");
+
+            // We only tell the position once, so as not to flood the room with notices.
+            if queued_notice_sent {
+                return;
+            }
+            queued_notice_sent = true;
+
+            queued_notice_event_id = bot
+                .messaging()
+                .send_notice_markdown_no_fail(
+                    message_context.room(),
+                    strings::scheduling::queued(position),
+                    response_type.clone(),
+                )
+                .await
+                .map(|response| response.event_id);
+        })
+        .await;
+
+    if let Some(event_id) = queued_notice_event_id {
+        bot.messaging()
+            .redact_event_no_fail(
+                message_context.room(),
+                event_id,
+                Some(strings::scheduling::redaction_reason_started().to_owned()),
+            )
+            .await;
+    }
+
+    match admission {
+        Admission::Proceed(scheduled_request) => Some(scheduled_request),
+        Admission::Coalesced => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheduling_for_controller_type() {
+        struct TestCase {
+            controller_type: ControllerType,
+            expected: Scheduling,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                controller_type: ControllerType::Help,
+                expected: Scheduling::Bypass,
+            },
+            TestCase {
+                controller_type: ControllerType::ChatCompletion(
+                    ChatCompletionControllerType::TextDirect,
+                ),
+                expected: Scheduling::QueueCoalescable,
+            },
+            TestCase {
+                controller_type: ControllerType::ChatCompletion(
+                    ChatCompletionControllerType::ThreadMention,
+                ),
+                expected: Scheduling::QueueCoalescable,
+            },
+            TestCase {
+                controller_type: ControllerType::ChatCompletion(
+                    ChatCompletionControllerType::Audio,
+                ),
+                expected: Scheduling::Queue,
+            },
+            TestCase {
+                controller_type: ControllerType::Regenerate(None),
+                expected: Scheduling::Queue,
+            },
+            TestCase {
+                controller_type: ControllerType::ImageGeneration("A cat".to_owned()),
+                expected: Scheduling::Queue,
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                scheduling_for_controller(&test_case.controller_type),
+                test_case.expected,
+                "Controller type: {:?}",
+                test_case.controller_type
+            );
+        }
+    }
+
+    #[test]
+    fn scheduling_for_reaction() {
+        struct TestCase {
+            action: Option<ReactionAction>,
+            expected: Scheduling,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                action: None,
+                expected: Scheduling::Bypass,
+            },
+            TestCase {
+                action: Some(ReactionAction::Disabled),
+                expected: Scheduling::Bypass,
+            },
+            TestCase {
+                action: Some(ReactionAction::Regenerate),
+                expected: Scheduling::Queue,
+            },
+            TestCase {
+                action: Some(ReactionAction::Translate(None)),
+                expected: Scheduling::Queue,
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                scheduling_for_reaction_action(test_case.action.as_ref()),
+                test_case.expected,
+                "Action: {:?}",
+                test_case.action
+            );
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+type ThreadQueues = Arc<Mutex<HashMap<String, Arc<ThreadQueue>>>>;
+
+#[derive(Default)]
+struct ThreadQueueState {
+    next_ticket: u64,
+
+    /// The ticket of the most recent coalescable request, which answers on behalf of earlier (not yet started) ones.
+    latest_coalescable_ticket: Option<u64>,
+
+    /// Tells if the request holding the turn has made it past coalescing and will certainly be handled.
+    running: bool,
+
+    /// Requests waiting for their turn (their ticket and whether they're coalescable), in order of arrival.
+    waiting: Vec<(u64, bool)>,
+}
+
+impl ThreadQueueState {
+    /// Returns how many requests will be handled before a request (coalescable or not) arriving now.
+    ///
+    /// Waiting coalescable requests are answered by the newest one among them,
+    /// so they count as a single request (or as none, for a newly arriving coalescable request which answers them all).
+    fn requests_ahead(&self, coalescable: bool) -> usize {
+        let waiting_non_coalescable = self
+            .waiting
+            .iter()
+            .filter(|(_, coalescable)| !coalescable)
+            .count();
+
+        let waiting_coalescable =
+            !coalescable && self.waiting.iter().any(|(_, coalescable)| *coalescable);
+
+        usize::from(self.running) + waiting_non_coalescable + usize::from(waiting_coalescable)
+    }
+}
+
+/// Serializes the requests of a single thread.
+#[derive(Default)]
+struct ThreadQueue {
+    turn: Arc<tokio::sync::Mutex<()>>,
+    state: Mutex<ThreadQueueState>,
+}
+
+impl ThreadQueue {
+    fn state(&self) -> std::sync::MutexGuard<'_, ThreadQueueState> {
+        self.state.lock().expect("thread queue state lock poisoned")
+    }
+}
+
+pub enum Admission {
+    /// The request may be handled now. It holds its slot until the value is dropped.
+    Proceed(ScheduledRequest),
+
+    /// A newer message in the same thread arrived within the coalescing window, and the request handling it answers this one as well.
+    Coalesced,
+}
+
+/// Decides when requests get handled, so that:
+///
+/// - the requests of each thread are handled one after another, in the order they arrived
+/// - no more than a certain number of requests are handled at the same time (across all rooms)
+/// - rapid consecutive messages in a thread are answered once (see [`Admission::Coalesced`])
+pub struct Scheduler {
+    threads: ThreadQueues,
+
+    /// `None` when the number of concurrent requests is unlimited.
+    requests_semaphore: Option<Arc<Semaphore>>,
+
+    requests_waiting: AtomicUsize,
+
+    coalescing_window: Duration,
+}
+
+impl Scheduler {
+    pub fn new(max_concurrent_requests: usize, coalescing_window: Duration) -> Self {
+        Self {
+            threads: Arc::new(Mutex::new(HashMap::new())),
+            requests_semaphore: (max_concurrent_requests > 0)
+                .then(|| Arc::new(Semaphore::new(max_concurrent_requests))),
+            requests_waiting: AtomicUsize::new(0),
+            coalescing_window,
+        }
+    }
+
+    /// Waits until the given request (for the thread identified by `thread_key`) can be handled.
+    ///
+    /// `on_wait` is called with the request's position in the queue (1 being the next one) whenever it has to wait for other requests,
+    /// first for those in the same thread and then (possibly) for those in other rooms.
+    pub async fn admit(
+        &self,
+        thread_key: &str,
+        coalescable: bool,
+        received_at: Instant,
+        mut on_wait: impl AsyncFnMut(usize),
+    ) -> Admission {
+        let (queue, ticket, requests_ahead) = {
+            let mut threads = self.threads.lock().expect("thread queues lock poisoned");
+
+            let queue = threads.entry(thread_key.to_owned()).or_default().clone();
+
+            let mut state = queue.state();
+
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+
+            let requests_ahead = state.requests_ahead(coalescable);
+
+            state.waiting.push((ticket, coalescable));
+
+            if coalescable {
+                state.latest_coalescable_ticket = Some(ticket);
+            }
+
+            drop(state);
+
+            (queue, ticket, requests_ahead)
+        };
+
+        if requests_ahead > 0 {
+            on_wait(requests_ahead).await;
+        }
+
+        let turn = queue.turn.clone().lock_owned().await;
+
+        queue
+            .state()
+            .waiting
+            .retain(|(waiting_ticket, _)| *waiting_ticket != ticket);
+
+        let mut request = ScheduledRequest {
+            threads: self.threads.clone(),
+            thread_key: thread_key.to_owned(),
+            queue: queue.clone(),
+            _turn: turn,
+            _permit: None,
+        };
+
+        if coalescable {
+            // Give the sender a moment to send follow-up messages, which this request would otherwise not see.
+            tokio::time::sleep_until(received_at + self.coalescing_window).await;
+
+            if queue.state().latest_coalescable_ticket != Some(ticket) {
+                return Admission::Coalesced;
+            }
+        }
+
+        queue.state().running = true;
+
+        if let Some(semaphore) = &self.requests_semaphore {
+            let permit = match semaphore.clone().try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(_) => {
+                    let position = self.requests_waiting.fetch_add(1, Ordering::SeqCst) + 1;
+
+                    on_wait(position).await;
+
+                    // We never close the semaphore, so acquiring cannot fail.
+                    let permit = semaphore.clone().acquire_owned().await;
+
+                    self.requests_waiting.fetch_sub(1, Ordering::SeqCst);
+
+                    permit.expect("requests semaphore closed")
+                }
+            };
+
+            request._permit = Some(permit);
+        }
+
+        Admission::Proceed(request)
+    }
+}
+
+/// A request which holds its thread's turn (and possibly a global slot) until dropped.
+pub struct ScheduledRequest {
+    threads: ThreadQueues,
+    thread_key: String,
+    queue: Arc<ThreadQueue>,
+    _turn: OwnedMutexGuard<()>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for ScheduledRequest {
+    fn drop(&mut self) {
+        let mut threads = self.threads.lock().expect("thread queues lock poisoned");
+
+        let idle = {
+            let mut state = self.queue.state();
+            state.running = false;
+
+            state.waiting.is_empty()
+        };
+
+        // Nobody else is interested in this thread, so we stop keeping track of it.
+        if idle {
+            threads.remove(&self.thread_key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proceed(admission: Admission) -> ScheduledRequest {
+        match admission {
+            Admission::Proceed(request) => request,
+            Admission::Coalesced => panic!("expected the request to proceed"),
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_in_a_thread_are_serialized() {
+        let scheduler = Scheduler::new(0, Duration::ZERO);
+
+        let first = proceed(
+            scheduler
+                .admit("thread", false, Instant::now(), async |_| {})
+                .await,
+        );
+
+        let mut positions = vec![];
+
+        let (second, _) = tokio::join!(
+            scheduler.admit("thread", false, Instant::now(), async |position| {
+                positions.push(position)
+            }),
+            async {
+                tokio::task::yield_now().await;
+                drop(first);
+            },
+        );
+
+        assert!(matches!(second, Admission::Proceed(_)));
+        assert_eq!(positions, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn requests_in_other_threads_do_not_wait() {
+        let scheduler = Scheduler::new(0, Duration::ZERO);
+
+        let _first = proceed(
+            scheduler
+                .admit("thread", false, Instant::now(), async |_| {})
+                .await,
+        );
+
+        let mut positions = vec![];
+
+        let _second = proceed(
+            scheduler
+                .admit("other-thread", false, Instant::now(), async |position| {
+                    positions.push(position)
+                })
+                .await,
+        );
+
+        assert!(positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rapid_messages_are_coalesced() {
+        let scheduler = Scheduler::new(0, Duration::from_millis(100));
+
+        let mut positions = vec![];
+
+        let (first, second) = tokio::join!(
+            scheduler.admit("thread", true, Instant::now(), async |position| {
+                positions.push(position)
+            }),
+            async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+
+                scheduler
+                    .admit("thread", true, Instant::now(), async |position| {
+                        positions.push(position)
+                    })
+                    .await
+            },
+        );
+
+        assert!(matches!(first, Admission::Coalesced));
+        assert!(matches!(second, Admission::Proceed(_)));
+
+        // Waiting for a request which is about to be coalesced is not worth reporting.
+        assert!(positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn non_coalescable_requests_are_not_coalesced() {
+        let scheduler = Scheduler::new(0, Duration::from_millis(100));
+
+        let (first, _second) = tokio::join!(
+            scheduler.admit("thread", false, Instant::now(), async |_| {}),
+            async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+
+                scheduler
+                    .admit("thread", true, Instant::now(), async |_| {})
+                    .await
+            },
+        );
+
+        assert!(matches!(first, Admission::Proceed(_)));
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_bounded() {
+        let scheduler = Scheduler::new(1, Duration::ZERO);
+
+        let first = proceed(
+            scheduler
+                .admit("thread", false, Instant::now(), async |_| {})
+                .await,
+        );
+
+        let mut positions = vec![];
+
+        let (second, _) = tokio::join!(
+            scheduler.admit("other-thread", false, Instant::now(), async |position| {
+                positions.push(position)
+            }),
+            async {
+                tokio::task::yield_now().await;
+                drop(first);
+            },
+        );
+
+        assert!(matches!(second, Admission::Proceed(_)));
+        assert_eq!(positions, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn threads_are_forgotten_once_idle() {
+        let scheduler = Scheduler::new(0, Duration::ZERO);
+
+        let request = proceed(
+            scheduler
+                .admit("thread", true, Instant::now(), async |_| {})
+                .await,
+        );
+
+        assert_eq!(scheduler.threads.lock().unwrap().len(), 1);
+
+        drop(request);
+
+        assert!(scheduler.threads.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn requests_ahead() {
+        struct TestCase {
+            name: &'static str,
+            running: bool,
+            waiting: Vec<(u64, bool)>,
+            coalescable: bool,
+            expected: usize,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "Idle thread",
+                running: false,
+                waiting: vec![],
+                coalescable: true,
+                expected: 0,
+            },
+            TestCase {
+                name: "Running request",
+                running: true,
+                waiting: vec![],
+                coalescable: true,
+                expected: 1,
+            },
+            TestCase {
+                name: "Coalescable request answers waiting coalescable ones",
+                running: true,
+                waiting: vec![(1, true), (2, true)],
+                coalescable: true,
+                expected: 1,
+            },
+            TestCase {
+                name: "Non-coalescable request waits for the newest coalescable one",
+                running: true,
+                waiting: vec![(1, true), (2, true)],
+                coalescable: false,
+                expected: 2,
+            },
+            TestCase {
+                name: "Non-coalescable requests are waited for one by one",
+                running: false,
+                waiting: vec![(1, false), (2, true), (3, false)],
+                coalescable: true,
+                expected: 2,
+            },
+        ];
+
+        for test_case in test_cases {
+            let state = ThreadQueueState {
+                running: test_case.running,
+                waiting: test_case.waiting,
+                ..Default::default()
+            };
+
+            assert_eq!(
+                state.requests_ahead(test_case.coalescable),
+                test_case.expected,
+                "Test case: {}",
+                test_case.name
+            );
+        }
+    }
+}
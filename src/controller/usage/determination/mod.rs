@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests;
+
+use super::super::ControllerType;
+
+#[derive(Debug, PartialEq)]
+pub enum UsageControllerType {
+    Room,
+    Global,
+}
+
+pub fn determine_controller(text: &str) -> ControllerType {
+    if text.starts_with("room") {
+        return ControllerType::Usage(UsageControllerType::Room);
+    }
+
+    if text.starts_with("global") {
+        return ControllerType::Usage(UsageControllerType::Global);
+    }
+
+    ControllerType::UsageHelp
+}
@@ -0,0 +1,36 @@
+#[test]
+fn determine_controller() {
+    struct TestCase {
+        name: &'static str,
+        input: &'static str,
+        expected: super::ControllerType,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "Top-level is the usage guide",
+            input: "",
+            expected: super::ControllerType::UsageHelp,
+        },
+        TestCase {
+            name: "Anything else goes to the usage guide",
+            input: "whatever",
+            expected: super::ControllerType::UsageHelp,
+        },
+        TestCase {
+            name: "Room",
+            input: "room",
+            expected: super::ControllerType::Usage(super::UsageControllerType::Room),
+        },
+        TestCase {
+            name: "Global",
+            input: "global",
+            expected: super::ControllerType::Usage(super::UsageControllerType::Global),
+        },
+    ];
+
+    for test_case in test_cases {
+        let result = super::determine_controller(test_case.input);
+        assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+    }
+}
@@ -0,0 +1,30 @@
+use mxlink::MessageResponseType;
+
+use crate::{Bot, entity::MessageContext, strings};
+
+use super::UsageControllerType;
+
+pub async fn dispatch_controller(
+    handler: &UsageControllerType,
+    message_context: &MessageContext,
+    bot: &Bot,
+) -> anyhow::Result<()> {
+    // Anyone can see the usage of the room they're in, but usage across rooms is for administrators only.
+    if *handler == UsageControllerType::Global && !message_context.sender_can_manage_global_config()
+    {
+        bot.messaging()
+            .send_error_markdown_no_fail(
+                message_context.room(),
+                strings::usage::no_permissions_to_see_global_usage(),
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    match handler {
+        UsageControllerType::Room => super::report::handle_room(bot, message_context).await,
+        UsageControllerType::Global => super::report::handle_global(bot, message_context).await,
+    }
+}
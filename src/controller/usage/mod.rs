@@ -1,12 +1,13 @@
+mod determination;
+mod dispatching;
+mod report;
+
 use mxlink::MessageResponseType;
 
 use crate::{Bot, entity::MessageContext, strings};
 
-use super::ControllerType;
-
-pub fn determine_controller(_text: &str) -> ControllerType {
-    ControllerType::UsageHelp
-}
+pub use determination::{UsageControllerType, determine_controller};
+pub use dispatching::dispatch_controller;
 
 pub async fn handle_help(message_context: &MessageContext, bot: &Bot) -> anyhow::Result<()> {
     bot.messaging()
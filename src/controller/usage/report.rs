@@ -0,0 +1,218 @@
+use mxlink::MessageResponseType;
+use mxlink::matrix_sdk::Room;
+
+use crate::entity::usage::{RoomUsage, UsageCounters, UsagePeriod};
+use crate::{Bot, entity::MessageContext, strings};
+
+pub async fn handle_room(bot: &Bot, message_context: &MessageContext) -> anyhow::Result<()> {
+    let room_usage = bot
+        .usage_tracker()
+        .room_usage(message_context.room())
+        .await?;
+
+    let now = chrono::Utc::now();
+
+    let mut message = String::new();
+    message.push_str(&format!("## {}", strings::usage::room_report_heading()));
+    message.push_str("\n\n");
+
+    if room_usage.totals(UsagePeriod::AllTime, now).is_empty() {
+        message.push_str(strings::usage::nothing_recorded_yet());
+    } else {
+        for period in UsagePeriod::choices() {
+            message.push_str(&build_period_section(
+                *period,
+                &room_usage.totals(*period, now),
+                vec![
+                    (
+                        strings::usage::by_agent_heading(),
+                        label_agents(room_usage.totals_by_agent(*period, now)),
+                    ),
+                    (
+                        strings::usage::by_user_heading(),
+                        room_usage.totals_by_user(*period, now),
+                    ),
+                ],
+            ));
+        }
+    }
+
+    bot.messaging()
+        .send_text_markdown_no_fail(
+            message_context.room(),
+            message,
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+pub async fn handle_global(bot: &Bot, message_context: &MessageContext) -> anyhow::Result<()> {
+    let mut rooms: Vec<(String, RoomUsage)> = vec![];
+
+    for room in bot.matrix_link().client().joined_rooms() {
+        match bot.usage_tracker().room_usage(&room).await {
+            Ok(room_usage) => rooms.push((room_label(&room), room_usage)),
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    room_id = room.room_id().as_str(),
+                    "Failed to load room usage, so leaving the room out of the global usage report"
+                );
+            }
+        }
+    }
+
+    // Entries are keyed by user and agent, so merging the entries of all rooms yields correct totals for both.
+    let combined_usage = RoomUsage {
+        entries: rooms
+            .iter()
+            .flat_map(|(_, room_usage)| room_usage.entries.clone())
+            .collect(),
+    };
+
+    let now = chrono::Utc::now();
+
+    let mut message = String::new();
+    message.push_str(&format!("## {}", strings::usage::global_report_heading()));
+    message.push_str("\n\n");
+
+    if combined_usage.totals(UsagePeriod::AllTime, now).is_empty() {
+        message.push_str(strings::usage::nothing_recorded_yet());
+    } else {
+        for period in UsagePeriod::choices() {
+            let mut by_room: Vec<(String, UsageCounters)> = rooms
+                .iter()
+                .map(|(label, room_usage)| (label.clone(), room_usage.totals(*period, now)))
+                .filter(|(_, counters)| !counters.is_empty())
+                .collect();
+
+            by_room.sort_by(|(_, a), (_, b)| {
+                b.total_tokens()
+                    .cmp(&a.total_tokens())
+                    .then(b.requests.cmp(&a.requests))
+            });
+
+            message.push_str(&build_period_section(
+                *period,
+                &combined_usage.totals(*period, now),
+                vec![
+                    (strings::usage::by_room_heading(), by_room),
+                    (
+                        strings::usage::by_agent_heading(),
+                        label_agents(combined_usage.totals_by_agent(*period, now)),
+                    ),
+                    (
+                        strings::usage::by_user_heading(),
+                        combined_usage.totals_by_user(*period, now),
+                    ),
+                ],
+            ));
+        }
+    }
+
+    // The report covers all rooms and users, so it's only ever posted in a room private to the requester.
+    let dm_room = match bot
+        .messaging()
+        .direct_message_room(message_context.sender_id())
+        .await
+    {
+        Ok(dm_room) => dm_room,
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                "Failed to get a direct-message room with the requester"
+            );
+
+            bot.messaging()
+                .send_error_markdown_no_fail(
+                    message_context.room(),
+                    strings::usage::global_report_failed_to_reach_requester(),
+                    MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+                )
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    // The command came from the private room with the requester, so that's where the answer goes.
+    if dm_room.room_id() == message_context.room().room_id() {
+        bot.messaging()
+            .send_text_markdown_no_fail(
+                &dm_room,
+                message,
+                MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+            )
+            .await;
+
+        return Ok(());
+    }
+
+    let sent = bot
+        .messaging()
+        .send_text_markdown_no_fail(&dm_room, message, MessageResponseType::InRoom)
+        .await;
+
+    let notice = if sent.is_some() {
+        strings::usage::global_report_sent_via_direct_message()
+    } else {
+        strings::usage::global_report_failed_to_reach_requester()
+    };
+
+    bot.messaging()
+        .send_notice_markdown_no_fail(
+            message_context.room(),
+            notice.to_owned(),
+            MessageResponseType::Reply(message_context.thread_info().root_event_id.clone()),
+        )
+        .await;
+
+    Ok(())
+}
+
+fn build_period_section(
+    period: UsagePeriod,
+    totals: &UsageCounters,
+    breakdowns: Vec<(&str, Vec<(String, UsageCounters)>)>,
+) -> String {
+    let mut message = String::new();
+
+    message.push_str(&format!("### {}", strings::usage::period_heading(period)));
+    message.push_str("\n\n");
+
+    if totals.is_empty() {
+        message.push_str(strings::usage::nothing_recorded_in_period());
+        message.push_str("\n\n");
+        return message;
+    }
+
+    message.push_str(&strings::usage::total(totals));
+    message.push_str("\n\n");
+
+    for (heading, entries) in breakdowns {
+        message.push_str(&format!("**{heading}**:\n"));
+        for (label, counters) in entries {
+            message.push_str(&strings::usage::breakdown_entry(&label, &counters));
+            message.push('\n');
+        }
+        message.push('\n');
+    }
+
+    message
+}
+
+fn label_agents(entries: Vec<(String, UsageCounters)>) -> Vec<(String, UsageCounters)> {
+    entries
+        .into_iter()
+        .map(|(agent_id, counters)| (format!("`{agent_id}`"), counters))
+        .collect()
+}
+
+fn room_label(room: &Room) -> String {
+    match room.name() {
+        Some(name) => format!("{} (`{}`)", name, room.room_id()),
+        None => format!("`{}`", room.room_id()),
+    }
+}
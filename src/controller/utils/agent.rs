@@ -17,6 +17,27 @@ pub async fn get_effective_agent_for_purpose_or_complain(
     response_type: MessageResponseType,
     complain_when_purpose_unsupported: bool,
 ) -> Option<AgentInstance> {
+    get_effective_agent_chain_for_purpose_or_complain(
+        bot,
+        message_context,
+        agent_purpose,
+        response_type,
+        complain_when_purpose_unsupported,
+    )
+    .await?
+    .into_iter()
+    .next()
+}
+
+/// Like [`get_effective_agent_for_purpose_or_complain`], but returns the whole (non-empty) chain of agents:
+/// the primary agent, followed by the fallback agents to try (in order) when the previous one fails.
+pub async fn get_effective_agent_chain_for_purpose_or_complain(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agent_purpose: AgentPurpose,
+    response_type: MessageResponseType,
+    complain_when_purpose_unsupported: bool,
+) -> Option<Vec<AgentInstance>> {
     let agent_info = get_effective_agent_for_purpose(
         bot.agent_manager(),
         message_context.room_config_context(),
@@ -25,7 +46,7 @@ pub async fn get_effective_agent_for_purpose_or_complain(
     .await;
 
     match agent_info {
-        Ok(agent_info) => Some(agent_info.instance),
+        Ok(agent_info) => Some(agent_info.chain()),
         Err(err) => {
             let error_message = match err {
                 AgentForPurposeDeterminationError::Unknown(err_string) => Some(err_string),
@@ -62,3 +83,35 @@ pub async fn get_effective_agent_for_purpose_or_complain(
         }
     }
 }
+
+/// Lets the room know when a fallback agent (instead of the primary one in the chain) served the request.
+///
+/// This is sent as a notice, so it does not become part of the conversation context.
+pub async fn notify_if_served_by_fallback_agent(
+    bot: &Bot,
+    message_context: &MessageContext,
+    agents: &[AgentInstance],
+    serving_agent: &AgentInstance,
+    agent_purpose: AgentPurpose,
+    response_type: MessageResponseType,
+) {
+    let Some(primary_agent) = agents.first() else {
+        return;
+    };
+
+    if primary_agent.identifier() == serving_agent.identifier() {
+        return;
+    }
+
+    bot.messaging()
+        .send_notice_markdown_no_fail(
+            message_context.room(),
+            strings::agent::answered_by_fallback_agent(
+                primary_agent.identifier(),
+                serving_agent.identifier(),
+                &agent_purpose,
+            ),
+            response_type,
+        )
+        .await;
+}
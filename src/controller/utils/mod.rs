@@ -2,10 +2,12 @@ use mxlink::MessageResponseType;
 
 use crate::{
     Bot,
+    conversation::llm::{Author, Conversation, MessageContent},
     entity::{MessageContext, MessagePayload},
 };
 
 pub mod agent;
+pub mod quota;
 pub mod text_to_speech;
 
 pub async fn get_text_body_or_complain<'a>(
@@ -27,3 +29,26 @@ pub async fn get_text_body_or_complain<'a>(
         }
     }
 }
+
+/// Drops commands which act on the conversation they're posted in (e.g. `!bai export`) from the conversation,
+/// as they're not part of it.
+pub fn without_conversation_commands(
+    mut conversation: Conversation,
+    command_prefix: &str,
+    bot_display_name: &Option<String>,
+) -> Conversation {
+    conversation.messages.retain(|message| {
+        let MessageContent::Text(text) = &message.content else {
+            return true;
+        };
+
+        message.author != Author::User
+            || !super::determination::is_conversation_command(
+                command_prefix,
+                text,
+                bot_display_name,
+            )
+    });
+
+    conversation
+}
@@ -0,0 +1,64 @@
+use mxlink::MessageResponseType;
+
+use crate::{
+    Bot,
+    entity::{MessageContext, roomconfig::QuotaMetric, usage::find_exceeded_quota},
+    strings,
+};
+
+/// Checks the quotas concerning the given metrics and tells the sender when one of them has been used up.
+///
+/// Returns `true` if the request may proceed.
+/// Administrators are not subject to quotas, which lets them help out when users hit a limit.
+pub async fn ensure_within_quotas(
+    bot: &Bot,
+    message_context: &MessageContext,
+    metrics: &[QuotaMetric],
+    response_type: MessageResponseType,
+) -> bool {
+    if message_context.sender_can_manage_global_config() {
+        return true;
+    }
+
+    let room_usage = match bot.usage_tracker().room_usage(message_context.room()).await {
+        Ok(room_usage) => room_usage,
+        Err(err) => {
+            // Being unable to read usage should not take the bot down, so we let the request through.
+            tracing::warn!(
+                ?err,
+                room_id = message_context.room_id().as_str(),
+                "Failed to load room usage, so skipping quota checks"
+            );
+            return true;
+        }
+    };
+
+    let Some(exceeded) = find_exceeded_quota(
+        message_context.room_config_context(),
+        &room_usage,
+        message_context.sender_id(),
+        metrics,
+        chrono::Utc::now(),
+    ) else {
+        return true;
+    };
+
+    tracing::info!(
+        room_id = message_context.room_id().as_str(),
+        sender_id = message_context.sender_id().as_str(),
+        limit = exceeded.limit.to_string(),
+        used = exceeded.used,
+        value = exceeded.value,
+        "Refusing request due to an exceeded quota"
+    );
+
+    bot.messaging()
+        .send_error_markdown_no_fail(
+            message_context.room(),
+            &strings::quota::quota_exceeded(&exceeded),
+            response_type,
+        )
+        .await;
+
+    false
+}
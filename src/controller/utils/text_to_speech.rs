@@ -6,20 +6,36 @@ use tracing::Instrument;
 use crate::utils::mime::get_file_extension;
 use crate::{
     Bot,
-    agent::{AgentInstance, AgentPurpose, ControllerTrait, provider::TextToSpeechParams},
-    entity::MessageContext,
+    agent::{
+        AgentInstance, AgentPurpose, ControllerTrait, failover::run_with_failover,
+        provider::TextToSpeechParams,
+    },
+    entity::{MessageContext, roomconfig::QuotaMetric, usage::UsageCounters},
     strings,
 };
 
+/// Generates speech for the given text via the first agent in the chain which can do it (see [`run_with_failover`])
+/// and sends it to the room.
 pub async fn generate_and_send_tts_for_message(
     bot: &Bot,
     matrix_link: MatrixLink,
     message_context: &MessageContext,
     response_type: MessageResponseType,
-    speech_agent: &AgentInstance,
+    speech_agents: &[AgentInstance],
     text_message_event_id: &OwnedEventId,
     text_content: &str,
 ) -> bool {
+    if !super::quota::ensure_within_quotas(
+        bot,
+        message_context,
+        &[QuotaMetric::Speech],
+        response_type.clone(),
+    )
+    .await
+    {
+        return false;
+    }
+
     let reaction_event_response = bot
         .reacting()
         .react_no_fail(
@@ -34,7 +50,7 @@ pub async fn generate_and_send_tts_for_message(
         matrix_link,
         message_context,
         response_type,
-        speech_agent,
+        speech_agents,
         text_content,
     )
     .await;
@@ -63,35 +79,42 @@ async fn do_generate_and_send_tts_for_message(
     matrix_link: MatrixLink,
     message_context: &MessageContext,
     response_type: MessageResponseType,
-    speech_agent: &AgentInstance,
+    speech_agents: &[AgentInstance],
     text_content: &str,
 ) -> bool {
-    let params = TextToSpeechParams {
-        speed_override: message_context
-            .room_config_context()
-            .text_to_speech_speed_override(),
-
-        voice_override: message_context
-            .room_config_context()
-            .text_to_speech_voice_override(),
-    };
-
     let text_content = if let Some(text_content) = text_content.strip_prefix(bot.command_prefix()) {
         text_content.trim()
     } else {
         text_content
     };
 
-    let span = tracing::debug_span!(
-        "text_to_speech_generation",
-        agent_id = speech_agent.identifier().as_string()
-    );
+    let (speech_agent, text_to_speech_result) = run_with_failover(
+        speech_agents,
+        AgentPurpose::TextToSpeech,
+        async |speech_agent| {
+            let params = TextToSpeechParams {
+                speed_override: message_context
+                    .room_config_context()
+                    .text_to_speech_speed_override(),
+
+                voice_override: message_context
+                    .room_config_context()
+                    .text_to_speech_voice_override(),
+            };
+
+            let span = tracing::debug_span!(
+                "text_to_speech_generation",
+                agent_id = speech_agent.identifier().as_string()
+            );
 
-    let text_to_speech_result = speech_agent
-        .controller()
-        .text_to_speech(text_content, params)
-        .instrument(span)
-        .await;
+            speech_agent
+                .controller()
+                .text_to_speech(text_content, params)
+                .instrument(span)
+                .await
+        },
+    )
+    .await;
 
     let text_to_speech_result = match text_to_speech_result {
         Ok(text_to_speech_result) => text_to_speech_result,
@@ -119,6 +142,15 @@ async fn do_generate_and_send_tts_for_message(
         }
     };
 
+    bot.usage_tracker()
+        .record_no_fail(
+            message_context.room(),
+            message_context.sender_id(),
+            &speech_agent.identifier().as_string(),
+            UsageCounters::for_text_to_speech(text_content),
+        )
+        .await;
+
     let attachment_body_text = format!(
         "generated-speech.{}",
         get_file_extension(&text_to_speech_result.mime_type)
@@ -158,6 +190,16 @@ async fn do_generate_and_send_tts_for_message(
         .await;
 
     let Err(err) = result else {
+        super::agent::notify_if_served_by_fallback_agent(
+            bot,
+            message_context,
+            speech_agents,
+            speech_agent,
+            AgentPurpose::TextToSpeech,
+            response_type,
+        )
+        .await;
+
         return true;
     };
 
@@ -0,0 +1,298 @@
+//! Estimating how many tokens images and files take up in a model's context window.
+//!
+//! Providers turn media into tokens in their own ways (mostly based on image dimensions and document page counts),
+//! so each provider picks the [`MediaTokenCost`] matching what its API does with the media we send.
+
+use super::{FileDetails, ImageDetails, MessageContent};
+
+/// Dimensions assumed for images whose event carries none.
+/// Large enough to be scaled down by every provider, so that such images are counted at (close to) their maximum cost.
+const UNKNOWN_IMAGE_DIMENSION: u32 = 2048;
+
+/// Document size assumed to make up a page, for PDFs whose pages could not be counted (e.g. due to compressed object streams).
+const PDF_BYTES_PER_PAGE_ESTIMATE: usize = 50 * 1024;
+
+/// How a provider counts the images and files sent to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaTokenCost {
+    /// Images and files are not sent to the model at all (e.g. the OpenAI-compatible provider skips them).
+    None,
+
+    /// OpenAI's tile-based image cost (as for `detail: high`).
+    /// Also used for OpenAI-like APIs which accept images and PDFs the same way (e.g. Venice).
+    OpenAI,
+
+    /// Anthropic's pixel-based image cost. Files are not sent by the Anthropic provider, so they cost nothing.
+    Anthropic,
+
+    /// Gemini's tile-based image cost and fixed per-page PDF cost.
+    Gemini,
+}
+
+impl MediaTokenCost {
+    /// Returns the estimated token cost of the given message content, or `None` for text (which is counted by a tokenizer).
+    ///
+    /// Text-like files (e.g. `text/plain`, `application/json`) are read by models as text,
+    /// so they are counted via the given text counter.
+    pub fn tokens_for_content(
+        &self,
+        content: &MessageContent,
+        count_text: &dyn Fn(&str) -> u32,
+    ) -> Option<u32> {
+        match content {
+            MessageContent::Text(_) => None,
+            MessageContent::Image(details) => Some(self.tokens_for_image_details(details)),
+            MessageContent::File(details) => Some(self.tokens_for_file(details, count_text)),
+        }
+    }
+
+    fn tokens_for_image_details(&self, details: &ImageDetails) -> u32 {
+        let dimensions = details
+            .event_content
+            .info
+            .as_ref()
+            .and_then(|info| Some((info.width?, info.height?)))
+            .and_then(|(width, height)| {
+                Some((
+                    u32::try_from(u64::from(width)).ok()?,
+                    u32::try_from(u64::from(height)).ok()?,
+                ))
+            })
+            .filter(|(width, height)| *width > 0 && *height > 0);
+
+        let (width, height) =
+            dimensions.unwrap_or((UNKNOWN_IMAGE_DIMENSION, UNKNOWN_IMAGE_DIMENSION));
+
+        self.tokens_for_image(width, height)
+    }
+
+    fn tokens_for_file(&self, details: &FileDetails, count_text: &dyn Fn(&str) -> u32) -> u32 {
+        match self {
+            Self::None | Self::Anthropic => 0,
+            Self::OpenAI | Self::Gemini => {
+                if details.mime.essence_str() == "application/pdf" {
+                    return self.tokens_for_pdf(count_pdf_pages(&details.data));
+                }
+
+                if is_text_like(&details.mime) {
+                    return count_text(&String::from_utf8_lossy(&details.data));
+                }
+
+                // Audio, video and other binary files have costs we cannot tell without decoding them (e.g. by duration).
+                0
+            }
+        }
+    }
+
+    /// Returns the estimated token cost of an image of the given dimensions (in pixels).
+    pub fn tokens_for_image(&self, width: u32, height: u32) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::OpenAI => openai_image_tokens(width, height),
+            Self::Anthropic => anthropic_image_tokens(width, height),
+            Self::Gemini => gemini_image_tokens(width, height),
+        }
+    }
+
+    /// Returns the estimated token cost of a PDF document with the given number of pages.
+    pub fn tokens_for_pdf(&self, pages: u32) -> u32 {
+        match self {
+            Self::None | Self::Anthropic => 0,
+            // Each page is passed as an image (765 tokens for a typical page at high detail) along with its extracted text.
+            Self::OpenAI => pages * 1_500,
+            Self::Gemini => pages * 258,
+        }
+    }
+}
+
+/// See: https://platform.openai.com/docs/guides/images-vision#calculating-costs
+fn openai_image_tokens(width: u32, height: u32) -> u32 {
+    let (width, height) = scale_to_fit(width as f64, height as f64, 2048.0);
+
+    // Afterwards, the shortest side is scaled down to 768px
+    let shortest_side = width.min(height);
+    let (width, height) = if shortest_side > 768.0 {
+        let factor = 768.0 / shortest_side;
+        (width * factor, height * factor)
+    } else {
+        (width, height)
+    };
+
+    let tiles = (width / 512.0).ceil() as u32 * (height / 512.0).ceil() as u32;
+
+    85 + 170 * tiles
+}
+
+/// See: https://docs.anthropic.com/en/docs/build-with-claude/vision#calculate-image-costs
+fn anthropic_image_tokens(width: u32, height: u32) -> u32 {
+    let (width, height) = scale_to_fit(width as f64, height as f64, 1568.0);
+
+    // Images larger than ~1.15 megapixels are scaled down further, which caps their cost at about 1600 tokens.
+    ((width * height / 750.0).ceil() as u32).min(1_600)
+}
+
+/// See: https://ai.google.dev/gemini-api/docs/tokens#multimodal-tokens
+fn gemini_image_tokens(width: u32, height: u32) -> u32 {
+    if width <= 384 && height <= 384 {
+        return 258;
+    }
+
+    // Larger images are cropped into tiles, sized after the shortest side.
+    let tile_size = (width.min(height) as f64 / 1.5).clamp(256.0, 768.0);
+
+    let tiles =
+        (width as f64 / tile_size).ceil() as u32 * (height as f64 / tile_size).ceil() as u32;
+
+    258 * tiles
+}
+
+/// Scales the given dimensions down (preserving the aspect ratio) so that neither exceeds `max_side`.
+fn scale_to_fit(width: f64, height: f64, max_side: f64) -> (f64, f64) {
+    let longest_side = width.max(height);
+
+    if longest_side <= max_side {
+        return (width, height);
+    }
+
+    let factor = max_side / longest_side;
+    (width * factor, height * factor)
+}
+
+fn is_text_like(mime: &mxlink::mime::Mime) -> bool {
+    if mime.type_() == mxlink::mime::TEXT {
+        return true;
+    }
+
+    matches!(
+        mime.essence_str(),
+        "application/json"
+            | "application/xml"
+            | "application/yaml"
+            | "application/x-yaml"
+            | "application/javascript"
+            | "application/x-sh"
+    )
+}
+
+/// Counts the pages of a PDF document without fully parsing it, by looking for page objects (`/Type /Page`)
+/// and the page count declared by the page tree (`/Count N`).
+///
+/// Page objects may be hidden in compressed object streams, in which case the page count is estimated from the document's size.
+pub fn count_pdf_pages(data: &[u8]) -> u32 {
+    let page_objects = count_pdf_page_objects(data);
+    let declared_pages = max_pdf_declared_count(data);
+
+    let pages = page_objects.max(declared_pages);
+
+    if pages > 0 {
+        return pages;
+    }
+
+    (data.len() / PDF_BYTES_PER_PAGE_ESTIMATE).max(1) as u32
+}
+
+fn count_pdf_page_objects(data: &[u8]) -> u32 {
+    const NEEDLE: &[u8] = b"/Type";
+
+    let mut count = 0;
+    let mut position = 0;
+
+    while let Some(offset) = find(&data[position..], NEEDLE) {
+        position += offset + NEEDLE.len();
+
+        let rest = skip_pdf_whitespace(&data[position..]);
+
+        // `/Page`, but not `/Pages` (the page tree nodes)
+        if rest.starts_with(b"/Page") && !rest[5..].first().is_some_and(u8::is_ascii_alphanumeric) {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn max_pdf_declared_count(data: &[u8]) -> u32 {
+    const NEEDLE: &[u8] = b"/Count";
+
+    let mut max = 0;
+    let mut position = 0;
+
+    while let Some(offset) = find(&data[position..], NEEDLE) {
+        position += offset + NEEDLE.len();
+
+        let rest = skip_pdf_whitespace(&data[position..]);
+
+        let digits = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+
+        if let Ok(count) = std::str::from_utf8(&rest[..digits])
+            .unwrap_or_default()
+            .parse::<u32>()
+        {
+            max = max.max(count);
+        }
+    }
+
+    max
+}
+
+fn skip_pdf_whitespace(data: &[u8]) -> &[u8] {
+    let whitespace = data
+        .iter()
+        .take_while(|byte| byte.is_ascii_whitespace())
+        .count();
+
+    &data[whitespace..]
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_costs_follow_provider_formulas() {
+        // 1024x1024 is scaled to 768x768, which takes 4 tiles
+        assert_eq!(765, MediaTokenCost::OpenAI.tokens_for_image(1024, 1024));
+        // 2048x4096 is scaled to 1024x2048, then to 768x1536, which takes 6 tiles
+        assert_eq!(1105, MediaTokenCost::OpenAI.tokens_for_image(2048, 4096));
+        assert_eq!(255, MediaTokenCost::OpenAI.tokens_for_image(512, 512));
+
+        assert_eq!(1399, MediaTokenCost::Anthropic.tokens_for_image(1024, 1024));
+        assert_eq!(1600, MediaTokenCost::Anthropic.tokens_for_image(4000, 4000));
+        assert_eq!(54, MediaTokenCost::Anthropic.tokens_for_image(200, 200));
+
+        assert_eq!(258, MediaTokenCost::Gemini.tokens_for_image(300, 384));
+        // Tiles of 682px (1024 / 1.5), 2 in each direction
+        assert_eq!(1032, MediaTokenCost::Gemini.tokens_for_image(1024, 1024));
+
+        assert_eq!(0, MediaTokenCost::None.tokens_for_image(1024, 1024));
+    }
+
+    #[test]
+    fn pdf_page_counting_works() {
+        let pdf = b"%PDF-1.4
+1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj
+2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >> endobj
+3 0 obj << /Type /Page /Parent 2 0 R >> endobj
+4 0 obj << /Type/Page /Parent 2 0 R >> endobj
+%%EOF";
+
+        assert_eq!(2, count_pdf_pages(pdf));
+        assert_eq!(516, MediaTokenCost::Gemini.tokens_for_pdf(2));
+
+        // Only the page tree's count is visible (the page objects being compressed)
+        assert_eq!(
+            12,
+            count_pdf_pages(b"%PDF-1.7 << /Type /Pages /Count 12 /Kids [] >>")
+        );
+
+        // Nothing to go by but the size
+        assert_eq!(1, count_pdf_pages(b"%PDF-1.7"));
+        assert_eq!(3, count_pdf_pages(&vec![0; 3 * 50 * 1024]));
+    }
+}
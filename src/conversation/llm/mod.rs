@@ -1,4 +1,6 @@
 mod entity;
+mod media;
+mod summary;
 mod tokenization;
 mod utils;
 
@@ -6,5 +8,11 @@ mod utils;
 mod tests;
 
 pub use entity::*;
-pub use tokenization::{TokenEstimate, shorten_messages_list_to_context_size};
+pub use media::MediaTokenCost;
+pub use summary::{ConversationSummary, ConversationSummaryCache};
+pub use tokenization::{
+    TokenEstimate, approximate_token_size_for_message, estimate_messages_token_size,
+    load_huggingface_tokenizer, shorten_messages_list_to_context_size,
+    split_messages_list_to_context_size,
+};
 pub use utils::*;
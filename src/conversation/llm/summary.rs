@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use quick_cache::sync::Cache;
+
+use super::Message;
+
+/// A running summary of the oldest messages of a conversation, which no longer fit in the model's context window.
+#[derive(Debug, Clone)]
+pub struct ConversationSummary {
+    pub text: String,
+
+    /// How many of the conversation's oldest messages the summary covers.
+    pub messages_count: usize,
+
+    /// The timestamp of the last message covered by the summary.
+    /// Used to tell whether the beginning of the conversation is still the one that was summarized.
+    pub last_message_timestamp: DateTime<Utc>,
+}
+
+impl ConversationSummary {
+    pub fn new(text: String, messages: &[Message]) -> Option<Self> {
+        let last_message = messages.last()?;
+
+        Some(Self {
+            text,
+            messages_count: messages.len(),
+            last_message_timestamp: last_message.timestamp,
+        })
+    }
+
+    /// Returns those of the given messages (oldest first) which the summary does not cover yet.
+    ///
+    /// Returns `None` if the summary cannot be built upon, because the given messages do not start with the ones it covers
+    /// (e.g. fewer messages overflow the context window now, or some of them were redacted since).
+    pub fn uncovered_messages<'a>(&self, messages: &'a [Message]) -> Option<&'a [Message]> {
+        if self.messages_count == 0 || messages.len() < self.messages_count {
+            return None;
+        }
+
+        if messages[self.messages_count - 1].timestamp != self.last_message_timestamp {
+            return None;
+        }
+
+        Some(&messages[self.messages_count..])
+    }
+}
+
+/// Keeps the most recently used conversation summaries in memory, so that they are not recomputed on each turn.
+///
+/// Summaries are keyed by conversation (see [`ConversationSummaryCache::key`]) and are lost on restart,
+/// in which case they get recomputed when needed.
+pub struct ConversationSummaryCache {
+    lru_cache: Cache<String, ConversationSummary>,
+}
+
+impl ConversationSummaryCache {
+    pub fn new(lru_cache_size: usize) -> Self {
+        Self {
+            lru_cache: Cache::new(lru_cache_size),
+        }
+    }
+
+    /// Builds the key for the conversation started by the given event (e.g. a thread root) in the given room.
+    pub fn key(room_id: &str, root_event_id: &str) -> String {
+        format!("{room_id}/{root_event_id}")
+    }
+
+    pub fn get(&self, key: &str) -> Option<ConversationSummary> {
+        self.lru_cache.get(key)
+    }
+
+    pub fn insert(&self, key: String, summary: ConversationSummary) {
+        self.lru_cache.insert(key, summary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::llm::{Author, MessageContent};
+
+    fn messages(count: i64) -> Vec<Message> {
+        (0..count)
+            .map(|index| Message {
+                author: Author::User,
+                sender_id: None,
+                content: MessageContent::Text(format!("message {index}")),
+                timestamp: DateTime::from_timestamp(index * 60, 0).unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn uncovered_messages_works() {
+        let all_messages = messages(5);
+
+        let summary = ConversationSummary::new("summary".to_owned(), &all_messages[..3]).unwrap();
+
+        let uncovered = summary.uncovered_messages(&all_messages).unwrap();
+        assert_eq!(2, uncovered.len());
+        assert_eq!(uncovered[0].content, all_messages[3].content);
+
+        assert_eq!(
+            0,
+            summary
+                .uncovered_messages(&all_messages[..3])
+                .unwrap()
+                .len()
+        );
+
+        // Fewer messages than the summary covers
+        assert!(summary.uncovered_messages(&all_messages[..2]).is_none());
+
+        // The beginning of the conversation changed (e.g. a message got redacted)
+        assert!(summary.uncovered_messages(&all_messages[1..]).is_none());
+
+        assert!(ConversationSummary::new("summary".to_owned(), &[]).is_none());
+    }
+}
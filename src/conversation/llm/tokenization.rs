@@ -1,10 +1,12 @@
 use tiktoken_rs::CoreBPE;
 use tiktoken_rs::bpe_for_tokenizer;
 use tiktoken_rs::tokenizer;
+use tokenizers::Tokenizer;
 
-use super::{Author, Message, MessageContent};
+use super::{Author, MediaTokenCost, Message, MessageContent};
 
 /// How to count the tokens in a conversation when trimming it to fit the context window.
+#[derive(Clone, Copy)]
 pub enum TokenEstimate<'a> {
     /// Count via the [tiktoken-rs](https://github.com/zurawiki/tiktoken-rs) library.
     /// Accurate for OpenAI models; every other model falls back to the gpt-4
@@ -16,8 +18,17 @@ pub enum TokenEstimate<'a> {
     /// to land within roughly 10-20% of the real count for typical text, leaning
     /// slightly high: over-counting trims a little extra history, while
     /// under-counting would overflow the model's real context window. Use this for
-    /// every non-OpenAI provider.
+    /// non-OpenAI providers which have nothing more accurate to go by.
     Approximate,
+
+    /// Count via a [HuggingFace tokenizer](https://github.com/huggingface/tokenizers) matching the model
+    /// (see [`load_huggingface_tokenizer`]). Accurate for text, while the chat template's framing of each message
+    /// is approximated (it differs between models).
+    HuggingFace(&'a Tokenizer),
+
+    /// The approximation, scaled by the given factor. Used when an exact count for the conversation is known
+    /// (e.g. as reported by the provider's token counting API), so that the same factor can be applied to each message.
+    Calibrated(f32),
 }
 
 fn get_bpe_for_model(model: &str) -> &'static CoreBPE {
@@ -28,63 +39,159 @@ fn get_bpe_for_model(model: &str) -> &'static CoreBPE {
     bpe_for_tokenizer(tokenizer).unwrap()
 }
 
+/// Loads a HuggingFace tokenizer from a `tokenizer.json` file (as published along with most open-weight models).
+pub fn load_huggingface_tokenizer(path: &str) -> anyhow::Result<Tokenizer> {
+    Tokenizer::from_file(path)
+        .map_err(|err| anyhow::anyhow!("Failed loading the tokenizer from `{path}`: {err}"))
+}
+
+/// Counts the tokens of messages (including the images and files they carry) in a given way.
+struct MessageTokenCounter<'a> {
+    estimate: TokenEstimate<'a>,
+    media: MediaTokenCost,
+    tiktoken: Option<(&'static CoreBPE, &'a str)>,
+}
+
+impl<'a> MessageTokenCounter<'a> {
+    fn new(estimate: TokenEstimate<'a>, media: MediaTokenCost) -> Self {
+        // Loading the tiktoken data is expensive, so we resolve it once up
+        // front and reuse it for every message.
+        let tiktoken = match estimate {
+            TokenEstimate::Tiktoken(model) => Some((get_bpe_for_model(model), model)),
+            _ => None,
+        };
+
+        Self {
+            estimate,
+            media,
+            tiktoken,
+        }
+    }
+
+    fn count(&self, message: &Message) -> u32 {
+        let tokens = match (self.tiktoken, self.estimate) {
+            (Some((bpe, model)), _) => tiktoken_token_size_for_message(bpe, model, message),
+            (None, TokenEstimate::HuggingFace(tokenizer)) => {
+                huggingface_token_size_for_message(tokenizer, message)
+            }
+            (None, _) => approximate_token_size_for_message(message),
+        };
+
+        let media_tokens = self
+            .media
+            .tokens_for_content(&message.content, &|text| self.count_text(text))
+            .unwrap_or(0);
+
+        let tokens = tokens + media_tokens;
+
+        match self.estimate {
+            TokenEstimate::Calibrated(factor) => (tokens as f32 * factor).ceil() as u32,
+            _ => tokens,
+        }
+    }
+
+    fn count_text(&self, text: &str) -> u32 {
+        match (self.tiktoken, self.estimate) {
+            (Some((bpe, _)), _) => bpe.encode_with_special_tokens(text).len() as u32,
+            (None, TokenEstimate::HuggingFace(tokenizer)) => {
+                huggingface_token_size_for_text(tokenizer, text)
+            }
+            (None, _) => approximate_token_size_for_text(text),
+        }
+    }
+}
+
+/// Returns the token size of the given prompt and messages, as they would be counted when trimming them
+/// (see [`split_messages_list_to_context_size`]).
+pub fn estimate_messages_token_size(
+    estimate: TokenEstimate<'_>,
+    media: MediaTokenCost,
+    prompt_message: &Option<Message>,
+    messages: &[Message],
+) -> u32 {
+    let counter = MessageTokenCounter::new(estimate, media);
+
+    prompt_message
+        .iter()
+        .chain(messages.iter())
+        .map(|message| counter.count(message))
+        .sum()
+}
+
 pub fn shorten_messages_list_to_context_size(
     estimate: TokenEstimate<'_>,
+    media: MediaTokenCost,
     prompt_message: &Option<Message>,
-    mut messages: Vec<Message>,
+    messages: Vec<Message>,
     max_response_tokens: Option<u32>,
     max_context_tokens: u32,
 ) -> Vec<Message> {
-    // Loading the tiktoken data is expensive, so we resolve the counter once up
-    // front and reuse it for every message.
-    let tiktoken = match estimate {
-        TokenEstimate::Tiktoken(model) => Some((get_bpe_for_model(model), model)),
-        TokenEstimate::Approximate => None,
-    };
-    let count = |message: &Message| match tiktoken {
-        Some((bpe, model)) => tiktoken_token_size_for_message(bpe, model, message),
-        None => approximate_token_size_for_message(message),
-    };
+    let (_messages_to_drop, messages_to_keep) = split_messages_list_to_context_size(
+        estimate,
+        media,
+        prompt_message,
+        messages,
+        max_response_tokens,
+        max_context_tokens,
+    );
+
+    messages_to_keep
+}
+
+/// Splits the messages (oldest first) into those which do not fit in the context window and those which do.
+///
+/// Returns `(messages_to_drop, messages_to_keep)`, both in their original order.
+/// [`shorten_messages_list_to_context_size`] keeps the latter and discards the former,
+/// while other context management strategies may make use of the dropped messages (e.g. by summarizing them).
+pub fn split_messages_list_to_context_size(
+    estimate: TokenEstimate<'_>,
+    media: MediaTokenCost,
+    prompt_message: &Option<Message>,
+    mut messages: Vec<Message>,
+    max_response_tokens: Option<u32>,
+    max_context_tokens: u32,
+) -> (Vec<Message>, Vec<Message>) {
+    let counter = MessageTokenCounter::new(estimate, media);
+    let count = |message: &Message| counter.count(message);
 
     // We want to retain the prompt in all cases, so we always count it first.
     // We also always reserve enough tokens for the maximum response we expect.
     let mut current_context_length: u32 = if let Some(prompt_message) = prompt_message {
-        count(prompt_message) + max_response_tokens.unwrap_or(0)
+        count(prompt_message).saturating_add(max_response_tokens.unwrap_or(0))
     } else {
         0
     };
 
-    messages.reverse();
-
-    let mut messages_to_keep: Vec<Message> = Vec::new();
+    let mut messages_to_keep_count = 0;
 
-    for message in messages {
-        let tokens_for_message = count(&message);
+    for message in messages.iter().rev() {
+        let tokens_for_message = count(message);
 
-        if current_context_length + tokens_for_message > max_context_tokens {
+        if current_context_length.saturating_add(tokens_for_message) > max_context_tokens {
             break;
         }
 
         current_context_length += tokens_for_message;
 
-        messages_to_keep.push(message);
+        messages_to_keep_count += 1;
     }
 
     // Cut on a turn boundary: the loop may stop right after an assistant reply
     // whose triggering user message did not fit, which would leave the kept window
-    // starting on an orphaned reply. `messages_to_keep` is newest-first here, so
-    // the oldest kept messages are at the end; drop any trailing assistant messages
-    // until the window begins at the start of a turn (a user message).
-    while matches!(
-        messages_to_keep.last().map(|message| &message.author),
-        Some(Author::Assistant)
-    ) {
-        messages_to_keep.pop();
+    // starting on an orphaned reply. Drop any assistant messages at the start of the
+    // kept window until it begins at the start of a turn (a user message).
+    while messages_to_keep_count > 0
+        && matches!(
+            messages[messages.len() - messages_to_keep_count].author,
+            Author::Assistant
+        )
+    {
+        messages_to_keep_count -= 1;
     }
 
-    messages_to_keep.reverse();
+    let messages_to_keep = messages.split_off(messages.len() - messages_to_keep_count);
 
-    messages_to_keep
+    (messages, messages_to_keep)
 }
 
 /// Token size of a message via tiktoken, for a preloaded CoreBPE object.
@@ -129,16 +236,40 @@ const APPROX_TOKENS_PER_MESSAGE: u32 = 4;
 
 /// Provider-neutral, tokenizer-free token size of a message
 /// (see [`TokenEstimate::Approximate`]).
-fn approximate_token_size_for_message(message: &Message) -> u32 {
+pub fn approximate_token_size_for_message(message: &Message) -> u32 {
     let text_tokens = match &message.content {
         MessageContent::Text(text) => approximate_token_size_for_text(text),
         // Images and files are not counted as text, matching the tiktoken path.
+        // Their cost depends on the provider (see [`MediaTokenCost`]).
         MessageContent::Image(..) | MessageContent::File(..) => 0,
     };
 
     text_tokens + APPROX_TOKENS_PER_MESSAGE
 }
 
+/// Token size of a message via a HuggingFace tokenizer (see [`TokenEstimate::HuggingFace`]).
+fn huggingface_token_size_for_message(tokenizer: &Tokenizer, message: &Message) -> u32 {
+    let text_tokens = match &message.content {
+        MessageContent::Text(text) => huggingface_token_size_for_text(tokenizer, text),
+        MessageContent::Image(..) | MessageContent::File(..) => 0,
+    };
+
+    text_tokens + APPROX_TOKENS_PER_MESSAGE
+}
+
+fn huggingface_token_size_for_text(tokenizer: &Tokenizer, text: &str) -> u32 {
+    match tokenizer.encode(text, false) {
+        Ok(encoding) => encoding.len() as u32,
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                "Failed tokenizing text, so approximating its size instead"
+            );
+            approximate_token_size_for_text(text)
+        }
+    }
+}
+
 /// Rough token estimate for a piece of text, with no tokenizer.
 ///
 /// ASCII characters count as a quarter-token each (~4 chars/token); characters
@@ -271,6 +402,7 @@ pub mod test {
 
         let new_conversation_messages = super::shorten_messages_list_to_context_size(
             super::TokenEstimate::Tiktoken(model),
+            super::MediaTokenCost::None,
             &Some(prompt),
             conversation_messages,
             max_response_tokens,
@@ -382,6 +514,7 @@ pub mod test {
 
         let new_conversation_messages = super::shorten_messages_list_to_context_size(
             super::TokenEstimate::Tiktoken(model),
+            super::MediaTokenCost::None,
             &Some(prompt),
             conversation_messages,
             max_response_tokens,
@@ -452,6 +585,7 @@ pub mod test {
 
         let new_conversation_messages = super::shorten_messages_list_to_context_size(
             super::TokenEstimate::Approximate,
+            super::MediaTokenCost::None,
             &Some(prompt),
             vec![older, newer.clone()],
             None,
@@ -511,6 +645,7 @@ pub mod test {
 
         let kept = super::shorten_messages_list_to_context_size(
             super::TokenEstimate::Approximate,
+            super::MediaTokenCost::None,
             &Some(prompt),
             vec![user_one, asst_one, user_two.clone(), asst_two.clone()],
             None,
@@ -522,4 +657,130 @@ pub mod test {
         assert_eq!(kept.first().unwrap().content, user_two.content);
         assert_eq!(kept.last().unwrap().content, asst_two.content);
     }
+
+    #[test]
+    fn splitting_returns_the_dropped_messages() {
+        let message = |author: super::Author, text: &str| super::Message {
+            author,
+            sender_id: None,
+            content: super::MessageContent::Text(text.to_string()),
+            timestamp: chrono::Utc::now(),
+        };
+
+        let prompt = message(super::Author::Prompt, "system");
+        let user_one = message(super::Author::User, "user msg 1");
+        let asst_one = message(super::Author::Assistant, "asst msg 1");
+        let user_two = message(super::Author::User, "user msg 2");
+        let asst_two = message(super::Author::Assistant, "asst msg 2");
+
+        let per_message = super::approximate_token_size_for_message(&user_one);
+        let max_context_tokens =
+            super::approximate_token_size_for_message(&prompt) + (per_message * 3);
+
+        let (dropped, kept) = super::split_messages_list_to_context_size(
+            super::TokenEstimate::Approximate,
+            super::MediaTokenCost::None,
+            &Some(prompt),
+            vec![
+                user_one.clone(),
+                asst_one.clone(),
+                user_two.clone(),
+                asst_two.clone(),
+            ],
+            None,
+            max_context_tokens,
+        );
+
+        // The orphaned assistant reply (cut off from its user message) is dropped along with it, in order.
+        assert_eq!(2, dropped.len());
+        assert_eq!(dropped[0].content, user_one.content);
+        assert_eq!(dropped[1].content, asst_one.content);
+
+        assert_eq!(2, kept.len());
+        assert_eq!(kept[0].content, user_two.content);
+        assert_eq!(kept[1].content, asst_two.content);
+
+        // When everything fits, nothing is dropped.
+        let (dropped, kept) = super::split_messages_list_to_context_size(
+            super::TokenEstimate::Approximate,
+            super::MediaTokenCost::None,
+            &None,
+            vec![user_two, asst_two],
+            None,
+            max_context_tokens,
+        );
+
+        assert!(dropped.is_empty());
+        assert_eq!(2, kept.len());
+    }
+
+    #[test]
+    fn calibrated_estimate_scales_the_approximation() {
+        let messages = vec![super::Message {
+            author: super::Author::User,
+            sender_id: None,
+            content: super::MessageContent::Text("Hello there!".to_string()),
+            timestamp: chrono::Utc::now(),
+        }];
+
+        let approximate = super::estimate_messages_token_size(
+            super::TokenEstimate::Approximate,
+            super::MediaTokenCost::None,
+            &None,
+            &messages,
+        );
+        assert_eq!(7, approximate);
+
+        let calibrated = super::estimate_messages_token_size(
+            super::TokenEstimate::Calibrated(2.0),
+            super::MediaTokenCost::None,
+            &None,
+            &messages,
+        );
+        assert_eq!(14, calibrated);
+    }
+
+    #[test]
+    fn text_files_are_counted_as_text() {
+        use mxlink::matrix_sdk::ruma::OwnedMxcUri;
+        use mxlink::matrix_sdk::ruma::events::room::message::FileMessageEventContent;
+
+        let file = super::Message {
+            author: super::Author::User,
+            sender_id: None,
+            content: super::MessageContent::File(super::super::FileDetails::new(
+                FileMessageEventContent::plain(
+                    "notes.txt".to_owned(),
+                    OwnedMxcUri::from("mxc://example.com/notes"),
+                ),
+                mxlink::mime::TEXT_PLAIN,
+                "Hello there!".as_bytes().to_vec(),
+            )),
+            timestamp: chrono::Utc::now(),
+        };
+
+        let messages = vec![file];
+
+        // 3 text tokens plus the per-message overhead (4)
+        assert_eq!(
+            7,
+            super::estimate_messages_token_size(
+                super::TokenEstimate::Approximate,
+                super::MediaTokenCost::Gemini,
+                &None,
+                &messages,
+            )
+        );
+
+        // Providers which skip files get only the per-message overhead counted
+        assert_eq!(
+            4,
+            super::estimate_messages_token_size(
+                super::TokenEstimate::Approximate,
+                super::MediaTokenCost::None,
+                &None,
+                &messages,
+            )
+        );
+    }
 }
@@ -43,4 +43,13 @@ impl RoomEventFetcher {
             }
         }
     }
+
+    /// Drops the given event from the cache, so that it gets fetched anew next time.
+    ///
+    /// Useful when an event is known to have changed (e.g. it got edited), as fetched events carry their latest edit.
+    pub fn forget_event(&self, event_id: &OwnedEventId) {
+        if let Some(lru_cache) = &self.lru_cache {
+            lru_cache.remove(event_id);
+        }
+    }
 }
@@ -54,6 +54,123 @@ pub async fn get_matrix_messages_in_thread(
     Ok(messages)
 }
 
+/// Returns the messages in the thread which precede the given event (leaving the event itself out),
+/// or `None` if the event is not part of the thread.
+pub async fn get_matrix_messages_in_thread_before_event(
+    matrix_link: &MatrixLink,
+    room: &Room,
+    thread_id: OwnedEventId,
+    event_id: &OwnedEventId,
+) -> Result<Option<Vec<MatrixMessage>>, mxlink::matrix_sdk::Error> {
+    let messages_native = matrix_link
+        .threads()
+        .get_messages(room, thread_id, ThreadGetMessagesParams::default())
+        .await?;
+
+    let Some(position) = messages_native
+        .iter()
+        .position(|message| message.event_id() == &**event_id)
+    else {
+        return Ok(None);
+    };
+
+    let mut messages: Vec<MatrixMessage> = Vec::new();
+
+    for matrix_native_message in &messages_native[..position] {
+        let message_result =
+            convert_matrix_native_event_to_matrix_message(matrix_link, matrix_native_message)
+                .await?;
+
+        if let Some(message) = message_result {
+            messages.push(message);
+        }
+    }
+
+    Ok(Some(messages))
+}
+
+/// Returns the ID of the most recent text message (not notice) sent to the thread by the given user.
+pub async fn find_last_text_message_in_thread_by_sender(
+    matrix_link: &MatrixLink,
+    room: &Room,
+    thread_id: OwnedEventId,
+    sender_id: &OwnedUserId,
+) -> Result<Option<OwnedEventId>, mxlink::matrix_sdk::Error> {
+    let messages_native = matrix_link
+        .threads()
+        .get_messages(room, thread_id, ThreadGetMessagesParams::default())
+        .await?;
+
+    let event_id = messages_native
+        .iter()
+        .rev()
+        .filter(|message| message.sender() == &**sender_id)
+        .find(|message| {
+            matches!(
+                latest_message_content(message),
+                Some(AnyMessageLikeEventContent::RoomMessage(
+                    RoomMessageEventContent {
+                        msgtype: MessageType::Text(_),
+                        ..
+                    }
+                ))
+            )
+        })
+        .map(|message| message.event_id().to_owned());
+
+    Ok(event_id)
+}
+
+/// Returns the ID of the bot's answer to the given message, provided that the message is the last one in the thread
+/// which was not sent by the bot.
+///
+/// The answer is the first text message (not notice) sent by the bot after the given message.
+pub async fn find_bot_answer_to_last_user_message_in_thread(
+    matrix_link: &MatrixLink,
+    room: &Room,
+    thread_id: OwnedEventId,
+    bot_user_id: &OwnedUserId,
+    event_id: &OwnedEventId,
+) -> Result<Option<OwnedEventId>, mxlink::matrix_sdk::Error> {
+    let messages_native = matrix_link
+        .threads()
+        .get_messages(room, thread_id, ThreadGetMessagesParams::default())
+        .await?;
+
+    let Some(position) = messages_native
+        .iter()
+        .position(|message| message.event_id() == &**event_id)
+    else {
+        return Ok(None);
+    };
+
+    let messages_after = &messages_native[position + 1..];
+
+    if messages_after
+        .iter()
+        .any(|message| message.sender() != &**bot_user_id)
+    {
+        return Ok(None);
+    }
+
+    let event_id = messages_after
+        .iter()
+        .find(|message| {
+            matches!(
+                latest_message_content(message),
+                Some(AnyMessageLikeEventContent::RoomMessage(
+                    RoomMessageEventContent {
+                        msgtype: MessageType::Text(_),
+                        ..
+                    }
+                ))
+            )
+        })
+        .map(|message| message.event_id().to_owned());
+
+    Ok(event_id)
+}
+
 pub async fn get_matrix_messages_in_reply_chain(
     matrix_link: &MatrixLink,
     event_fetcher: &Arc<RoomEventFetcher>,
@@ -220,7 +337,7 @@ pub async fn convert_matrix_native_event_to_matrix_message(
     matrix_link: &MatrixLink,
     matrix_native_event: &AnySyncMessageLikeEvent,
 ) -> Result<Option<MatrixMessage>, mxlink::matrix_sdk::Error> {
-    let Some(content) = matrix_native_event.original_content() else {
+    let Some(content) = latest_message_content(matrix_native_event) else {
         // Redacted message
         return Ok(None);
     };
@@ -230,6 +347,11 @@ pub async fn convert_matrix_native_event_to_matrix_message(
         return Ok(None);
     };
 
+    if matches!(room_message.relates_to, Some(Relation::Replacement(_))) {
+        // Edits are applied to the messages they edit (see `latest_message_content`), so they're not messages of their own.
+        return Ok(None);
+    }
+
     let (text, is_notice) = match &room_message.msgtype {
         MessageType::Text(text_content) => (text_content.body.clone(), false),
         MessageType::Notice(notice_content) => (notice_content.body.clone(), true),
@@ -365,6 +487,31 @@ pub async fn convert_matrix_native_event_to_matrix_message(
     }))
 }
 
+/// Returns the content of the given message, taking its latest edit (`m.replace`) into account.
+///
+/// Servers bundle the latest edit of a message with it (see https://spec.matrix.org/v1.11/client-server-api/#server-side-aggregation-of-mreplace-relationships),
+/// so we don't need to fetch edits separately. Returns `None` for redacted messages.
+pub fn latest_message_content(
+    matrix_native_event: &AnySyncMessageLikeEvent,
+) -> Option<AnyMessageLikeEventContent> {
+    if let AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(original)) =
+        matrix_native_event
+        && let Some(edit) = &original.unsigned.relations.replace
+        && edit.sender == original.sender
+        && let Some(Relation::Replacement(replacement)) = &edit.content.relates_to
+    {
+        // The new content lacks the original relation (thread, reply), which we'd like to preserve.
+        let content = replacement
+            .new_content
+            .clone()
+            .with_relation(original.content.relates_to.clone());
+
+        return Some(AnyMessageLikeEventContent::RoomMessage(content));
+    }
+
+    matrix_native_event.original_content()
+}
+
 /// Determines the interaction context for an incoming (new) room event.
 ///
 /// This context is created based on the "newest message" (`current_event`), which is:
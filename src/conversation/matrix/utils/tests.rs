@@ -325,3 +325,109 @@ fn create_list_of_bot_user_prefixes_to_strip() {
         ]
     );
 }
+
+#[test]
+fn latest_message_content() {
+    use mxlink::matrix_sdk::ruma::events::{
+        AnyMessageLikeEventContent, AnySyncMessageLikeEvent,
+        room::message::{MessageType, Relation},
+    };
+
+    fn thread_message(unsigned: serde_json::Value) -> AnySyncMessageLikeEvent {
+        serde_json::from_value(serde_json::json!({
+            "type": "m.room.message",
+            "event_id": "$original:example.com",
+            "sender": "@user:example.com",
+            "origin_server_ts": 1,
+            "content": {
+                "msgtype": "m.text",
+                "body": "Original text",
+                "m.relates_to": {
+                    "rel_type": "m.thread",
+                    "event_id": "$root:example.com",
+                },
+            },
+            "unsigned": unsigned,
+        }))
+        .unwrap()
+    }
+
+    fn edit_by(sender: &str) -> serde_json::Value {
+        serde_json::json!({
+            "m.relations": {
+                "m.replace": {
+                    "type": "m.room.message",
+                    "event_id": "$edit:example.com",
+                    "sender": sender,
+                    "origin_server_ts": 2,
+                    "content": {
+                        "msgtype": "m.text",
+                        "body": "* Edited text",
+                        "m.new_content": {
+                            "msgtype": "m.text",
+                            "body": "Edited text",
+                        },
+                        "m.relates_to": {
+                            "rel_type": "m.replace",
+                            "event_id": "$original:example.com",
+                        },
+                    },
+                },
+            },
+        })
+    }
+
+    struct TestCase {
+        name: &'static str,
+        unsigned: serde_json::Value,
+        expected_body: &'static str,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            name: "Not edited",
+            unsigned: serde_json::json!({}),
+            expected_body: "Original text",
+        },
+        TestCase {
+            name: "Edited by the sender",
+            unsigned: edit_by("@user:example.com"),
+            expected_body: "Edited text",
+        },
+        TestCase {
+            name: "Edited by someone else",
+            unsigned: edit_by("@another:example.com"),
+            expected_body: "Original text",
+        },
+    ];
+
+    for test_case in test_cases {
+        let event = thread_message(test_case.unsigned);
+
+        let Some(AnyMessageLikeEventContent::RoomMessage(content)) =
+            super::latest_message_content(&event)
+        else {
+            panic!(
+                "Test case: {}: expected room message content",
+                test_case.name
+            );
+        };
+
+        let MessageType::Text(text_content) = &content.msgtype else {
+            panic!("Test case: {}: expected text message", test_case.name);
+        };
+
+        assert_eq!(
+            text_content.body, test_case.expected_body,
+            "Test case: {}",
+            test_case.name
+        );
+
+        // The thread relation of the original message is to be preserved, even when edited.
+        assert!(
+            matches!(&content.relates_to, Some(Relation::Thread(thread)) if thread.event_id == "$root:example.com"),
+            "Test case: {}",
+            test_case.name
+        );
+    }
+}
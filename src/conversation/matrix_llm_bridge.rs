@@ -8,7 +8,8 @@ use crate::conversation::matrix::MatrixMessage;
 use super::llm::{Conversation, Message, convert_matrix_message_to_llm_message};
 use super::matrix::{
     MatrixMessageProcessingParams, RoomEventFetcher, get_matrix_messages_in_reply_chain,
-    get_matrix_messages_in_thread, process_matrix_messages,
+    get_matrix_messages_in_thread, get_matrix_messages_in_thread_before_event,
+    process_matrix_messages,
 };
 
 pub async fn create_llm_conversation_for_matrix_thread(
@@ -26,6 +27,28 @@ pub async fn create_llm_conversation_for_matrix_thread(
     })
 }
 
+/// Like [`create_llm_conversation_for_matrix_thread`], but only with the messages preceding the given event.
+/// Returns `None` if the event is not part of the thread.
+pub async fn create_llm_conversation_for_matrix_thread_before_event(
+    matrix_link: &MatrixLink,
+    room: &mxlink::matrix_sdk::Room,
+    thread_id: OwnedEventId,
+    event_id: &OwnedEventId,
+    params: &MatrixMessageProcessingParams,
+) -> Result<Option<Conversation>, mxlink::matrix_sdk::Error> {
+    let Some(messages) =
+        get_matrix_messages_in_thread_before_event(matrix_link, room, thread_id, event_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let llm_messages = filter_messages_and_convert_to_llm_messages(messages, params).await;
+
+    Ok(Some(Conversation {
+        messages: llm_messages,
+    }))
+}
+
 pub async fn create_llm_conversation_for_matrix_reply_chain(
     matrix_link: &MatrixLink,
     event_fetcher: &Arc<RoomEventFetcher>,
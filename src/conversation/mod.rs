@@ -4,4 +4,5 @@ mod matrix_llm_bridge;
 
 pub(crate) use matrix_llm_bridge::{
     create_llm_conversation_for_matrix_reply_chain, create_llm_conversation_for_matrix_thread,
+    create_llm_conversation_for_matrix_thread_before_event,
 };
@@ -5,8 +5,17 @@ use mxlink::matrix_sdk::ruma::{OwnedDeviceId, OwnedUserId};
 use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::{
-    agent::{AgentDefinition, AgentPurpose, PublicIdentifier},
-    entity::{globalconfig::GlobalConfig, roomconfig::RoomSettingsHandler},
+    agent::{
+        AgentDefinition, AgentPurpose, PublicIdentifier,
+        tool::mcp::{
+            ServerDefinition as McpServerDefinition,
+            validate_server_definitions as validate_mcp_server_definitions,
+        },
+    },
+    entity::{
+        globalconfig::GlobalConfig,
+        roomconfig::{RoomSettingsHandler, split_agent_chain},
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -27,6 +36,12 @@ pub struct Config {
 
     pub agents: ConfigAgents,
 
+    #[serde(default)]
+    pub mcp: ConfigMcp,
+
+    #[serde(default)]
+    pub scheduling: ConfigScheduling,
+
     // Contains the initial global configuration values.
     // Not all properties of the object make sense to be configured statically,
     // so not all of them will be reflected onto the actual global configuration.
@@ -52,6 +67,7 @@ impl Config {
         }
 
         self.agents.validate()?;
+        self.mcp.validate()?;
         self.initial_global_config.clone().validate()?;
 
         Ok(())
@@ -425,6 +441,53 @@ impl ConfigAgents {
     }
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConfigMcp {
+    // MCP servers which agents may reference (by ID) in their tools configuration.
+    #[serde(default)]
+    pub servers: Vec<McpServerDefinition>,
+}
+
+impl ConfigMcp {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_mcp_server_definitions(&self.servers)
+            .map_err(|err| anyhow::anyhow!("The mcp.servers configuration is invalid: {}", err))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigScheduling {
+    // How many requests to agents (across all rooms) may be handled at the same time. 0 means unlimited.
+    #[serde(default = "super::defaults::scheduling_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+
+    // How many requests may be in progress against the same agent at the same time. 0 means unlimited.
+    #[serde(default = "super::defaults::scheduling_max_concurrent_requests_per_agent")]
+    pub max_concurrent_requests_per_agent: usize,
+
+    // Messages sent to the same thread within this many milliseconds of one another get a single answer. 0 disables coalescing.
+    #[serde(default = "super::defaults::scheduling_coalescing_window_milliseconds")]
+    pub coalescing_window_milliseconds: u64,
+}
+
+impl ConfigScheduling {
+    pub fn coalescing_window(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.coalescing_window_milliseconds)
+    }
+}
+
+impl Default for ConfigScheduling {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: super::defaults::scheduling_max_concurrent_requests(),
+            max_concurrent_requests_per_agent:
+                super::defaults::scheduling_max_concurrent_requests_per_agent(),
+            coalescing_window_milliseconds:
+                super::defaults::scheduling_coalescing_window_milliseconds(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigInitialGlobalConfig {
     #[serde(default)]
@@ -467,32 +530,35 @@ impl ConfigInitialGlobalConfig {
                 purpose.as_str().replace("-", "_")
             );
 
-            if agent_id.is_empty() {
+            if split_agent_chain(&agent_id).is_empty() {
                 return Err(anyhow::anyhow!(
                     "The {} configuration key must be pointing to a valid agent id or be set to null",
                     config_key,
                 ));
             }
 
-            let agent_identifier = PublicIdentifier::from_str(&agent_id);
-
-            let Some(agent_identifier) = agent_identifier else {
-                return Err(anyhow::anyhow!(
-                    "The {} configuration key specifies an agent id (`{}`) that cannot be parsed. {}",
-                    config_key,
-                    agent_id,
-                    crate::strings::agent::invalid_id_generic()
-                ));
-            };
-
-            // We only allow statically-defined agents for now, although DynamicGlobal may make sense too.
-            let PublicIdentifier::Static(_) = agent_identifier else {
-                return Err(anyhow::anyhow!(
-                    "The {} configuration key specifies an agent id (`{}`) which does not refer to a static agent.",
-                    config_key,
-                    agent_id,
-                ));
-            };
+            // The value may also be a comma-separated chain of agents to fail over between.
+            for agent_id in split_agent_chain(&agent_id) {
+                let agent_identifier = PublicIdentifier::from_str(&agent_id);
+
+                let Some(agent_identifier) = agent_identifier else {
+                    return Err(anyhow::anyhow!(
+                        "The {} configuration key specifies an agent id (`{}`) that cannot be parsed. {}",
+                        config_key,
+                        agent_id,
+                        crate::strings::agent::invalid_id_generic()
+                    ));
+                };
+
+                // We only allow statically-defined agents for now, although DynamicGlobal may make sense too.
+                let PublicIdentifier::Static(_) = agent_identifier else {
+                    return Err(anyhow::anyhow!(
+                        "The {} configuration key specifies an agent id (`{}`) which does not refer to a static agent.",
+                        config_key,
+                        agent_id,
+                    ));
+                };
+            }
         }
 
         let _: GlobalConfig = self.try_into()?;
@@ -1,4 +1,6 @@
-use super::{Avatar, ConfigUser, ConfigUserAuth, ConfigUserEncryption};
+use super::{
+    Avatar, ConfigInitialGlobalConfig, ConfigMcp, ConfigUser, ConfigUserAuth, ConfigUserEncryption,
+};
 use crate::entity::cfg::env;
 
 fn base_user() -> ConfigUser {
@@ -115,3 +117,70 @@ fn auth_config_treats_empty_strings_as_unset() {
 
     assert!(err.to_string().contains("Set one authentication method"));
 }
+
+#[test]
+fn mcp_config_parses_and_validates_servers() {
+    let mcp: ConfigMcp = serde_yaml_ng::from_str(
+        r#"
+servers:
+  - id: filesystem
+    transport: stdio
+    command: npx
+    args: ["-y", "@modelcontextprotocol/server-filesystem", "/data"]
+  - id: github
+    transport: http
+    url: https://api.githubcopilot.com/mcp/
+    headers:
+      Authorization: Bearer token
+"#,
+    )
+    .expect("the MCP configuration should parse");
+
+    assert_eq!(mcp.servers.len(), 2);
+    assert!(mcp.validate().is_ok());
+
+    let mcp: ConfigMcp = serde_yaml_ng::from_str(
+        r#"
+servers:
+  - id: filesystem
+    transport: stdio
+"#,
+    )
+    .expect("the MCP configuration should parse");
+
+    let err = mcp
+        .validate()
+        .expect_err("a stdio server without a command should be rejected");
+
+    assert!(err.to_string().contains("`filesystem`"));
+}
+
+#[test]
+fn initial_global_config_accepts_handler_chains_of_static_agents() {
+    let config: ConfigInitialGlobalConfig = serde_yaml_ng::from_str(
+        r#"
+handler:
+  catch_all: static/openai,static/groq
+  text_generation: null
+user_patterns: null
+"#,
+    )
+    .expect("the initial global configuration should parse");
+
+    assert!(config.validate().is_ok());
+
+    let config: ConfigInitialGlobalConfig = serde_yaml_ng::from_str(
+        r#"
+handler:
+  catch_all: static/openai,global/groq
+user_patterns: null
+"#,
+    )
+    .expect("the initial global configuration should parse");
+
+    let err = config
+        .validate()
+        .expect_err("a chain with a non-static agent should be rejected");
+
+    assert!(err.to_string().contains("`global/groq`"));
+}
@@ -22,6 +22,18 @@ pub(super) fn room_post_join_self_introduction_enabled() -> bool {
     true
 }
 
+pub(super) fn scheduling_max_concurrent_requests() -> usize {
+    10
+}
+
+pub(super) fn scheduling_max_concurrent_requests_per_agent() -> usize {
+    4
+}
+
+pub(super) fn scheduling_coalescing_window_milliseconds() -> u64 {
+    1000
+}
+
 pub(super) fn persistence_data_dir_path() -> Option<String> {
     None
 }
@@ -31,6 +31,9 @@ pub struct GlobalConfig {
     pub access: GlobalConfigAccess,
 
     pub agents: Vec<AgentDefinition>,
+
+    #[serde(default)]
+    pub prompts: Vec<PromptDefinition>,
 }
 
 impl GlobalConfig {
@@ -44,8 +47,14 @@ impl GlobalConfig {
             },
 
             agents: vec![],
+
+            prompts: vec![],
         }
     }
+
+    pub fn prompt_by_name(&self, name: &str) -> Option<&PromptDefinition> {
+        self.prompts.iter().find(|prompt| prompt.name == name)
+    }
 }
 
 impl GlobalConfigTrait for GlobalConfig {}
@@ -62,3 +71,36 @@ pub struct GlobalConfigAccess {
     // Example: `["@*:example.com"]`
     pub room_local_agent_manager_patterns: Option<Vec<String>>,
 }
+
+/// A named system prompt in the prompt library, which rooms can use by name instead of each keeping its own copy.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PromptDefinition {
+    pub name: String,
+
+    // A short explanation of what the prompt is for, shown when listing prompts.
+    pub description: String,
+
+    // The prompt itself. Prompt variables (e.g. `{{ baibot_now_utc }}`) are applied when it gets used.
+    pub text: String,
+}
+
+impl PromptDefinition {
+    pub fn validate_name(name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("The prompt name must not be empty.".to_owned());
+        }
+
+        // Names are used as command arguments, so we keep them simple.
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            return Err(
+                "The prompt name may only contain letters, digits and the `-`, `_` and `.` characters."
+                    .to_owned(),
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ mod entity;
 
 use mxlink::helpers::account_data_config::GlobalConfigManager as AccountDataGlobalConfigManager;
 
-pub use entity::{GlobalConfig, GlobalConfigCarrierContent};
+pub use entity::{GlobalConfig, GlobalConfigCarrierContent, PromptDefinition};
 
 pub type GlobalConfigurationManager =
     AccountDataGlobalConfigManager<GlobalConfig, GlobalConfigCarrierContent>;
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use mxlink::matrix_sdk::ruma::{EventId, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId, UserId};
+
+#[derive(Debug, PartialEq)]
+pub enum CancellationOutcome {
+    Cancelled,
+    /// The requester is neither the one who asked for the generation, nor an administrator.
+    NotAllowed,
+    /// There is no generation in progress for the given event.
+    NotFound,
+}
+
+struct InFlightGenerationState {
+    room_id: OwnedRoomId,
+    sender_id: OwnedUserId,
+    cancellation_sender: tokio::sync::watch::Sender<bool>,
+}
+
+type InFlightGenerationsByEventId = HashMap<OwnedEventId, Arc<InFlightGenerationState>>;
+
+/// Keeps track of generations which are in progress, so that they can be cancelled.
+///
+/// Each generation is known by the event which triggered it and (once posted) by the placeholder message
+/// which will become its answer, so that it can be cancelled via either of them.
+#[derive(Clone, Default)]
+pub struct InFlightGenerationTracker {
+    generations: Arc<Mutex<InFlightGenerationsByEventId>>,
+}
+
+impl InFlightGenerationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking a generation triggered by the given event.
+    ///
+    /// The generation stops being tracked when the returned [`InFlightGeneration`] is dropped.
+    pub fn start(
+        &self,
+        room_id: &RoomId,
+        trigger_event_id: &EventId,
+        sender_id: &UserId,
+    ) -> InFlightGeneration {
+        let (cancellation_sender, _) = tokio::sync::watch::channel(false);
+
+        let mut in_flight_generation = InFlightGeneration {
+            generations: self.generations.clone(),
+            state: Arc::new(InFlightGenerationState {
+                room_id: room_id.to_owned(),
+                sender_id: sender_id.to_owned(),
+                cancellation_sender,
+            }),
+            event_ids: vec![],
+        };
+
+        in_flight_generation.track_event(trigger_event_id.to_owned());
+
+        in_flight_generation
+    }
+
+    /// Cancels the generation associated with the given event (its trigger event or placeholder), if the requester may do so.
+    pub fn cancel(
+        &self,
+        room_id: &RoomId,
+        event_id: &EventId,
+        requester_id: &UserId,
+        requester_is_admin: bool,
+    ) -> CancellationOutcome {
+        let state = self
+            .generations
+            .lock()
+            .expect("in-flight generations lock poisoned")
+            .get(event_id)
+            .cloned();
+
+        let Some(state) = state.filter(|state| *state.room_id == *room_id) else {
+            return CancellationOutcome::NotFound;
+        };
+
+        if *state.sender_id != *requester_id && !requester_is_admin {
+            return CancellationOutcome::NotAllowed;
+        }
+
+        state.cancellation_sender.send_replace(true);
+
+        CancellationOutcome::Cancelled
+    }
+}
+
+/// A generation which is being tracked by an [`InFlightGenerationTracker`].
+pub struct InFlightGeneration {
+    generations: Arc<Mutex<InFlightGenerationsByEventId>>,
+    state: Arc<InFlightGenerationState>,
+    event_ids: Vec<OwnedEventId>,
+}
+
+impl InFlightGeneration {
+    /// Makes the generation cancellable via another event as well (e.g. the placeholder message posted for it).
+    pub fn track_event(&mut self, event_id: OwnedEventId) {
+        self.generations
+            .lock()
+            .expect("in-flight generations lock poisoned")
+            .insert(event_id.clone(), self.state.clone());
+
+        self.event_ids.push(event_id);
+    }
+
+    /// Resolves once the generation gets cancelled.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.state.cancellation_sender.subscribe();
+
+        // We hold the sender, so waiting cannot fail.
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+
+    /// Drives the given future to completion, unless the generation gets cancelled first (in which case `None` is returned).
+    ///
+    /// On cancellation, the future is dropped, which aborts whatever request it was making.
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            biased;
+            output = future => Some(output),
+            _ = self.cancelled() => None,
+        }
+    }
+}
+
+impl Drop for InFlightGeneration {
+    fn drop(&mut self) {
+        let mut generations = self
+            .generations
+            .lock()
+            .expect("in-flight generations lock poisoned");
+
+        for event_id in &self.event_ids {
+            generations.remove(event_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mxlink::matrix_sdk::ruma::{event_id, room_id, user_id};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn cancel_by_sender_aborts_run() {
+        let tracker = InFlightGenerationTracker::new();
+
+        let generation = tracker.start(
+            room_id!("!room:example.com"),
+            event_id!("$trigger:example.com"),
+            user_id!("@user:example.com"),
+        );
+
+        assert_eq!(
+            tracker.cancel(
+                room_id!("!room:example.com"),
+                event_id!("$trigger:example.com"),
+                user_id!("@user:example.com"),
+                false,
+            ),
+            CancellationOutcome::Cancelled,
+        );
+
+        let output = generation.run(std::future::pending::<()>()).await;
+
+        assert_eq!(output, None);
+    }
+
+    #[tokio::test]
+    async fn run_completes_when_not_cancelled() {
+        let tracker = InFlightGenerationTracker::new();
+
+        let generation = tracker.start(
+            room_id!("!room:example.com"),
+            event_id!("$trigger:example.com"),
+            user_id!("@user:example.com"),
+        );
+
+        assert_eq!(generation.run(async { 42 }).await, Some(42));
+    }
+
+    #[test]
+    fn cancel() {
+        struct TestCase {
+            name: &'static str,
+            room_id: &'static RoomId,
+            event_id: &'static EventId,
+            requester_id: &'static UserId,
+            requester_is_admin: bool,
+            expected: CancellationOutcome,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "Sender via the placeholder",
+                room_id: room_id!("!room:example.com"),
+                event_id: event_id!("$placeholder:example.com"),
+                requester_id: user_id!("@user:example.com"),
+                requester_is_admin: false,
+                expected: CancellationOutcome::Cancelled,
+            },
+            TestCase {
+                name: "Another user",
+                room_id: room_id!("!room:example.com"),
+                event_id: event_id!("$trigger:example.com"),
+                requester_id: user_id!("@another:example.com"),
+                requester_is_admin: false,
+                expected: CancellationOutcome::NotAllowed,
+            },
+            TestCase {
+                name: "Administrator",
+                room_id: room_id!("!room:example.com"),
+                event_id: event_id!("$trigger:example.com"),
+                requester_id: user_id!("@admin:example.com"),
+                requester_is_admin: true,
+                expected: CancellationOutcome::Cancelled,
+            },
+            TestCase {
+                name: "Unknown event",
+                room_id: room_id!("!room:example.com"),
+                event_id: event_id!("$unknown:example.com"),
+                requester_id: user_id!("@user:example.com"),
+                requester_is_admin: false,
+                expected: CancellationOutcome::NotFound,
+            },
+            TestCase {
+                name: "Another room",
+                room_id: room_id!("!another-room:example.com"),
+                event_id: event_id!("$trigger:example.com"),
+                requester_id: user_id!("@user:example.com"),
+                requester_is_admin: false,
+                expected: CancellationOutcome::NotFound,
+            },
+        ];
+
+        for test_case in test_cases {
+            let tracker = InFlightGenerationTracker::new();
+
+            let mut generation = tracker.start(
+                room_id!("!room:example.com"),
+                event_id!("$trigger:example.com"),
+                user_id!("@user:example.com"),
+            );
+            generation.track_event(event_id!("$placeholder:example.com").to_owned());
+
+            let result = tracker.cancel(
+                test_case.room_id,
+                test_case.event_id,
+                test_case.requester_id,
+                test_case.requester_is_admin,
+            );
+
+            assert_eq!(result, test_case.expected, "Test case: {}", test_case.name);
+        }
+    }
+
+    #[test]
+    fn dropped_generation_is_no_longer_tracked() {
+        let tracker = InFlightGenerationTracker::new();
+
+        let mut generation = tracker.start(
+            room_id!("!room:example.com"),
+            event_id!("$trigger:example.com"),
+            user_id!("@user:example.com"),
+        );
+        generation.track_event(event_id!("$placeholder:example.com").to_owned());
+
+        drop(generation);
+
+        for event_id in [
+            event_id!("$trigger:example.com"),
+            event_id!("$placeholder:example.com"),
+        ] {
+            assert_eq!(
+                tracker.cancel(
+                    room_id!("!room:example.com"),
+                    event_id,
+                    user_id!("@user:example.com"),
+                    false,
+                ),
+                CancellationOutcome::NotFound,
+            );
+        }
+    }
+}
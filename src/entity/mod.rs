@@ -1,13 +1,18 @@
 pub mod catch_up_marker;
 pub mod cfg;
 pub mod globalconfig;
+mod in_flight_generation;
 mod interaction_context;
 mod message_context;
 mod message_payload;
 mod room_config_context;
 pub mod roomconfig;
 mod trigger_event_info;
+pub mod usage;
 
+pub use in_flight_generation::{
+    CancellationOutcome, InFlightGeneration, InFlightGenerationTracker,
+};
 pub use interaction_context::{InteractionContext, InteractionTrigger};
 pub use message_context::MessageContext;
 pub use message_payload::MessagePayload;
@@ -1,29 +1,46 @@
-use mxlink::matrix_sdk::ruma::OwnedUserId;
+use chrono::FixedOffset;
+use mxlink::matrix_sdk::ruma::{OwnedRoomId, OwnedUserId, RoomId};
 
 use super::globalconfig::GlobalConfig;
 use super::roomconfig::RoomConfig;
 
+use crate::agent::provider::ReasoningEffort;
+use crate::utils::time::parse_utc_offset;
+
 use crate::entity::roomconfig::{
-    SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
-    TextGenerationAutoUsage, TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
-    TextToSpeechBotMessagesFlowType, TextToSpeechUserMessagesFlowType,
-    defaults as roomconfig_defaults,
+    QuotaLimit, ReactionAction, SpeechToTextFlowType,
+    SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages, TextGenerationAutoUsage,
+    TextGenerationContextManagementStrategy, TextGenerationPrefixRequirementType,
+    TextGenerationSenderContextMode, TextToSpeechBotMessagesFlowType,
+    TextToSpeechUserMessagesFlowType, default_reaction_actions, defaults as roomconfig_defaults,
+    reaction_keys_match,
 };
 
 #[derive(Debug)]
 pub struct RoomConfigContext {
+    room_id: OwnedRoomId,
     pub(crate) global_config: GlobalConfig,
     pub(crate) room_config: RoomConfig,
 }
 
 impl RoomConfigContext {
-    pub fn new(global_config: GlobalConfig, room_config: RoomConfig) -> RoomConfigContext {
+    pub fn new(
+        room_id: OwnedRoomId,
+        global_config: GlobalConfig,
+        room_config: RoomConfig,
+    ) -> RoomConfigContext {
         Self {
+            room_id,
             global_config,
             room_config,
         }
     }
 
+    /// The room whose configuration this is.
+    pub fn room_id(&self) -> &RoomId {
+        &self.room_id
+    }
+
     pub fn speech_to_text_flow_type(&self) -> SpeechToTextFlowType {
         self.room_config
             .settings
@@ -94,19 +111,42 @@ impl RoomConfigContext {
         }
     }
 
+    /// Returns the effective system prompt override, if any.
+    ///
+    /// Room settings take precedence over global ones. At each level, a free-form prompt override
+    /// takes precedence over a prompt referenced (by name) from the prompt library.
+    /// References to prompts missing from the library are skipped.
     pub fn text_generation_prompt_override(&self) -> Option<String> {
+        [
+            &self.room_config.settings.text_generation,
+            &self.global_config.fallback_room_settings.text_generation,
+        ]
+        .into_iter()
+        .find_map(|settings| {
+            settings.prompt_override.clone().or_else(|| {
+                settings
+                    .prompt_name
+                    .as_deref()
+                    .and_then(|name| self.global_config.prompt_by_name(name))
+                    .map(|prompt| prompt.text.clone())
+            })
+        })
+    }
+
+    pub fn text_generation_timezone(&self) -> Option<FixedOffset> {
         self.room_config
             .settings
             .text_generation
-            .prompt_override
+            .timezone
             .clone()
             .or_else(|| {
                 self.global_config
                     .fallback_room_settings
                     .text_generation
-                    .prompt_override
+                    .timezone
                     .clone()
             })
+            .and_then(|timezone| parse_utc_offset(&timezone))
     }
 
     pub fn text_generation_temperature_override(&self) -> Option<f32> {
@@ -122,6 +162,47 @@ impl RoomConfigContext {
             })
     }
 
+    pub fn text_generation_model_override(&self) -> Option<String> {
+        self.room_config
+            .settings
+            .text_generation
+            .model_override
+            .clone()
+            .or_else(|| {
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .model_override
+                    .clone()
+            })
+    }
+
+    pub fn text_generation_max_response_tokens_override(&self) -> Option<u32> {
+        self.room_config
+            .settings
+            .text_generation
+            .max_response_tokens_override
+            .or({
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .max_response_tokens_override
+            })
+    }
+
+    pub fn text_generation_reasoning_effort_override(&self) -> Option<ReasoningEffort> {
+        self.room_config
+            .settings
+            .text_generation
+            .reasoning_effort_override
+            .or({
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .reasoning_effort_override
+            })
+    }
+
     pub fn text_generation_context_management_enabled(&self) -> bool {
         self.room_config
             .settings
@@ -136,6 +217,37 @@ impl RoomConfigContext {
             .unwrap_or(false)
     }
 
+    pub fn text_generation_context_management_strategy(
+        &self,
+    ) -> TextGenerationContextManagementStrategy {
+        self.room_config
+            .settings
+            .text_generation
+            .context_management_strategy
+            .or({
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .context_management_strategy
+            })
+            .unwrap_or(roomconfig_defaults::TEXT_GENERATION_CONTEXT_MANAGEMENT_STRATEGY)
+    }
+
+    pub fn text_generation_context_summary_agent(&self) -> Option<String> {
+        self.room_config
+            .settings
+            .text_generation
+            .context_summary_agent
+            .clone()
+            .or_else(|| {
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .context_summary_agent
+                    .clone()
+            })
+    }
+
     pub fn text_generation_thinking_notice_enabled(&self) -> bool {
         self.room_config
             .settings
@@ -150,6 +262,34 @@ impl RoomConfigContext {
             .unwrap_or(false)
     }
 
+    pub fn text_generation_streaming_enabled(&self) -> bool {
+        self.room_config
+            .settings
+            .text_generation
+            .streaming_enabled
+            .or({
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .streaming_enabled
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn text_generation_regenerate_on_edit_enabled(&self) -> bool {
+        self.room_config
+            .settings
+            .text_generation
+            .regenerate_on_edit_enabled
+            .or({
+                self.global_config
+                    .fallback_room_settings
+                    .text_generation
+                    .regenerate_on_edit_enabled
+            })
+            .unwrap_or(false)
+    }
+
     pub fn text_generation_sender_context_mode(&self) -> TextGenerationSenderContextMode {
         self.room_config
             .settings
@@ -230,6 +370,46 @@ impl RoomConfigContext {
             })
     }
 
+    pub fn quota_limit(&self, limit: &QuotaLimit) -> Option<f64> {
+        limit
+            .get(&self.room_config.settings.quota)
+            .or_else(|| limit.get(&self.global_config.fallback_room_settings.quota))
+    }
+
+    pub fn quota_cost_per_million_tokens(&self) -> Option<f64> {
+        self.room_config.settings.quota.cost_per_million_tokens.or({
+            self.global_config
+                .fallback_room_settings
+                .quota
+                .cost_per_million_tokens
+        })
+    }
+
+    /// Returns the action to perform for a reaction with the given key (if any).
+    ///
+    /// The room configuration takes precedence over the global one, which takes precedence over the default mapping.
+    pub fn reaction_action(&self, key: &str) -> Option<ReactionAction> {
+        let action = self
+            .room_config
+            .settings
+            .reactions
+            .get(key)
+            .or_else(|| self.global_config.fallback_room_settings.reactions.get(key))
+            .cloned()
+            .or_else(|| {
+                default_reaction_actions()
+                    .into_iter()
+                    .find(|(default_key, _)| reaction_keys_match(default_key, key))
+                    .map(|(_, action)| action)
+            })?;
+
+        if action == ReactionAction::Disabled {
+            return None;
+        }
+
+        Some(action)
+    }
+
     pub fn is_user_allowed_room_local_agent_manager(
         &self,
         user_id: OwnedUserId,
@@ -244,3 +424,122 @@ impl RoomConfigContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mxlink::matrix_sdk::ruma::room_id;
+
+    use crate::entity::globalconfig::PromptDefinition;
+
+    #[test]
+    fn text_generation_prompt_override_resolves_library_prompts() {
+        let mut global_config = GlobalConfig::default();
+        global_config.prompts.push(PromptDefinition {
+            name: "translator".to_owned(),
+            description: "Translates messages".to_owned(),
+            text: "You are a translator.".to_owned(),
+        });
+
+        let mut room_config = RoomConfig::default();
+
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
+        assert_eq!(context.text_generation_prompt_override(), None);
+
+        // A library prompt set globally applies to all rooms
+        global_config
+            .fallback_room_settings
+            .text_generation
+            .prompt_name = Some("translator".to_owned());
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
+        assert_eq!(
+            context.text_generation_prompt_override().as_deref(),
+            Some("You are a translator.")
+        );
+
+        // A free-form prompt set in the room takes precedence over it
+        room_config.settings.text_generation.prompt_override = Some("You are a pirate.".to_owned());
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
+        assert_eq!(
+            context.text_generation_prompt_override().as_deref(),
+            Some("You are a pirate.")
+        );
+
+        // References to missing prompts are skipped, falling back to the global settings
+        room_config.settings.text_generation.prompt_override = None;
+        room_config.settings.text_generation.prompt_name = Some("missing".to_owned());
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config,
+            room_config,
+        );
+        assert_eq!(
+            context.text_generation_prompt_override().as_deref(),
+            Some("You are a translator.")
+        );
+    }
+
+    #[test]
+    fn reaction_action_falls_back_to_global_config_and_defaults() {
+        let mut global_config = GlobalConfig::default();
+        let mut room_config = RoomConfig::default();
+
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
+        assert_eq!(
+            context.reaction_action("🗣️"),
+            Some(ReactionAction::TextToSpeech)
+        );
+        assert_eq!(context.reaction_action("🌍"), None);
+
+        global_config
+            .fallback_room_settings
+            .reactions
+            .set("🌍", Some(ReactionAction::Translate(None)));
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config.clone(),
+            room_config.clone(),
+        );
+        assert_eq!(
+            context.reaction_action("🌍"),
+            Some(ReactionAction::Translate(None))
+        );
+
+        // The room may remap keys and disable default mappings
+        room_config
+            .settings
+            .reactions
+            .set("🌍", Some(ReactionAction::Prompt("tldr".to_owned())));
+        room_config
+            .settings
+            .reactions
+            .set("🗣", Some(ReactionAction::Disabled));
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config,
+            room_config,
+        );
+        assert_eq!(
+            context.reaction_action("🌍"),
+            Some(ReactionAction::Prompt("tldr".to_owned()))
+        );
+        assert_eq!(context.reaction_action("🗣️"), None);
+    }
+}
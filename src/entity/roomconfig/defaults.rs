@@ -1,6 +1,7 @@
 use super::{SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages};
 use super::{
-    TextGenerationAutoUsage, TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
+    TextGenerationAutoUsage, TextGenerationContextManagementStrategy,
+    TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
 };
 use super::{TextToSpeechBotMessagesFlowType, TextToSpeechUserMessagesFlowType};
 
@@ -9,6 +10,9 @@ pub const TEXT_GENERATION_PREFIX_REQUIREMENT_TYPE: TextGenerationPrefixRequireme
 
 pub const TEXT_GENERATION_AUTO_USAGE: TextGenerationAutoUsage = TextGenerationAutoUsage::Always;
 
+pub const TEXT_GENERATION_CONTEXT_MANAGEMENT_STRATEGY: TextGenerationContextManagementStrategy =
+    TextGenerationContextManagementStrategy::Truncate;
+
 pub const TEXT_GENERATION_SENDER_CONTEXT_MODE: TextGenerationSenderContextMode =
     TextGenerationSenderContextMode::Disabled;
 
@@ -2,6 +2,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::agent::AgentPurpose;
 
+/// Separates the agents in a handler's fallback chain (e.g. `static/openai,global/groq-llama`).
+pub const AGENT_CHAIN_SEPARATOR: char = ',';
+
+/// Each handler holds either a single agent ID or an ordered, comma-separated chain of agent IDs.
+/// The agents after the first are only used when the ones before them fail with a retryable error.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct RoomSettingsHandler {
     /// The agent used for any of the tasks which do not have a dedicated agent for them
@@ -38,6 +43,18 @@ impl RoomSettingsHandler {
         }
     }
 
+    /// Returns the ordered chain of agent IDs configured for the given purpose (falling back to the catch-all one).
+    ///
+    /// The chain is empty when nothing is configured.
+    pub fn get_chain_by_purpose_with_catch_all_fallback(
+        &self,
+        purpose: AgentPurpose,
+    ) -> Vec<String> {
+        self.get_by_purpose_with_catch_all_fallback(purpose)
+            .map(|value| split_agent_chain(&value))
+            .unwrap_or_default()
+    }
+
     pub fn set_by_purpose(&mut self, purpose: AgentPurpose, agent_id: Option<String>) {
         match purpose {
             AgentPurpose::CatchAll => {
@@ -58,3 +75,59 @@ impl RoomSettingsHandler {
         };
     }
 }
+
+pub fn split_agent_chain(value: &str) -> Vec<String> {
+    value
+        .split(AGENT_CHAIN_SEPARATOR)
+        .map(|agent_id| agent_id.trim())
+        .filter(|agent_id| !agent_id.is_empty())
+        .map(|agent_id| agent_id.to_owned())
+        .collect()
+}
+
+pub fn join_agent_chain(agent_ids: &[String]) -> Option<String> {
+    if agent_ids.is_empty() {
+        return None;
+    }
+
+    Some(agent_ids.join(&AGENT_CHAIN_SEPARATOR.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_are_split_and_joined() {
+        assert_eq!(
+            split_agent_chain("static/openai, global/groq-llama,"),
+            vec!["static/openai".to_owned(), "global/groq-llama".to_owned()],
+        );
+        assert_eq!(
+            split_agent_chain("static/openai"),
+            vec!["static/openai".to_owned()]
+        );
+
+        assert_eq!(join_agent_chain(&[]), None);
+        assert_eq!(
+            join_agent_chain(&["static/openai".to_owned(), "global/groq-llama".to_owned()]),
+            Some("static/openai,global/groq-llama".to_owned()),
+        );
+    }
+
+    #[test]
+    fn chain_falls_back_to_catch_all() {
+        let mut handler = RoomSettingsHandler::default();
+        assert!(
+            handler
+                .get_chain_by_purpose_with_catch_all_fallback(AgentPurpose::TextGeneration)
+                .is_empty()
+        );
+
+        handler.set_by_purpose(AgentPurpose::CatchAll, Some("static/a,static/b".to_owned()));
+        assert_eq!(
+            handler.get_chain_by_purpose_with_catch_all_fallback(AgentPurpose::TextGeneration),
+            vec!["static/a".to_owned(), "static/b".to_owned()],
+        );
+    }
+}
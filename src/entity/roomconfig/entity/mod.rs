@@ -8,16 +8,23 @@ use serde::{Deserialize, Serialize};
 use crate::agent::AgentDefinition;
 
 mod handler;
+mod quota;
+mod reactions;
 mod speech_to_text;
 mod text_generation;
 mod text_to_speech;
 
-pub use handler::RoomSettingsHandler;
+pub use handler::{RoomSettingsHandler, join_agent_chain, split_agent_chain};
+pub use quota::{QuotaLimit, QuotaLimits, QuotaMetric, QuotaPeriod, QuotaScope, RoomSettingsQuota};
+pub use reactions::{
+    ReactionAction, RoomSettingsReactions, default_reaction_actions, reaction_keys_match,
+};
 pub use speech_to_text::{
     SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
 };
 pub use text_generation::{
-    TextGenerationAutoUsage, TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
+    TextGenerationAutoUsage, TextGenerationContextManagementStrategy,
+    TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
 };
 pub use text_to_speech::{TextToSpeechBotMessagesFlowType, TextToSpeechUserMessagesFlowType};
 
@@ -99,4 +106,10 @@ pub struct RoomSettings {
 
     #[serde(default)]
     pub text_to_speech: text_to_speech::RoomSettingsTextToSpeech,
+
+    #[serde(default)]
+    pub quota: quota::RoomSettingsQuota,
+
+    #[serde(default)]
+    pub reactions: reactions::RoomSettingsReactions,
 }
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RoomSettingsQuota {
+    /// Limits applying to each user separately
+    #[serde(default)]
+    pub per_user: QuotaLimits,
+
+    /// Limits applying to everyone in the room combined
+    #[serde(default)]
+    pub per_room: QuotaLimits,
+
+    /// The price (in whatever currency you budget in) of 1 million tokens, used for estimating costs
+    pub cost_per_million_tokens: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct QuotaLimits {
+    pub daily_tokens: Option<u64>,
+    pub monthly_tokens: Option<u64>,
+
+    pub daily_cost: Option<f64>,
+    pub monthly_cost: Option<f64>,
+
+    pub daily_images: Option<u64>,
+    pub monthly_images: Option<u64>,
+
+    pub daily_speech: Option<u64>,
+    pub monthly_speech: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuotaScope {
+    User,
+    Room,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuotaPeriod {
+    Daily,
+    Monthly,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuotaMetric {
+    /// Text-generation tokens (prompt + completion)
+    Tokens,
+
+    /// Estimated text-generation cost, based on the configured cost per million tokens
+    Cost,
+
+    /// Generated (or edited) images
+    Images,
+
+    /// Text-to-speech generations
+    Speech,
+}
+
+impl QuotaScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Room => "room",
+        }
+    }
+}
+
+impl QuotaPeriod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+        }
+    }
+}
+
+impl QuotaMetric {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Cost => "cost",
+            Self::Images => "images",
+            Self::Speech => "speech",
+        }
+    }
+
+    /// Tells if the limit is a whole number (a count), as opposed to an amount of money.
+    pub fn is_count(&self) -> bool {
+        !matches!(self, Self::Cost)
+    }
+}
+
+/// A single quota setting (e.g. `user-daily-tokens`), identifying a field of `RoomSettingsQuota`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuotaLimit {
+    pub scope: QuotaScope,
+    pub period: QuotaPeriod,
+    pub metric: QuotaMetric,
+}
+
+impl QuotaLimit {
+    pub fn new(scope: QuotaScope, period: QuotaPeriod, metric: QuotaMetric) -> Self {
+        Self {
+            scope,
+            period,
+            metric,
+        }
+    }
+
+    pub fn choices() -> Vec<Self> {
+        let mut choices = vec![];
+
+        for scope in [QuotaScope::User, QuotaScope::Room] {
+            for metric in [
+                QuotaMetric::Tokens,
+                QuotaMetric::Cost,
+                QuotaMetric::Images,
+                QuotaMetric::Speech,
+            ] {
+                for period in [QuotaPeriod::Daily, QuotaPeriod::Monthly] {
+                    choices.push(Self::new(scope, period, metric));
+                }
+            }
+        }
+
+        choices
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::choices()
+            .into_iter()
+            .find(|choice| choice.to_string() == s)
+    }
+
+    pub fn get(&self, settings: &RoomSettingsQuota) -> Option<f64> {
+        let limits = match self.scope {
+            QuotaScope::User => &settings.per_user,
+            QuotaScope::Room => &settings.per_room,
+        };
+
+        match (self.metric, self.period) {
+            (QuotaMetric::Tokens, QuotaPeriod::Daily) => limits.daily_tokens.map(|v| v as f64),
+            (QuotaMetric::Tokens, QuotaPeriod::Monthly) => limits.monthly_tokens.map(|v| v as f64),
+            (QuotaMetric::Cost, QuotaPeriod::Daily) => limits.daily_cost,
+            (QuotaMetric::Cost, QuotaPeriod::Monthly) => limits.monthly_cost,
+            (QuotaMetric::Images, QuotaPeriod::Daily) => limits.daily_images.map(|v| v as f64),
+            (QuotaMetric::Images, QuotaPeriod::Monthly) => limits.monthly_images.map(|v| v as f64),
+            (QuotaMetric::Speech, QuotaPeriod::Daily) => limits.daily_speech.map(|v| v as f64),
+            (QuotaMetric::Speech, QuotaPeriod::Monthly) => limits.monthly_speech.map(|v| v as f64),
+        }
+    }
+
+    /// Sets (or unsets) the limit. Values for count metrics are expected to be whole numbers.
+    pub fn set(&self, settings: &mut RoomSettingsQuota, value: Option<f64>) {
+        let limits = match self.scope {
+            QuotaScope::User => &mut settings.per_user,
+            QuotaScope::Room => &mut settings.per_room,
+        };
+
+        let count = value.map(|v| v as u64);
+
+        match (self.metric, self.period) {
+            (QuotaMetric::Tokens, QuotaPeriod::Daily) => limits.daily_tokens = count,
+            (QuotaMetric::Tokens, QuotaPeriod::Monthly) => limits.monthly_tokens = count,
+            (QuotaMetric::Cost, QuotaPeriod::Daily) => limits.daily_cost = value,
+            (QuotaMetric::Cost, QuotaPeriod::Monthly) => limits.monthly_cost = value,
+            (QuotaMetric::Images, QuotaPeriod::Daily) => limits.daily_images = count,
+            (QuotaMetric::Images, QuotaPeriod::Monthly) => limits.monthly_images = count,
+            (QuotaMetric::Speech, QuotaPeriod::Daily) => limits.daily_speech = count,
+            (QuotaMetric::Speech, QuotaPeriod::Monthly) => limits.monthly_speech = count,
+        }
+    }
+}
+
+impl std::fmt::Display for QuotaLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}-{}",
+            self.scope.as_str(),
+            self.period.as_str(),
+            self.metric.as_str()
+        )
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::AgentPurpose;
+
+const PROMPT_ACTION_PREFIX: &str = "prompt:";
+const TRANSLATE_ACTION_PREFIX: &str = "translate:";
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RoomSettingsReactions {
+    /// Maps reaction keys (emojis) to the action performed when someone reacts to a message with them.
+    ///
+    /// Keys missing here are looked up in the global configuration and then in the default mapping (see `default_reaction_actions()`).
+    #[serde(default)]
+    pub actions: BTreeMap<String, ReactionAction>,
+}
+
+impl RoomSettingsReactions {
+    pub fn get(&self, key: &str) -> Option<&ReactionAction> {
+        self.actions
+            .iter()
+            .find(|(action_key, _)| reaction_keys_match(action_key, key))
+            .map(|(_, action)| action)
+    }
+
+    /// Sets (or unsets) the action for the given key, replacing any entry for the same emoji.
+    pub fn set(&mut self, key: &str, action: Option<ReactionAction>) {
+        self.actions
+            .retain(|action_key, _| !reaction_keys_match(action_key, key));
+
+        if let Some(action) = action {
+            self.actions.insert(key.to_owned(), action);
+        }
+    }
+}
+
+/// An action performed on a message when someone reacts to it with a certain emoji.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ReactionAction {
+    /// Turns a text message into audio (voice), like offered for text-to-speech
+    TextToSpeech,
+
+    /// Regenerates one of the bot's answers in a threaded conversation
+    Regenerate,
+
+    /// Turns a voice message into text
+    Transcribe,
+
+    /// Translates a text message into the given language (English, if not specified)
+    Translate(Option<String>),
+
+    /// Summarizes a text message
+    Summarize,
+
+    /// Explains a text message in simple terms
+    Explain,
+
+    /// Turns a text message into a detailed prompt suitable for image generation
+    ImagePrompt,
+
+    /// Runs the named prompt from the prompt library against a text message
+    Prompt(String),
+
+    /// Does nothing. Useful for disabling a key which is mapped to an action globally or by default.
+    Disabled,
+}
+
+impl ReactionAction {
+    /// Lists the built-in actions (with example values for those which take a parameter), for help purposes.
+    pub fn choices() -> Vec<Self> {
+        vec![
+            Self::TextToSpeech,
+            Self::Regenerate,
+            Self::Transcribe,
+            Self::Translate(None),
+            Self::Translate(Some("LANGUAGE".to_owned())),
+            Self::Summarize,
+            Self::Explain,
+            Self::ImagePrompt,
+            Self::Prompt("NAME".to_owned()),
+            Self::Disabled,
+        ]
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        if let Some(name) = s.strip_prefix(PROMPT_ACTION_PREFIX) {
+            let name = name.trim();
+            return (!name.is_empty()).then(|| Self::Prompt(name.to_owned()));
+        }
+
+        if let Some(language) = s.strip_prefix(TRANSLATE_ACTION_PREFIX) {
+            let language = language.trim();
+            return (!language.is_empty()).then(|| Self::Translate(Some(language.to_owned())));
+        }
+
+        match s {
+            "text-to-speech" => Some(Self::TextToSpeech),
+            "regenerate" => Some(Self::Regenerate),
+            "transcribe" => Some(Self::Transcribe),
+            "translate" => Some(Self::Translate(None)),
+            "summarize" => Some(Self::Summarize),
+            "explain" => Some(Self::Explain),
+            "image-prompt" => Some(Self::ImagePrompt),
+            "none" => Some(Self::Disabled),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ReactionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TextToSpeech => write!(f, "text-to-speech"),
+            Self::Regenerate => write!(f, "regenerate"),
+            Self::Transcribe => write!(f, "transcribe"),
+            Self::Translate(None) => write!(f, "translate"),
+            Self::Translate(Some(language)) => write!(f, "{TRANSLATE_ACTION_PREFIX}{language}"),
+            Self::Summarize => write!(f, "summarize"),
+            Self::Explain => write!(f, "explain"),
+            Self::ImagePrompt => write!(f, "image-prompt"),
+            Self::Prompt(name) => write!(f, "{PROMPT_ACTION_PREFIX}{name}"),
+            Self::Disabled => write!(f, "none"),
+        }
+    }
+}
+
+impl TryFrom<String> for ReactionAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value).ok_or_else(|| format!("Unknown reaction action: {value}"))
+    }
+}
+
+impl From<ReactionAction> for String {
+    fn from(value: ReactionAction) -> Self {
+        value.to_string()
+    }
+}
+
+/// The actions which apply when neither the room nor the global configuration maps a key to something else.
+pub fn default_reaction_actions() -> Vec<(&'static str, ReactionAction)> {
+    vec![
+        (
+            AgentPurpose::TextToSpeech.emoji(),
+            ReactionAction::TextToSpeech,
+        ),
+        (crate::strings::REGENERATE_EMOJI, ReactionAction::Regenerate),
+    ]
+}
+
+/// Tells if two reaction keys represent the same emoji.
+///
+/// Clients are inconsistent about including the emoji variation selector (U+FE0F),
+/// so `🗣` and `🗣️` are considered the same key.
+pub fn reaction_keys_match(a: &str, b: &str) -> bool {
+    let strip = |s: &str| s.replace('\u{FE0F}', "");
+
+    strip(a.trim()) == strip(b.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reaction_action_round_trips_through_strings() {
+        let mut actions = ReactionAction::choices();
+        actions.push(ReactionAction::Translate(Some("Bulgarian".to_owned())));
+        actions.push(ReactionAction::Prompt("tldr".to_owned()));
+
+        for action in actions {
+            assert_eq!(
+                ReactionAction::from_str(&action.to_string()),
+                Some(action.clone()),
+                "Action: {action}"
+            );
+        }
+
+        assert_eq!(ReactionAction::from_str("prompt:"), None);
+        assert_eq!(ReactionAction::from_str("translate: "), None);
+        assert_eq!(ReactionAction::from_str("whatever"), None);
+    }
+
+    #[test]
+    fn room_settings_reactions_ignore_variation_selectors() {
+        let mut settings = RoomSettingsReactions::default();
+
+        settings.set("🗣️", Some(ReactionAction::Summarize));
+        assert_eq!(settings.get("🗣"), Some(&ReactionAction::Summarize));
+
+        // Setting the same emoji (spelled differently) replaces the previous entry
+        settings.set("🗣", Some(ReactionAction::Explain));
+        assert_eq!(settings.actions.len(), 1);
+        assert_eq!(settings.get("🗣️"), Some(&ReactionAction::Explain));
+
+        settings.set("🗣️", None);
+        assert!(settings.actions.is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::agent::provider::ReasoningEffort;
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct RoomSettingsTextGeneration {
     /// Controls whether initial text messages require a prefix to trigger text generation.
@@ -14,18 +16,49 @@ pub struct RoomSettingsTextGeneration {
     /// When enabled, the bot will automatically tokenize messages and try to shorten the message context intelligently.
     pub context_management_enabled: Option<bool>,
 
+    /// Controls what happens to the messages which do not fit in the context window (when context management is enabled).
+    pub context_management_strategy: Option<TextGenerationContextManagementStrategy>,
+
+    /// The agent (ID) which summarizes the messages that do not fit in the context window (see `TextGenerationContextManagementStrategy::Summarize`).
+    /// When not set, the agent handling text generation summarizes them itself. A cheaper agent is usually good enough for the job.
+    pub context_summary_agent: Option<String>,
+
     /// Controls whether a "thinking…" notice is posted while text generation runs longer than a threshold.
     /// When enabled, a placeholder message appears for slow responses and is edited in place (with elapsed-tiered flavor text) until it becomes the final answer.
     pub thinking_notice_enabled: Option<bool>,
 
+    /// Controls whether responses are streamed into the room as they are being generated.
+    /// When enabled (and supported by the agent's provider), the first tokens are posted early and the same message is then edited (throttled) until it becomes the final answer.
+    pub streaming_enabled: Option<bool>,
+
+    /// Controls whether editing the last message in a threaded conversation regenerates the bot's reply to it.
+    /// When enabled, the bot's reply is edited in place with an answer based on the edited message.
+    pub regenerate_on_edit_enabled: Option<bool>,
+
     /// Controls how each message in the conversation context is annotated with sender metadata.
     pub sender_context_mode: Option<TextGenerationSenderContextMode>,
 
     /// Allows customizing the system prompt that the agent would use
     pub prompt_override: Option<String>,
 
+    /// Allows using a prompt from the global prompt library (by name) as the system prompt.
+    /// A `prompt_override` set at the same level takes precedence over it.
+    pub prompt_name: Option<String>,
+
+    /// The UTC offset (e.g. `+02:00`) of the room's users, made available to prompts as the `room_timezone` and `room_now_local` variables.
+    pub timezone: Option<String>,
+
     /// Allows customizing the temperature that the agent would use
     pub temperature_override: Option<f32>,
+
+    /// Allows using another model (of the same provider) than the one the agent is configured with
+    pub model_override: Option<String>,
+
+    /// Allows customizing the maximum number of tokens that the agent would generate in a response
+    pub max_response_tokens_override: Option<u32>,
+
+    /// Allows customizing how much effort reasoning models would put into thinking
+    pub reasoning_effort_override: Option<ReasoningEffort>,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
@@ -119,6 +152,41 @@ impl std::fmt::Display for TextGenerationAutoUsage {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub enum TextGenerationContextManagementStrategy {
+    /// The oldest messages which do not fit in the context window are dropped
+    #[serde(rename = "truncate")]
+    Truncate,
+
+    /// The oldest messages which do not fit in the context window are condensed into a running summary,
+    /// which is passed to the model along with the system prompt
+    #[serde(rename = "summarize")]
+    Summarize,
+}
+
+impl TextGenerationContextManagementStrategy {
+    pub fn choices() -> Vec<Self> {
+        vec![Self::Truncate, Self::Summarize]
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "truncate" => Some(Self::Truncate),
+            "summarize" => Some(Self::Summarize),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TextGenerationContextManagementStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextGenerationContextManagementStrategy::Truncate => write!(f, "truncate"),
+            TextGenerationContextManagementStrategy::Summarize => write!(f, "summarize"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
 pub enum TextGenerationSenderContextMode {
     #[serde(rename = "disabled")]
@@ -3,10 +3,20 @@ mod entity;
 
 use mxlink::helpers::account_data_config::RoomConfigManager as AccountDataRoomConfigManager;
 
-pub use entity::{RoomConfig, RoomConfigCarrierContent, RoomSettings, RoomSettingsHandler};
+pub use entity::{
+    QuotaLimit, QuotaLimits, QuotaMetric, QuotaPeriod, QuotaScope, RoomSettingsQuota,
+};
+pub use entity::{
+    ReactionAction, RoomSettingsReactions, default_reaction_actions, reaction_keys_match,
+};
+pub use entity::{
+    RoomConfig, RoomConfigCarrierContent, RoomSettings, RoomSettingsHandler, join_agent_chain,
+    split_agent_chain,
+};
 pub use entity::{
     SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
-    TextGenerationAutoUsage, TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
+    TextGenerationAutoUsage, TextGenerationContextManagementStrategy,
+    TextGenerationPrefixRequirementType, TextGenerationSenderContextMode,
     TextToSpeechBotMessagesFlowType, TextToSpeechUserMessagesFlowType,
 };
 
@@ -0,0 +1,417 @@
+use chrono::{DateTime, Utc};
+
+use mxlink::helpers::account_data_config::RoomConfig as RoomConfigTrait;
+use mxlink::helpers::account_data_config::RoomConfigCarrierContent as RoomConfigCarrierContentTrait;
+use mxlink::matrix_sdk::ruma::events::macros::EventContent;
+use mxlink::matrix_sdk::ruma::{OwnedUserId, UserId};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::provider::TokenUsage;
+
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "cc.etke.baibot.usage", kind = RoomAccountData)]
+pub struct RoomUsageCarrierContent {
+    pub payload: String,
+}
+
+impl RoomConfigCarrierContentTrait for RoomUsageCarrierContent {
+    fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    fn new(payload: String) -> Self {
+        Self { payload }
+    }
+}
+
+/// The periods usage is reported for. Days and months are calendar periods in UTC.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UsagePeriod {
+    Today,
+    ThisMonth,
+    AllTime,
+}
+
+impl UsagePeriod {
+    pub fn choices() -> Vec<&'static Self> {
+        vec![&Self::Today, &Self::ThisMonth, &Self::AllTime]
+    }
+
+    fn key(&self, now: DateTime<Utc>) -> Option<String> {
+        match self {
+            Self::Today => Some(now.format("%Y-%m-%d").to_string()),
+            Self::ThisMonth => Some(now.format("%Y-%m").to_string()),
+            Self::AllTime => None,
+        }
+    }
+}
+
+/// Counters for everything we keep track of. Each purpose only bumps the counters relevant to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct UsageCounters {
+    #[serde(default)]
+    pub requests: u64,
+
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    #[serde(default)]
+    pub completion_tokens: u64,
+
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+
+    #[serde(default)]
+    pub cached_tokens: u64,
+
+    #[serde(default)]
+    pub images: u64,
+
+    #[serde(default)]
+    pub audio_seconds: u64,
+
+    #[serde(default)]
+    pub speech_characters: u64,
+
+    #[serde(default)]
+    pub speech_generations: u64,
+}
+
+impl UsageCounters {
+    pub fn for_text_generation(usage: &TokenUsage) -> Self {
+        Self {
+            requests: 1,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            reasoning_tokens: usage.reasoning_tokens,
+            cached_tokens: usage.cached_tokens,
+            ..Default::default()
+        }
+    }
+
+    /// The duration is only known when the sending client included it in the audio message.
+    pub fn for_speech_to_text(audio_duration: Option<std::time::Duration>) -> Self {
+        Self {
+            requests: 1,
+            audio_seconds: audio_duration
+                .map(|duration| duration.as_secs_f64().round() as u64)
+                .unwrap_or(0),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_text_to_speech(text: &str) -> Self {
+        Self {
+            requests: 1,
+            speech_characters: text.chars().count() as u64,
+            speech_generations: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn for_image_generation(images: u64) -> Self {
+        Self {
+            requests: 1,
+            images,
+            ..Default::default()
+        }
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests == 0
+    }
+
+    pub fn add(&mut self, other: &Self) {
+        self.requests += other.requests;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cached_tokens += other.cached_tokens;
+        self.images += other.images;
+        self.audio_seconds += other.audio_seconds;
+        self.speech_characters += other.speech_characters;
+        self.speech_generations += other.speech_generations;
+    }
+}
+
+/// Counters for a single day or month. Once that period is over, the counters are stale
+/// and get reset on the next recording (or ignored when reporting).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PeriodUsageCounters {
+    pub period: String,
+
+    #[serde(default)]
+    pub counters: UsageCounters,
+}
+
+impl PeriodUsageCounters {
+    fn counters_for(&self, period: &str) -> UsageCounters {
+        if self.period == period {
+            self.counters
+        } else {
+            UsageCounters::default()
+        }
+    }
+
+    fn add(&mut self, period: String, counters: &UsageCounters) {
+        if self.period != period {
+            self.period = period;
+            self.counters = UsageCounters::default();
+        }
+
+        self.counters.add(counters);
+    }
+}
+
+/// Usage by a given user via a given agent.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UsageEntry {
+    pub user_id: OwnedUserId,
+    pub agent_id: String,
+
+    #[serde(default)]
+    pub all_time: UsageCounters,
+
+    #[serde(default)]
+    pub month: PeriodUsageCounters,
+
+    #[serde(default)]
+    pub day: PeriodUsageCounters,
+}
+
+impl UsageEntry {
+    pub fn counters(&self, period: UsagePeriod, now: DateTime<Utc>) -> UsageCounters {
+        match (period, period.key(now)) {
+            (UsagePeriod::Today, Some(key)) => self.day.counters_for(&key),
+            (UsagePeriod::ThisMonth, Some(key)) => self.month.counters_for(&key),
+            _ => self.all_time,
+        }
+    }
+}
+
+/// Usage recorded in a given room, persisted in the room's account data (next to the room config).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RoomUsage {
+    #[serde(default)]
+    pub entries: Vec<UsageEntry>,
+}
+
+impl RoomConfigTrait for RoomUsage {}
+
+impl RoomUsage {
+    pub fn record(
+        &mut self,
+        user_id: &UserId,
+        agent_id: &str,
+        counters: &UsageCounters,
+        now: DateTime<Utc>,
+    ) {
+        let index = self.entries.iter().position(|entry| {
+            entry.user_id.as_str() == user_id.as_str() && entry.agent_id == agent_id
+        });
+
+        let entry = match index {
+            Some(index) => &mut self.entries[index],
+            None => {
+                self.entries.push(UsageEntry {
+                    user_id: user_id.to_owned(),
+                    agent_id: agent_id.to_owned(),
+                    all_time: UsageCounters::default(),
+                    month: PeriodUsageCounters::default(),
+                    day: PeriodUsageCounters::default(),
+                });
+
+                self.entries.last_mut().expect("an entry was just pushed")
+            }
+        };
+
+        entry.all_time.add(counters);
+
+        if let Some(key) = UsagePeriod::ThisMonth.key(now) {
+            entry.month.add(key, counters);
+        }
+
+        if let Some(key) = UsagePeriod::Today.key(now) {
+            entry.day.add(key, counters);
+        }
+    }
+
+    pub fn totals(&self, period: UsagePeriod, now: DateTime<Utc>) -> UsageCounters {
+        let mut totals = UsageCounters::default();
+
+        for entry in &self.entries {
+            totals.add(&entry.counters(period, now));
+        }
+
+        totals
+    }
+
+    pub fn totals_for_user(
+        &self,
+        user_id: &UserId,
+        period: UsagePeriod,
+        now: DateTime<Utc>,
+    ) -> UsageCounters {
+        let mut totals = UsageCounters::default();
+
+        for entry in &self.entries {
+            if entry.user_id.as_str() == user_id.as_str() {
+                totals.add(&entry.counters(period, now));
+            }
+        }
+
+        totals
+    }
+
+    pub fn totals_by_agent(
+        &self,
+        period: UsagePeriod,
+        now: DateTime<Utc>,
+    ) -> Vec<(String, UsageCounters)> {
+        self.totals_by(period, now, |entry| entry.agent_id.clone())
+    }
+
+    pub fn totals_by_user(
+        &self,
+        period: UsagePeriod,
+        now: DateTime<Utc>,
+    ) -> Vec<(String, UsageCounters)> {
+        self.totals_by(period, now, |entry| entry.user_id.to_string())
+    }
+
+    /// Groups the entries by the given key, leaving out groups without any usage in the period.
+    /// The result is sorted by total tokens (then by requests), biggest consumers first.
+    fn totals_by(
+        &self,
+        period: UsagePeriod,
+        now: DateTime<Utc>,
+        key: impl Fn(&UsageEntry) -> String,
+    ) -> Vec<(String, UsageCounters)> {
+        let mut groups: Vec<(String, UsageCounters)> = vec![];
+
+        for entry in &self.entries {
+            let counters = entry.counters(period, now);
+            if counters.is_empty() {
+                continue;
+            }
+
+            let entry_key = key(entry);
+
+            match groups
+                .iter_mut()
+                .find(|(group_key, _)| *group_key == entry_key)
+            {
+                Some((_, group_counters)) => group_counters.add(&counters),
+                None => groups.push((entry_key, counters)),
+            }
+        }
+
+        groups.sort_by(|(a_key, a), (b_key, b)| {
+            b.total_tokens()
+                .cmp(&a.total_tokens())
+                .then(b.requests.cmp(&a.requests))
+                .then(a_key.cmp(b_key))
+        });
+
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn user(id: &str) -> OwnedUserId {
+        id.try_into().unwrap()
+    }
+
+    fn text_generation(prompt_tokens: u64, completion_tokens: u64) -> UsageCounters {
+        UsageCounters::for_text_generation(&TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn periods_roll_over_lazily() {
+        let alice = user("@alice:example.com");
+
+        let day_1 = Utc.with_ymd_and_hms(2025, 1, 31, 23, 0, 0).unwrap();
+        let day_2 = Utc.with_ymd_and_hms(2025, 2, 1, 1, 0, 0).unwrap();
+
+        let mut usage = RoomUsage::default();
+        usage.record(&alice, "static/openai", &text_generation(10, 5), day_1);
+        usage.record(&alice, "static/openai", &text_generation(20, 10), day_1);
+
+        assert_eq!(usage.totals(UsagePeriod::Today, day_1).requests, 2);
+        assert_eq!(usage.totals(UsagePeriod::Today, day_1).total_tokens(), 45);
+
+        // Nothing recorded yet on the new day (and month), so stale counters don't count.
+        assert!(usage.totals(UsagePeriod::Today, day_2).is_empty());
+        assert!(usage.totals(UsagePeriod::ThisMonth, day_2).is_empty());
+        assert_eq!(usage.totals(UsagePeriod::AllTime, day_2).total_tokens(), 45);
+
+        usage.record(&alice, "static/openai", &text_generation(1, 1), day_2);
+
+        assert_eq!(usage.entries.len(), 1);
+        assert_eq!(usage.totals(UsagePeriod::Today, day_2).total_tokens(), 2);
+        assert_eq!(
+            usage.totals(UsagePeriod::ThisMonth, day_2).total_tokens(),
+            2
+        );
+        assert_eq!(usage.totals(UsagePeriod::AllTime, day_2).total_tokens(), 47);
+    }
+
+    #[test]
+    fn totals_are_grouped_by_agent_and_user() {
+        let alice = user("@alice:example.com");
+        let bob = user("@bob:example.com");
+
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+
+        let mut usage = RoomUsage::default();
+        usage.record(&alice, "static/openai", &text_generation(10, 10), now);
+        usage.record(&bob, "static/openai", &text_generation(100, 100), now);
+        usage.record(
+            &alice,
+            "room-local/dalle",
+            &UsageCounters::for_image_generation(1),
+            now,
+        );
+
+        let by_agent = usage.totals_by_agent(UsagePeriod::Today, now);
+        assert_eq!(by_agent.len(), 2);
+        assert_eq!(by_agent[0].0, "static/openai");
+        assert_eq!(by_agent[0].1.requests, 2);
+        assert_eq!(by_agent[0].1.total_tokens(), 220);
+        assert_eq!(by_agent[1].0, "room-local/dalle");
+        assert_eq!(by_agent[1].1.images, 1);
+
+        let by_user = usage.totals_by_user(UsagePeriod::AllTime, now);
+        assert_eq!(by_user[0].0, "@bob:example.com");
+        assert_eq!(by_user[1].0, "@alice:example.com");
+        assert_eq!(by_user[1].1.requests, 2);
+    }
+
+    #[test]
+    fn missing_counters_deserialize_as_zero() {
+        let usage: RoomUsage = serde_json::from_str(
+            r#"{"entries": [{"user_id": "@alice:example.com", "agent_id": "static/openai", "all_time": {"requests": 3}}]}"#,
+        )
+        .unwrap();
+
+        let now = Utc::now();
+
+        assert_eq!(usage.totals(UsagePeriod::AllTime, now).requests, 3);
+        assert_eq!(usage.totals(UsagePeriod::AllTime, now).images, 0);
+        assert!(usage.totals(UsagePeriod::Today, now).is_empty());
+    }
+}
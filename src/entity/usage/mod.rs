@@ -0,0 +1,13 @@
+mod entity;
+mod quota;
+mod tracker;
+
+use mxlink::helpers::account_data_config::RoomConfigManager as AccountDataRoomConfigManager;
+
+pub use entity::{
+    PeriodUsageCounters, RoomUsage, RoomUsageCarrierContent, UsageCounters, UsageEntry, UsagePeriod,
+};
+pub use quota::{QuotaExceeded, estimate_cost, find_exceeded_quota};
+pub use tracker::UsageTracker;
+
+pub type RoomUsageManager = AccountDataRoomConfigManager<RoomUsage, RoomUsageCarrierContent>;
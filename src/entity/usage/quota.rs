@@ -0,0 +1,236 @@
+use chrono::{DateTime, Utc};
+
+use mxlink::matrix_sdk::ruma::UserId;
+
+use crate::entity::RoomConfigContext;
+use crate::entity::roomconfig::{QuotaLimit, QuotaMetric, QuotaPeriod, QuotaScope};
+
+use super::{RoomUsage, UsageCounters, UsagePeriod};
+
+/// A quota which has been used up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuotaExceeded {
+    pub limit: QuotaLimit,
+    pub value: f64,
+    pub used: f64,
+}
+
+/// Finds the first quota (among those concerning the given metrics) which has been used up in the room,
+/// either by the given user alone or by everyone in the room combined.
+///
+/// Cost quotas are only enforced when a cost per million tokens is configured, as costs cannot be estimated otherwise.
+pub fn find_exceeded_quota(
+    room_config_context: &RoomConfigContext,
+    room_usage: &RoomUsage,
+    user_id: &UserId,
+    metrics: &[QuotaMetric],
+    now: DateTime<Utc>,
+) -> Option<QuotaExceeded> {
+    let cost_per_million_tokens = room_config_context.quota_cost_per_million_tokens();
+
+    for limit in QuotaLimit::choices() {
+        if !metrics.contains(&limit.metric) {
+            continue;
+        }
+
+        let Some(value) = room_config_context.quota_limit(&limit) else {
+            continue;
+        };
+
+        let period = match limit.period {
+            QuotaPeriod::Daily => UsagePeriod::Today,
+            QuotaPeriod::Monthly => UsagePeriod::ThisMonth,
+        };
+
+        let counters = match limit.scope {
+            QuotaScope::User => room_usage.totals_for_user(user_id, period, now),
+            QuotaScope::Room => room_usage.totals(period, now),
+        };
+
+        let used = match limit.metric {
+            QuotaMetric::Tokens => counters.total_tokens() as f64,
+            QuotaMetric::Cost => {
+                let Some(cost_per_million_tokens) = cost_per_million_tokens else {
+                    continue;
+                };
+
+                estimate_cost(&counters, cost_per_million_tokens)
+            }
+            QuotaMetric::Images => counters.images as f64,
+            QuotaMetric::Speech => counters.speech_generations as f64,
+        };
+
+        if used >= value {
+            return Some(QuotaExceeded { limit, value, used });
+        }
+    }
+
+    None
+}
+
+pub fn estimate_cost(counters: &UsageCounters, cost_per_million_tokens: f64) -> f64 {
+    counters.total_tokens() as f64 * cost_per_million_tokens / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use mxlink::matrix_sdk::ruma::{OwnedUserId, room_id};
+
+    use super::*;
+    use crate::agent::provider::TokenUsage;
+    use crate::entity::globalconfig::GlobalConfig;
+    use crate::entity::roomconfig::RoomConfig;
+
+    fn user(id: &str) -> OwnedUserId {
+        id.try_into().unwrap()
+    }
+
+    fn text_generation(total_tokens: u64) -> UsageCounters {
+        UsageCounters::for_text_generation(&TokenUsage {
+            prompt_tokens: total_tokens,
+            ..Default::default()
+        })
+    }
+
+    fn limit(scope: QuotaScope, period: QuotaPeriod, metric: QuotaMetric) -> QuotaLimit {
+        QuotaLimit::new(scope, period, metric)
+    }
+
+    #[test]
+    fn per_user_limits_only_count_the_users_own_usage() {
+        let alice = user("@alice:example.com");
+        let bob = user("@bob:example.com");
+
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+
+        let mut usage = RoomUsage::default();
+        usage.record(&alice, "static/openai", &text_generation(900), now);
+        usage.record(&bob, "static/openai", &text_generation(100), now);
+
+        let user_daily_tokens = limit(QuotaScope::User, QuotaPeriod::Daily, QuotaMetric::Tokens);
+
+        let mut global_config = GlobalConfig::default();
+        user_daily_tokens.set(&mut global_config.fallback_room_settings.quota, Some(500.0));
+
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config,
+            RoomConfig::default(),
+        );
+
+        let exceeded =
+            find_exceeded_quota(&context, &usage, &alice, &[QuotaMetric::Tokens], now).unwrap();
+        assert_eq!(exceeded.limit, user_daily_tokens);
+        assert_eq!(exceeded.used, 900.0);
+
+        assert!(find_exceeded_quota(&context, &usage, &bob, &[QuotaMetric::Tokens], now).is_none());
+
+        // Limits for other metrics are not relevant to image generation.
+        assert!(
+            find_exceeded_quota(&context, &usage, &alice, &[QuotaMetric::Images], now).is_none()
+        );
+
+        // Usage from yesterday does not count towards today's limit.
+        let tomorrow = Utc.with_ymd_and_hms(2025, 3, 16, 12, 0, 0).unwrap();
+        assert!(
+            find_exceeded_quota(&context, &usage, &alice, &[QuotaMetric::Tokens], tomorrow)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn room_limits_take_priority_over_global_ones() {
+        let alice = user("@alice:example.com");
+        let bob = user("@bob:example.com");
+
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+
+        let mut usage = RoomUsage::default();
+        usage.record(
+            &alice,
+            "static/dalle",
+            &UsageCounters::for_image_generation(2),
+            now,
+        );
+        usage.record(
+            &bob,
+            "static/dalle",
+            &UsageCounters::for_image_generation(2),
+            now,
+        );
+
+        let room_monthly_images =
+            limit(QuotaScope::Room, QuotaPeriod::Monthly, QuotaMetric::Images);
+
+        let mut global_config = GlobalConfig::default();
+        room_monthly_images.set(&mut global_config.fallback_room_settings.quota, Some(3.0));
+
+        let mut room_config = RoomConfig::default();
+        room_monthly_images.set(&mut room_config.settings.quota, Some(10.0));
+
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config,
+            room_config,
+        );
+
+        assert!(
+            find_exceeded_quota(&context, &usage, &alice, &[QuotaMetric::Images], now).is_none()
+        );
+    }
+
+    #[test]
+    fn cost_limits_require_a_cost_per_million_tokens() {
+        let alice = user("@alice:example.com");
+
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+
+        let mut usage = RoomUsage::default();
+        usage.record(&alice, "static/openai", &text_generation(2_000_000), now);
+
+        let user_monthly_cost = limit(QuotaScope::User, QuotaPeriod::Monthly, QuotaMetric::Cost);
+
+        let mut global_config = GlobalConfig::default();
+        user_monthly_cost.set(&mut global_config.fallback_room_settings.quota, Some(5.0));
+
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config.clone(),
+            RoomConfig::default(),
+        );
+        assert!(find_exceeded_quota(&context, &usage, &alice, &[QuotaMetric::Cost], now).is_none());
+
+        global_config
+            .fallback_room_settings
+            .quota
+            .cost_per_million_tokens = Some(2.5);
+
+        let context = RoomConfigContext::new(
+            room_id!("!room:example.com").to_owned(),
+            global_config,
+            RoomConfig::default(),
+        );
+        let exceeded =
+            find_exceeded_quota(&context, &usage, &alice, &[QuotaMetric::Cost], now).unwrap();
+        assert_eq!(exceeded.used, 5.0);
+    }
+
+    #[test]
+    fn limit_names_round_trip() {
+        for choice in QuotaLimit::choices() {
+            assert_eq!(QuotaLimit::from_str(&choice.to_string()), Some(choice));
+        }
+
+        assert_eq!(
+            QuotaLimit::from_str("user-daily-tokens"),
+            Some(limit(
+                QuotaScope::User,
+                QuotaPeriod::Daily,
+                QuotaMetric::Tokens
+            ))
+        );
+        assert_eq!(QuotaLimit::from_str("daily-tokens"), None);
+    }
+}
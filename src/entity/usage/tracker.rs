@@ -0,0 +1,65 @@
+use mxlink::helpers::account_data_config::ConfigError;
+use mxlink::matrix_sdk::Room;
+use mxlink::matrix_sdk::ruma::UserId;
+
+use tokio::sync::Mutex;
+
+use super::{RoomUsage, RoomUsageManager, UsageCounters};
+
+/// Records usage (tokens, images, etc.) into each room's account data.
+///
+/// Recording is a read-modify-write of the whole room usage document,
+/// so all of it happens under a single lock to avoid losing concurrent updates.
+pub struct UsageTracker {
+    room_usage_manager: Mutex<RoomUsageManager>,
+}
+
+impl UsageTracker {
+    pub fn new(room_usage_manager: RoomUsageManager) -> Self {
+        Self {
+            room_usage_manager: Mutex::new(room_usage_manager),
+        }
+    }
+
+    pub async fn record(
+        &self,
+        room: &Room,
+        user_id: &UserId,
+        agent_id: &str,
+        counters: UsageCounters,
+    ) -> Result<(), ConfigError> {
+        let room_usage_manager = self.room_usage_manager.lock().await;
+
+        let mut room_usage = room_usage_manager.get_or_create_for_room(room).await?;
+
+        room_usage.record(user_id, agent_id, &counters, chrono::Utc::now());
+
+        room_usage_manager.persist(room, &room_usage).await
+    }
+
+    /// Like `record`, but only logs failures, as failing to account for usage should not fail the request that caused it.
+    pub async fn record_no_fail(
+        &self,
+        room: &Room,
+        user_id: &UserId,
+        agent_id: &str,
+        counters: UsageCounters,
+    ) {
+        if let Err(err) = self.record(room, user_id, agent_id, counters).await {
+            tracing::warn!(
+                ?err,
+                room_id = room.room_id().as_str(),
+                agent_id,
+                "Failed to record usage"
+            );
+        }
+    }
+
+    pub async fn room_usage(&self, room: &Room) -> Result<RoomUsage, ConfigError> {
+        self.room_usage_manager
+            .lock()
+            .await
+            .get_or_create_for_room(room)
+            .await
+    }
+}
@@ -1,5 +1,10 @@
+use std::collections::BTreeSet;
+
 use crate::{
-    agent::{AgentInstance, AgentProvider, AgentPurpose, ControllerTrait, PublicIdentifier},
+    agent::{
+        AgentInstance, AgentProvider, AgentPurpose, ControllerTrait, PublicIdentifier,
+        tool::ToolDefinition,
+    },
     utils::text::block_quote,
 };
 
@@ -169,6 +174,11 @@ pub fn creation_guide(
 
     message.push_str("\n\n");
     message.push_str(creation_howto().as_str());
+
+    if let PublicIdentifier::DynamicGlobal(_) = agent_identifier {
+        message.push_str("\n\n");
+        message.push_str(creation_secret_references_hint().as_str());
+    }
     message.push_str("\n\n");
     message.push_str(creation_raw_or_codeblock_ok().as_str());
 
@@ -195,10 +205,71 @@ fn creation_howto() -> String {
     )
 }
 
+fn creation_secret_references_hint() -> String {
+    "Instead of pasting API keys here, you may reference secrets kept on the bot's server, like `api_key: {env: OPENAI_API_KEY}` (an environment variable) or `api_key: {file: /run/secrets/openai}` (a file).".to_owned()
+}
+
 fn creation_raw_or_codeblock_ok() -> String {
     "You can send the configuration as-is in a plain-text message or optionally wrap it in a [Markdown codeblock](https://www.markdownguide.org/extended-syntax/#fenced-code-blocks).".to_string()
 }
 
+pub fn update_guide(agent_identifier: &PublicIdentifier, current_pretty_yaml: &str) -> String {
+    let mut message = String::new();
+
+    message.push_str(&format!(
+        "You're updating the `{agent_identifier}` agent. Its current configuration is:\n```yml\n{}\n```",
+        current_pretty_yaml.trim()
+    ));
+    message.push_str("\n\n");
+
+    message.push_str(&format!(
+        "Secret values are masked (`{}`). Leave them out of your changes to keep them as they are.",
+        crate::agent::secrets::REDACTED
+    ));
+    message.push_str("\n\n");
+
+    message.push_str("Send the keys you'd like to change to this message thread as [YAML](https://en.wikipedia.org/wiki/YAML). The rest of the configuration stays as it is. For example:\n```yml\ntext_generation:\n  temperature: 0.5\n```");
+    message.push_str("\n\n");
+
+    message.push_str("Nested sections are merged, lists are replaced as a whole and setting a key to `null` removes it.");
+    message.push_str("\n\n");
+
+    message.push_str(&creation_raw_or_codeblock_ok());
+
+    message
+}
+
+pub fn details_secrets_redacted_reveal_hint(
+    command_prefix: &str,
+    agent_identifier: &PublicIdentifier,
+) -> String {
+    format!(
+        "Secrets (API keys, header values, passwords in URLs, etc.) are masked. To receive the full configuration via a direct message, use: `{command_prefix} agent details {agent_identifier} --reveal`"
+    )
+}
+
+pub fn details_revealed_via_direct_message(agent_identifier: &PublicIdentifier) -> String {
+    format!(
+        "The full configuration for agent `{agent_identifier}` was sent to you via a direct message."
+    )
+}
+
+pub fn details_reveal_failed_to_reach_requester() -> String {
+    "Failed to send you the full configuration via a direct message. Make sure you can receive direct messages from me and try again.".to_owned()
+}
+
+pub fn update_changes_nothing(agent_identifier: &PublicIdentifier) -> String {
+    format!(
+        "The provided changes leave the configuration of the `{agent_identifier}` agent as it is, so there's nothing to update."
+    )
+}
+
+pub fn updated(agent_identifier: &PublicIdentifier, diff: &str) -> String {
+    format!(
+        "Agent `{agent_identifier}` updated. Handlers which use it keep doing so.\n\nChanges:\n```diff\n{diff}\n```"
+    )
+}
+
 pub fn removed_room_local(agent_identifier: &PublicIdentifier, command_prefix: &str) -> String {
     let mut message = String::new();
 
@@ -301,10 +372,108 @@ pub fn error_while_serving_purpose(
     )
 }
 
+/// Describes a handler's agent chain, like "the `static/openai` agent" or "the `static/openai` → `global/groq` agent chain".
+pub fn describe_agent_chain(agent_identifiers: &[PublicIdentifier]) -> String {
+    let agents = agent_identifiers
+        .iter()
+        .map(|agent_identifier| format!("`{agent_identifier}`"))
+        .collect::<Vec<_>>()
+        .join(" → ");
+
+    if agent_identifiers.len() > 1 {
+        format!("the {agents} agent chain")
+    } else {
+        format!("the {agents} agent")
+    }
+}
+
+pub fn answered_by_fallback_agent(
+    primary_agent_identifier: &PublicIdentifier,
+    answering_agent_identifier: &PublicIdentifier,
+    purpose: &AgentPurpose,
+) -> String {
+    format!(
+        "↪️ The `{primary_agent_identifier}` agent is currently unavailable, so {purpose} was performed by the `{answering_agent_identifier}` fallback agent instead."
+    )
+}
+
 pub fn empty_response_returned(agent_identifier: &PublicIdentifier) -> String {
     format!("The `{agent_identifier}` agent returned an empty response.")
 }
 
+pub fn tool_call_rounds_exhausted(max_rounds: usize) -> String {
+    format!(
+        "The model kept on calling tools and did not produce an answer after {max_rounds} rounds of tool calls."
+    )
+}
+
+pub fn tools_none(agent_identifier: &PublicIdentifier) -> String {
+    format!("The `{agent_identifier}` agent does not offer any tools to the model.")
+}
+
+pub fn tools_intro(agent_identifier: &PublicIdentifier) -> String {
+    format!("The `{agent_identifier}` agent offers these tools to the model:")
+}
+
+pub fn tools_builtin_heading() -> &'static str {
+    "**🧰 Client-side tools**"
+}
+
+pub fn tools_mcp_heading() -> &'static str {
+    "**🔌 MCP tools**"
+}
+
+pub fn tools_list_entry(definition: &ToolDefinition) -> String {
+    // Descriptions can be whole paragraphs. The first line is enough for an overview.
+    let description = definition.description.lines().next().unwrap_or("").trim();
+
+    if description.is_empty() {
+        return format!("- `{}`", definition.name);
+    }
+
+    format!("- `{}`: {}", definition.name, description)
+}
+
+pub fn tools_mcp_failures_intro() -> &'static str {
+    "⚠️ The tools of these MCP servers are unavailable (see the bot's logs for details):"
+}
+
+pub fn tools_mcp_failure_entry(server_id: &str) -> String {
+    format!("- `{server_id}`")
+}
+
+pub fn tools_mcp_not_available_to_room_local_agents() -> &'static str {
+    "ℹ️ MCP servers are only available to static and global agents, so this room-local agent's MCP configuration is ignored."
+}
+
+pub fn models_intro(agent_identifier: &PublicIdentifier) -> String {
+    format!("The provider of the `{agent_identifier}` agent offers these models:")
+}
+
+pub fn models_none(agent_identifier: &PublicIdentifier) -> String {
+    format!("The provider of the `{agent_identifier}` agent does not report any models.")
+}
+
+pub fn models_listing_failed(agent_identifier: &PublicIdentifier, err: anyhow::Error) -> String {
+    format!(
+        "Failed to list the models available to the `{agent_identifier}` agent. The following error was encountered when trying to talk to the agent API:\n```\n{err}\n```"
+    )
+}
+
+pub fn learned_unsupported_fields_intro() -> &'static str {
+    "🩹 These fields were rejected as unsupported by the models below, so they are no longer sent to them (until the bot restarts):"
+}
+
+pub fn learned_unsupported_fields_entry(model_id: &str, fields: &BTreeSet<String>) -> String {
+    let fields = fields
+        .iter()
+        .map(|field| format!("`{field}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("- `{model_id}`: {fields}")
+}
+
 pub fn no_configuration_for_purpose_so_cannot_be_used(purpose: &AgentPurpose) -> String {
     format!(
         "This agent does not contain configuration for {} {}, so it cannot be used for that.",
@@ -0,0 +1,3 @@
+pub fn cancelled() -> &'static str {
+    "Cancelled. No answer will be generated for this request."
+}
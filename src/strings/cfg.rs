@@ -1,12 +1,13 @@
 use crate::{
     agent::{
-        AgentInstance, AgentPurpose, PublicIdentifier,
+        AgentInstance, AgentPurpose, PublicIdentifier, provider::ReasoningEffort,
         utils::AgentForPurposeDeterminationInfoConfigurationSource,
     },
     entity::roomconfig::{
-        SpeechToTextFlowType, SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages,
-        TextGenerationAutoUsage, TextGenerationPrefixRequirementType,
-        TextToSpeechBotMessagesFlowType, TextToSpeechUserMessagesFlowType,
+        QuotaLimit, ReactionAction, SpeechToTextFlowType,
+        SpeechToTextMessageTypeForNonThreadedOnlyTranscribedMessages, TextGenerationAutoUsage,
+        TextGenerationPrefixRequirementType, TextToSpeechBotMessagesFlowType,
+        TextToSpeechUserMessagesFlowType,
     },
     utils::text::block_quote,
 };
@@ -61,6 +62,27 @@ pub fn configuration_value_not_f32(value: &str) -> String {
     )
 }
 
+pub fn configuration_value_not_whole_number(value: &str) -> String {
+    format!(
+        "The value `{}` is not a whole number (e.g. `100000`).",
+        value
+    )
+}
+
+pub fn configuration_value_out_of_range(value: &str, min: u32, max: u32) -> String {
+    format!(
+        "The value `{}` is out of range. It must be between `{}` and `{}`.",
+        value, min, max
+    )
+}
+
+pub fn configuration_value_not_non_negative_number(value: &str) -> String {
+    format!(
+        "The value `{}` is not a non-negative number (e.g. `2.5`).",
+        value
+    )
+}
+
 pub fn status_room_config_handlers_heading() -> &'static str {
     "📍 Room-specific handlers"
 }
@@ -111,24 +133,26 @@ fn status_agent_not_found() -> &'static str {
     "not found"
 }
 
+/// Renders a handler line for the given agent chain (the primary agent, followed by its fallbacks).
+/// Agents which could not be found are represented by `None`.
 pub fn status_handler_line_agent_found(
     purpose: &AgentPurpose,
-    agent_id: &str,
-    agent: Option<&AgentInstance>,
+    agent_chain: &[(String, Option<&AgentInstance>)],
 ) -> String {
-    let agent_status = match agent {
-        Some(agent) => super::agent::create_support_badges_text(agent.controller()),
-        None => status_agent_not_found().to_string(),
-    };
+    let agents = agent_chain
+        .iter()
+        .map(|(agent_id, agent)| {
+            let agent_status = match agent {
+                Some(agent) => super::agent::create_support_badges_text(agent.controller()),
+                None => status_agent_not_found().to_string(),
+            };
 
-    format!(
-        "- {} {}: `{}` ({})",
-        purpose.emoji(),
-        purpose,
-        agent_id,
-        agent_status,
-    )
-    .to_owned()
+            format!("`{}` ({})", agent_id, agent_status)
+        })
+        .collect::<Vec<_>>()
+        .join(" → ");
+
+    format!("- {} {}: {}", purpose.emoji(), purpose, agents)
 }
 
 pub fn status_handler_line_catch_all_agent_not_set_globally() -> String {
@@ -249,10 +273,40 @@ pub fn status_text_generation_entry_context_management(value: bool, set_where: &
     format!("- ♻️ Context management: `{}` ({})\n", value, set_where)
 }
 
+pub fn status_text_generation_entry_context_management_strategy(
+    value: impl std::fmt::Display,
+    set_where: &str,
+) -> String {
+    format!(
+        "- 🗜️ Context management strategy: `{}` ({})\n",
+        value, set_where
+    )
+}
+
+pub fn status_text_generation_entry_context_summary_agent(
+    value: Option<&str>,
+    set_where: &str,
+) -> String {
+    let formatted = match value {
+        Some(value) => format!("`{}` ({})", value, set_where),
+        None => "the text-generation agent itself".to_string(),
+    };
+
+    format!("- 📝 Context summary agent: {}\n", formatted)
+}
+
 pub fn status_text_generation_entry_thinking_notice(value: bool, set_where: &str) -> String {
     format!("- 💭 Thinking notice: `{}` ({})\n", value, set_where)
 }
 
+pub fn status_text_generation_entry_streaming(value: bool, set_where: &str) -> String {
+    format!("- 🌊 Streaming: `{}` ({})\n", value, set_where)
+}
+
+pub fn status_text_generation_entry_regenerate_on_edit(value: bool, set_where: &str) -> String {
+    format!("- ✏️ Regenerate on edit: `{}` ({})\n", value, set_where)
+}
+
 pub fn status_text_generation_entry_sender_context(
     value: impl std::fmt::Display,
     set_where: &str,
@@ -270,6 +324,22 @@ pub fn status_text_generation_entry_prompt(value: &str, set_where: &str) -> Stri
     }
 }
 
+pub fn status_text_generation_entry_prompt_missing(name: &str, set_where: &str) -> String {
+    format!(
+        "- ⚠️ Prompt: the `{}` prompt ({}) is missing from the prompt library and is being ignored\n",
+        name, set_where
+    )
+}
+
+pub fn status_text_generation_entry_timezone(value: Option<&str>, set_where: &str) -> String {
+    let formatted = match value {
+        Some(value) => format!("`{}` ({})", value, set_where),
+        None => "not set".to_string(),
+    };
+
+    format!("- 🕰️ Timezone: {}\n", formatted)
+}
+
 pub fn status_text_generation_entry_temperature(value: Option<f32>, set_where: &str) -> String {
     let formatted = match value {
         Some(value) => format!("`{:.1}` ({})", value, set_where),
@@ -279,6 +349,39 @@ pub fn status_text_generation_entry_temperature(value: Option<f32>, set_where: &
     format!("- 🌡️ Temperature: {}\n", formatted)
 }
 
+pub fn status_text_generation_entry_model(value: Option<&str>, set_where: &str) -> String {
+    let formatted = match value {
+        Some(value) => format!("`{}` ({})", value, set_where),
+        None => "not set".to_string(),
+    };
+
+    format!("- 🧠 Model: {}\n", formatted)
+}
+
+pub fn status_text_generation_entry_max_response_tokens(
+    value: Option<u32>,
+    set_where: &str,
+) -> String {
+    let formatted = match value {
+        Some(value) => format!("`{}`", value),
+        None => "as configured".to_string(),
+    };
+
+    format!("- 📏 Max response tokens: {} ({})\n", formatted, set_where)
+}
+
+pub fn status_text_generation_entry_reasoning_effort(
+    value: Option<ReasoningEffort>,
+    set_where: &str,
+) -> String {
+    let formatted = match value {
+        Some(value) => format!("`{}`", value),
+        None => "as configured".to_string(),
+    };
+
+    format!("- 🤔 Reasoning effort: {} ({})\n", formatted, set_where)
+}
+
 pub fn status_speech_to_text_entry_flow_type(
     value: SpeechToTextFlowType,
     set_where: &str,
@@ -343,12 +446,48 @@ pub fn status_text_to_speech_entry_voice(value: Option<String>, set_where: &str)
     format!("- 👫 Voice: {}\n", formatted)
 }
 
+pub fn status_quota_heading() -> &'static str {
+    "⛔ Quotas"
+}
+
+pub fn status_quota_entry_limit(limit: &QuotaLimit, value: f64, set_where: &str) -> String {
+    format!(
+        "- {}: `{}` (`{}`, {})\n",
+        crate::strings::quota::describe_limit(limit),
+        crate::strings::quota::format_value(limit.metric, value),
+        limit,
+        set_where
+    )
+}
+
+pub fn status_quota_entry_no_limits() -> &'static str {
+    "- 🎚️ Limits: none configured\n"
+}
+
+pub fn status_quota_entry_cost_per_million_tokens(value: Option<f64>, set_where: &str) -> String {
+    let formatted = match value {
+        Some(value) => format!("`{}` ({})", value, set_where),
+        None => "not set, so `cost` limits are not enforced".to_string(),
+    };
+
+    format!("- 💰 Cost per million tokens: {}\n", formatted)
+}
+
+pub fn status_reactions_heading() -> &'static str {
+    "👆 Reactions"
+}
+
+pub fn status_reactions_entry(key: &str, action: &ReactionAction, set_where: &str) -> String {
+    format!("- {}: `{}` ({})\n", key, action, set_where)
+}
+
 pub fn status_entry_effective_agent_error() -> String {
     "- 🤖 Effective handler agent: error determining agent\n".to_string()
 }
 
 pub fn status_entry_effective_agent(
     value: &PublicIdentifier,
+    fallbacks: &[AgentInstance],
     source: AgentForPurposeDeterminationInfoConfigurationSource,
 ) -> String {
     let set_where = match source {
@@ -360,9 +499,14 @@ pub fn status_entry_effective_agent(
         }
     };
 
+    let fallbacks = fallbacks
+        .iter()
+        .map(|agent| format!(" → `{}`", agent.identifier()))
+        .collect::<String>();
+
     format!(
-        "- 🤖 Effective handler agent: `{}` ({})\n",
-        value, set_where
+        "- 🤖 Effective handler agent: `{}`{} ({})\n",
+        value, fallbacks, set_where
     )
 }
 
@@ -378,6 +522,10 @@ pub fn status_badge_using_hardcoded_default() -> &'static str {
     "📝 using hardcoded default"
 }
 
+pub fn status_badge_from_prompt_library(name: &str, set_where: &str) -> String {
+    format!("`{}` from the prompt library, {}", name, set_where)
+}
+
 pub fn status_badge_set_in_agent_config() -> &'static str {
     "🤖 set at the agent level"
 }
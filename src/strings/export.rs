@@ -0,0 +1,19 @@
+use crate::controller::export::ExportFormat;
+
+pub fn unknown_format(format: &str) -> String {
+    let choices = ExportFormat::choices()
+        .iter()
+        .map(|choice| format!("`{choice}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("Conversations cannot be exported as `{format}`. Supported formats: {choices}.")
+}
+
+pub fn not_in_conversation() -> &'static str {
+    "There is nothing to export here. Send this command in a thread or as a reply to a message (mentioning the bot) to export that conversation."
+}
+
+pub fn nothing_to_export() -> &'static str {
+    "This conversation does not contain any messages which can be exported."
+}
@@ -19,12 +19,13 @@ pub fn global_config_lacks_specific_agent_for_purpose(purpose: AgentPurpose) ->
 }
 
 pub fn configured_to_use_agent_for_purpose(
-    agent_identifier: &PublicIdentifier,
+    agent_identifiers: &[PublicIdentifier],
     purpose: AgentPurpose,
 ) -> String {
     format!(
-        "The global configuration specifies that the `{}` agent is to be used for the `{}` purpose.",
-        agent_identifier, purpose
+        "The global configuration specifies that {} is to be used for the `{}` purpose.",
+        super::agent::describe_agent_chain(agent_identifiers),
+        purpose
     )
 }
 
@@ -39,12 +40,13 @@ pub fn configures_agent_for_purpose_but_does_not_exist(
 }
 
 pub fn reconfigured_to_use_agent_for_purpose(
-    agent_identifier: &PublicIdentifier,
+    agent_identifiers: &[PublicIdentifier],
     purpose: AgentPurpose,
 ) -> String {
     format!(
-        "The global configuration has been adjusted to use the `{}` agent for the `{}` purpose.",
-        agent_identifier, purpose
+        "The global configuration has been adjusted to use {} for the `{}` purpose.",
+        super::agent::describe_agent_chain(agent_identifiers),
+        purpose
     )
 }
 
@@ -28,7 +28,19 @@ pub fn list_agents(command_prefix: &str) -> String {
 
 pub fn show_agent_details(command_prefix: &str) -> String {
     format!(
-        "- **Show** full details for a given agent: `{command_prefix} agent details FULL_AGENT_IDENTIFIER`"
+        "- **Show** full details for a given agent (with secrets masked): `{command_prefix} agent details FULL_AGENT_IDENTIFIER`. Administrators can add `--reveal` to receive the unmasked configuration via a direct message"
+    )
+}
+
+pub fn show_agent_tools(command_prefix: &str) -> String {
+    format!(
+        "- **List** the tools (built-in and MCP) a given agent can use: `{command_prefix} agent tools FULL_AGENT_IDENTIFIER`"
+    )
+}
+
+pub fn list_agent_models(command_prefix: &str) -> String {
+    format!(
+        "- **List** the models offered by a given agent's provider (to help you pick a model ID): `{command_prefix} agent models FULL_AGENT_IDENTIFIER`"
     )
 }
 
@@ -56,6 +68,8 @@ pub fn delete_agent(command_prefix: &str) -> String {
     format!("- **Delete** an agent: `{command_prefix} agent delete FULL_AGENT_IDENTIFIER`")
 }
 
-pub fn available_commands_outro_update_note() -> &'static str {
-    "To **update** a given agent's configuration: show the agent's **details** (current configuration), then **delete** it and finally **re-create** it."
+pub fn update_agent(command_prefix: &str) -> String {
+    format!(
+        "- **Update** an agent's configuration (keeping it set as a handler wherever it is): `{command_prefix} agent update FULL_AGENT_IDENTIFIER`"
+    )
 }
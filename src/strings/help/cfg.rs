@@ -66,7 +66,7 @@ pub fn handlers_show(command_prefix: &str) -> String {
 
 pub fn handlers_set(command_prefix: &str) -> String {
     format!(
-        "**Set** the agent to be used for the given purpose: `{command_prefix} config CONFIG_TYPE set-handler PURPOSE AGENT_ID`"
+        "**Set** the agent to be used for the given purpose: `{command_prefix} config CONFIG_TYPE set-handler PURPOSE AGENT_ID`. To fail over to other agents when one is unavailable, list them in order: `AGENT_ID,FALLBACK_AGENT_ID`"
     )
 }
 
@@ -132,6 +132,26 @@ pub fn text_generation_context_management_intro() -> String {
     )
 }
 
+pub fn text_generation_context_management_strategy_heading() -> &'static str {
+    "🗜️ Context Management Strategy"
+}
+
+pub fn text_generation_context_management_strategy_intro() -> String {
+    format!(
+        "{}\n{}",
+        "Controls what happens to the oldest messages which do not fit in the context window (when context management is enabled).",
+        "`truncate` drops them, so long conversations forget how they started. `summarize` condenses them into a running summary (kept in memory for each conversation and only extended as more messages overflow), which is passed to the model along with the system prompt.",
+    )
+}
+
+pub fn text_generation_context_summary_agent_heading() -> &'static str {
+    "📝 Context Summary Agent"
+}
+
+pub fn text_generation_context_summary_agent_intro() -> String {
+    "The agent (e.g. `global/cheap-model`) which summarizes old messages when the `summarize` context management strategy is used. When not set, the agent handling text generation summarizes them itself. A cheaper and faster agent is usually good enough for the job.".to_string()
+}
+
 pub fn text_generation_thinking_notice_heading() -> &'static str {
     "💭 Thinking Notice"
 }
@@ -144,6 +164,30 @@ pub fn text_generation_thinking_notice_intro() -> String {
     )
 }
 
+pub fn text_generation_streaming_heading() -> &'static str {
+    "🌊 Streaming"
+}
+
+pub fn text_generation_streaming_intro() -> String {
+    format!(
+        "{}\n{}",
+        "Controls whether the bot **streams responses** into the room while they are being generated, instead of posting them all at once when done.",
+        "When enabled, the first words are posted as soon as they arrive and the same message is then edited periodically until it becomes the final answer. Providers which cannot stream fall back to regular (non-streamed) responses. Disabled by default.",
+    )
+}
+
+pub fn text_generation_regenerate_on_edit_heading() -> &'static str {
+    "✏️ Regenerate on Edit"
+}
+
+pub fn text_generation_regenerate_on_edit_intro() -> String {
+    format!(
+        "{}\n{}",
+        "Controls whether **editing your last message** in a threaded conversation makes the bot regenerate its reply to it.",
+        "When enabled, the bot's reply is edited in place with an answer based on the edited message, so earlier versions remain in the reply's edit history. Disabled by default.",
+    )
+}
+
 pub fn text_generation_sender_context_heading() -> &'static str {
     "👤 Sender Context Mode"
 }
@@ -161,7 +205,25 @@ pub fn text_generation_prompt_override_heading() -> &'static str {
 }
 
 pub fn text_generation_prompt_override_intro() -> String {
-    "Lets you override the [system prompt](https://huggingface.co/docs/transformers/en/tasks/prompting) parameter configured at the agent level.".to_string()
+    "Lets you override the [system prompt](https://huggingface.co/docs/transformers/en/tasks/prompting) parameter configured at the agent level. Prompts may use variables (e.g. `{{ room_name }}`, `{{ sender_display_name | default(\"the user\") }}`) and conditionals (e.g. `{% if room_is_dm %}…{% else %}…{% endif %}`). Mistakes in them are reported when the prompt is set.".to_string()
+}
+
+pub fn text_generation_timezone_heading() -> &'static str {
+    "🕰️ Timezone"
+}
+
+pub fn text_generation_timezone_intro() -> String {
+    "The UTC offset of the room's users, made available to prompts via the `{{ room_timezone }}` and `{{ room_now_local }}` variables.".to_string()
+}
+
+pub fn text_generation_prompt_name_heading() -> &'static str {
+    "📚 Prompt From Library"
+}
+
+pub fn text_generation_prompt_name_intro(command_prefix: &str) -> String {
+    format!(
+        "Lets you use a named prompt from the shared prompt library (see `{command_prefix} prompt list`) instead of the prompt configured at the agent level. A prompt override set at the same level takes precedence over it."
+    )
 }
 
 pub fn text_generation_temperature_override_heading() -> &'static str {
@@ -172,6 +234,30 @@ pub fn text_generation_temperature_override_intro() -> String {
     "Lets you override the [temperature](https://blogs.novita.ai/what-are-large-language-model-settings-temperature-top-p-and-max-tokens/#what-is-llm-temperature) (randomness / creativity) parameter configured at the agent level.".to_string()
 }
 
+pub fn text_generation_model_override_heading() -> &'static str {
+    "🧠 Model Override"
+}
+
+pub fn text_generation_model_override_intro() -> String {
+    "Lets you use another model (e.g. a cheaper or faster one) than the one configured at the agent level, without having to create a new agent. The model must be offered by the agent's provider. Fallback agents (if any) keep using their own models.".to_string()
+}
+
+pub fn text_generation_max_response_tokens_override_heading() -> &'static str {
+    "📏 Max Response Tokens Override"
+}
+
+pub fn text_generation_max_response_tokens_override_intro() -> String {
+    "Lets you override the maximum number of tokens the agent generates in a response, as configured at the agent level.".to_string()
+}
+
+pub fn text_generation_reasoning_effort_override_heading() -> &'static str {
+    "🤔 Reasoning Effort Override"
+}
+
+pub fn text_generation_reasoning_effort_override_intro() -> String {
+    "Lets you control how much effort reasoning models put into thinking before answering. Higher effort usually means better answers, but slower and more expensive ones. Not supported by the Anthropic provider.".to_string()
+}
+
 pub fn current_setting_show(command_prefix: &str, setting_path_parts: &str) -> String {
     format!(
         "**Show** the current setting: `{command_prefix} config CONFIG_TYPE {setting_path_parts}`"
@@ -314,3 +400,58 @@ pub fn image_generation_heading() -> String {
 pub fn image_generation_common() -> &'static str {
     "Image-generation is the bot's ability to **generate images** based on text prompts.\n\nThis feature is not configurable at the moment."
 }
+
+pub fn quota_heading() -> &'static str {
+    "⛔ Quotas"
+}
+
+pub fn quota_common() -> String {
+    format!(
+        "{}\n{}\n\n{}",
+        "Quotas **cap how much of the bot can be used** in a room, per user or for the whole room combined, per day or per month (in UTC).",
+        "Once a quota is reached, requests are refused with an explanation until the quota resets. **Administrators are not subject to quotas**.",
+        "Only administrators can change quotas (whether in the room or in the global configuration). Usage is counted separately in each room.",
+    )
+}
+
+pub fn quota_limits_heading() -> &'static str {
+    "🎚️ Limits"
+}
+
+pub fn quota_limits_intro() -> String {
+    format!(
+        "{}\n{}",
+        "Each limit is named `SCOPE-PERIOD-METRIC`, where `SCOPE` is `user` or `room`, `PERIOD` is `daily` or `monthly`, and `METRIC` is `tokens` (text-generation tokens), `cost` (estimated text-generation cost), `images` (generated or edited images) or `speech` (text-to-speech generations).",
+        "`cost` limits only take effect when a cost per million tokens is configured (see below).",
+    )
+}
+
+pub fn quota_cost_per_million_tokens_heading() -> &'static str {
+    "💰 Cost per million tokens"
+}
+
+pub fn quota_cost_per_million_tokens_intro() -> String {
+    "Lets you specify the price of 1 million text-generation tokens (in whatever currency you budget in), which is used for estimating costs for `cost` limits.".to_owned()
+}
+
+pub fn reactions_heading() -> &'static str {
+    "👆 Reactions"
+}
+
+pub fn reactions_common() -> String {
+    format!(
+        "{}\n{}\n\n{}",
+        "Reacting to a message with a certain emoji **performs an action on that message** (e.g. translating or summarizing it).",
+        "The room configuration takes precedence over the global one. By default, 🗣️ turns text to speech and 🔁 regenerates an answer of the bot.",
+        "To disable an emoji which is mapped to an action globally or by default, map it to `none`.",
+    )
+}
+
+pub fn reactions_actions_intro() -> String {
+    format!(
+        "{}\n{}\n{}",
+        "`text-to-speech`, `transcribe` (for voice messages) and `regenerate` (for the bot's answers in threads) work like the respective features.",
+        "`translate` (into English, unless a language is specified like `translate:German`), `summarize`, `explain` and `image-prompt` (writing a prompt for image generation) ask the 💬 text-generation agent to work on the message.",
+        "`prompt:NAME` runs the named prompt from the prompt library against the message.",
+    )
+}
@@ -0,0 +1,9 @@
+pub fn heading() -> &'static str {
+    "📤 Export"
+}
+
+pub fn intro(command_prefix: &str) -> String {
+    format!(
+        "Conversations (threads and reply chains) can be exported to a file, which the bot uploads to the conversation. Send `{command_prefix} export` in a thread (or as a reply which mentions the bot) to get a Markdown transcript, or `{command_prefix} export json` to get the messages in the format used by OpenAI-compatible APIs."
+    )
+}
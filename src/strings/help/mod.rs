@@ -1,6 +1,8 @@
 pub mod access;
 pub mod agent;
 pub mod cfg;
+pub mod export;
+pub mod prompt;
 pub mod provider;
 pub mod usage;
 
@@ -0,0 +1,37 @@
+pub fn heading() -> &'static str {
+    "⌨️ Prompt library"
+}
+
+pub fn intro() -> String {
+    "The prompt library holds named system prompts (e.g. a `code-reviewer` or `translator` persona) shared across rooms, so that rooms don't each need to keep their own copy of the same prompt. Prompt variables (e.g. `{{ baibot_now_utc }}`) are applied to library prompts, just like to prompt overrides.".to_owned()
+}
+
+pub fn usage_in_rooms(command_prefix: &str) -> String {
+    format!(
+        "To use a library prompt in a room, send `{command_prefix} config room text-generation set-prompt-name NAME` (or `{command_prefix} config global …` to use it in all rooms). A prompt override set via `set-prompt-override` takes precedence over it."
+    )
+}
+
+pub fn command_list(command_prefix: &str) -> String {
+    format!("- **List** the available prompts: `{command_prefix} prompt list`")
+}
+
+pub fn command_show(command_prefix: &str) -> String {
+    format!("- **Show** a prompt: `{command_prefix} prompt show NAME`")
+}
+
+pub fn command_add(command_prefix: &str) -> String {
+    format!(
+        "- **Add** (or replace) a prompt: `{command_prefix} prompt add NAME DESCRIPTION`, followed by the prompt itself on the next lines"
+    )
+}
+
+pub fn command_delete(command_prefix: &str) -> String {
+    format!("- **Delete** a prompt: `{command_prefix} prompt delete NAME`")
+}
+
+pub fn example_add(command_prefix: &str) -> String {
+    format!(
+        "Example:\n\n```\n{command_prefix} prompt add code-reviewer Reviews code strictly, but fairly\nYou are {{{{ baibot_name }}}}, a strict, but fair code reviewer. Point out bugs first, then style issues.\n```"
+    )
+}
@@ -1,14 +1,20 @@
 pub mod access;
 pub mod agent;
+pub mod cancellation;
 pub mod cfg;
 pub mod error;
+pub mod export;
 pub mod global_config;
 pub mod help;
 pub mod image_edit;
 pub mod image_generation;
 pub mod introduction;
+pub mod prompt;
 pub mod provider;
+pub mod quota;
+pub mod regenerate;
 pub mod room_config;
+pub mod scheduling;
 pub mod speech_to_text;
 pub mod text_to_speech;
 pub mod thinking;
@@ -16,6 +22,10 @@ pub mod usage;
 
 pub const PROGRESS_INDICATOR_EMOJI: &str = "⏳";
 
+pub const REGENERATE_EMOJI: &str = "🔁";
+
+pub const CANCEL_EMOJI: &str = "❌";
+
 pub fn the_following_commands_are_available() -> &'static str {
     "The following commands are available:"
 }
@@ -0,0 +1,87 @@
+use crate::{
+    agent::provider::TextGenerationPromptVariables, entity::globalconfig::PromptDefinition,
+    utils::text::block_quote,
+};
+
+pub fn invalid_name_validation_error(validation_error: String) -> String {
+    format!(
+        "The provided prompt name is not valid. {}",
+        validation_error
+    )
+}
+
+pub fn incorrect_invocation_expects_name_arg(command_prefix: &str) -> String {
+    format!(
+        "Incorrect command invocation. This command expects a prompt name. See `{command_prefix} prompt` for help."
+    )
+}
+
+pub fn incorrect_add_invocation(command_prefix: &str) -> String {
+    format!(
+        "Incorrect command invocation. This command expects a prompt name and a description on the first line, followed by the prompt itself on the next lines. See `{command_prefix} prompt` for help."
+    )
+}
+
+pub fn prompt_with_given_name_missing(name: &str, command_prefix: &str) -> String {
+    format!(
+        "There is no prompt named `{name}` in the prompt library. Send `{command_prefix} prompt list` to see the available prompts."
+    )
+}
+
+pub fn template_invalid(error: &str) -> String {
+    let variables = TextGenerationPromptVariables::NAMES
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "The prompt was not saved, because it contains a templating mistake ({error}).\n\nPrompts may use variables (e.g. `{{{{ room_name }}}}` or `{{{{ room_topic | default(\"none\") }}}}`) and conditionals (e.g. `{{% if room_is_dm %}}…{{% else %}}…{{% endif %}}`). The following variables are available: {variables}."
+    )
+}
+
+pub fn library_empty(command_prefix: &str) -> String {
+    format!(
+        "The prompt library is empty. Send `{command_prefix} prompt` to learn how prompts can be added."
+    )
+}
+
+pub fn library_intro() -> &'static str {
+    "The following prompts are available in the prompt library:"
+}
+
+pub fn library_entry(prompt: &PromptDefinition) -> String {
+    format!("- `{}`: {}", prompt.name, prompt.description)
+}
+
+pub fn library_outro(command_prefix: &str) -> String {
+    format!(
+        "To see a prompt, send `{command_prefix} prompt show NAME`. To use it in a room, send `{command_prefix} config room text-generation set-prompt-name NAME`."
+    )
+}
+
+pub fn details(prompt: &PromptDefinition, command_prefix: &str) -> String {
+    format!(
+        "### ⌨️ `{}`\n\n{}\n\n{}\n\nTo use this prompt in a room, send `{command_prefix} config room text-generation set-prompt-name {}`.",
+        prompt.name,
+        prompt.description,
+        block_quote(&prompt.text),
+        prompt.name,
+    )
+}
+
+pub fn added(name: &str, command_prefix: &str) -> String {
+    format!(
+        "The `{name}` prompt was added to the prompt library. To use it in a room, send `{command_prefix} config room text-generation set-prompt-name {name}`."
+    )
+}
+
+pub fn updated(name: &str) -> String {
+    format!("The `{name}` prompt was updated. Rooms using it will use the new version from now on.")
+}
+
+pub fn deleted(name: &str) -> String {
+    format!(
+        "The `{name}` prompt was deleted from the prompt library. Rooms which were using it will fall back to the agent's own prompt."
+    )
+}
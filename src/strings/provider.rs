@@ -2,6 +2,10 @@ use crate::agent::AgentInstantiationError;
 use crate::agent::AgentProvider;
 use crate::agent::AgentProviderInfo;
 use crate::agent::AgentPurpose;
+use crate::agent::provider::ModelInfo;
+
+// Providers like OpenRouter offer hundreds of models, which would make for an unwieldy message.
+const MAX_MODELS_LISTED_PER_PURPOSE: usize = 100;
 
 pub fn invalid(provider: &str) -> String {
     let choices_string = AgentProvider::choices()
@@ -71,6 +75,23 @@ pub fn help_how_to_use_description(command_prefix: &str) -> String {
         .to_owned()
 }
 
+pub fn help_how_to_find_models_heading() -> String {
+    "How to find models".to_string()
+}
+
+pub fn help_how_to_find_models_description(command_prefix: &str) -> String {
+    let str = r#"
+Each provider below links to its models list, but you can also ask the provider's API directly:
+
+- 🤖 for an existing agent: `%command_prefix% agent models FULL_AGENT_IDENTIFIER`
+- ☁️ for a provider which doesn't require an API key (e.g. a self-hosted one): `%command_prefix% provider models PROVIDER_ID [BASE_URL]` (e.g. `%command_prefix% provider models ollama http://my-ollama-self-hosted-service:11434/v1`)
+"#;
+
+    str.replace("%command_prefix%", command_prefix)
+        .trim()
+        .to_owned()
+}
+
 pub fn help_provider_heading(provider_name: &str, homepage_url: &Option<String>) -> String {
     match homepage_url {
         Some(url) => format!("[{}]({})", provider_name, url),
@@ -133,3 +154,101 @@ pub fn help_provider_details(id: &str, info: &AgentProviderInfo) -> String {
 
     message
 }
+
+pub fn models_incorrect_invocation(command_prefix: &str) -> String {
+    format!(
+        "Incorrect command invocation. This command expects a provider ID and optionally a base URL (e.g. `{command_prefix} provider models ollama http://ollama:11434/v1`). See `{command_prefix} provider` for help."
+    )
+}
+
+pub fn models_not_allowed() -> &'static str {
+    "Only those who can create agents in this room can list the models of a provider."
+}
+
+pub fn models_cannot_be_listed_without_agent(
+    provider: &AgentProvider,
+    err: AgentInstantiationError,
+    command_prefix: &str,
+) -> String {
+    format!(
+        "The models of the `{provider}` provider cannot be listed without an agent (it likely requires an API key):\n```\n{err:?}\n```\n\nCreate an agent and use `{command_prefix} agent models AGENT_ID` instead."
+    )
+}
+
+pub fn models_intro(provider: &AgentProvider, base_url: Option<&str>) -> String {
+    match base_url {
+        Some(base_url) => format!("The `{provider}` provider at `{base_url}` offers these models:"),
+        None => format!("The `{provider}` provider offers these models:"),
+    }
+}
+
+pub fn models_none(provider: &AgentProvider) -> String {
+    format!("The `{provider}` provider does not report any models.")
+}
+
+pub fn models_listing_failed(provider: &AgentProvider, err: anyhow::Error) -> String {
+    format!(
+        "Failed to list the models of the `{provider}` provider. The following error was encountered when trying to talk to its API:\n```\n{err}\n```"
+    )
+}
+
+/// Lists the models under a heading for each purpose they can serve (a model may appear under several).
+pub fn models_grouped_by_purpose_block(models: &[ModelInfo]) -> String {
+    let mut message = String::new();
+
+    for purpose in AgentPurpose::choices() {
+        if let AgentPurpose::CatchAll = purpose {
+            continue;
+        }
+
+        let purpose_models = models
+            .iter()
+            .filter(|model| model.purposes.contains(purpose))
+            .collect::<Vec<_>>();
+
+        message.push_str(&models_group_block(
+            &format!("**{} {}**", purpose.emoji(), purpose.heading()),
+            &purpose_models,
+        ));
+    }
+
+    let other_models = models
+        .iter()
+        .filter(|model| model.purposes.is_empty())
+        .collect::<Vec<_>>();
+
+    message.push_str(&models_group_block(
+        "**🧩 Other** (embeddings, moderation, etc.)",
+        &other_models,
+    ));
+
+    message.trim_end().to_owned()
+}
+
+fn models_group_block(heading: &str, models: &[&ModelInfo]) -> String {
+    if models.is_empty() {
+        return String::new();
+    }
+
+    let mut message = String::new();
+    message.push_str(heading);
+
+    for model in models.iter().take(MAX_MODELS_LISTED_PER_PURPOSE) {
+        message.push('\n');
+        message.push_str(&match &model.details {
+            Some(details) => format!("- `{}` ({})", model.id, details),
+            None => format!("- `{}`", model.id),
+        });
+    }
+
+    if models.len() > MAX_MODELS_LISTED_PER_PURPOSE {
+        message.push_str(&format!(
+            "\n- …and {} more",
+            models.len() - MAX_MODELS_LISTED_PER_PURPOSE
+        ));
+    }
+
+    message.push_str("\n\n");
+
+    message
+}
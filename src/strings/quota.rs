@@ -0,0 +1,55 @@
+use crate::entity::roomconfig::{QuotaLimit, QuotaMetric, QuotaPeriod, QuotaScope};
+use crate::entity::usage::QuotaExceeded;
+
+pub fn no_permissions_to_change_quotas() -> &'static str {
+    "Only administrators can change quotas, whether in the room or in the global configuration."
+}
+
+pub fn describe_limit(limit: &QuotaLimit) -> String {
+    let what = match limit.metric {
+        QuotaMetric::Tokens => "tokens",
+        QuotaMetric::Cost => "estimated cost",
+        QuotaMetric::Images => "generated images",
+        QuotaMetric::Speech => "text-to-speech generations",
+    };
+
+    let whom = match limit.scope {
+        QuotaScope::User => "per user",
+        QuotaScope::Room => "for the whole room",
+    };
+
+    let when = match limit.period {
+        QuotaPeriod::Daily => "per day",
+        QuotaPeriod::Monthly => "per month",
+    };
+
+    format!("{what} {whom} {when}")
+}
+
+pub fn format_value(metric: QuotaMetric, value: f64) -> String {
+    if metric.is_count() {
+        format!("{}", value as u64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+pub fn quota_exceeded(exceeded: &QuotaExceeded) -> String {
+    let who = match exceeded.limit.scope {
+        QuotaScope::User => "You have",
+        QuotaScope::Room => "This room has",
+    };
+
+    let resets = match exceeded.limit.period {
+        QuotaPeriod::Daily => "at midnight (UTC)",
+        QuotaPeriod::Monthly => "at the start of next month (UTC)",
+    };
+
+    format!(
+        "⛔ {who} reached the quota for **{}** (`{}`): used `{}` out of `{}`.\n\nThe quota resets {resets}. Until then, ask an administrator if you need more.",
+        describe_limit(&exceeded.limit),
+        exceeded.limit,
+        format_value(exceeded.limit.metric, exceeded.used),
+        format_value(exceeded.limit.metric, exceeded.value),
+    )
+}
@@ -0,0 +1,15 @@
+use crate::agent::PublicIdentifier;
+
+pub fn not_in_thread() -> &'static str {
+    "Only answers in threaded conversations can be regenerated. Send this command in the thread whose last answer you'd like to regenerate."
+}
+
+pub fn nothing_to_regenerate() -> &'static str {
+    "There is no answer of mine in this thread which could be regenerated."
+}
+
+pub fn agent_does_not_support_text_generation(agent_identifier: &PublicIdentifier) -> String {
+    format!(
+        "The `{agent_identifier}` agent does not support 💬 text-generation, so it cannot regenerate answers."
+    )
+}
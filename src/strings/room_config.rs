@@ -8,11 +8,12 @@ pub fn room_not_configured_with_specific_agent_for_purpose(purpose: AgentPurpose
 }
 
 pub fn configured_to_use_agent_for_purpose(
-    agent_identifier: &PublicIdentifier,
+    agent_identifiers: &[PublicIdentifier],
     purpose: AgentPurpose,
 ) -> String {
     format!(
-        "This room is configured to use the `{agent_identifier}` agent for the `{purpose}` purpose.",
+        "This room is configured to use {} for the `{purpose}` purpose.",
+        super::agent::describe_agent_chain(agent_identifiers),
     )
 }
 
@@ -39,12 +40,13 @@ pub fn configures_agent_for_purpose_but_agent_does_not_support_it(
 }
 
 pub fn reconfigured_to_use_agent_for_purpose(
-    agent_identifier: &PublicIdentifier,
+    agent_identifiers: &[PublicIdentifier],
     purpose: AgentPurpose,
 ) -> String {
     format!(
-        "This room has been reconfigured to use the `{}` agent for the `{}` purpose.",
-        agent_identifier, purpose
+        "This room has been reconfigured to use {} for the `{}` purpose.",
+        super::agent::describe_agent_chain(agent_identifiers),
+        purpose
     )
 }
 
@@ -0,0 +1,9 @@
+pub fn queued(position: usize) -> String {
+    format!(
+        "⏳ Other requests are being handled right now. Yours is #{position} in the queue and will be handled as soon as possible."
+    )
+}
+
+pub fn redaction_reason_started() -> &'static str {
+    "The request is now being handled."
+}
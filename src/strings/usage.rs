@@ -1,3 +1,5 @@
+use crate::entity::usage::{UsageCounters, UsagePeriod};
+
 pub fn intro(command_prefix: &str) -> String {
     let message = r#"
 ## 📖 Usage
@@ -16,6 +18,8 @@ For multi-user rooms, this setting defaults to "required".
 
 Room messages start a threaded conversation where you can continue back-and-forth communication with the bot.
 
+To **regenerate an answer** of the bot's in a thread, react to it with 🔁. To regenerate the last answer via another agent, send `%command_prefix% regenerate AGENT_ID` in the thread. The answer is edited in place, so earlier versions remain available in its edit history.
+
 ### 🗣️ Text-to-Speech
 
 If there's a text-to-speech handler agent configured (see `%command_prefix% config status`), the bot **may** convert text messages sent to the room to audio (voice).
@@ -67,7 +71,116 @@ The difference from **creating images** is that the bot will:
 - create a smaller-resolution image (as small as the model allows) - smaller/quicker, but still good enough for a sticker
 - potentially switch to a different (cheaper or otherwise more suitable) model, if available
 - post the image directly to the room (as a reply to your message), without starting a threaded conversation
+
+### 📊 Usage statistics
+
+The bot keeps track of how much it's being used (requests, tokens, images, etc.) in each room, per user and per agent.
+
+- To **see usage statistics for this room**, send a `%command_prefix% usage room` command.
+- Administrators can **see usage statistics across all rooms** by sending a `%command_prefix% usage global` command.
 "#;
 
     message.replace("%command_prefix%", command_prefix)
 }
+
+pub fn no_permissions_to_see_global_usage() -> &'static str {
+    "Only administrators can see usage statistics across all rooms."
+}
+
+pub fn room_report_heading() -> &'static str {
+    "📊 Usage statistics for this room"
+}
+
+pub fn global_report_heading() -> &'static str {
+    "📊 Usage statistics across all rooms"
+}
+
+pub fn global_report_sent_via_direct_message() -> &'static str {
+    "The global usage report covers all rooms and users, so it was sent to you via a direct message."
+}
+
+pub fn global_report_failed_to_reach_requester() -> &'static str {
+    "Failed to send you the global usage report via a direct message. Make sure you can receive direct messages from me and try again."
+}
+
+pub fn nothing_recorded_yet() -> &'static str {
+    "No usage has been recorded yet."
+}
+
+pub fn period_heading(period: UsagePeriod) -> &'static str {
+    match period {
+        UsagePeriod::Today => "📅 Today (UTC)",
+        UsagePeriod::ThisMonth => "🗓️ This month (UTC)",
+        UsagePeriod::AllTime => "♾️ All time",
+    }
+}
+
+pub fn nothing_recorded_in_period() -> &'static str {
+    "Nothing recorded."
+}
+
+pub fn total(counters: &UsageCounters) -> String {
+    format!("**Total**: {}", counters_summary(counters))
+}
+
+pub fn by_agent_heading() -> &'static str {
+    "By agent"
+}
+
+pub fn by_user_heading() -> &'static str {
+    "By user"
+}
+
+pub fn by_room_heading() -> &'static str {
+    "By room"
+}
+
+pub fn breakdown_entry(label: &str, counters: &UsageCounters) -> String {
+    format!("- {}: {}", label, counters_summary(counters))
+}
+
+pub fn counters_summary(counters: &UsageCounters) -> String {
+    let mut parts = vec![format!("{} requests", counters.requests)];
+
+    if counters.total_tokens() > 0 {
+        let mut prompt = format!("{} prompt", counters.prompt_tokens);
+        if counters.cached_tokens > 0 {
+            prompt.push_str(&format!(" (of which {} cached)", counters.cached_tokens));
+        }
+
+        let mut completion = format!("{} completion", counters.completion_tokens);
+        if counters.reasoning_tokens > 0 {
+            completion.push_str(&format!(
+                " (of which {} reasoning)",
+                counters.reasoning_tokens
+            ));
+        }
+
+        parts.push(format!(
+            "{} tokens ({}, {})",
+            counters.total_tokens(),
+            prompt,
+            completion
+        ));
+    }
+
+    if counters.images > 0 {
+        parts.push(format!("🖌️ {} images", counters.images));
+    }
+
+    if counters.audio_seconds > 0 {
+        parts.push(format!(
+            "🦻 {}s of audio transcribed",
+            counters.audio_seconds
+        ));
+    }
+
+    if counters.speech_characters > 0 {
+        parts.push(format!(
+            "🗣️ {} characters spoken",
+            counters.speech_characters
+        ));
+    }
+
+    parts.join(" · ")
+}
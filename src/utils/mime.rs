@@ -6,6 +6,7 @@ pub fn get_file_extension(mime_type: &mime::Mime) -> String {
         (mime::AUDIO, mime::MPEG) => "mp3",
         (mime::AUDIO, mime::MP4) => "m4a",
         (mime::AUDIO, mime::OGG) => "ogg",
+        (mime::AUDIO, subtype) if subtype == "wav" => "wav",
         (mime::IMAGE, mime::BMP) => "bmp",
         (mime::IMAGE, mime::GIF) => "gif",
         (mime::IMAGE, mime::JPEG) => "jpg",
@@ -3,3 +3,4 @@ pub(crate) mod mime;
 pub mod status;
 pub mod text;
 pub mod text_to_speech;
+pub mod time;
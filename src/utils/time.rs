@@ -0,0 +1,64 @@
+use chrono::FixedOffset;
+
+/// Parses UTC offsets like `Z`, `UTC`, `+02`, `+0200` and `-05:30`.
+pub fn parse_utc_offset(value: &str) -> Option<FixedOffset> {
+    let value = value.trim();
+
+    if value.is_empty() || value.eq_ignore_ascii_case("z") || value.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+
+    let digits = rest.replace(':', "");
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+
+    if minutes >= 60 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utc_offsets() {
+        let cases = [
+            ("Z", Some(0)),
+            ("utc", Some(0)),
+            ("+02", Some(2 * 3600)),
+            ("+0200", Some(2 * 3600)),
+            ("-05:30", Some(-(5 * 3600 + 30 * 60))),
+            ("+5:30", None),
+            ("+02:75", None),
+            ("02:00", None),
+            ("+99:00", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_utc_offset(input).map(|offset| offset.local_minus_utc()),
+                expected,
+                "input: {input}"
+            );
+        }
+    }
+}